@@ -1,6 +1,7 @@
 use criterion::{Criterion, Throughput, criterion_group, criterion_main};
 use raflow_lib::audio::{AudioResampler, Quality, RingBuffer};
 use std::hint::black_box;
+use std::time::Instant;
 
 fn bench_resampler(c: &mut Criterion) {
     let mut group = c.benchmark_group("resampler");
@@ -69,13 +70,13 @@ fn bench_ring_buffer(c: &mut Criterion) {
 
     group.bench_function("push", |b| {
         b.iter(|| {
-            buffer.push(black_box(&data));
+            buffer.push(black_box(&data), Instant::now());
         });
     });
 
     // 填充一些数据用于 pop 测试
     for _ in 0..50 {
-        buffer.push(&data);
+        buffer.push(&data, Instant::now());
     }
 
     group.bench_function("pop", |b| {
@@ -104,13 +105,13 @@ fn bench_end_to_end(c: &mut Criterion) {
     group.bench_function("capture_to_i16", |b| {
         b.iter(|| {
             // 模拟完整流程：推送 -> 弹出 -> 重采样 -> 量化
-            buffer.push(black_box(&input));
+            buffer.push(black_box(&input), Instant::now());
             if let Some(audio_chunk) = buffer.pop() {
-                if let Ok(resampled) = resampler.process(&audio_chunk) {
+                if let Ok(resampled) = resampler.process(&audio_chunk.samples) {
                     let i16_samples = AudioResampler::quantize_to_i16(&resampled);
                     black_box(i16_samples);
                 }
-                buffer.recycle(audio_chunk);
+                buffer.recycle(audio_chunk.samples);
             }
         });
     });
@@ -0,0 +1,226 @@
+//! 自动增益控制模块
+//!
+//! 跟踪语音的滑动 RMS，把音量缓慢调整到目标电平，改善小声说话时的识别效果
+
+/// 默认目标电平（dBFS）
+pub const DEFAULT_AGC_TARGET_DBFS: f32 = -20.0;
+
+/// 默认最大增益（倍数，约 +20dB）
+pub const DEFAULT_AGC_MAX_GAIN: f32 = 10.0;
+
+/// 低于该语音概率时冻结增益调整，避免静音时增益逐渐升高（泵浦）
+const AGC_VAD_THRESHOLD: f32 = 0.3;
+
+/// 低于该 RMS（约 -60dBFS）的音频块视为静音，不参与调整
+const AGC_SILENCE_RMS: f32 = 0.001;
+
+/// 滑动 RMS 的更新系数（按音频块）
+const AGC_RMS_SMOOTHING: f32 = 0.3;
+
+/// 增益下降的平滑系数（按音频块，快速压低避免爆音）
+const AGC_ATTACK: f32 = 0.5;
+
+/// 增益上升的平滑系数（按音频块，缓慢提升）
+const AGC_RELEASE: f32 = 0.1;
+
+/// 输出峰值上限
+const AGC_CEILING: f32 = 0.99;
+
+/// 自动增益控制配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgcConfig {
+    /// 是否启用自动增益
+    pub enabled: bool,
+    /// 目标电平（dBFS，取值应小于 0）
+    pub target_dbfs: f32,
+    /// 最大增益（倍数），同时限制最大衰减为 1 / max_gain
+    pub max_gain: f32,
+}
+
+impl Default for AgcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_dbfs: DEFAULT_AGC_TARGET_DBFS,
+            max_gain: DEFAULT_AGC_MAX_GAIN,
+        }
+    }
+}
+
+/// 自动增益控制器
+///
+/// 只在检测到语音时更新滑动 RMS 和增益；块内增益线性过渡，
+/// 并按块峰值限制增益，保证输出不超过满幅
+#[derive(Debug)]
+pub struct AutomaticGainControl {
+    target_rms: f32,
+    max_gain: f32,
+    /// 语音的滑动均方值，None 表示尚未检测到语音
+    mean_square: Option<f32>,
+    gain: f32,
+}
+
+impl AutomaticGainControl {
+    /// 创建自动增益控制器
+    ///
+    /// # Arguments
+    /// * `config` - 自动增益配置（`enabled` 由调用方判断）
+    pub fn new(config: &AgcConfig) -> Self {
+        Self {
+            target_rms: 10f32.powf(config.target_dbfs.min(0.0) / 20.0),
+            max_gain: config.max_gain.max(1.0),
+            mean_square: None,
+            gain: 1.0,
+        }
+    }
+
+    /// 处理一个音频块（原地调整）
+    ///
+    /// # Arguments
+    /// * `samples` - 单声道 f32 音频
+    /// * `vad` - 降噪器给出的语音概率，None 表示没有 VAD，仅按能量判断
+    pub fn process(&mut self, samples: &mut [f32], vad: Option<f32>) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let block_ms = samples.iter().map(|&x| x * x).sum::<f32>() / samples.len() as f32;
+        let is_voice =
+            vad.is_none_or(|v| v >= AGC_VAD_THRESHOLD) && block_ms.sqrt() >= AGC_SILENCE_RMS;
+
+        let mut target_gain = self.gain;
+        if is_voice {
+            let mean_square = match self.mean_square {
+                Some(ms) => ms + (block_ms - ms) * AGC_RMS_SMOOTHING,
+                None => block_ms,
+            };
+            self.mean_square = Some(mean_square);
+
+            let desired =
+                (self.target_rms / mean_square.sqrt()).clamp(1.0 / self.max_gain, self.max_gain);
+            let smoothing = if desired < self.gain {
+                AGC_ATTACK
+            } else {
+                AGC_RELEASE
+            };
+            target_gain = self.gain + (desired - self.gain) * smoothing;
+        }
+
+        // 峰值限制：当前块放大后不能超过上限
+        let peak = samples.iter().fold(0.0f32, |max, &x| max.max(x.abs()));
+        let limit = if peak > 0.0 {
+            AGC_CEILING / peak
+        } else {
+            f32::MAX
+        };
+        target_gain = target_gain.min(limit);
+
+        // 块内从上一增益线性过渡到新增益，避免增益跳变产生杂音
+        let start_gain = self.gain.min(limit);
+        let step = (target_gain - start_gain) / samples.len() as f32;
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let gain = start_gain + step * (i + 1) as f32;
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
+        }
+
+        self.gain = target_gain;
+    }
+
+    /// 当前增益
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (i as f32 * 2.0 * std::f32::consts::PI * 440.0 / 16000.0).sin() * amplitude)
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|&x| x * x).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn enabled() -> AgcConfig {
+        AgcConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_boosts_quiet_speech_toward_target() {
+        let mut agc = AutomaticGainControl::new(&enabled());
+        let input = sine(0.01, 320);
+        let input_rms = rms(&input);
+        let target_rms = 10f32.powf(DEFAULT_AGC_TARGET_DBFS / 20.0);
+
+        let mut output = Vec::new();
+        for _ in 0..100 {
+            output = input.clone();
+            agc.process(&mut output, Some(0.9));
+            assert!(output.iter().all(|x| x.abs() <= 1.0));
+        }
+
+        let output_rms = rms(&output);
+        assert!(
+            output_rms > input_rms * 5.0,
+            "rms {} -> {}",
+            input_rms,
+            output_rms
+        );
+        assert!(output_rms <= target_rms);
+        // 受最大增益限制
+        assert!((agc.gain() - DEFAULT_AGC_MAX_GAIN).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_ceiling_prevents_clipping() {
+        let mut agc = AutomaticGainControl::new(&AgcConfig {
+            enabled: true,
+            target_dbfs: -1.0,
+            max_gain: 100.0,
+        });
+
+        for _ in 0..50 {
+            let mut block = sine(0.5, 320);
+            agc.process(&mut block, Some(0.9));
+            assert!(block.iter().all(|x| x.abs() <= AGC_CEILING + 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_gain_frozen_on_silence() {
+        let mut agc = AutomaticGainControl::new(&enabled());
+        for _ in 0..5 {
+            agc.process(&mut sine(0.01, 320), Some(0.9));
+        }
+        let gain = agc.gain();
+
+        // VAD 低：即使有能量也不调整
+        for _ in 0..50 {
+            agc.process(&mut sine(0.001, 320), Some(0.0));
+        }
+        assert_eq!(agc.gain(), gain);
+
+        // 没有 VAD 时，静音块同样不调整
+        for _ in 0..50 {
+            agc.process(&mut vec![0.0; 320], None);
+        }
+        assert_eq!(agc.gain(), gain);
+    }
+
+    #[test]
+    fn test_attenuates_loud_speech() {
+        let mut agc = AutomaticGainControl::new(&enabled());
+        for _ in 0..50 {
+            agc.process(&mut sine(0.8, 320), Some(0.9));
+        }
+        assert!(agc.gain() < 1.0);
+    }
+}
@@ -3,19 +3,48 @@
 //! 使用对象池模式减少内存分配，提供高性能的音频数据传输
 
 use crossbeam::queue::ArrayQueue;
+use serde::Serialize;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 use tracing::warn;
 
+/// 缓冲区中的音频块
+#[derive(Debug)]
+pub struct CapturedChunk {
+    /// 音频采样数据
+    pub samples: Vec<f32>,
+    /// 采集回调收到这块数据的时间
+    pub captured_at: Instant,
+}
+
+/// 缓冲区压力统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BufferStats {
+    /// 当前队列长度
+    pub len: usize,
+    /// 队列容量
+    pub capacity: usize,
+    /// 对象池剩余数量
+    pub pool_available: usize,
+    /// 队列已满被丢弃的音频块总数
+    pub dropped: u64,
+    /// 对象池耗尽时额外分配的次数
+    pub pool_misses: u64,
+}
+
 /// 无锁环形缓冲区
 ///
 /// 使用 crossbeam 的 ArrayQueue 实现线程安全的无锁队列
 /// 结合对象池模式复用 Vec，减少内存分配开销
 #[derive(Clone)]
 pub struct RingBuffer {
-    queue: Arc<ArrayQueue<Vec<f32>>>,
+    queue: Arc<ArrayQueue<CapturedChunk>>,
     pool: Arc<ArrayQueue<Vec<f32>>>,
     capacity: usize,
     buffer_size: usize,
+    dropped_chunks: Arc<AtomicU64>,
+    pool_misses: Arc<AtomicU64>,
 }
 
 impl RingBuffer {
@@ -47,6 +76,8 @@ impl RingBuffer {
             pool,
             capacity,
             buffer_size,
+            dropped_chunks: Arc::new(AtomicU64::new(0)),
+            pool_misses: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -54,32 +85,67 @@ impl RingBuffer {
     ///
     /// # Arguments
     /// * `data` - 音频采样数据
+    /// * `captured_at` - 采集回调收到数据的时间
     ///
     /// # Returns
     /// * `true` - 推送成功
     /// * `false` - 队列已满，推送失败
-    pub fn push(&self, data: &[f32]) -> bool {
+    pub fn push(&self, data: &[f32], captured_at: Instant) -> bool {
         // 从对象池获取 Vec
-        if let Some(mut buffer) = self.pool.pop() {
+        let buffer = if let Some(mut buffer) = self.pool.pop() {
             buffer.clear();
             buffer.extend_from_slice(data);
-            self.queue.push(buffer).is_ok()
+            buffer
         } else {
             // 对象池耗尽，分配新 Vec（降级处理）
             warn!("Buffer pool exhausted, allocating new Vec");
-            self.queue.push(data.to_vec()).is_ok()
+            self.pool_misses.fetch_add(1, Ordering::Relaxed);
+            data.to_vec()
+        };
+
+        let chunk = CapturedChunk {
+            samples: buffer,
+            captured_at,
+        };
+        match self.queue.push(chunk) {
+            Ok(()) => true,
+            Err(chunk) => {
+                // 队列已满，丢弃本块并把 Vec 还给对象池
+                self.dropped_chunks.fetch_add(1, Ordering::Relaxed);
+                self.recycle(chunk.samples);
+                false
+            }
         }
     }
 
     /// 从缓冲区弹出音频数据（消费者端）
     ///
     /// # Returns
-    /// * `Some(Vec<f32>)` - 成功获取音频数据
+    /// * `Some(CapturedChunk)` - 成功获取音频数据及其采集时间
     /// * `None` - 队列为空
-    pub fn pop(&self) -> Option<Vec<f32>> {
+    pub fn pop(&self) -> Option<CapturedChunk> {
         self.queue.pop()
     }
 
+    /// 取出队列中所有已缓冲的音频块（消费者端）
+    ///
+    /// 以调用时的队列长度为快照，最多弹出该数量的块，
+    /// 生产者并发写入时不会无限循环。返回的块按 FIFO 顺序排列，
+    /// 处理完后应将 `samples` 通过 `recycle` 归还到对象池
+    pub fn drain(&self) -> Vec<CapturedChunk> {
+        let pending = self.queue.len();
+        let mut chunks = Vec::with_capacity(pending);
+
+        while chunks.len() < pending {
+            match self.queue.pop() {
+                Some(chunk) => chunks.push(chunk),
+                None => break,
+            }
+        }
+
+        chunks
+    }
+
     /// 回收 Vec 到对象池
     ///
     /// 用于将处理完的音频缓冲区归还到对象池，供后续复用
@@ -110,6 +176,27 @@ impl RingBuffer {
     pub fn pool_available(&self) -> usize {
         self.pool.len()
     }
+
+    /// 队列已满被丢弃的音频块总数
+    pub fn dropped_chunks(&self) -> u64 {
+        self.dropped_chunks.load(Ordering::Relaxed)
+    }
+
+    /// 对象池耗尽时额外分配的次数
+    pub fn pool_misses(&self) -> u64 {
+        self.pool_misses.load(Ordering::Relaxed)
+    }
+
+    /// 获取缓冲区统计快照
+    pub fn stats(&self) -> BufferStats {
+        BufferStats {
+            len: self.len(),
+            capacity: self.capacity,
+            pool_available: self.pool_available(),
+            dropped: self.dropped_chunks(),
+            pool_misses: self.pool_misses(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -131,30 +218,91 @@ mod tests {
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
 
         // 推送数据
-        assert!(buffer.push(&data));
+        assert!(buffer.push(&data, Instant::now()));
         assert_eq!(buffer.len(), 1);
         assert!(!buffer.is_empty());
 
         // 弹出数据
         let popped = buffer.pop();
         assert!(popped.is_some());
-        assert_eq!(popped.unwrap(), data);
+        assert_eq!(popped.unwrap().samples, data);
         assert_eq!(buffer.len(), 0);
         assert!(buffer.is_empty());
     }
 
+    #[test]
+    fn test_capture_time_carried_through() {
+        let buffer = RingBuffer::new(5, 10);
+        let first = Instant::now();
+        let second = first + std::time::Duration::from_millis(10);
+
+        assert!(buffer.push(&[1.0; 10], first));
+        assert!(buffer.push(&[2.0; 10], second));
+
+        // 弹出时保留采集时间，而不是弹出时刻
+        assert_eq!(buffer.pop().unwrap().captured_at, first);
+        assert_eq!(buffer.drain()[0].captured_at, second);
+    }
+
     #[test]
     fn test_buffer_full() {
         let buffer = RingBuffer::new(3, 10);
         let data = vec![1.0; 10];
 
         // 填满缓冲区
-        assert!(buffer.push(&data));
-        assert!(buffer.push(&data));
-        assert!(buffer.push(&data));
+        assert!(buffer.push(&data, Instant::now()));
+        assert!(buffer.push(&data, Instant::now()));
+        assert!(buffer.push(&data, Instant::now()));
 
         // 再推送应该失败
-        assert!(!buffer.push(&data));
+        assert!(!buffer.push(&data, Instant::now()));
+    }
+
+    #[test]
+    fn test_dropped_chunks_counted() {
+        let buffer = RingBuffer::new(3, 10);
+        let data = vec![1.0; 10];
+
+        for _ in 0..3 {
+            assert!(buffer.push(&data, Instant::now()));
+        }
+        assert_eq!(buffer.dropped_chunks(), 0);
+
+        assert!(!buffer.push(&data, Instant::now()));
+        assert!(!buffer.push(&data, Instant::now()));
+        assert_eq!(buffer.dropped_chunks(), 2);
+
+        // 第一次丢弃时对象池已耗尽（额外分配一次），丢弃的 Vec 归还到对象池
+        assert_eq!(buffer.pool_misses(), 1);
+        assert_eq!(buffer.pool_available(), 1);
+
+        // 克隆共享计数器
+        let stats = buffer.clone().stats();
+        assert_eq!(
+            stats,
+            BufferStats {
+                len: 3,
+                capacity: 3,
+                pool_available: 1,
+                dropped: 2,
+                pool_misses: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_pool_misses_counted() {
+        let buffer = RingBuffer::new(2, 10);
+        let data = vec![1.0; 10];
+
+        // 弹出后不回收，对象池逐渐耗尽
+        for _ in 0..3 {
+            assert!(buffer.push(&data, Instant::now()));
+            let _ = buffer.pop().unwrap();
+        }
+
+        assert_eq!(buffer.pool_misses(), 1);
+        assert_eq!(buffer.dropped_chunks(), 0);
     }
 
     #[test]
@@ -165,16 +313,39 @@ mod tests {
         let initial_pool = buffer.pool_available();
 
         // 推送并弹出
-        buffer.push(&data);
+        buffer.push(&data, Instant::now());
         let popped = buffer.pop().unwrap();
 
         // 回收
-        buffer.recycle(popped);
+        buffer.recycle(popped.samples);
 
         // 对象池应该恢复
         assert_eq!(buffer.pool_available(), initial_pool);
     }
 
+    #[test]
+    fn test_drain() {
+        let buffer = RingBuffer::new(5, 10);
+
+        for i in 0..3 {
+            assert!(buffer.push(&[i as f32; 10], Instant::now()));
+        }
+
+        let drained = buffer.drain();
+        assert_eq!(drained.len(), 3);
+        for (i, chunk) in drained.iter().enumerate() {
+            assert_eq!(chunk.samples, vec![i as f32; 10]);
+        }
+        assert!(buffer.is_empty());
+        assert!(buffer.drain().is_empty());
+
+        // 回收后对象池恢复
+        for chunk in drained {
+            buffer.recycle(chunk.samples);
+        }
+        assert_eq!(buffer.pool_available(), 5);
+    }
+
     #[test]
     fn test_concurrent_access() {
         let buffer = RingBuffer::new(100, 10);
@@ -184,7 +355,7 @@ mod tests {
         let producer = thread::spawn(move || {
             for i in 0..50 {
                 let data = vec![i as f32; 10];
-                while !buffer_clone.push(&data) {
+                while !buffer_clone.push(&data, Instant::now()) {
                     thread::yield_now();
                 }
             }
@@ -218,14 +389,14 @@ mod tests {
         assert_eq!(initial_pool, 10);
 
         // 推送并弹出
-        buffer.push(&data);
+        buffer.push(&data, Instant::now());
         let popped = buffer.pop().unwrap();
 
         // 对象池减少了 1
         assert_eq!(buffer.pool_available(), initial_pool - 1);
 
         // 回收后恢复
-        buffer.recycle(popped);
+        buffer.recycle(popped.samples);
         assert_eq!(buffer.pool_available(), initial_pool);
     }
 }
@@ -2,9 +2,15 @@
 //!
 //! 基于 cpal 库实现跨平台音频采集
 
+use super::preroll::{AudioSink, PreRollRouter};
+use super::recovery::StreamFault;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, Host, Stream, StreamConfig};
+use cpal::{Device, Host, HostId, SampleFormat, SampleRate, Stream, StreamConfig};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc;
+use std::time::Duration;
 use thiserror::Error;
+use tokio::sync::oneshot;
 use tracing::{debug, info, warn};
 
 #[derive(Error, Debug)]
@@ -23,10 +29,105 @@ pub enum CaptureError {
 
     #[error("Device error: {0}")]
     DeviceError(String),
+
+    #[error("Audio stream produced no data within {0:?}")]
+    NoData(Duration),
 }
 
 type Result<T> = std::result::Result<T, CaptureError>;
 
+/// 启动后等待第一次音频回调的超时时间
+const FIRST_DATA_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 备选配置优先使用的采样率
+const PREFERRED_SAMPLE_RATES: [u32; 3] = [48000, 44100, 16000];
+
+/// 设备信息中检查的常用采样率
+const STANDARD_SAMPLE_RATES: [u32; 7] = [8000, 16000, 22050, 32000, 44100, 48000, 96000];
+
+/// 采集流故障的发送端（错误回调在音频线程中调用，满时丢弃）
+type FaultSender = tokio::sync::mpsc::Sender<StreamFault>;
+
+/// 输入设备可用性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputAvailability {
+    /// 输入设备可用
+    Available,
+    /// 没有输入设备
+    NoDevice,
+    /// 有输入设备但无法访问（通常是缺少麦克风权限）
+    AccessDenied,
+}
+
+/// 多声道输入的采集方式
+///
+/// 默认混为单声道；音乐或采访录音中各声道是不同说话人时，可以只取一个声道或保留立体声
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// 平均所有声道混为单声道
+    #[default]
+    Mono,
+    /// 只取左声道（第 1 声道）
+    LeftOnly,
+    /// 只取右声道（第 2 声道，单声道设备时取唯一的声道）
+    RightOnly,
+    /// 保留左右两个声道（交织格式），单声道设备复制到两个声道
+    ///
+    /// 转写仍发送混音后的单声道，录音文件保留双声道
+    Stereo,
+}
+
+impl CaptureMode {
+    /// 采集回调输出的声道数
+    pub fn channels(self) -> u16 {
+        match self {
+            CaptureMode::Stereo => 2,
+            CaptureMode::Mono | CaptureMode::LeftOnly | CaptureMode::RightOnly => 1,
+        }
+    }
+}
+
+/// 按采集方式从交织的多声道输入中选出声道
+///
+/// # Arguments
+/// * `data` - 交织格式的输入（如 L, R, L, R, ...）
+/// * `channels` - 输入声道数
+/// * `mode` - 采集方式
+///
+/// # Returns
+/// `mode.channels()` 个声道的交织数据，不完整的末尾帧被丢弃
+pub fn select_channels(data: &[f32], channels: u16, mode: CaptureMode) -> Vec<f32> {
+    let channels = usize::from(channels.max(1));
+    let frames = data.chunks_exact(channels);
+    let last = channels - 1;
+
+    match mode {
+        CaptureMode::Mono => frames
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect(),
+        CaptureMode::LeftOnly => frames.map(|frame| frame[0]).collect(),
+        CaptureMode::RightOnly => frames.map(|frame| frame[1.min(last)]).collect(),
+        CaptureMode::Stereo => frames
+            .flat_map(|frame| [frame[0], frame[1.min(last)]])
+            .collect(),
+    }
+}
+
+/// 输入设备详情
+///
+/// 供设置界面标出默认设备以及设备支持的采样率
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AudioDeviceInfo {
+    pub name: String,
+    /// 是否为系统默认输入设备
+    pub is_default: bool,
+    /// 支持的常用采样率（升序）
+    pub sample_rates: Vec<u32>,
+    /// 支持的最大声道数
+    pub channels: u16,
+}
+
 /// 音频采集器
 pub struct AudioCapture {
     #[allow(dead_code)]
@@ -34,15 +135,55 @@ pub struct AudioCapture {
     device: Device,
     config: StreamConfig,
     stream: Option<Stream>,
+    /// 预录模式下安装音频回调的通道
+    pre_roll_tx: Option<mpsc::Sender<AudioSink>>,
+    /// 采集流故障输出
+    fault_tx: Option<FaultSender>,
+    /// 向运行中采集流的错误回调安装故障输出的通道
+    fault_slot_tx: Option<mpsc::Sender<FaultSender>>,
+    /// 多声道输入的采集方式
+    mode: CaptureMode,
 }
 
 impl AudioCapture {
     /// 创建新的音频采集器，使用默认输入设备
     pub fn new() -> Result<Self> {
-        let host = cpal::default_host();
+        Self::with_host(None)
+    }
+
+    /// 使用指定音频主机（如 WASAPI、ASIO、CoreAudio）创建音频采集器
+    ///
+    /// 指定的主机不可用时回退到默认主机
+    ///
+    /// # Arguments
+    /// * `host_name` - 音频主机名称（大小写不敏感），`None` 表示使用默认主机
+    pub fn with_host(host_name: Option<&str>) -> Result<Self> {
+        Self::with_host_and_device(host_name, None)
+    }
+
+    /// 使用指定音频主机和输入设备创建音频采集器
+    ///
+    /// 指定的设备不存在时（如已拔出的 USB 麦克风）回退到默认输入设备
+    ///
+    /// # Arguments
+    /// * `host_name` - 音频主机名称，`None` 表示使用默认主机
+    /// * `device_name` - 输入设备名称，`None` 表示使用默认设备
+    pub fn with_host_and_device(
+        host_name: Option<&str>,
+        device_name: Option<&str>,
+    ) -> Result<Self> {
+        let host = Self::resolve_host(host_name);
         info!("Audio host: {:?}", host.id());
 
-        let device = host.default_input_device().ok_or(CaptureError::NoDevice)?;
+        let device = match device_name.and_then(|name| Self::find_input_device(&host, name)) {
+            Some(device) => device,
+            None => {
+                if let Some(name) = device_name {
+                    warn!("Input device {} not found, falling back to default", name);
+                }
+                host.default_input_device().ok_or(CaptureError::NoDevice)?
+            }
+        };
 
         let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
         info!("Input device: {}", device_name);
@@ -55,6 +196,10 @@ impl AudioCapture {
             device,
             config,
             stream: None,
+            pre_roll_tx: None,
+            fault_tx: None,
+            fault_slot_tx: None,
+            mode: CaptureMode::default(),
         })
     }
 
@@ -79,70 +224,232 @@ impl AudioCapture {
             device,
             config,
             stream: None,
+            pre_roll_tx: None,
+            fault_tx: None,
+            fault_slot_tx: None,
+            mode: CaptureMode::default(),
+        })
+    }
+
+    /// 设置多声道输入的采集方式
+    ///
+    /// 只对之后打开的采集流生效，已在运行的采集流（如预录）保持原来的方式
+    pub fn set_capture_mode(&mut self, mode: CaptureMode) {
+        self.mode = mode;
+    }
+
+    /// 多声道输入的采集方式
+    pub fn capture_mode(&self) -> CaptureMode {
+        self.mode
+    }
+
+    /// 设置采集流故障输出
+    ///
+    /// 采集流的错误回调把故障（如设备被拔出）发送到该通道；
+    /// 已在运行的采集流（如预录）同样生效
+    pub fn set_fault_signal(&mut self, fault_tx: FaultSender) {
+        if let Some(slot) = &self.fault_slot_tx {
+            let _ = slot.send(fault_tx.clone());
+        }
+        self.fault_tx = Some(fault_tx);
+    }
+
+    /// 输入设备名称
+    pub fn device_name(&self) -> String {
+        self.device.name().unwrap_or_else(|_| "Unknown".to_string())
+    }
+
+    /// 启动预录
+    ///
+    /// 提前打开音频流，未开始录音时只保留最近 `duration_ms` 毫秒的音频；
+    /// 随后调用 `start` 时先把预录音频交给回调，再继续传递实时音频。
+    /// 预录期间麦克风保持打开
+    ///
+    /// # Arguments
+    /// * `duration_ms` - 预录时长（毫秒）
+    pub async fn start_pre_roll(&mut self, duration_ms: u64) -> Result<()> {
+        if self.stream.is_some() {
+            return Ok(());
+        }
+
+        // 预录按采样点计长度，立体声需要两倍的采样点
+        let output_channels = u32::from(self.mode.channels());
+        let mut pre_roll_tx = None;
+        self.open_first_working(|config| {
+            let (mut router, tx) =
+                PreRollRouter::new(config.sample_rate.0 * output_channels, duration_ms);
+            pre_roll_tx = Some(tx);
+            move |data: &[f32]| router.process(data)
         })
+        .await?;
+        self.pre_roll_tx = pre_roll_tx;
+
+        info!("Audio pre-roll started ({}ms)", duration_ms);
+        Ok(())
     }
 
     /// 启动音频流
     ///
     /// # Arguments
-    /// * `callback` - 音频数据回调函数，每次接收到新的音频数据时调用（单声道数据）
+    /// * `callback` - 音频数据回调函数，每次接收到新的音频数据时调用
+    ///   （按采集方式选出的声道，默认为单声道，见 `set_capture_mode`）
     ///
     /// # Example
     /// ```no_run
     /// use raflow_lib::audio::AudioCapture;
     ///
-    /// let mut capture = AudioCapture::new().unwrap();
-    /// capture.start(|data| {
-    ///     println!("Received {} samples", data.len());
-    /// }).unwrap();
+    /// async fn capture() {
+    ///     let mut capture = AudioCapture::new().unwrap();
+    ///     capture.start(|data| {
+    ///         println!("Received {} samples", data.len());
+    ///     }).await.unwrap();
+    /// }
     /// ```
-    pub fn start<F>(&mut self, mut callback: F) -> Result<()>
+    pub async fn start<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnMut(&[f32]) + Clone + Send + 'static,
+    {
+        // 预录流已在运行：接上回调，预录音频会先于实时音频送出
+        if let (Some(_), Some(tx)) = (&self.stream, &self.pre_roll_tx) {
+            if tx.send(Box::new(callback.clone())).is_ok() {
+                info!("Audio stream attached to pre-roll");
+                return Ok(());
+            }
+            warn!("Pre-roll stream is gone, reopening audio stream");
+            self.stop();
+        }
+
+        self.open_first_working(|_| callback.clone()).await?;
+        info!("Audio stream started");
+        Ok(())
+    }
+
+    /// 依次尝试候选配置，直到音频流确实有数据到达
+    ///
+    /// # Arguments
+    /// * `make_callback` - 为每次尝试的配置创建音频回调
+    async fn open_first_working<F>(
+        &mut self,
+        mut make_callback: impl FnMut(&StreamConfig) -> F,
+    ) -> Result<()>
+    where
+        F: FnMut(&[f32]) + Send + 'static,
+    {
+        let mut last_error = None;
+
+        for config in self.candidate_configs() {
+            match self.open_stream(&config, make_callback(&config)).await {
+                Ok((stream, fault_slot_tx)) => {
+                    if config != self.config {
+                        info!("Using fallback audio config: {:?}", config);
+                        self.config = config;
+                    }
+                    self.stream = Some(stream);
+                    self.fault_slot_tx = Some(fault_slot_tx);
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!("Audio config {:?} failed: {}", config, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or(CaptureError::NoDevice))
+    }
+
+    /// 使用指定配置打开音频流，并确认启动后确实有音频数据到达
+    ///
+    /// 部分设备 `play()` 成功但从不回调（表现为麦克风已选中却没有声音），
+    /// 超时未收到数据时返回 `NoData`，流随之销毁。
+    /// 等待第一次回调期间不阻塞运行时线程
+    ///
+    /// # Returns
+    /// 音频流，以及向其错误回调安装故障输出的通道
+    async fn open_stream<F>(
+        &self,
+        config: &StreamConfig,
+        mut callback: F,
+    ) -> Result<(Stream, mpsc::Sender<FaultSender>)>
     where
         F: FnMut(&[f32]) + Send + 'static,
     {
-        let error_callback = |err| {
+        let (fault_slot_tx, fault_slot_rx) = mpsc::channel::<FaultSender>();
+        if let Some(fault_tx) = &self.fault_tx {
+            let _ = fault_slot_tx.send(fault_tx.clone());
+        }
+
+        let mut fault_tx: Option<FaultSender> = None;
+        let error_callback = move |err: cpal::StreamError| {
             warn!("Audio stream error: {}", err);
+
+            while let Ok(tx) = fault_slot_rx.try_recv() {
+                fault_tx = Some(tx);
+            }
+            if let Some(tx) = &fault_tx
+                && tx.try_send(StreamFault::from(err)).is_err()
+            {
+                debug!("Stream fault dropped, receiver busy or gone");
+            }
         };
 
-        let channels = self.config.channels;
-        info!("Audio capture channels: {}", channels);
+        let channels = config.channels;
+        let mode = self.mode;
+        info!("Audio capture channels: {}, mode: {:?}", channels, mode);
+
+        let (mut callback, first_data) = signal_first_data(move |data: &[f32]| {
+            if channels == 1 && mode != CaptureMode::Stereo {
+                // 已经是单声道，直接传递
+                callback(data);
+            } else {
+                // 多声道按采集方式选出声道（单声道设备的立体声模式复制到两个声道）
+                callback(&select_channels(data, channels, mode));
+            }
+        });
 
         let stream = self.device.build_input_stream(
-            &self.config,
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                // 如果是立体声或多声道，转换为单声道
-                if channels > 1 {
-                    // 平均所有声道的数据转换为单声道
-                    let mono_samples = data.len() / channels as usize;
-                    let mut mono_data = Vec::with_capacity(mono_samples);
-
-                    for i in 0..mono_samples {
-                        let mut sum = 0.0f32;
-                        for ch in 0..channels as usize {
-                            sum += data[i * channels as usize + ch];
-                        }
-                        mono_data.push(sum / channels as f32);
-                    }
-
-                    callback(&mono_data);
-                } else {
-                    // 已经是单声道，直接传递
-                    callback(data);
-                }
-            },
+            config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| callback(data),
             error_callback,
             None,
         )?;
 
         stream.play()?;
-        self.stream = Some(stream);
-        info!("Audio stream started");
 
-        Ok(())
+        if !wait_for_first_data(first_data, FIRST_DATA_TIMEOUT).await {
+            return Err(CaptureError::NoData(FIRST_DATA_TIMEOUT));
+        }
+
+        Ok((stream, fault_slot_tx))
+    }
+
+    /// 候选配置：当前配置优先，其次是设备支持的其他 f32 配置
+    fn candidate_configs(&self) -> Vec<StreamConfig> {
+        let mut configs = vec![self.config.clone()];
+
+        match self.device.supported_input_configs() {
+            Ok(ranges) => {
+                for range in ranges.filter(|r| r.sample_format() == SampleFormat::F32) {
+                    let rate =
+                        preferred_sample_rate(range.min_sample_rate().0, range.max_sample_rate().0);
+                    let config: StreamConfig = range.with_sample_rate(SampleRate(rate)).into();
+                    if !configs.contains(&config) {
+                        configs.push(config);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to list supported input configs: {}", e),
+        }
+
+        configs
     }
 
     /// 停止音频流
+    ///
+    /// 预录模式下同时丢弃预录音频，避免旧音频进入下一次录音
     pub fn stop(&mut self) {
+        self.pre_roll_tx = None;
+        self.fault_slot_tx = None;
         if let Some(stream) = self.stream.take() {
             drop(stream);
             info!("Audio stream stopped");
@@ -159,9 +466,83 @@ impl AudioCapture {
         self.config.channels
     }
 
-    /// 列出所有可用的输入设备
-    pub fn list_devices() -> Result<Vec<String>> {
-        let host = cpal::default_host();
+    /// 采集回调输出的声道数（由采集方式决定，与设备声道数无关）
+    pub fn output_channels(&self) -> u16 {
+        self.mode.channels()
+    }
+
+    /// 列出所有可用的音频主机名称
+    pub fn list_hosts() -> Vec<String> {
+        cpal::available_hosts()
+            .into_iter()
+            .map(|id| id.name().to_string())
+            .collect()
+    }
+
+    /// 在主机的输入设备中按名称查找设备
+    fn find_input_device(host: &Host, device_name: &str) -> Option<Device> {
+        let devices: Vec<(String, Device)> = match host.input_devices() {
+            Ok(devices) => devices
+                .filter_map(|d| d.name().ok().map(|name| (name, d)))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to list input devices: {}", e);
+                return None;
+            }
+        };
+
+        let name =
+            select_device_name(device_name, devices.iter().map(|(n, _)| n.as_str()))?.to_string();
+        devices
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, d)| d)
+    }
+
+    /// 根据名称解析音频主机，不可用时回退到默认主机
+    fn resolve_host(host_name: Option<&str>) -> Host {
+        let Some(requested) = host_name else {
+            return cpal::default_host();
+        };
+
+        let available = cpal::available_hosts();
+        match select_host_id(requested, &available) {
+            Some(id) => match cpal::host_from_id(id) {
+                Ok(host) => return host,
+                Err(e) => warn!("Failed to open audio host {}: {}", id.name(), e),
+            },
+            None => warn!("Audio host {} not available", requested),
+        }
+
+        info!("Falling back to default audio host");
+        cpal::default_host()
+    }
+
+    /// 检查输入设备可用性
+    ///
+    /// 不启动音频流，只探测默认输入设备及其配置能否获取
+    pub fn check_input(host_name: Option<&str>) -> InputAvailability {
+        let host = Self::resolve_host(host_name);
+
+        let Some(device) = host.default_input_device() else {
+            return InputAvailability::NoDevice;
+        };
+
+        match device.default_input_config() {
+            Ok(_) => InputAvailability::Available,
+            Err(e) => {
+                warn!("Input device not accessible: {}", e);
+                InputAvailability::AccessDenied
+            }
+        }
+    }
+
+    /// 列出音频主机上所有可用的输入设备
+    ///
+    /// # Arguments
+    /// * `host_name` - 音频主机名称，`None` 表示使用默认主机（与采集时的主机解析一致）
+    pub fn list_devices(host_name: Option<&str>) -> Result<Vec<String>> {
+        let host = Self::resolve_host(host_name);
         let devices = host
             .input_devices()
             .map_err(|e| CaptureError::DeviceError(e.to_string()))?;
@@ -170,6 +551,133 @@ impl AudioCapture {
 
         Ok(device_names)
     }
+
+    /// 列出音频主机上所有可用输入设备的详情
+    ///
+    /// 无法查询名称或支持配置的设备会被跳过
+    ///
+    /// # Arguments
+    /// * `host_name` - 音频主机名称，`None` 表示使用默认主机（与采集时的主机解析一致）
+    pub fn list_devices_detailed(host_name: Option<&str>) -> Result<Vec<AudioDeviceInfo>> {
+        let host = Self::resolve_host(host_name);
+        let default_name = host.default_input_device().and_then(|d| d.name().ok());
+        let devices = host
+            .input_devices()
+            .map_err(|e| CaptureError::DeviceError(e.to_string()))?;
+
+        let infos = devices
+            .filter_map(|device| {
+                let name = match device.name() {
+                    Ok(name) => name,
+                    Err(e) => {
+                        warn!("Skipping input device without name: {}", e);
+                        return None;
+                    }
+                };
+
+                let ranges: Vec<_> = match device.supported_input_configs() {
+                    Ok(configs) => configs.collect(),
+                    Err(e) => {
+                        warn!("Skipping input device {}: {}", name, e);
+                        return None;
+                    }
+                };
+
+                let rate_ranges: Vec<(u32, u32)> = ranges
+                    .iter()
+                    .map(|r| (r.min_sample_rate().0, r.max_sample_rate().0))
+                    .collect();
+
+                Some(AudioDeviceInfo {
+                    is_default: default_name.as_deref() == Some(name.as_str()),
+                    sample_rates: supported_standard_rates(&rate_ranges),
+                    channels: ranges.iter().map(|r| r.channels()).max().unwrap_or(0),
+                    name,
+                })
+            })
+            .collect();
+
+        Ok(infos)
+    }
+}
+
+/// 支持的采样率范围内包含的常用采样率
+fn supported_standard_rates(ranges: &[(u32, u32)]) -> Vec<u32> {
+    STANDARD_SAMPLE_RATES
+        .into_iter()
+        .filter(|rate| ranges.iter().any(|(min, max)| (*min..=*max).contains(rate)))
+        .collect()
+}
+
+/// 包装音频回调，第一次回调时发出信号
+fn signal_first_data<F>(mut callback: F) -> (impl FnMut(&[f32]), oneshot::Receiver<()>)
+where
+    F: FnMut(&[f32]),
+{
+    let (tx, rx) = oneshot::channel();
+    let mut first_data = Some(tx);
+
+    let wrapped = move |data: &[f32]| {
+        if let Some(tx) = first_data.take() {
+            let _ = tx.send(());
+        }
+        callback(data);
+    };
+
+    (wrapped, rx)
+}
+
+/// 等待第一次音频回调，超时或流已销毁时返回 false
+async fn wait_for_first_data(first_data: oneshot::Receiver<()>, timeout: Duration) -> bool {
+    matches!(tokio::time::timeout(timeout, first_data).await, Ok(Ok(())))
+}
+
+/// 在支持的采样率范围内选择采样率，优先常用采样率，否则使用最大值
+fn preferred_sample_rate(min: u32, max: u32) -> u32 {
+    PREFERRED_SAMPLE_RATES
+        .into_iter()
+        .find(|rate| (min..=max).contains(rate))
+        .unwrap_or(max)
+}
+
+/// 从可用主机中按名称（大小写不敏感）选择主机
+fn select_host_id(requested: &str, available: &[HostId]) -> Option<HostId> {
+    select_host_name(requested, available.iter().map(|id| id.name()))
+        .and_then(|name| available.iter().copied().find(|id| id.name() == name))
+}
+
+/// 从可用主机名称中匹配请求的名称
+fn select_host_name<'a>(
+    requested: &str,
+    available: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    available
+        .into_iter()
+        .find(|name| name.eq_ignore_ascii_case(requested.trim()))
+}
+
+/// 从可用设备名称中匹配请求的设备
+///
+/// 优先完全匹配，其次是大小写不敏感的匹配；不做部分匹配，避免选中名称相近的其他设备
+pub fn select_device_name<'a>(
+    requested: &str,
+    available: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let requested = requested.trim();
+    if requested.is_empty() {
+        return None;
+    }
+
+    let available: Vec<&str> = available.into_iter().collect();
+    available
+        .iter()
+        .find(|name| **name == requested)
+        .or_else(|| {
+            available
+                .iter()
+                .find(|name| name.to_lowercase() == requested.to_lowercase())
+        })
+        .copied()
 }
 
 impl Drop for AudioCapture {
@@ -185,7 +693,7 @@ mod tests {
         Arc,
         atomic::{AtomicUsize, Ordering},
     };
-    use std::time::Duration;
+    use tokio::time::Instant;
 
     #[test]
     fn test_audio_capture_initialization() {
@@ -195,7 +703,7 @@ mod tests {
 
     #[test]
     fn test_list_devices() {
-        let devices = AudioCapture::list_devices();
+        let devices = AudioCapture::list_devices(None);
         assert!(devices.is_ok());
         let device_list = devices.unwrap();
         println!("Available input devices: {:?}", device_list);
@@ -203,7 +711,38 @@ mod tests {
 
     #[test]
     #[ignore] // 需要实际音频设备
-    fn test_audio_stream_callback() {
+    fn test_list_devices_detailed() {
+        let devices = AudioCapture::list_devices_detailed(None).unwrap();
+        println!("Available input devices: {:?}", devices);
+        assert!(devices.iter().filter(|d| d.is_default).count() <= 1);
+        assert!(devices.iter().all(|d| d.channels > 0));
+    }
+
+    #[test]
+    fn test_audio_device_info_serialization() {
+        let info = AudioDeviceInfo {
+            name: "USB Microphone".to_string(),
+            is_default: true,
+            sample_rates: supported_standard_rates(&[(16000, 16000), (44100, 48000)]),
+            channels: 2,
+        };
+        assert_eq!(info.sample_rates, vec![16000, 44100, 48000]);
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "USB Microphone",
+                "is_default": true,
+                "sample_rates": [16000, 44100, 48000],
+                "channels": 2,
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[ignore] // 需要实际音频设备
+    async fn test_audio_stream_callback() {
         let mut capture = AudioCapture::new().unwrap();
         let counter = Arc::new(AtomicUsize::new(0));
         let counter_clone = counter.clone();
@@ -212,10 +751,11 @@ mod tests {
             .start(move |_data| {
                 counter_clone.fetch_add(1, Ordering::Relaxed);
             })
+            .await
             .unwrap();
 
         // 等待 100ms
-        std::thread::sleep(Duration::from_millis(100));
+        tokio::time::sleep(Duration::from_millis(100)).await;
 
         // 检查回调是否被调用
         let count = counter.load(Ordering::Relaxed);
@@ -223,6 +763,108 @@ mod tests {
         assert!(count > 0);
     }
 
+    #[test]
+    fn test_select_host_name() {
+        let available = ["WASAPI", "ASIO"];
+
+        assert_eq!(select_host_name("ASIO", available), Some("ASIO"));
+        assert_eq!(select_host_name("wasapi", available), Some("WASAPI"));
+        assert_eq!(select_host_name(" asio ", available), Some("ASIO"));
+
+        // 未知或不可用的主机
+        assert_eq!(select_host_name("JACK", available), None);
+        assert_eq!(select_host_name("", available), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_no_data_within_timeout() {
+        // 模拟从不调用回调的流：持有回调但不调用
+        let (_stalled_stream, first_data) = signal_first_data(|_: &[f32]| {});
+
+        let start = Instant::now();
+        assert!(!wait_for_first_data(first_data, Duration::from_millis(50)).await);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_first_data_detected() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+        let (mut callback, first_data) = signal_first_data(move |data: &[f32]| {
+            counter_clone.fetch_add(data.len(), Ordering::Relaxed);
+        });
+
+        // 模拟音频线程：延迟后回调两次
+        let stream = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            callback(&[0.0; 4]);
+            callback(&[0.0; 4]);
+        });
+
+        assert!(wait_for_first_data(first_data, Duration::from_secs(1)).await);
+        stream.join().unwrap();
+        assert_eq!(counter.load(Ordering::Relaxed), 8);
+    }
+
+    #[tokio::test]
+    async fn test_destroyed_stream_is_no_data() {
+        let (callback, first_data) = signal_first_data(|_: &[f32]| {});
+        drop(callback);
+
+        assert!(!wait_for_first_data(first_data, Duration::from_secs(1)).await);
+    }
+
+    #[test]
+    fn test_preferred_sample_rate() {
+        assert_eq!(preferred_sample_rate(8000, 96000), 48000);
+        assert_eq!(preferred_sample_rate(8000, 44100), 44100);
+        assert_eq!(preferred_sample_rate(8000, 22050), 16000);
+        assert_eq!(preferred_sample_rate(8000, 11025), 11025);
+    }
+
+    #[test]
+    fn test_select_device_name() {
+        let available = ["MacBook Pro Microphone", "USB Microphone", "usb microphone"];
+
+        assert_eq!(
+            select_device_name("USB Microphone", available),
+            Some("USB Microphone")
+        );
+        // 完全匹配优先于大小写不敏感的匹配
+        assert_eq!(
+            select_device_name("usb microphone", available),
+            Some("usb microphone")
+        );
+        assert_eq!(
+            select_device_name(" macbook pro microphone ", available),
+            Some("MacBook Pro Microphone")
+        );
+        // 非 ASCII 名称同样大小写不敏感
+        assert_eq!(
+            select_device_name("ÄUSSERES MIKROFON", ["Äusseres Mikrofon"]),
+            Some("Äusseres Mikrofon")
+        );
+    }
+
+    #[test]
+    fn test_select_missing_device_falls_back() {
+        let available = ["MacBook Pro Microphone"];
+
+        // 设备已拔出：返回 None，由调用方回退到默认设备
+        assert_eq!(select_device_name("USB Microphone", available), None);
+        assert_eq!(select_device_name("", available), None);
+        assert_eq!(select_device_name("USB Microphone", []), None);
+        // 只包含部分名称不算匹配
+        assert_eq!(select_device_name("MacBook", available), None);
+        assert_eq!(select_device_name("Microphone", available), None);
+    }
+
+    #[test]
+    fn test_list_hosts() {
+        let hosts = AudioCapture::list_hosts();
+        assert!(!hosts.is_empty());
+    }
+
     #[test]
     fn test_sample_rate() {
         let capture = AudioCapture::new().unwrap();
@@ -235,23 +877,13 @@ mod tests {
     fn test_stereo_to_mono_conversion() {
         // 测试立体声到单声道的转换逻辑
         let channels = 2u16;
-        let stereo_data = vec![
+        let stereo_data = [
             1.0, 2.0, // 第一个样本: L=1.0, R=2.0
             3.0, 4.0, // 第二个样本: L=3.0, R=4.0
             5.0, 6.0, // 第三个样本: L=5.0, R=6.0
         ];
 
-        // 模拟转换逻辑
-        let mono_samples = stereo_data.len() / channels as usize;
-        let mut mono_data = Vec::with_capacity(mono_samples);
-
-        for i in 0..mono_samples {
-            let mut sum = 0.0f32;
-            for ch in 0..channels as usize {
-                sum += stereo_data[i * channels as usize + ch];
-            }
-            mono_data.push(sum / channels as f32);
-        }
+        let mono_data = select_channels(&stereo_data, channels, CaptureMode::Mono);
 
         // 验证结果
         assert_eq!(mono_data.len(), 3);
@@ -259,4 +891,55 @@ mod tests {
         assert_eq!(mono_data[1], 3.5); // (3.0 + 4.0) / 2
         assert_eq!(mono_data[2], 5.5); // (5.0 + 6.0) / 2
     }
+
+    #[test]
+    fn test_select_single_channel() {
+        let stereo_data = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+
+        assert_eq!(
+            select_channels(&stereo_data, 2, CaptureMode::LeftOnly),
+            vec![1.0, 3.0, 5.0]
+        );
+        assert_eq!(
+            select_channels(&stereo_data, 2, CaptureMode::RightOnly),
+            vec![2.0, 4.0, 6.0]
+        );
+
+        // 四声道设备：左右声道为前两个声道，混音时平均全部声道
+        let quad = [1.0, 2.0, 3.0, 6.0];
+        assert_eq!(select_channels(&quad, 4, CaptureMode::LeftOnly), vec![1.0]);
+        assert_eq!(select_channels(&quad, 4, CaptureMode::RightOnly), vec![2.0]);
+        assert_eq!(select_channels(&quad, 4, CaptureMode::Mono), vec![3.0]);
+
+        // 单声道设备只有一个声道可取
+        assert_eq!(
+            select_channels(&[0.5, 0.25], 1, CaptureMode::RightOnly),
+            vec![0.5, 0.25]
+        );
+    }
+
+    #[test]
+    fn test_select_stereo_preserves_channels() {
+        let stereo_data = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+        // 不完整的末尾帧被丢弃
+        assert_eq!(
+            select_channels(&stereo_data, 2, CaptureMode::Stereo),
+            vec![1.0, 2.0, 3.0, 4.0]
+        );
+        // 多余声道被丢弃
+        assert_eq!(
+            select_channels(&[1.0, 2.0, 3.0, 4.0], 4, CaptureMode::Stereo),
+            vec![1.0, 2.0]
+        );
+        // 单声道设备复制到两个声道
+        assert_eq!(
+            select_channels(&[0.5, 0.25], 1, CaptureMode::Stereo),
+            vec![0.5, 0.5, 0.25, 0.25]
+        );
+
+        assert_eq!(CaptureMode::default(), CaptureMode::Mono);
+        assert_eq!(CaptureMode::Stereo.channels(), 2);
+        assert_eq!(CaptureMode::LeftOnly.channels(), 1);
+    }
 }
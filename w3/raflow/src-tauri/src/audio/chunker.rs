@@ -0,0 +1,263 @@
+//! 固定块重采样模块
+//!
+//! cpal 回调给出的缓冲区大小会抖动，而重采样器按固定输入块大小创建。
+//! 先把采样累积成固定大小的帧再重采样，重采样器只需创建一次
+
+use super::resampler::{AudioResampler, Quality, ResamplerError};
+use tracing::info;
+
+type Result<T> = std::result::Result<T, ResamplerError>;
+
+/// 重采样输入帧大小（采样点数，48kHz 下为 10ms）
+pub const RESAMPLE_FRAME_SIZE: usize = 480;
+
+/// 固定块累积器
+///
+/// 把任意长度的输入拼接成固定大小的帧，不足一帧的采样留到下次
+#[derive(Debug, Clone)]
+pub struct ChunkAccumulator {
+    frame_size: usize,
+    pending: Vec<f32>,
+}
+
+impl ChunkAccumulator {
+    /// 创建累积器
+    ///
+    /// # Arguments
+    /// * `frame_size` - 输出帧大小（采样点数，至少为 1）
+    pub fn new(frame_size: usize) -> Self {
+        let frame_size = frame_size.max(1);
+        Self {
+            frame_size,
+            pending: Vec::with_capacity(frame_size * 2),
+        }
+    }
+
+    /// 输出帧大小
+    pub fn frame_size(&self) -> usize {
+        self.frame_size
+    }
+
+    /// 尚未凑满一帧的采样数
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// 追加采样，依次对每个凑满的帧调用 `f`
+    pub fn push<E>(
+        &mut self,
+        samples: &[f32],
+        mut f: impl FnMut(&[f32]) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        self.pending.extend_from_slice(samples);
+
+        let full = self.pending.len() / self.frame_size * self.frame_size;
+        let result = self.pending[..full]
+            .chunks_exact(self.frame_size)
+            .try_for_each(&mut f);
+        self.pending.drain(..full);
+
+        result
+    }
+
+    /// 丢弃未凑满一帧的采样
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// 固定块重采样器
+///
+/// 输入先经 `ChunkAccumulator` 累积成 `RESAMPLE_FRAME_SIZE` 大小的帧（多通道时每个通道
+/// `RESAMPLE_FRAME_SIZE` 个采样点），重采样器在第一次需要时按该帧大小创建，之后不再重建
+pub struct FixedChunkResampler {
+    input_rate: u32,
+    output_rate: u32,
+    accumulator: ChunkAccumulator,
+    resampler: Option<AudioResampler>,
+    quality: Quality,
+    channels: usize,
+    builds: usize,
+}
+
+impl FixedChunkResampler {
+    /// 创建重采样器（实际的重采样器延迟到第一帧时创建）
+    ///
+    /// # Arguments
+    /// * `input_rate` - 输入采样率（Hz）
+    /// * `output_rate` - 输出采样率（Hz）
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        Self {
+            input_rate,
+            output_rate,
+            accumulator: ChunkAccumulator::new(RESAMPLE_FRAME_SIZE),
+            resampler: None,
+            quality: Quality::Low,
+            channels: 1,
+            builds: 0,
+        }
+    }
+
+    /// 设置输入通道数（默认单声道），多通道输入为交织格式，输出同样交织
+    pub fn with_channels(mut self, channels: usize) -> Self {
+        let channels = channels.max(1);
+        self.channels = channels;
+        self.accumulator = ChunkAccumulator::new(RESAMPLE_FRAME_SIZE * channels);
+        self
+    }
+
+    /// 设置重采样质量（默认 `Quality::Low`）
+    pub fn with_quality(mut self, quality: Quality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// 重采样任意长度的输入（按 `with_channels` 设置的通道数交织）
+    ///
+    /// 返回本次凑满的帧的重采样结果；输入不足一帧时返回空
+    pub fn process(&mut self, input: &[f32]) -> Result<Vec<f32>> {
+        let Self {
+            input_rate,
+            output_rate,
+            accumulator,
+            resampler,
+            quality,
+            channels,
+            builds,
+        } = self;
+
+        let mut output = Vec::new();
+        accumulator.push(input, |frame| {
+            let resampler = match resampler {
+                Some(resampler) => resampler,
+                None => {
+                    let created = AudioResampler::new(
+                        *input_rate,
+                        *output_rate,
+                        frame.len() / *channels,
+                        *channels,
+                        *quality,
+                    )?;
+                    *builds += 1;
+                    info!(
+                        "Resampler created: {}Hz -> {}Hz, frame {} -> up to {} samples",
+                        input_rate,
+                        output_rate,
+                        frame.len(),
+                        created.expected_output_len()
+                    );
+                    resampler.insert(created)
+                }
+            };
+            output.extend(resampler.process(frame)?);
+            Ok(())
+        })?;
+
+        Ok(output)
+    }
+
+    /// 输入采样率
+    pub fn input_rate(&self) -> u32 {
+        self.input_rate
+    }
+
+    /// 实际使用的质量
+    ///
+    /// 重采样器创建后为其实际质量（`AutoOnce` 已解析），重建时沿用可避免再次试运行
+    pub fn quality(&self) -> Quality {
+        self.resampler
+            .as_ref()
+            .map_or(self.quality, AudioResampler::quality)
+    }
+
+    /// 已创建重采样器的次数（用于诊断）
+    pub fn builds(&self) -> usize {
+        self.builds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accumulator_emits_fixed_frames() {
+        let mut accumulator = ChunkAccumulator::new(4);
+        let mut frames: Vec<Vec<f32>> = Vec::new();
+
+        let samples: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        accumulator
+            .push::<()>(&samples[..3], |frame| {
+                frames.push(frame.to_vec());
+                Ok(())
+            })
+            .unwrap();
+        assert!(frames.is_empty());
+        assert_eq!(accumulator.pending(), 3);
+
+        accumulator
+            .push::<()>(&samples[3..], |frame| {
+                frames.push(frame.to_vec());
+                Ok(())
+            })
+            .unwrap();
+        // 帧按原顺序拼接，剩余 2 个采样留到下次
+        assert_eq!(
+            frames,
+            vec![vec![0.0, 1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0, 7.0]]
+        );
+        assert_eq!(accumulator.pending(), 2);
+
+        accumulator.clear();
+        assert_eq!(accumulator.pending(), 0);
+    }
+
+    #[test]
+    fn test_irregular_chunks_build_resampler_once() {
+        let mut resampler = FixedChunkResampler::new(48000, 16000);
+        let mut total_output = 0;
+
+        for _ in 0..4 {
+            for size in [300, 512, 441] {
+                let input = vec![0.1; size];
+                total_output += resampler.process(&input).unwrap().len();
+            }
+        }
+
+        assert_eq!(resampler.builds(), 1);
+        // 4 * 1253 = 5012 个采样凑满 10 帧，约输出 10 * 160 个采样
+        assert!((1500..=1650).contains(&total_output), "{}", total_output);
+    }
+
+    #[test]
+    fn test_short_input_defers_creation() {
+        let mut resampler = FixedChunkResampler::new(44100, 16000);
+        assert!(resampler.process(&[0.0; 100]).unwrap().is_empty());
+        assert_eq!(resampler.builds(), 0);
+        assert_eq!(resampler.input_rate(), 44100);
+    }
+
+    #[test]
+    fn test_stereo_frames_keep_channels() {
+        let mut resampler = FixedChunkResampler::new(48000, 16000).with_channels(2);
+
+        // 一帧立体声需要 2 * RESAMPLE_FRAME_SIZE 个采样点
+        let first = resampler.process(&[0.0; RESAMPLE_FRAME_SIZE]).unwrap();
+        assert!(first.is_empty());
+        let output = resampler.process(&[0.0; RESAMPLE_FRAME_SIZE]).unwrap();
+
+        assert_eq!(resampler.builds(), 1);
+        assert_eq!(output.len() % 2, 0);
+        assert!(output.len() > RESAMPLE_FRAME_SIZE / 3, "{}", output.len());
+    }
+
+    #[test]
+    fn test_auto_quality_fixed_after_build() {
+        let mut resampler = FixedChunkResampler::new(48000, 16000).with_quality(Quality::AutoOnce);
+        assert_eq!(resampler.quality(), Quality::AutoOnce);
+
+        resampler.process(&[0.0; RESAMPLE_FRAME_SIZE]).unwrap();
+        assert_ne!(resampler.quality(), Quality::AutoOnce);
+        assert_eq!(resampler.builds(), 1);
+    }
+}
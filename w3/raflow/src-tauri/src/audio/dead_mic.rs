@@ -0,0 +1,143 @@
+//! 麦克风无输入检测模块
+//!
+//! 系统层面静音的麦克风仍会正常采集，但样本几乎全为零。
+//! 与静音门限（按 VAD 判断是否有人说话）不同，这里只看原始输入是否有任何信号；
+//! 真实麦克风即使没人说话也有底噪，长时间低于极低阈值说明输入已被静音
+
+use std::time::Duration;
+
+/// 默认判定时长：连续无输入超过该时长才提醒
+pub const DEAD_MIC_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 默认峰值阈值（约 -80 dBFS，远低于任何麦克风的底噪）
+pub const DEAD_MIC_FLOOR: f32 = 1e-4;
+
+/// 无输入状态变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadMicEvent {
+    /// 状态不变
+    None,
+    /// 持续无输入，应提醒用户（每次会话至多一次）
+    Silent,
+    /// 提醒之后重新收到输入，应清除提醒
+    Recovered,
+}
+
+/// 麦克风无输入检测器
+///
+/// 按音频块时长累计连续无输入的时长，不依赖处理速度；
+/// 每个检测器只提醒一次，收到输入后清除提醒但不再重复提醒
+#[derive(Debug)]
+pub struct DeadMicDetector {
+    floor: f32,
+    timeout: Duration,
+    silent_for: Duration,
+    warned: bool,
+    active: bool,
+}
+
+impl DeadMicDetector {
+    /// 创建检测器
+    ///
+    /// # Arguments
+    /// * `floor` - 峰值低于该值的音频块视为无输入
+    /// * `timeout` - 连续无输入多久后提醒
+    pub fn new(floor: f32, timeout: Duration) -> Self {
+        Self {
+            floor,
+            timeout,
+            silent_for: Duration::ZERO,
+            warned: false,
+            active: false,
+        }
+    }
+
+    /// 处理一个原始音频块的峰值
+    ///
+    /// # Arguments
+    /// * `peak` - 该块的峰值（降噪之前）
+    /// * `duration` - 该块的音频时长
+    pub fn update(&mut self, peak: f32, duration: Duration) -> DeadMicEvent {
+        if peak >= self.floor {
+            self.silent_for = Duration::ZERO;
+            if std::mem::take(&mut self.active) {
+                return DeadMicEvent::Recovered;
+            }
+            return DeadMicEvent::None;
+        }
+
+        self.silent_for += duration;
+
+        if !self.warned && self.silent_for >= self.timeout {
+            self.warned = true;
+            self.active = true;
+            return DeadMicEvent::Silent;
+        }
+
+        DeadMicEvent::None
+    }
+
+    /// 当前是否处于无输入提醒状态
+    pub fn is_silent(&self) -> bool {
+        self.active
+    }
+}
+
+impl Default for DeadMicDetector {
+    fn default() -> Self {
+        Self::new(DEAD_MIC_FLOOR, DEAD_MIC_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK: Duration = Duration::from_millis(20);
+
+    #[test]
+    fn test_dead_mic_warns_once_per_session() {
+        let mut detector = DeadMicDetector::default();
+
+        // 连续 3 秒接近零的输入：第 150 块时提醒，之后不再重复
+        let events: Vec<_> = (0..300).map(|_| detector.update(1e-6, CHUNK)).collect();
+        assert_eq!(events[149], DeadMicEvent::Silent);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| **e == DeadMicEvent::Silent)
+                .count(),
+            1
+        );
+        assert!(detector.is_silent());
+
+        // 收到真实输入后清除提醒
+        assert_eq!(detector.update(0.01, CHUNK), DeadMicEvent::Recovered);
+        assert!(!detector.is_silent());
+        assert_eq!(detector.update(0.01, CHUNK), DeadMicEvent::None);
+
+        // 同一会话内再次无输入不重复提醒
+        for _ in 0..300 {
+            assert_eq!(detector.update(0.0, CHUNK), DeadMicEvent::None);
+        }
+    }
+
+    #[test]
+    fn test_noise_floor_resets_timer() {
+        let mut detector = DeadMicDetector::new(DEAD_MIC_FLOOR, Duration::from_millis(100));
+
+        // 间歇出现的底噪使计时重新开始
+        for _ in 0..10 {
+            for _ in 0..4 {
+                assert_eq!(detector.update(0.0, CHUNK), DeadMicEvent::None);
+            }
+            assert_eq!(detector.update(0.002, CHUNK), DeadMicEvent::None);
+        }
+        assert!(!detector.is_silent());
+
+        for _ in 0..4 {
+            detector.update(0.0, CHUNK);
+        }
+        assert_eq!(detector.update(0.0, CHUNK), DeadMicEvent::Silent);
+    }
+}
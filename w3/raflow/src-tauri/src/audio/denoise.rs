@@ -0,0 +1,256 @@
+//! 非 48kHz 设备的降噪流水线
+//!
+//! RNNoise 只接受 48kHz 音频，对于 44.1kHz 等设备需要先重采样到 48kHz，
+//! 按 480 采样点对齐分帧降噪，再将降噪结果重采样到发送采样率（默认 16kHz）
+
+use super::DEFAULT_OUTPUT_SAMPLE_RATE;
+use super::processor::{
+    AudioProcessor, DEFAULT_DENOISE_MIX, NoiseSuppressionLevel, ProcessorError,
+};
+use super::resampler::{AudioResampler, Quality, ResamplerError};
+use thiserror::Error;
+use tracing::debug;
+
+/// RNNoise 要求的采样率
+pub const DENOISE_SAMPLE_RATE: u32 = 48000;
+
+/// 前置重采样器每次处理的输入时长（毫秒），与 RNNoise 的 10ms 帧对齐
+const PRE_RESAMPLER_CHUNK_MS: u32 = 10;
+
+#[derive(Error, Debug)]
+pub enum DenoiseError {
+    #[error(transparent)]
+    Resampler(#[from] ResamplerError),
+
+    #[error(transparent)]
+    Processor(#[from] ProcessorError),
+}
+
+type Result<T> = std::result::Result<T, DenoiseError>;
+
+/// 降噪输出
+#[derive(Debug, Default)]
+pub struct DenoiseOutput {
+    /// 重采样到发送采样率的降噪后音频
+    pub samples: Vec<f32>,
+    /// 本次处理的帧的平均语音概率，没有完整帧时为 None
+    pub vad: Option<f32>,
+}
+
+/// 重采样降噪器
+///
+/// 设备采样率 -> 48kHz -> RNNoise -> 发送采样率（默认 16kHz）
+pub struct ResamplingDenoiser {
+    /// 固定块大小的前置重采样器，创建后不再重建
+    pre_resampler: AudioResampler,
+    post_resampler: AudioResampler,
+    processor: AudioProcessor,
+    /// 尚未凑满前置重采样块的设备采样
+    input_pending: Vec<f32>,
+    /// 尚未凑满一帧的 48kHz 采样
+    pending: Vec<f32>,
+}
+
+impl ResamplingDenoiser {
+    /// 创建重采样降噪器
+    ///
+    /// # Arguments
+    /// * `input_rate` - 设备采样率（Hz）
+    pub fn new(input_rate: u32) -> Result<Self> {
+        Self::with_mix(input_rate, DEFAULT_DENOISE_MIX)
+    }
+
+    /// 创建带干湿混合比例的重采样降噪器
+    ///
+    /// # Arguments
+    /// * `input_rate` - 设备采样率（Hz）
+    /// * `mix` - 降噪结果的比例（0.0 = 原始音频，1.0 = 完全降噪）
+    pub fn with_mix(input_rate: u32, mix: f32) -> Result<Self> {
+        Self::with_level_and_mix(input_rate, NoiseSuppressionLevel::default(), mix)
+    }
+
+    /// 创建指定噪声抑制级别的重采样降噪器
+    ///
+    /// # Arguments
+    /// * `input_rate` - 设备采样率（Hz）
+    /// * `level` - 噪声抑制级别
+    /// * `mix` - 对级别混合比例的缩放（0.0 ~ 1.0）
+    pub fn with_level_and_mix(
+        input_rate: u32,
+        level: NoiseSuppressionLevel,
+        mix: f32,
+    ) -> Result<Self> {
+        let processor = AudioProcessor::with_level_and_mix(level, mix);
+        let post_resampler = Self::post_resampler(&processor, DEFAULT_OUTPUT_SAMPLE_RATE)?;
+
+        let chunk_size = (input_rate * PRE_RESAMPLER_CHUNK_MS / 1000).max(1) as usize;
+        let pre_resampler =
+            AudioResampler::new(input_rate, DENOISE_SAMPLE_RATE, chunk_size, 1, Quality::Low)?;
+        debug!(
+            "Pre-resampler {}Hz -> {}Hz, chunk {}, ratio={:.4}",
+            input_rate,
+            DENOISE_SAMPLE_RATE,
+            chunk_size,
+            pre_resampler.ratio()
+        );
+
+        Ok(Self {
+            pre_resampler,
+            post_resampler,
+            processor,
+            input_pending: Vec::with_capacity(chunk_size * 2),
+            pending: Vec::new(),
+        })
+    }
+
+    /// 设置降噪结果的输出采样率
+    ///
+    /// # Arguments
+    /// * `output_rate` - 发送采样率（Hz）
+    pub fn with_output_rate(mut self, output_rate: u32) -> Result<Self> {
+        self.post_resampler = Self::post_resampler(&self.processor, output_rate)?;
+        Ok(self)
+    }
+
+    /// 创建 48kHz -> 输出采样率的后置重采样器
+    fn post_resampler(processor: &AudioProcessor, output_rate: u32) -> Result<AudioResampler> {
+        Ok(AudioResampler::new(
+            DENOISE_SAMPLE_RATE,
+            output_rate,
+            processor.frame_size(),
+            1,
+            Quality::Low,
+        )?)
+    }
+
+    /// 处理一个设备采样率的单声道音频块
+    ///
+    /// 输入块可以是任意长度：先累积到前置重采样器的固定块大小再重采样，
+    /// 不足一块或一帧的剩余采样会保留到下一次调用
+    pub fn process(&mut self, chunk: &[f32]) -> Result<DenoiseOutput> {
+        if chunk.is_empty() {
+            return Ok(DenoiseOutput::default());
+        }
+
+        self.input_pending.extend_from_slice(chunk);
+        let block = self.pre_resampler.chunk_size();
+        let blocks = self.input_pending.len() / block;
+        for input in self.input_pending[..blocks * block].chunks_exact(block) {
+            let upsampled = self.pre_resampler.process(input)?;
+            self.pending.extend_from_slice(&upsampled);
+        }
+        self.input_pending.drain(..blocks * block);
+
+        let frame_size = self.processor.frame_size();
+        let frames = self.pending.len() / frame_size;
+        let mut output = DenoiseOutput {
+            samples: Vec::with_capacity(frames * frame_size / 3),
+            vad: None,
+        };
+        let mut vad_sum = 0.0f32;
+
+        for frame in self.pending[..frames * frame_size].chunks_exact(frame_size) {
+            let (denoised, vad_prob) = self.processor.process(frame)?;
+            vad_sum += vad_prob;
+
+            let resampled = self.post_resampler.process(&denoised)?;
+            output.samples.extend_from_slice(&resampled);
+        }
+
+        self.pending.drain(..frames * frame_size);
+
+        if frames > 0 {
+            output.vad = Some(vad_sum / frames as f32);
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_44100_chunk_is_denoised_to_16k() {
+        let mut denoiser = ResamplingDenoiser::new(44100).unwrap();
+
+        // 10 个 100ms 的 44.1kHz 块，共 1 秒
+        let chunk: Vec<f32> = (0..4410)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 44100.0).sin())
+            .collect();
+
+        let mut total = 0;
+        let mut vad_seen = false;
+        for _ in 0..10 {
+            let output = denoiser.process(&chunk).unwrap();
+            total += output.samples.len();
+            vad_seen |= output.vad.is_some();
+        }
+
+        assert!(vad_seen, "RNNoise should have processed at least one frame");
+        // 1 秒 @ 16kHz，允许重采样延迟和未凑满帧的剩余采样
+        assert!(
+            total > 16000 - 320 && total <= 16000,
+            "got {} samples",
+            total
+        );
+    }
+
+    #[test]
+    fn test_output_rate_follows_encoding() {
+        let mut denoiser = ResamplingDenoiser::new(44100)
+            .unwrap()
+            .with_output_rate(8000)
+            .unwrap();
+
+        let chunk = vec![0.1f32; 4410];
+        let total: usize = (0..10)
+            .map(|_| denoiser.process(&chunk).unwrap().samples.len())
+            .sum();
+
+        // 1 秒 @ 8kHz
+        assert!(total > 8000 - 160 && total <= 8000, "got {} samples", total);
+    }
+
+    #[test]
+    fn test_partial_frame_is_carried_over() {
+        let mut denoiser = ResamplingDenoiser::new(44100).unwrap();
+
+        // 100 个采样点上采样后不足一帧
+        let output = denoiser.process(&[0.0; 100]).unwrap();
+        assert!(output.samples.is_empty());
+        assert!(output.vad.is_none());
+        assert!(!denoiser.input_pending.is_empty());
+    }
+
+    #[test]
+    fn test_varying_chunk_sizes_share_one_resampler() {
+        let mut denoiser = ResamplingDenoiser::new(44100).unwrap();
+        let chunk_size = denoiser.pre_resampler.chunk_size();
+        assert_eq!(chunk_size, 441);
+
+        // 共 1 秒，块大小不断变化
+        let mut total = 0;
+        let mut fed = 0;
+        for len in [1000, 4410, 333, 2048, 441, 7000, 2877, 5991, 10000, 9999]
+            .into_iter()
+            .cycle()
+        {
+            let len = len.min(44100 - fed);
+            if len == 0 {
+                break;
+            }
+            total += denoiser.process(&vec![0.1f32; len]).unwrap().samples.len();
+            fed += len;
+            assert_eq!(denoiser.pre_resampler.chunk_size(), chunk_size);
+            assert!(denoiser.input_pending.len() < chunk_size);
+        }
+
+        assert!(
+            total > 16000 - 320 && total <= 16000,
+            "got {} samples",
+            total
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! 音频帧模块
+//!
+//! 发送到网络模块的 16kHz 音频附带采集时间，网络阻塞后可以丢弃过旧的音频
+
+use std::time::{Duration, Instant};
+
+/// 一段处理完成的 16kHz 音频
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFrame {
+    /// 16kHz 单声道 PCM 采样
+    pub samples: Vec<i16>,
+    /// 采集时间（消费者任务从缓冲区取出原始音频的时刻）
+    pub captured_at: Instant,
+}
+
+impl AudioFrame {
+    /// 创建音频帧
+    pub fn new(samples: Vec<i16>, captured_at: Instant) -> Self {
+        Self {
+            samples,
+            captured_at,
+        }
+    }
+
+    /// 以当前时间为采集时间创建音频帧
+    pub fn now(samples: Vec<i16>) -> Self {
+        Self::new(samples, Instant::now())
+    }
+
+    /// 距采集的时长
+    pub fn age(&self) -> Duration {
+        self.captured_at.elapsed()
+    }
+
+    /// 是否超过最大帧龄（`max_age` 为零表示不限制）
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        !max_age.is_zero() && self.age() > max_age
+    }
+}
@@ -0,0 +1,141 @@
+//! 静音门限模块
+//!
+//! 决定静音音频块是否继续发送。检测到语音后门限保持打开一段时间，
+//! 词与词之间的短暂停顿不会被截断
+
+use std::time::Duration;
+
+/// 默认保持时间
+pub const DEFAULT_SILENCE_HOLD: Duration = Duration::from_millis(1000);
+
+/// 连续多少个静音块后才允许关闭门限
+const SILENCE_CHUNK_THRESHOLD: usize = 6;
+
+/// 门限状态变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateEvent {
+    /// 门限保持不变
+    None,
+    /// 检测到语音
+    Voice {
+        /// 之前连续的静音块数
+        silent_chunks: usize,
+    },
+    /// 门限刚刚关闭（持续静音）
+    Closed,
+}
+
+/// 带保持时间的静音门限
+///
+/// 连续静音块数达到阈值，且距上次检测到语音的音频时长超过保持时间后才关闭；
+/// 时长按音频块本身的长度累计，不依赖处理速度
+#[derive(Debug)]
+pub struct SilenceGate {
+    hold: Duration,
+    silent_chunks: usize,
+    silent_for: Duration,
+    open: bool,
+}
+
+impl SilenceGate {
+    /// 创建静音门限
+    ///
+    /// # Arguments
+    /// * `hold` - 检测到语音后门限至少保持打开的时长
+    pub fn new(hold: Duration) -> Self {
+        Self {
+            hold,
+            silent_chunks: 0,
+            silent_for: Duration::ZERO,
+            open: true,
+        }
+    }
+
+    /// 处理一个音频块的静音判定
+    ///
+    /// # Arguments
+    /// * `is_silence` - 该块是否被判定为静音
+    /// * `duration` - 该块的音频时长
+    pub fn update(&mut self, is_silence: bool, duration: Duration) -> GateEvent {
+        if !is_silence {
+            let silent_chunks = std::mem::take(&mut self.silent_chunks);
+            self.silent_for = Duration::ZERO;
+            self.open = true;
+            return GateEvent::Voice { silent_chunks };
+        }
+
+        self.silent_chunks += 1;
+        self.silent_for += duration;
+
+        if self.open
+            && self.silent_chunks >= SILENCE_CHUNK_THRESHOLD
+            && self.silent_for >= self.hold
+        {
+            self.open = false;
+            return GateEvent::Closed;
+        }
+
+        GateEvent::None
+    }
+
+    /// 门限是否打开（打开时音频继续发送）
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// 当前连续静音的音频时长
+    pub fn silent_for(&self) -> Duration {
+        self.silent_for
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK: Duration = Duration::from_millis(20);
+
+    #[test]
+    fn test_hold_keeps_gate_open_through_short_pause() {
+        let mut gate = SilenceGate::new(Duration::from_millis(500));
+        gate.update(false, CHUNK);
+
+        // 300ms 的词间停顿：超过静音块阈值但未超过保持时间
+        for _ in 0..15 {
+            assert_eq!(gate.update(true, CHUNK), GateEvent::None);
+            assert!(gate.is_open());
+        }
+
+        // 语音恢复，静音计时重置
+        assert_eq!(
+            gate.update(false, CHUNK),
+            GateEvent::Voice { silent_chunks: 15 }
+        );
+        assert_eq!(gate.silent_for(), Duration::ZERO);
+
+        // 持续静音超过保持时间后关闭
+        let events: Vec<_> = (0..30).map(|_| gate.update(true, CHUNK)).collect();
+        assert_eq!(events[24], GateEvent::Closed);
+        assert_eq!(
+            events.iter().filter(|e| **e == GateEvent::Closed).count(),
+            1
+        );
+        assert!(!gate.is_open());
+
+        // 再次检测到语音时打开
+        gate.update(false, CHUNK);
+        assert!(gate.is_open());
+    }
+
+    #[test]
+    fn test_zero_hold_uses_chunk_threshold() {
+        let mut gate = SilenceGate::new(Duration::ZERO);
+
+        for _ in 0..SILENCE_CHUNK_THRESHOLD - 1 {
+            gate.update(true, CHUNK);
+            assert!(gate.is_open());
+        }
+        assert_eq!(gate.update(true, CHUNK), GateEvent::Closed);
+        assert!(!gate.is_open());
+    }
+}
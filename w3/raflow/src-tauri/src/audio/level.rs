@@ -0,0 +1,227 @@
+//! 音频电平模块
+//!
+//! 计算每个音频块的 RMS/峰值和语音概率，并对发送到前端的电平事件限流
+
+use super::resampler::AudioResampler;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 默认电平事件间隔（约 20Hz）
+pub const LEVEL_EMIT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 语音概率事件间隔（约 15Hz）
+pub const VAD_EMIT_INTERVAL: Duration = Duration::from_millis(66);
+
+/// 伪 VAD 为 0 的 RMS 电平（dBFS）
+const PSEUDO_VAD_FLOOR_DBFS: f32 = -60.0;
+
+/// 伪 VAD 为 1 的 RMS 电平（dBFS）
+const PSEUDO_VAD_CEIL_DBFS: f32 = -20.0;
+
+/// 按 RMS 电平估算语音概率（0.0 - 1.0）
+///
+/// 未降噪时没有 RNNoise 的 VAD 可用，用电平代替，让"正在说话"指示仍能跟随声音变化：
+/// `PSEUDO_VAD_FLOOR_DBFS` 及以下为 0，`PSEUDO_VAD_CEIL_DBFS` 及以上为 1，之间按 dB 线性变化
+pub fn pseudo_vad(rms: f32) -> f32 {
+    if rms.is_nan() || rms <= 0.0 {
+        return 0.0;
+    }
+
+    let dbfs = 20.0 * rms.log10();
+    ((dbfs - PSEUDO_VAD_FLOOR_DBFS) / (PSEUDO_VAD_CEIL_DBFS - PSEUDO_VAD_FLOOR_DBFS))
+        .clamp(0.0, 1.0)
+}
+
+/// 音频电平
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AudioLevel {
+    /// 均方根音量（0.0 - 1.0）
+    pub rms: f32,
+    /// 峰值音量（0.0 - 1.0）
+    pub peak: f32,
+}
+
+impl AudioLevel {
+    /// 计算音频块的电平
+    pub fn from_samples(samples: &[f32]) -> Self {
+        Self {
+            rms: AudioResampler::calculate_rms(samples),
+            peak: AudioResampler::calculate_peak(samples),
+        }
+    }
+
+    /// 打包为 64 位整数：高 32 位为 RMS，低 32 位为峰值（均为 f32 位模式）
+    pub fn pack(self) -> u64 {
+        (u64::from(self.rms.to_bits()) << 32) | u64::from(self.peak.to_bits())
+    }
+
+    /// 从 `pack` 的结果还原
+    pub fn unpack(bits: u64) -> Self {
+        Self {
+            rms: f32::from_bits((bits >> 32) as u32),
+            peak: f32::from_bits(bits as u32),
+        }
+    }
+}
+
+/// 最新电平
+///
+/// 消费者任务每个音频块更新一次，可在任意线程同步读取；
+/// RMS 和峰值打包在同一个原子变量中，读到的总是同一音频块的值
+#[derive(Debug, Default)]
+pub struct LevelMeter {
+    bits: AtomicU64,
+}
+
+impl LevelMeter {
+    /// 创建电平为零的电平表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 更新最新电平
+    pub fn store(&self, level: AudioLevel) {
+        self.bits.store(level.pack(), Ordering::Relaxed);
+    }
+
+    /// 读取最新电平
+    pub fn load(&self) -> AudioLevel {
+        AudioLevel::unpack(self.bits.load(Ordering::Relaxed))
+    }
+
+    /// 重置为零（停止采集后）
+    pub fn reset(&self) {
+        self.bits.store(0, Ordering::Relaxed);
+    }
+}
+
+/// 电平事件限流器
+///
+/// 距离上一次发送不足间隔时丢弃更新
+#[derive(Debug)]
+pub struct LevelThrottle {
+    interval: Duration,
+    last_emit: Option<Instant>,
+}
+
+impl LevelThrottle {
+    /// 创建限流器
+    ///
+    /// # Arguments
+    /// * `interval` - 两次发送之间的最小间隔
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_emit: None,
+        }
+    }
+
+    /// 检查当前是否应该发送，应发送时记录发送时间
+    pub fn should_emit(&mut self, now: Instant) -> bool {
+        if let Some(last) = self.last_emit
+            && now.saturating_duration_since(last) < self.interval
+        {
+            return false;
+        }
+
+        self.last_emit = Some(now);
+        true
+    }
+}
+
+impl Default for LevelThrottle {
+    fn default() -> Self {
+        Self::new(LEVEL_EMIT_INTERVAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_from_samples() {
+        let level = AudioLevel::from_samples(&[0.5, -0.5, 0.5, -0.5]);
+        assert!((level.rms - 0.5).abs() < 1e-6);
+        assert_eq!(level.peak, 0.5);
+
+        let silence = AudioLevel::from_samples(&[]);
+        assert_eq!(silence.rms, 0.0);
+        assert_eq!(silence.peak, 0.0);
+    }
+
+    #[test]
+    fn test_level_pack_round_trip() {
+        let level = |rms, peak| AudioLevel { rms, peak };
+
+        for level in [
+            level(0.0, 0.0),
+            level(0.123_456_79, 0.987_654_3),
+            level(1.0, f32::MIN_POSITIVE),
+        ] {
+            assert_eq!(AudioLevel::unpack(level.pack()), level);
+        }
+
+        let meter = LevelMeter::new();
+        assert_eq!(meter.load(), level(0.0, 0.0));
+        meter.store(level(0.25, 0.75));
+        assert_eq!(meter.load(), level(0.25, 0.75));
+        meter.reset();
+        assert_eq!(meter.load(), level(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_pseudo_vad_in_unit_range() {
+        assert_eq!(pseudo_vad(0.0), 0.0);
+        assert_eq!(pseudo_vad(f32::NAN), 0.0);
+        assert_eq!(pseudo_vad(-0.5), 0.0);
+        assert_eq!(pseudo_vad(f32::INFINITY), 1.0);
+
+        // -60 dBFS 及以下为 0，-20 dBFS 及以上为 1，-40 dBFS 居中
+        assert_eq!(pseudo_vad(0.0001), 0.0);
+        assert_eq!(pseudo_vad(0.5), 1.0);
+        assert!((pseudo_vad(0.01) - 0.5).abs() < 1e-4);
+
+        // 电平越高估算的语音概率越高，且始终在 [0, 1] 内
+        let mut last = 0.0;
+        for i in 0..=1000 {
+            let vad = pseudo_vad(i as f32 / 1000.0);
+            assert!((0.0..=1.0).contains(&vad), "{}", vad);
+            assert!(vad >= last);
+            last = vad;
+        }
+    }
+
+    #[test]
+    fn test_throttle_first_update_emits() {
+        let mut throttle = LevelThrottle::default();
+        assert!(throttle.should_emit(Instant::now()));
+    }
+
+    #[test]
+    fn test_throttle_drops_updates_within_interval() {
+        let mut throttle = LevelThrottle::new(Duration::from_millis(50));
+        let start = Instant::now();
+
+        assert!(throttle.should_emit(start));
+        assert!(!throttle.should_emit(start + Duration::from_millis(10)));
+        assert!(!throttle.should_emit(start + Duration::from_millis(49)));
+        assert!(throttle.should_emit(start + Duration::from_millis(50)));
+        assert!(!throttle.should_emit(start + Duration::from_millis(70)));
+        assert!(throttle.should_emit(start + Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn test_throttle_rate_is_about_20hz() {
+        let mut throttle = LevelThrottle::default();
+        let start = Instant::now();
+
+        // 1 秒内每 10ms 一个音频块
+        let emitted = (0..100)
+            .filter(|i| throttle.should_emit(start + Duration::from_millis(i * 10)))
+            .count();
+
+        assert_eq!(emitted, 20);
+    }
+}
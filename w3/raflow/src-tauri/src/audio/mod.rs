@@ -2,18 +2,117 @@
 //!
 //! 包含音频采集、缓冲、重采样、噪声抑制等功能
 
+mod agc;
 mod buffer;
 mod capture;
+mod chunker;
+mod dead_mic;
+mod denoise;
+mod frame;
+mod gate;
+mod level;
+mod preroll;
 mod processor;
+mod rate;
+mod recovery;
 mod resampler;
+mod wav;
+
+pub use agc::{AgcConfig, AutomaticGainControl, DEFAULT_AGC_MAX_GAIN, DEFAULT_AGC_TARGET_DBFS};
+pub use buffer::{BufferStats, CapturedChunk, RingBuffer};
+pub use capture::{
+    AudioCapture, AudioDeviceInfo, CaptureError, CaptureMode, InputAvailability, select_channels,
+    select_device_name,
+};
+pub use chunker::{ChunkAccumulator, FixedChunkResampler, RESAMPLE_FRAME_SIZE};
+pub use dead_mic::{DEAD_MIC_FLOOR, DEAD_MIC_TIMEOUT, DeadMicDetector, DeadMicEvent};
+pub use denoise::{DenoiseError, DenoiseOutput, ResamplingDenoiser};
+pub use frame::AudioFrame;
+pub use gate::{DEFAULT_SILENCE_HOLD, GateEvent, SilenceGate};
+pub use level::{
+    AudioLevel, LEVEL_EMIT_INTERVAL, LevelMeter, LevelThrottle, VAD_EMIT_INTERVAL, pseudo_vad,
+};
+pub use preroll::PreRollBuffer;
+pub use processor::{
+    AudioProcessor, AudioProcessorConfig, DEFAULT_DENOISE_MIX, MAX_RING_CHUNK_SIZE,
+    MIN_RING_CAPACITY, MIN_RING_CHUNK_SIZE, NOISE_SUPPRESSION_COMPILED, NoiseSuppressionInfo,
+    NoiseSuppressionLevel, ProcessorError,
+};
+pub use rate::{RateChange, SampleRateMonitor};
+pub use recovery::{MAX_DEVICE_SWITCHES, RecoveryAction, StreamFault, recovery_action};
+pub use resampler::{
+    AudioResampler, CALIBRATION_FRAMES, Quality, REALTIME_BUDGET_FRACTION, ResamplerError,
+    select_quality,
+};
+pub use wav::{WAV_SAMPLE_RATE, WavRecorder, WavWriter};
+
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tracing::{trace, debug, error, info, warn};
+
+/// 环形缓冲区块数（约 4 秒缓冲）
+pub const RING_BUFFER_CHUNKS: usize = 200;
+
+/// 环形缓冲区每块最大帧数
+pub const RING_BUFFER_CHUNK_FRAMES: usize = 2048;
+
+/// 默认发送采样率（与默认编码 `pcm_16000` 一致）
+pub const DEFAULT_OUTPUT_SAMPLE_RATE: u32 = 16000;
+
+/// 缓冲区统计更新间隔
+const BUFFER_STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 音频管理器配置
+#[derive(Debug, Clone)]
+pub struct AudioManagerConfig {
+    /// 音频主机名称（如 WASAPI、ASIO），None 表示使用默认主机
+    pub audio_host: Option<String>,
+    /// 输入设备名称，None 表示使用默认设备
+    pub input_device: Option<String>,
+    /// 是否启用噪声抑制
+    pub enable_noise_suppression: bool,
+    /// 噪声抑制级别
+    pub noise_suppression_level: NoiseSuppressionLevel,
+    /// 对噪声抑制级别混合比例的缩放（0.0 = 原始音频，1.0 = 按级别降噪）
+    pub denoise_mix: f32,
+    /// 降噪之后的处理配置（自动增益等）
+    pub processor: AudioProcessorConfig,
+    /// 检测到语音后静音门限至少保持打开的时长，避免词间停顿被截断
+    pub silence_hold: Duration,
+    /// 重采样质量（`AutoOnce` 时首次重采样前试运行选择，本次录音内固定）
+    pub resampler_quality: Quality,
+    /// 输出采样率（Hz），须与网络发送的编码格式一致
+    pub output_sample_rate: u32,
+    /// 多声道输入的采集方式；立体声保留到重采样之后，发送前再混为单声道
+    pub capture_mode: CaptureMode,
+}
 
-pub use buffer::RingBuffer;
-pub use capture::{AudioCapture, CaptureError};
-pub use processor::{AudioProcessor, AudioProcessorConfig, NoiseSuppressionLevel, ProcessorError};
-pub use resampler::{AudioResampler, Quality, ResamplerError};
+impl Default for AudioManagerConfig {
+    fn default() -> Self {
+        Self {
+            audio_host: None,
+            input_device: None,
+            enable_noise_suppression: true,
+            noise_suppression_level: NoiseSuppressionLevel::default(),
+            denoise_mix: DEFAULT_DENOISE_MIX,
+            processor: AudioProcessorConfig::default(),
+            silence_hold: DEFAULT_SILENCE_HOLD,
+            resampler_quality: Quality::Low,
+            output_sample_rate: DEFAULT_OUTPUT_SAMPLE_RATE,
+            capture_mode: CaptureMode::default(),
+        }
+    }
+}
 
-use tokio::sync::mpsc;
-use tracing::{trace, debug, error, info};
+/// 音频处理流水线意外停止的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStop {
+    /// 到网络模块的输出通道已关闭
+    OutputClosed,
+}
 
 /// 音频管理器
 ///
@@ -21,9 +120,34 @@ use tracing::{trace, debug, error, info};
 pub struct AudioManager {
     capture: AudioCapture,
     buffer: RingBuffer,
-    output_tx: mpsc::Sender<Vec<i16>>,
-    enable_noise_suppression: bool,
-    noise_suppression_level: NoiseSuppressionLevel,
+    output_tx: mpsc::Sender<AudioFrame>,
+    config: AudioManagerConfig,
+    /// 最近一次检测到语音的时间
+    voice_tx: watch::Sender<Instant>,
+    /// 停止信号，消费者任务排空缓冲区后退出
+    stop_tx: watch::Sender<bool>,
+    /// 音频电平发送端（有界，满时丢弃更新）
+    level_tx: mpsc::Sender<AudioLevel>,
+    /// 音频电平接收端，由调用方取走
+    level_rx: Option<mpsc::Receiver<AudioLevel>>,
+    /// 最新电平，可同步读取
+    level_meter: Arc<LevelMeter>,
+    /// 每个音频块的平均语音概率（0.0 - 1.0）
+    vad_tx: watch::Sender<f32>,
+    /// 缓冲区统计（消费者任务定期更新）
+    stats_tx: watch::Sender<BufferStats>,
+    /// 消费者任务是否创建了降噪器
+    noise_suppression_tx: watch::Sender<bool>,
+    /// 麦克风是否持续无输入（系统层面静音等）
+    mic_silent_tx: watch::Sender<bool>,
+    /// 采集流的实际采样率（启动或恢复采集后更新）
+    rate_tx: watch::Sender<u32>,
+    /// 消费者任务意外退出时发布停止原因（正常停止时保持 None）
+    pipeline_tx: watch::Sender<Option<PipelineStop>>,
+    /// 采集流故障发送端（交给采集器的错误回调）
+    fault_tx: mpsc::Sender<StreamFault>,
+    /// 采集流故障接收端，由调用方取走
+    fault_rx: Option<mpsc::Receiver<StreamFault>>,
 }
 
 impl AudioManager {
@@ -44,13 +168,13 @@ impl AudioManager {
     ///
     ///     // 接收处理后的音频数据
     ///     tokio::spawn(async move {
-    ///         while let Some(audio_data) = rx.recv().await {
-    ///             println!("Received {} samples", audio_data.len());
+    ///         while let Some(frame) = rx.recv().await {
+    ///             println!("Received {} samples", frame.samples.len());
     ///         }
     ///     });
     /// }
     /// ```
-    pub fn new(output_tx: mpsc::Sender<Vec<i16>>) -> Result<Self, CaptureError> {
+    pub fn new(output_tx: mpsc::Sender<AudioFrame>) -> Result<Self, CaptureError> {
         // 默认启用降噪（但会根据采样率自动决定是否实际使用）
         Self::with_noise_suppression(output_tx, true, NoiseSuppressionLevel::default())
     }
@@ -62,56 +186,200 @@ impl AudioManager {
     /// * `enable_noise_suppression` - 是否启用噪声抑制
     /// * `noise_suppression_level` - 噪声抑制级别
     pub fn with_noise_suppression(
-        output_tx: mpsc::Sender<Vec<i16>>,
+        output_tx: mpsc::Sender<AudioFrame>,
         enable_noise_suppression: bool,
         noise_suppression_level: NoiseSuppressionLevel,
     ) -> Result<Self, CaptureError> {
-        let capture = AudioCapture::new()?;
+        Self::with_config(
+            output_tx,
+            AudioManagerConfig {
+                enable_noise_suppression,
+                noise_suppression_level,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// 使用自定义配置创建音频管理器
+    ///
+    /// # Arguments
+    /// * `output_tx` - 用于发送处理后音频数据的通道
+    /// * `config` - 音频管理器配置
+    pub fn with_config(
+        output_tx: mpsc::Sender<AudioFrame>,
+        config: AudioManagerConfig,
+    ) -> Result<Self, CaptureError> {
+        let capture = AudioCapture::with_host_and_device(
+            config.audio_host.as_deref(),
+            config.input_device.as_deref(),
+        )?;
+
+        Ok(Self::with_capture(output_tx, config, capture))
+    }
+
+    /// 使用已创建的音频采集器创建音频管理器
+    ///
+    /// 采集器可能已在预录（见 `AudioCapture::start_pre_roll`），
+    /// 启动时预录音频会先进入处理流水线
+    ///
+    /// # Arguments
+    /// * `output_tx` - 用于发送处理后音频数据的通道
+    /// * `config` - 音频管理器配置（其中的主机和设备设置不再生效）
+    /// * `capture` - 音频采集器
+    pub fn with_capture(
+        output_tx: mpsc::Sender<AudioFrame>,
+        mut config: AudioManagerConfig,
+        mut capture: AudioCapture,
+    ) -> Self {
         let sample_rate = capture.sample_rate();
 
         info!("Device sample rate: {}Hz", sample_rate);
         info!(
-            "Noise suppression: enabled={}, level={:?}",
-            enable_noise_suppression, noise_suppression_level
+            "Noise suppression: enabled={}, level={:?}, mix={}",
+            config.enable_noise_suppression, config.noise_suppression_level, config.denoise_mix
+        );
+        info!("Automatic gain control: {:?}", config.processor.agc);
+
+        // 创建环形缓冲区，大小无效时使用默认值
+        if let Err(e) = config.processor.validate() {
+            warn!("{}, using default ring buffer size", e);
+            config.processor.ring_capacity = RING_BUFFER_CHUNKS;
+            config.processor.ring_chunk_size = RING_BUFFER_CHUNK_FRAMES;
+        }
+        info!(
+            "Ring buffer: {} chunks x {} frames",
+            config.processor.ring_capacity, config.processor.ring_chunk_size
+        );
+        let buffer = RingBuffer::new(
+            config.processor.ring_capacity,
+            config.processor.ring_chunk_size,
         );
 
-        // 创建环形缓冲区：200 个块（约 4 秒缓冲），每块最大 2048 帧
-        let buffer = RingBuffer::new(200, 2048);
-
-        Ok(Self {
+        let (voice_tx, _) = watch::channel(Instant::now());
+        let (stop_tx, _) = watch::channel(false);
+        let (level_tx, level_rx) = mpsc::channel(16);
+        let (vad_tx, _) = watch::channel(0.0);
+        let (stats_tx, _) = watch::channel(buffer.stats());
+        let (noise_suppression_tx, _) = watch::channel(false);
+        let (mic_silent_tx, _) = watch::channel(false);
+        let (rate_tx, _) = watch::channel(sample_rate);
+        let (pipeline_tx, _) = watch::channel(None);
+        let (fault_tx, fault_rx) = mpsc::channel(8);
+        capture.set_fault_signal(fault_tx.clone());
+        capture.set_capture_mode(config.capture_mode);
+
+        Self {
             capture,
             buffer,
             output_tx,
-            enable_noise_suppression,
-            noise_suppression_level,
-        })
+            config,
+            voice_tx,
+            stop_tx,
+            level_tx,
+            level_rx: Some(level_rx),
+            level_meter: Arc::new(LevelMeter::new()),
+            vad_tx,
+            stats_tx,
+            noise_suppression_tx,
+            mic_silent_tx,
+            rate_tx,
+            pipeline_tx,
+            fault_tx,
+            fault_rx: Some(fault_rx),
+        }
     }
 
     /// 启动音频处理
     ///
     /// 启动音频采集并创建消费者任务处理音频数据
-    pub fn start(&mut self) -> Result<(), CaptureError> {
-        let buffer = self.buffer.clone();
+    pub async fn start(&mut self) -> Result<(), CaptureError> {
+        // 启动音频采集（设备无数据时可能回退到其他配置，采样率以启动后为准）
+        self.start_capture().await?;
+
         let sample_rate = self.capture.sample_rate();
+        info!("Audio capture started at {}Hz", sample_rate);
+        self.publish_sample_rate();
 
-        info!("Starting audio capture at {}Hz", sample_rate);
+        // 启动消费者任务
+        self.spawn_consumer_task(self.config.enable_noise_suppression, self.config.noise_suppression_level, self.config.denoise_mix, &self.config.processor);
 
-        // 启动音频采集
-        self.capture.start(move |data| {
-            if !buffer.push(data) {
-                debug!("Audio buffer full, dropping samples");
-            }
-        })?;
+        Ok(())
+    }
 
-        // 启动消费者任务
-        self.spawn_consumer_task(sample_rate, self.enable_noise_suppression, self.noise_suppression_level);
+    /// 暂停音频采集
+    ///
+    /// 只停止采集流，消费者任务和输出通道保持不变，已缓冲的音频会继续发送
+    pub fn pause(&mut self) {
+        self.capture.stop();
+        info!("Audio capture paused");
+    }
 
+    /// 恢复音频采集
+    ///
+    /// 重新启动采集流，音频继续发送到同一个输出通道
+    pub async fn resume(&mut self) -> Result<(), CaptureError> {
+        self.start_capture().await?;
+        info!("Audio capture resumed");
+        self.publish_sample_rate();
         Ok(())
     }
 
+    /// 切换到默认输入设备
+    ///
+    /// 当前设备故障（如被拔出）时调用：在默认设备上重新打开采集流，
+    /// 消费者任务和输出通道保持不变；采样率不同时消费者任务会重建处理流水线
+    ///
+    /// # Returns
+    /// 新的输入设备名称
+    pub async fn switch_to_default_device(&mut self) -> Result<String, CaptureError> {
+        self.capture.stop();
+
+        let mut capture = AudioCapture::with_host(self.config.audio_host.as_deref())?;
+        capture.set_fault_signal(self.fault_tx.clone());
+        capture.set_capture_mode(self.config.capture_mode);
+        self.capture = capture;
+
+        self.start_capture().await?;
+        self.publish_sample_rate();
+
+        let device = self.capture.device_name();
+        info!("Audio capture switched to default device: {}", device);
+        Ok(device)
+    }
+
+    /// 发布采集流的实际采样率
+    ///
+    /// 重新打开采集流时可能回退到其他采样率，变化时消费者任务据此重建处理流水线
+    fn publish_sample_rate(&self) {
+        let rate = self.capture.sample_rate();
+        let changed = self.rate_tx.send_if_modified(|current| {
+            let changed = *current != rate;
+            *current = rate;
+            changed
+        });
+        if changed {
+            warn!("Capture sample rate changed to {}Hz", rate);
+        }
+    }
+
+    /// 启动采集流，将采集到的音频写入缓冲区
+    async fn start_capture(&mut self) -> Result<(), CaptureError> {
+        let buffer = self.buffer.clone();
+
+        self.capture
+            .start(move |data| {
+                // 在采集回调中记录时间，排队等待不计入采集时刻
+                if !buffer.push(data, Instant::now()) {
+                    debug!("Audio buffer full, dropping samples");
+                }
+            })
+            .await
+    }
+
     /// 停止音频处理
     pub fn stop(&mut self) {
         self.capture.stop();
+        self.stop_tx.send_replace(true);
         info!("Audio capture stopped");
     }
 
@@ -120,75 +388,223 @@ impl AudioManager {
         self.capture.sample_rate()
     }
 
+    /// 订阅语音活动
+    ///
+    /// 每当音频块被判定为非静音时更新为当前时间
+    pub fn voice_activity(&self) -> watch::Receiver<Instant> {
+        self.voice_tx.subscribe()
+    }
+
+    /// 取走音频电平接收端
+    ///
+    /// 每个音频块产生一次 RMS/峰值更新，接收端处理不及时时更新会被丢弃；
+    /// 只能取走一次
+    pub fn take_level_receiver(&mut self) -> Option<mpsc::Receiver<AudioLevel>> {
+        self.level_rx.take()
+    }
+
+    /// 取走采集流故障接收端
+    ///
+    /// 采集设备被拔出等故障时收到 `StreamFault`，由调用方决定切换设备还是停止；
+    /// 只能取走一次
+    pub fn take_fault_receiver(&mut self) -> Option<mpsc::Receiver<StreamFault>> {
+        self.fault_rx.take()
+    }
+
+    /// 使用外部的电平表（如应用状态中长期存在的电平表），需在 `start` 之前设置
+    pub fn with_level_meter(mut self, level_meter: Arc<LevelMeter>) -> Self {
+        self.level_meter = level_meter;
+        self
+    }
+
+    /// 最新的音频电平（未在采集时为零）
+    pub fn current_level(&self) -> AudioLevel {
+        self.level_meter.load()
+    }
+
+    /// 订阅语音概率
+    ///
+    /// 每个音频块更新一次：降噪时为 RNNoise VAD 的平均值，未降噪时按电平估算（见 `pseudo_vad`）；
+    /// 音频处理停止后归零
+    pub fn voice_probability(&self) -> watch::Receiver<f32> {
+        self.vad_tx.subscribe()
+    }
+
     /// 获取缓冲区状态
-    pub fn buffer_status(&self) -> (usize, usize) {
-        (self.buffer.len(), self.buffer.capacity())
+    pub fn buffer_status(&self) -> BufferStats {
+        self.buffer.stats()
+    }
+
+    /// 订阅缓冲区统计
+    ///
+    /// 音频处理运行期间约每秒更新一次
+    pub fn buffer_stats(&self) -> watch::Receiver<BufferStats> {
+        self.stats_tx.subscribe()
+    }
+
+    /// 订阅采集采样率变化
+    ///
+    /// 只在采样率确实变化时通知
+    pub fn sample_rate_changes(&self) -> watch::Receiver<u32> {
+        self.rate_tx.subscribe()
+    }
+
+    /// 订阅噪声抑制是否生效
+    ///
+    /// 消费者任务初始化降噪器后更新，音频处理停止后发送端关闭
+    pub fn noise_suppression_active(&self) -> watch::Receiver<bool> {
+        self.noise_suppression_tx.subscribe()
+    }
+
+    /// 订阅麦克风无输入状态
+    ///
+    /// 每次录音至多变为 true 一次，之后收到输入时恢复为 false
+    pub fn mic_silent(&self) -> watch::Receiver<bool> {
+        self.mic_silent_tx.subscribe()
+    }
+
+    /// 订阅音频流水线意外停止信号
+    ///
+    /// 消费者任务因输出通道关闭而退出时发布停止原因；调用 `stop` 正常停止时不发布
+    pub fn pipeline_stopped(&self) -> watch::Receiver<Option<PipelineStop>> {
+        self.pipeline_tx.subscribe()
     }
 
     /// 生成消费者任务
     ///
-    /// 从缓冲区读取音频数据，进行重采样、噪声抑制和量化，然后发送到输出通道
-    fn spawn_consumer_task(&self, sample_rate: u32, enable_noise_suppression: bool, _noise_level: NoiseSuppressionLevel) {
+    /// 从缓冲区读取音频数据，进行噪声抑制、自动增益、重采样和量化，然后发送到输出通道
+    fn spawn_consumer_task(&self, enable_noise_suppression: bool, noise_level: NoiseSuppressionLevel, denoise_mix: f32, processor_config: &AudioProcessorConfig) {
         let buffer = self.buffer.clone();
         let output_tx = self.output_tx.clone();
+        let voice_tx = self.voice_tx.clone();
+        let stop_rx = self.stop_tx.subscribe();
+        let silence_hold = self.config.silence_hold;
+        let resampler_quality = self.config.resampler_quality;
+        let output_rate = self.config.output_sample_rate;
+        // 缓冲区中的音频按采集方式交织，声道数不随设备切换变化
+        let channels = usize::from(self.config.capture_mode.channels());
+        // RNNoise 只处理单声道，保留立体声时不降噪
+        let enable_noise_suppression = if channels > 1 && enable_noise_suppression {
+            info!("Noise suppression disabled for {}-channel capture", channels);
+            false
+        } else {
+            enable_noise_suppression
+        };
+        let level_tx = self.level_tx.clone();
+        let level_meter = self.level_meter.clone();
+        let vad_tx = self.vad_tx.clone();
+        let stats_tx = self.stats_tx.clone();
+        let noise_suppression_tx = self.noise_suppression_tx.clone();
+        let mic_silent_tx = self.mic_silent_tx.clone();
+        let mut rate_monitor = SampleRateMonitor::new(self.rate_tx.subscribe());
+        let pipeline_tx = self.pipeline_tx.clone();
+        pipeline_tx.send_replace(None);
+        let mut agc = processor_config
+            .agc
+            .enabled
+            .then(|| AutomaticGainControl::new(&processor_config.agc));
+        // 录音文件在写入线程中写入；任务结束时等待写入线程回填文件头
+        // 立体声采集按双声道录制混音前的音频
+        let recorder = processor_config
+            .record_to_file
+            .as_deref()
+            .and_then(|path| match WavRecorder::spawn(path, output_rate, channels as u16) {
+                Ok(recorder) => {
+                    info!("Recording audio to {}", path.display());
+                    Some(recorder)
+                }
+                Err(e) => {
+                    error!("Failed to create recording file {}: {}", path.display(), e);
+                    None
+                }
+            });
 
         tokio::spawn(async move {
             info!("Audio consumer task started");
 
-            // 使用 Low 质量（最快初始化，够用）
-            let mut resampler: Option<AudioResampler> = None;
-            let mut last_chunk_size = 0usize;
-
             // 创建噪声抑制处理器（如果启用）
-            // 注意：RNNoise 严格要求 48kHz 采样率，音频已在 AudioCapture 中转换为单声道
-            let mut noise_processor: Option<AudioProcessor> = if enable_noise_suppression {
-                // 检查设备采样率
-                if sample_rate == 48000 {
-                    info!("Noise suppression processor initialized (48kHz, mono)");
-                    Some(AudioProcessor::new())
-                } else {
-                    info!("Noise suppression disabled: device sample rate is {}Hz, RNNoise requires 48kHz", sample_rate);
-                    None
-                }
-            } else {
-                info!("Noise suppression disabled by configuration");
-                None
-            };
+            let mut sample_rate = rate_monitor.current();
 
-            // 静音检测状态
-            let mut silence_chunks = 0usize; // 连续静音的块数
-            let silence_threshold = 6; // 连续 6 个块（约 3 秒）认为是持续静音，避免吞掉尾音
+            // 输入累积成固定帧后再重采样，块大小抖动时无需重建重采样器
+            let mut resampler = FixedChunkResampler::new(sample_rate, output_rate).with_quality(resampler_quality).with_channels(channels);
+            let (mut noise_processor, mut resampling_denoiser) =
+                init_noise_suppression(sample_rate, output_rate, enable_noise_suppression, noise_level, denoise_mix);
+            noise_suppression_tx.send_replace(noise_processor.is_some() || resampling_denoiser.is_some());
+
+            // 静音门限：检测到语音后保持打开，持续静音后才停止发送，避免吞掉词间停顿和尾音
+            let mut gate = SilenceGate::new(silence_hold);
+            // 无输入检测：看原始输入，与静音门限无关
+            let mut dead_mic = DeadMicDetector::default();
+
+            let mut last_stats = Instant::now();
 
             loop {
-                if let Some(audio_chunk) = buffer.pop() {
+                // 定期发布缓冲区统计
+                let now = Instant::now();
+                if now.duration_since(last_stats) >= BUFFER_STATS_INTERVAL {
+                    stats_tx.send_replace(buffer.stats());
+                    last_stats = now;
+                }
+
+                if let Some(CapturedChunk { samples: audio_chunk, captured_at }) = buffer.pop() {
                     let chunk_len = audio_chunk.len();
 
-                    // 只在块大小变化时重新创建
-                    if chunk_len != last_chunk_size {
-                        info!("Creating resampler for chunk size: {} (was: {})", chunk_len, last_chunk_size);
+                    // 采集采样率变化：按新采样率重建重采样器和降噪器
+                    if let Some(change) = rate_monitor.poll() {
+                        warn!("Rebuilding audio pipeline for sample rate change: {}Hz -> {}Hz", change.from, change.to);
+                        sample_rate = change.to;
+                        // 沿用已选出的质量，不再重新试运行
+                        resampler = FixedChunkResampler::new(sample_rate, output_rate).with_quality(resampler.quality()).with_channels(channels);
+                        (noise_processor, resampling_denoiser) =
+                            init_noise_suppression(sample_rate, output_rate, enable_noise_suppression, noise_level, denoise_mix);
+                        noise_suppression_tx.send_replace(noise_processor.is_some() || resampling_denoiser.is_some());
+                    }
 
-                        let start = std::time::Instant::now();
+                    // 发送电平（不阻塞音频处理，通道满时丢弃）
+                    let level = AudioLevel::from_samples(&audio_chunk);
+                    level_meter.store(level);
+                    let _ = level_tx.try_send(level);
 
-                        resampler = match AudioResampler::new(sample_rate, 16000, chunk_len, 1, Quality::Low)
-                        {
-                            Ok(r) => {
-                                last_chunk_size = chunk_len;
-                                info!("Resampler created in {:?}", start.elapsed());
-                                Some(r)
-                            },
-                            Err(e) => {
-                                error!("Failed to create resampler: {}", e);
-                                buffer.recycle(audio_chunk);
-                                continue;
-                            }
-                        };
+                    let chunk_duration = Duration::from_secs_f64((chunk_len / channels) as f64 / sample_rate.max(1) as f64);
+                    match dead_mic.update(level.peak, chunk_duration) {
+                        DeadMicEvent::Silent => {
+                            warn!("No input from microphone for {:?}, it may be muted", DEAD_MIC_TIMEOUT);
+                            mic_silent_tx.send_replace(true);
+                        }
+                        DeadMicEvent::Recovered => {
+                            info!("Microphone input resumed");
+                            mic_silent_tx.send_replace(false);
+                        }
+                        DeadMicEvent::None => {}
                     }
 
                     // 应用噪声抑制（在重采样前，因为 RNNoise 需要 48kHz）
                     let mut processed_chunk = audio_chunk.clone();
                     let mut is_silence = false;
-
-                    if let Some(ref mut processor) = noise_processor {
+                    // 降噪器给出的平均语音概率（未降噪时为 None）
+                    let mut vad: Option<f32> = None;
+                    // 重采样降噪路径已直接输出发送采样率的音频
+                    let mut denoised_output: Option<Vec<f32>> = None;
+
+                    if let Some(ref mut denoiser) = resampling_denoiser {
+                        match denoiser.process(&audio_chunk) {
+                            Ok(output) => {
+                                vad = output.vad;
+                                if let Some(avg_vad) = output.vad {
+                                    let energy: f32 = output.samples.iter().map(|&x| x * x).sum::<f32>() / output.samples.len().max(1) as f32;
+                                    is_silence = avg_vad < 0.05 && energy < 0.00005;
+
+                                    if is_silence {
+                                        trace!("Silence detected: VAD={:.3}, Energy={:.6}", avg_vad, energy);
+                                    }
+                                }
+                                denoised_output = Some(output.samples);
+                            }
+                            Err(e) => {
+                                error!("Noise suppression error: {}", e);
+                            }
+                        }
+                    } else if let Some(ref mut processor) = noise_processor {
                         let frame_size = processor.frame_size();
                         let mut temp_output = Vec::with_capacity(audio_chunk.len());
                         let mut vad_sum = 0.0f32;
@@ -217,6 +633,7 @@ impl AudioManager {
                         // 静音检测：VAD + 能量双重检测
                         if vad_count > 0 {
                             let avg_vad = vad_sum / vad_count as f32;
+                            vad = Some(avg_vad);
                             let energy: f32 = processed_chunk.iter().map(|&x| x * x).sum::<f32>() / processed_chunk.len() as f32;
 
                             // 静音判断：VAD < 0.05 且 能量 < 0.00005（更宽松的阈值，避免吞字）
@@ -232,57 +649,169 @@ impl AudioManager {
                         is_silence = energy < 0.00005;
                     }
 
-                    // 更新静音计数器
-                    if is_silence {
-                        silence_chunks += 1;
-                        if silence_chunks == silence_threshold {
-                            info!("Continuous silence detected ({} chunks, ~3s), will stop sending if continues", silence_chunks);
+                    // 发布语音概率（未降噪时按电平估算，指示仍能跟随声音）
+                    vad_tx.send_replace(vad.unwrap_or_else(|| pseudo_vad(level.rms)));
+
+                    // 更新静音门限
+                    match gate.update(is_silence, chunk_duration) {
+                        GateEvent::Closed => {
+                            info!("Continuous silence detected ({:?}), will stop sending if continues", gate.silent_for());
                         }
-                    } else {
-                        if silence_chunks > 0 {
-                            debug!("Voice detected, resetting silence counter (was {})", silence_chunks);
+                        GateEvent::Voice { silent_chunks } => {
+                            if silent_chunks > 0 {
+                                debug!("Voice detected, resetting silence counter (was {})", silent_chunks);
+                            }
+                            voice_tx.send_replace(Instant::now());
                         }
-                        silence_chunks = 0;
+                        GateEvent::None => {}
                     }
 
-                    // 如果连续静音超过阈值，跳过发送（但继续处理，保持流畅）
-                    if silence_chunks >= silence_threshold {
+                    // 门限关闭（持续静音）时跳过发送（但继续处理，保持流畅）
+                    if !gate.is_open() {
                         buffer.recycle(audio_chunk);
                         continue;
                     }
 
-                    // 重采样
-                    if let Some(ref mut r) = resampler {
-                        match r.process(&processed_chunk) {
-                            Ok(resampled) => {
-                                // 量化为 i16
-                                let i16_samples = AudioResampler::quantize_to_i16(&resampled);
-
-                                // 发送到网络模块
-                                if output_tx.send(i16_samples).await.is_err() {
-                                    error!("Output channel closed, stopping consumer");
-                                    break;
+                    // 自动增益（降噪之后、重采样之前；低 VAD 时冻结调整）
+                    if let Some(ref mut agc) = agc {
+                        let samples = denoised_output.as_mut().unwrap_or(&mut processed_chunk);
+                        agc.process(samples, vad);
+                    }
+
+                    // 重采样（重采样降噪路径无需再次重采样）
+                    let resampled = match denoised_output {
+                        Some(samples) => Ok(samples),
+                        None => resampler.process(&processed_chunk),
+                    };
+
+                    match resampled {
+                        Ok(resampled) if resampled.is_empty() => {}
+                        Ok(resampled) => {
+                            // 转写只接受单声道，立体声在发送前混音（录音保留双声道）
+                            let resampled = if channels > 1 {
+                                if let Some(ref recorder) = recorder {
+                                    recorder.record(&AudioResampler::quantize_to_i16(&resampled));
                                 }
+                                select_channels(&resampled, channels as u16, CaptureMode::Mono)
+                            } else {
+                                resampled
+                            };
+
+                            // 量化为 i16
+                            let i16_samples = AudioResampler::quantize_to_i16(&resampled);
+
+                            if channels == 1 && let Some(ref recorder) = recorder {
+                                recorder.record(&i16_samples);
                             }
-                            Err(e) => {
-                                error!("Resampling error: {}", e);
+
+                            // 发送到网络模块
+                            if output_tx.send(AudioFrame::new(i16_samples, captured_at)).await.is_err() {
+                                error!("Output channel closed, stopping consumer");
+                                report_pipeline_stop(&pipeline_tx, &stop_rx, PipelineStop::OutputClosed);
+                                break;
                             }
                         }
+                        Err(e) => {
+                            error!("Resampling error: {}", e);
+                        }
                     }
 
                     // 回收缓冲区
                     buffer.recycle(audio_chunk);
                 } else {
+                    // 已停止且缓冲区已排空，退出并释放输出通道
+                    if *stop_rx.borrow() {
+                        break;
+                    }
+
+                    // 静音期间不发送音频，也要发现输出通道已关闭
+                    if output_tx.is_closed() {
+                        error!("Output channel closed while idle, stopping consumer");
+                        report_pipeline_stop(&pipeline_tx, &stop_rx, PipelineStop::OutputClosed);
+                        break;
+                    }
+
                     // 缓冲区为空，短暂休眠
                     tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
                 }
             }
 
+            level_meter.reset();
+            vad_tx.send_replace(0.0);
+
+            if let Some(recorder) = recorder {
+                match tokio::task::spawn_blocking(move || recorder.finish().join()).await {
+                    Ok(Ok(Ok(()))) => {}
+                    Ok(Ok(Err(e))) => error!("Failed to finalize recording file: {}", e),
+                    Ok(Err(_)) | Err(_) => error!("Recording writer thread panicked"),
+                }
+            }
+
             info!("Audio consumer task stopped");
         });
     }
 }
 
+/// 发布流水线意外停止信号
+///
+/// 已请求停止时网络模块可能先于消费者退出，此时输出通道关闭属于正常停止，不发布
+fn report_pipeline_stop(
+    pipeline_tx: &watch::Sender<Option<PipelineStop>>,
+    stop_rx: &watch::Receiver<bool>,
+    reason: PipelineStop,
+) {
+    if *stop_rx.borrow() {
+        return;
+    }
+    pipeline_tx.send_replace(Some(reason));
+}
+
+/// 按采样率创建降噪器
+///
+/// RNNoise 严格要求 48kHz 采样率，音频已在 AudioCapture 中转换为单声道；
+/// 48kHz 设备直接降噪，其他采样率先重采样到 48kHz 再降噪，降噪结果重采样到 `output_rate`
+///
+/// # Returns
+/// `(48kHz 降噪器, 重采样降噪器)`，至多一个为 Some；未启用或创建失败时都为 None
+fn init_noise_suppression(
+    sample_rate: u32,
+    output_rate: u32,
+    enabled: bool,
+    level: NoiseSuppressionLevel,
+    mix: f32,
+) -> (Option<AudioProcessor>, Option<ResamplingDenoiser>) {
+    if !enabled {
+        info!("Noise suppression disabled by configuration");
+        return (None, None);
+    }
+
+    if !NOISE_SUPPRESSION_COMPILED {
+        warn!("Noise suppression requested but not compiled in (feature `noise-suppression`)");
+        return (None, None);
+    }
+
+    if sample_rate == denoise::DENOISE_SAMPLE_RATE {
+        info!("Noise suppression processor initialized (48kHz, mono, {:?})", level);
+        return (Some(AudioProcessor::with_level_and_mix(level, mix)), None);
+    }
+
+    match ResamplingDenoiser::with_level_and_mix(sample_rate, level, mix)
+        .and_then(|denoiser| denoiser.with_output_rate(output_rate))
+    {
+        Ok(denoiser) => {
+            info!(
+                "Noise suppression initialized with pre-resampling ({}Hz -> 48kHz)",
+                sample_rate
+            );
+            (None, Some(denoiser))
+        }
+        Err(e) => {
+            error!("Failed to create resampling denoiser, noise suppression disabled: {}", e);
+            (None, None)
+        }
+    }
+}
+
 impl Drop for AudioManager {
     fn drop(&mut self) {
         self.stop();
@@ -293,6 +822,17 @@ impl Drop for AudioManager {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_audio_manager_config_default() {
+        let config = AudioManagerConfig::default();
+        assert!(config.audio_host.is_none());
+        assert!(config.input_device.is_none());
+        assert!(config.enable_noise_suppression);
+        assert_eq!(config.noise_suppression_level, NoiseSuppressionLevel::VeryHigh);
+        assert_eq!(config.denoise_mix, 1.0);
+        assert!(!config.processor.agc.enabled);
+    }
+
     #[tokio::test]
     async fn test_audio_manager_creation() {
         let (tx, _rx) = mpsc::channel(100);
@@ -307,14 +847,14 @@ mod tests {
         let mut manager = AudioManager::new(tx).unwrap();
 
         // 启动音频处理
-        manager.start().unwrap();
+        manager.start().await.unwrap();
 
         // 接收一些数据
         tokio::time::timeout(tokio::time::Duration::from_secs(1), async {
             let mut count = 0;
             while let Some(data) = rx.recv().await {
                 count += 1;
-                println!("Received chunk {}: {} samples", count, data.len());
+                println!("Received chunk {}: {} samples", count, data.samples.len());
                 if count >= 5 {
                     break;
                 }
@@ -331,8 +871,70 @@ mod tests {
     fn test_buffer_status() {
         let (tx, _rx) = mpsc::channel(100);
         let manager = AudioManager::new(tx).unwrap();
-        let (len, cap) = manager.buffer_status();
-        assert_eq!(len, 0);
-        assert_eq!(cap, 200); // 更新为新的容量
+        let stats = manager.buffer_status();
+        assert_eq!(stats.len, 0);
+        assert_eq!(stats.capacity, 200); // 更新为新的容量
+        assert_eq!(stats.dropped, 0);
+    }
+
+    #[tokio::test]
+    async fn test_closed_output_reports_pipeline_stop() {
+        let (tx, rx) = mpsc::channel(100);
+        let manager = AudioManager::new(tx).unwrap();
+        let mut pipeline_rx = manager.pipeline_stopped();
+        drop(rx);
+
+        manager.spawn_consumer_task(
+            false,
+            NoiseSuppressionLevel::default(),
+            1.0,
+            &AudioProcessorConfig::default(),
+        );
+
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            pipeline_rx.wait_for(Option::is_some),
+        )
+        .await
+        .expect("consumer did not report pipeline stop")
+        .unwrap();
+        assert_eq!(*pipeline_rx.borrow(), Some(PipelineStop::OutputClosed));
+    }
+
+    #[test]
+    fn test_report_pipeline_stop_ignored_after_stop() {
+        let (pipeline_tx, pipeline_rx) = watch::channel(None);
+        let (stop_tx, stop_rx) = watch::channel(false);
+
+        stop_tx.send_replace(true);
+        report_pipeline_stop(&pipeline_tx, &stop_rx, PipelineStop::OutputClosed);
+        assert_eq!(*pipeline_rx.borrow(), None);
+
+        stop_tx.send_replace(false);
+        report_pipeline_stop(&pipeline_tx, &stop_rx, PipelineStop::OutputClosed);
+        assert_eq!(*pipeline_rx.borrow(), Some(PipelineStop::OutputClosed));
+    }
+
+    #[test]
+    #[cfg(feature = "noise-suppression")]
+    fn test_noise_suppression_rebuilt_for_new_rate() {
+        let (rate_tx, rate_rx) = watch::channel(48000);
+        let mut monitor = SampleRateMonitor::new(rate_rx);
+        let level = NoiseSuppressionLevel::VeryHigh;
+
+        let (direct, resampling) = init_noise_suppression(monitor.current(), DEFAULT_OUTPUT_SAMPLE_RATE, true, level, 1.0);
+        assert!(direct.is_some());
+        assert!(resampling.is_none());
+
+        // 恢复采集后回退到 44.1kHz：改为先重采样再降噪
+        rate_tx.send_replace(44100);
+        let change = monitor.poll().unwrap();
+        assert_eq!(change.to, 44100);
+        let (direct, resampling) = init_noise_suppression(change.to, DEFAULT_OUTPUT_SAMPLE_RATE, true, level, 1.0);
+        assert!(direct.is_none());
+        assert!(resampling.is_some());
+
+        let (direct, resampling) = init_noise_suppression(change.to, DEFAULT_OUTPUT_SAMPLE_RATE, false, level, 1.0);
+        assert!(direct.is_none() && resampling.is_none());
     }
 }
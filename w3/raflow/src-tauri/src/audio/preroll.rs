@@ -0,0 +1,206 @@
+//! 预录缓冲模块
+//!
+//! 采集从热键触发才开始时，音频流启动前的第一个字常被截掉。
+//! 待命期间音频流持续运行，只保留最近一段音频；开始录音时先把这段
+//! 预录音频送入处理流水线，再继续转发实时音频
+
+use std::collections::VecDeque;
+use std::sync::mpsc;
+
+/// 音频块回调
+pub type AudioSink = Box<dyn FnMut(&[f32]) + Send>;
+
+/// 预录缓冲区
+///
+/// 按音频块保存最近的音频，总采样点数不超过 `capacity`，超出时丢弃最旧的块。
+/// 保持原始块大小，避免消费者因块大小变化重建重采样器
+#[derive(Debug)]
+pub struct PreRollBuffer {
+    chunks: VecDeque<Vec<f32>>,
+    len: usize,
+    capacity: usize,
+}
+
+impl PreRollBuffer {
+    /// 创建预录缓冲区
+    ///
+    /// # Arguments
+    /// * `sample_rate` - 单声道采样率（Hz）
+    /// * `duration_ms` - 保留的时长（毫秒）
+    pub fn new(sample_rate: u32, duration_ms: u64) -> Self {
+        let capacity = (sample_rate as u64 * duration_ms / 1000) as usize;
+
+        Self {
+            chunks: VecDeque::new(),
+            len: 0,
+            capacity,
+        }
+    }
+
+    /// 写入一个音频块，超出容量时丢弃最旧的块
+    pub fn push(&mut self, data: &[f32]) {
+        if data.len() > self.capacity {
+            self.clear();
+            return;
+        }
+
+        // 复用被淘汰的块，避免在音频回调中频繁分配
+        let mut chunk = Vec::new();
+        while self.len + data.len() > self.capacity {
+            let Some(oldest) = self.chunks.pop_front() else {
+                break;
+            };
+            self.len -= oldest.len();
+            chunk = oldest;
+        }
+
+        chunk.clear();
+        chunk.extend_from_slice(data);
+        self.len += chunk.len();
+        self.chunks.push_back(chunk);
+    }
+
+    /// 取出所有预录音频块（按时间顺序）
+    pub fn take(&mut self) -> Vec<Vec<f32>> {
+        self.len = 0;
+        self.chunks.drain(..).collect()
+    }
+
+    /// 清空预录音频
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+
+    /// 当前保留的采样点数量
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// 预录路由器
+///
+/// 运行在音频回调线程中：没有接收端时写入预录缓冲区；
+/// 接收端通过通道送达后，先送出预录音频，之后转发实时音频。
+/// 路由器随音频流销毁，预录音频不会带入下一次录音
+pub struct PreRollRouter {
+    pre_roll: PreRollBuffer,
+    sink: Option<AudioSink>,
+    sink_rx: mpsc::Receiver<AudioSink>,
+}
+
+impl PreRollRouter {
+    /// 创建预录路由器及其控制通道
+    ///
+    /// # Arguments
+    /// * `sample_rate` - 单声道采样率（Hz）
+    /// * `duration_ms` - 预录时长（毫秒）
+    pub fn new(sample_rate: u32, duration_ms: u64) -> (Self, mpsc::Sender<AudioSink>) {
+        let (tx, rx) = mpsc::channel();
+
+        let router = Self {
+            pre_roll: PreRollBuffer::new(sample_rate, duration_ms),
+            sink: None,
+            sink_rx: rx,
+        };
+
+        (router, tx)
+    }
+
+    /// 处理一个单声道音频块
+    pub fn process(&mut self, data: &[f32]) {
+        if let Ok(mut sink) = self.sink_rx.try_recv() {
+            for chunk in self.pre_roll.take() {
+                sink(&chunk);
+            }
+            self.sink = Some(sink);
+        }
+
+        match self.sink.as_mut() {
+            Some(sink) => sink(data),
+            None => self.pre_roll.push(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 将收到的音频写入通道的接收端
+    fn collecting_sink() -> (AudioSink, mpsc::Receiver<Vec<f32>>) {
+        let (tx, rx) = mpsc::channel();
+        let sink = Box::new(move |data: &[f32]| {
+            let _ = tx.send(data.to_vec());
+        });
+        (sink, rx)
+    }
+
+    fn ramp(range: std::ops::Range<u32>) -> Vec<f32> {
+        range.map(|i| i as f32).collect()
+    }
+
+    #[test]
+    fn test_pre_roll_keeps_latest_samples() {
+        // 1kHz 下 5ms = 5 个采样点
+        let mut buffer = PreRollBuffer::new(1000, 5);
+
+        buffer.push(&ramp(0..2));
+        buffer.push(&ramp(2..4));
+        buffer.push(&ramp(4..6));
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.take(), vec![ramp(2..4), ramp(4..6)]);
+        assert!(buffer.is_empty());
+
+        // 单块超过容量时不保留
+        buffer.push(&ramp(0..2));
+        buffer.push(&ramp(0..12));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_pre_roll_prepended_on_attach() {
+        let (mut router, sink_tx) = PreRollRouter::new(1000, 5);
+
+        // 待命期间的合成音频，只保留最近的 5 个采样点以内的块
+        router.process(&ramp(0..2));
+        router.process(&ramp(2..4));
+        router.process(&ramp(4..6));
+
+        let (sink, received) = collecting_sink();
+        sink_tx.send(sink).unwrap();
+        router.process(&ramp(6..8));
+
+        // 预录音频先于实时音频到达，且保持原始块大小
+        let chunks: Vec<Vec<f32>> = received.try_iter().collect();
+        assert_eq!(chunks, vec![ramp(2..4), ramp(4..6), ramp(6..8)]);
+    }
+
+    #[test]
+    fn test_pre_roll_not_repeated_after_attach() {
+        let (mut router, sink_tx) = PreRollRouter::new(1000, 5);
+        router.process(&ramp(0..2));
+
+        let (sink, received) = collecting_sink();
+        sink_tx.send(sink).unwrap();
+        router.process(&ramp(2..4));
+        router.process(&ramp(4..6));
+
+        // 预录音频只送出一次，之后只转发实时音频
+        let samples: Vec<f32> = received.try_iter().flatten().collect();
+        assert_eq!(samples, ramp(0..6));
+        assert!(router.pre_roll.is_empty());
+    }
+
+    #[test]
+    fn test_zero_duration_keeps_nothing() {
+        let mut buffer = PreRollBuffer::new(48000, 0);
+        buffer.push(&[0.5; 480]);
+        assert!(buffer.is_empty());
+    }
+}
@@ -2,7 +2,13 @@
 //!
 //! 使用 RNNoise 算法提供噪声抑制功能
 
+use super::agc::AgcConfig;
+use super::denoise::DENOISE_SAMPLE_RATE;
+use super::{RING_BUFFER_CHUNK_FRAMES, RING_BUFFER_CHUNKS};
+#[cfg(feature = "noise-suppression")]
 use nnnoiseless::DenoiseState;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,16 +18,49 @@ pub enum ProcessorError {
 
     #[error("Failed to process audio: {0}")]
     ProcessError(String),
+
+    #[error("Invalid ring buffer config: {0}")]
+    InvalidRingBuffer(String),
 }
 
 type Result<T> = std::result::Result<T, ProcessorError>;
 
+/// 默认干湿混合比例（完全使用降噪结果）
+pub const DEFAULT_DENOISE_MIX: f32 = 1.0;
+
+/// 门限打开的语音概率阈值
+const GATE_VAD_THRESHOLD: f32 = 0.2;
+
+/// 门限关闭时的最小增益（约 -20dB）
+const GATE_FLOOR: f32 = 0.1;
+
+/// 门限关闭时每帧的增益衰减系数（10ms 一帧，约 100ms 衰减到最小增益）
+const GATE_RELEASE: f32 = 0.8;
+
+/// RNNoise 降噪器（未编译噪声抑制时为原样输出的占位实现）
+#[cfg(feature = "noise-suppression")]
+type Denoiser = DenoiseState<'static>;
+#[cfg(not(feature = "noise-suppression"))]
+type Denoiser = passthrough::DenoiseState;
+
+/// RNNoise 每帧采样点数
+#[cfg(feature = "noise-suppression")]
+const RNNOISE_FRAME_SIZE: usize = DenoiseState::FRAME_SIZE;
+#[cfg(not(feature = "noise-suppression"))]
+const RNNOISE_FRAME_SIZE: usize = passthrough::DenoiseState::FRAME_SIZE;
+
 /// 噪声抑制处理器
 ///
 /// 基于 RNNoise 算法的音频降噪处理器
 pub struct AudioProcessor {
-    denoiser: Box<DenoiseState<'static>>,
+    denoiser: Box<Denoiser>,
     frame_size: usize,
+    /// 噪声抑制级别
+    level: NoiseSuppressionLevel,
+    /// 降噪结果的混合比例（0.0 = 原始音频，1.0 = 完全降噪）
+    mix: f32,
+    /// 门限当前增益（仅 `Gated` 级别使用）
+    gate_gain: f32,
 }
 
 impl AudioProcessor {
@@ -34,13 +73,51 @@ impl AudioProcessor {
     /// let processor = AudioProcessor::new();
     /// ```
     pub fn new() -> Self {
-        let denoiser = DenoiseState::new();
+        Self::with_mix(DEFAULT_DENOISE_MIX)
+    }
+
+    /// 创建指定噪声抑制级别的音频处理器
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raflow_lib::audio::{AudioProcessor, NoiseSuppressionLevel};
+    ///
+    /// let processor = AudioProcessor::with_level(NoiseSuppressionLevel::Low);
+    /// assert_eq!(processor.mix(), 0.3);
+    /// ```
+    pub fn with_level(level: NoiseSuppressionLevel) -> Self {
+        Self::with_level_and_mix(level, DEFAULT_DENOISE_MIX)
+    }
+
+    /// 创建指定噪声抑制级别和干湿混合比例的音频处理器
+    ///
+    /// 实际混合比例为级别对应的比例乘以 `mix`
+    ///
+    /// # Arguments
+    /// * `level` - 噪声抑制级别
+    /// * `mix` - 对级别混合比例的缩放（0.0 ~ 1.0），超出范围会被截断
+    pub fn with_level_and_mix(level: NoiseSuppressionLevel, mix: f32) -> Self {
+        let denoiser = Denoiser::new();
         Self {
             denoiser,
-            frame_size: DenoiseState::FRAME_SIZE,
+            frame_size: RNNOISE_FRAME_SIZE,
+            level,
+            mix: (level.mix() * mix.clamp(0.0, 1.0)).clamp(0.0, 1.0),
+            gate_gain: 1.0,
         }
     }
 
+    /// 创建带干湿混合比例的音频处理器
+    ///
+    /// RNNoise 在部分输入上会放大残留噪声或引入失真，此时可以降低混合比例，
+    /// 将降噪结果与原始音频混合
+    ///
+    /// # Arguments
+    /// * `mix` - 降噪结果的比例（0.0 = 原始音频，1.0 = 完全降噪），超出范围会被截断
+    pub fn with_mix(mix: f32) -> Self {
+        Self::with_level_and_mix(NoiseSuppressionLevel::default(), mix)
+    }
+
     /// 处理音频帧
     ///
     /// # Arguments
@@ -72,6 +149,10 @@ impl AudioProcessor {
         // 处理音频
         let mut output = vec![0.0f32; self.frame_size];
         let vad_prob = self.denoiser.process_frame(&mut output, frame);
+        if self.level.gated() {
+            self.apply_gate(&mut output, vad_prob);
+        }
+        blend(&mut output, frame, self.mix);
 
         Ok((output, vad_prob))
     }
@@ -80,6 +161,45 @@ impl AudioProcessor {
     pub fn frame_size(&self) -> usize {
         self.frame_size
     }
+
+    /// 获取干湿混合比例
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// 获取噪声抑制级别
+    pub fn level(&self) -> NoiseSuppressionLevel {
+        self.level
+    }
+
+    /// 门限：语音概率低时逐帧衰减降噪结果，检测到语音时立即恢复
+    ///
+    /// RNNoise 不暴露各频带增益，这里用整帧的语音概率近似频谱门限，
+    /// 进一步压低语音间隙中的残留噪声
+    fn apply_gate(&mut self, output: &mut [f32], vad_prob: f32) {
+        self.gate_gain = if vad_prob >= GATE_VAD_THRESHOLD {
+            1.0
+        } else {
+            (self.gate_gain * GATE_RELEASE).max(GATE_FLOOR)
+        };
+
+        if self.gate_gain < 1.0 {
+            for sample in output.iter_mut() {
+                *sample *= self.gate_gain;
+            }
+        }
+    }
+}
+
+/// 按比例将原始音频混入降噪结果：`wet * mix + dry * (1 - mix)`
+fn blend(wet: &mut [f32], dry: &[f32], mix: f32) {
+    if mix >= 1.0 {
+        return;
+    }
+
+    for (w, &d) in wet.iter_mut().zip(dry) {
+        *w = *w * mix + d * (1.0 - mix);
+    }
 }
 
 impl Default for AudioProcessor {
@@ -88,35 +208,167 @@ impl Default for AudioProcessor {
     }
 }
 
-/// 音频处理器配置（为了保持与之前 API 的兼容性）
+/// 环形缓冲区最少块数
+pub const MIN_RING_CAPACITY: usize = 8;
+
+/// 环形缓冲区每块帧数的下限
+pub const MIN_RING_CHUNK_SIZE: usize = 64;
+
+/// 环形缓冲区每块帧数的上限
+pub const MAX_RING_CHUNK_SIZE: usize = 16384;
+
+/// 音频处理器配置
+///
+/// RNNoise 本身不需要额外配置，这里是降噪之后的处理阶段，以及采集回调和处理任务之间的环形缓冲区。
+///
+/// 环形缓冲区预分配 `ring_capacity` 个 `ring_chunk_size` 帧的 f32 块，
+/// 默认 200 × 2048 约占 1.6MB：低内存设备可以调小；
+/// 处理任务可能长时间落后（如慢速机器上降噪）时应调大容量，否则队列满后会丢弃音频
 #[derive(Debug, Clone)]
 pub struct AudioProcessorConfig {
-    /// 占位字段（RNNoise 不需要额外配置）
-    _placeholder: (),
+    /// 自动增益控制（降噪之后、重采样之前）
+    pub agc: AgcConfig,
+    /// 把发送到网络的 16kHz 音频另存为 WAV 文件，None 表示不保存
+    pub record_to_file: Option<PathBuf>,
+    /// 环形缓冲区块数（至少 `MIN_RING_CAPACITY`）
+    pub ring_capacity: usize,
+    /// 环形缓冲区每块预分配的帧数（2 的幂，范围见 `MIN_RING_CHUNK_SIZE`/`MAX_RING_CHUNK_SIZE`）
+    pub ring_chunk_size: usize,
 }
 
 impl Default for AudioProcessorConfig {
     fn default() -> Self {
-        Self { _placeholder: () }
+        Self {
+            agc: AgcConfig::default(),
+            record_to_file: None,
+            ring_capacity: RING_BUFFER_CHUNKS,
+            ring_chunk_size: RING_BUFFER_CHUNK_FRAMES,
+        }
+    }
+}
+
+impl AudioProcessorConfig {
+    /// 检查环形缓冲区大小
+    ///
+    /// 容量不能少于 `MIN_RING_CAPACITY`；每块帧数必须是 2 的幂且在上下限之间
+    pub fn validate(&self) -> Result<()> {
+        if self.ring_capacity < MIN_RING_CAPACITY {
+            return Err(ProcessorError::InvalidRingBuffer(format!(
+                "capacity {} is below {}",
+                self.ring_capacity, MIN_RING_CAPACITY
+            )));
+        }
+
+        let chunk_size = self.ring_chunk_size;
+        if !chunk_size.is_power_of_two()
+            || !(MIN_RING_CHUNK_SIZE..=MAX_RING_CHUNK_SIZE).contains(&chunk_size)
+        {
+            return Err(ProcessorError::InvalidRingBuffer(format!(
+                "chunk size {} must be a power of two between {} and {}",
+                chunk_size, MIN_RING_CHUNK_SIZE, MAX_RING_CHUNK_SIZE
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// 是否编译了噪声抑制（`noise-suppression` feature）
+pub const NOISE_SUPPRESSION_COMPILED: bool = cfg!(feature = "noise-suppression");
+
+/// 噪声抑制可用性信息
+///
+/// 供设置界面解释降噪为何没有生效
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NoiseSuppressionInfo {
+    /// 是否编译了噪声抑制
+    pub compiled: bool,
+    /// RNNoise 每帧采样点数
+    pub frame_size: usize,
+    /// RNNoise 要求的采样率
+    pub sample_rate: u32,
+    /// 当前录音会话是否正在降噪
+    pub active: bool,
+}
+
+impl NoiseSuppressionInfo {
+    /// 组装噪声抑制信息
+    ///
+    /// # Arguments
+    /// * `compiled` - 是否编译了噪声抑制，未编译时 `active` 始终为 false
+    /// * `active` - 当前会话是否创建了降噪器
+    pub fn new(compiled: bool, active: bool) -> Self {
+        Self {
+            compiled,
+            frame_size: RNNOISE_FRAME_SIZE,
+            sample_rate: DENOISE_SAMPLE_RATE,
+            active: compiled && active,
+        }
+    }
+
+    /// 当前构建的噪声抑制信息
+    pub fn current(active: bool) -> Self {
+        Self::new(NOISE_SUPPRESSION_COMPILED, active)
     }
 }
 
-/// 噪声抑制级别（为了保持 API 兼容性，RNNoise 不支持级别调整）
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// 噪声抑制级别
+///
+/// RNNoise 本身没有强度参数，级别通过降噪结果与原始音频的混合比例实现：
+/// 级别越低保留的原始音频越多，失真越少但残留噪声越多
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NoiseSuppressionLevel {
-    /// 低级别（占位）
+    /// 低级别：30% 降噪结果
     Low,
-    /// 中级别（占位）
+    /// 中级别：60% 降噪结果
     Moderate,
-    /// 高级别（占位）
+    /// 高级别：85% 降噪结果
     High,
-    /// 极高级别（占位）
+    /// 极高级别：完全使用降噪结果（默认，与引入级别之前的输出相同）
+    #[default]
     VeryHigh,
+    /// 门限级别：完全使用降噪结果，并对非语音帧加门限（需手动选择）
+    Gated,
 }
 
-impl Default for NoiseSuppressionLevel {
-    fn default() -> Self {
-        Self::Moderate
+impl NoiseSuppressionLevel {
+    /// 降噪结果的混合比例
+    pub fn mix(self) -> f32 {
+        match self {
+            NoiseSuppressionLevel::Low => 0.3,
+            NoiseSuppressionLevel::Moderate => 0.6,
+            NoiseSuppressionLevel::High => 0.85,
+            NoiseSuppressionLevel::VeryHigh | NoiseSuppressionLevel::Gated => 1.0,
+        }
+    }
+
+    /// 是否对非语音帧加门限
+    fn gated(self) -> bool {
+        self == NoiseSuppressionLevel::Gated
+    }
+}
+
+/// 未编译噪声抑制时的占位降噪器
+///
+/// 未编译时 `init_noise_suppression` 不会创建降噪器，这里只为让处理器在两种构建下类型一致
+#[cfg(not(feature = "noise-suppression"))]
+mod passthrough {
+    pub struct DenoiseState;
+
+    impl DenoiseState {
+        /// 与 RNNoise 相同的帧大小（10ms @ 48kHz）
+        pub const FRAME_SIZE: usize = 480;
+
+        pub fn new() -> Box<Self> {
+            Box::new(Self)
+        }
+
+        /// 原样输出，语音概率为 0
+        pub fn process_frame(&mut self, output: &mut [f32], input: &[f32]) -> f32 {
+            output.copy_from_slice(input);
+            0.0
+        }
     }
 }
 
@@ -124,6 +376,22 @@ impl Default for NoiseSuppressionLevel {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ring_buffer_config_validation() {
+        assert!(AudioProcessorConfig::default().validate().is_ok());
+
+        let config = |ring_capacity, ring_chunk_size| AudioProcessorConfig {
+            ring_capacity,
+            ring_chunk_size,
+            ..Default::default()
+        };
+        assert!(config(8, MIN_RING_CHUNK_SIZE).validate().is_ok());
+        assert!(config(MIN_RING_CAPACITY - 1, 1024).validate().is_err());
+        assert!(config(64, 1000).validate().is_err());
+        assert!(config(64, MAX_RING_CHUNK_SIZE * 2).validate().is_err());
+        assert!(config(64, 32).validate().is_err());
+    }
+
     #[test]
     fn test_processor_creation() {
         let processor = AudioProcessor::new();
@@ -146,7 +414,7 @@ mod tests {
 
         let (processed, vad_prob) = result.unwrap();
         assert_eq!(processed.len(), frame_size);
-        assert!(vad_prob >= 0.0 && vad_prob <= 1.0);
+        assert!((0.0..=1.0).contains(&vad_prob));
     }
 
     #[test]
@@ -180,6 +448,139 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mix_clamped() {
+        assert_eq!(AudioProcessor::new().mix(), 1.0);
+        assert_eq!(AudioProcessor::with_mix(1.5).mix(), 1.0);
+        assert_eq!(AudioProcessor::with_mix(-0.5).mix(), 0.0);
+    }
+
+    #[test]
+    fn test_dry_mix_returns_input() {
+        let mut processor = AudioProcessor::with_mix(0.0);
+        let frame: Vec<f32> = (0..processor.frame_size())
+            .map(|i| (i as f32 / 20.0).sin() * 0.5)
+            .collect();
+
+        let (processed, _) = processor.process(&frame).unwrap();
+        assert_eq!(processed, frame);
+    }
+
+    #[test]
+    fn test_half_mix_averages_input_and_denoised() {
+        let mut wet_processor = AudioProcessor::new();
+        let mut half_processor = AudioProcessor::with_mix(0.5);
+        let frame: Vec<f32> = (0..wet_processor.frame_size())
+            .map(|i| (i as f32 / 20.0).sin() * 0.5)
+            .collect();
+
+        let (denoised, _) = wet_processor.process(&frame).unwrap();
+        let (mixed, _) = half_processor.process(&frame).unwrap();
+
+        for ((m, d), x) in mixed.iter().zip(&denoised).zip(&frame) {
+            assert!((m - (d + x) / 2.0).abs() < 1e-5);
+        }
+    }
+
+    /// 带白噪声的正弦波（固定种子，结果可复现）
+    #[cfg(feature = "noise-suppression")]
+    fn noisy_frames(count: usize, frame_size: usize) -> Vec<Vec<f32>> {
+        let mut rng = fastrand::Rng::with_seed(7);
+        (0..count)
+            .map(|f| {
+                (0..frame_size)
+                    .map(|i| {
+                        let t = (f * frame_size + i) as f32 / 48000.0;
+                        let tone = 0.2 * (2.0 * std::f32::consts::PI * 300.0 * t).sin();
+                        tone + 0.1 * (rng.f32() * 2.0 - 1.0)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// 处理所有帧，返回输出与输入的均方误差
+    #[cfg(feature = "noise-suppression")]
+    fn distance_from_input(level: NoiseSuppressionLevel, frames: &[Vec<f32>]) -> f32 {
+        let mut processor = AudioProcessor::with_level(level);
+        let mut sum = 0.0f32;
+        let mut count = 0usize;
+
+        for frame in frames {
+            let (output, _) = processor.process(frame).unwrap();
+            sum += output
+                .iter()
+                .zip(frame)
+                .map(|(o, x)| (o - x) * (o - x))
+                .sum::<f32>();
+            count += frame.len();
+        }
+
+        sum / count as f32
+    }
+
+    #[test]
+    fn test_level_mix() {
+        assert_eq!(
+            AudioProcessor::with_level(NoiseSuppressionLevel::Low).mix(),
+            0.3
+        );
+        assert_eq!(
+            AudioProcessor::with_level_and_mix(NoiseSuppressionLevel::High, 0.5).mix(),
+            0.425
+        );
+        assert_eq!(
+            AudioProcessor::new().level(),
+            NoiseSuppressionLevel::default()
+        );
+    }
+
+    #[test]
+    fn test_default_level_full_mix_without_gate() {
+        let level = NoiseSuppressionLevel::default();
+        assert_eq!(level, NoiseSuppressionLevel::VeryHigh);
+        assert_eq!(level.mix(), 1.0);
+        assert!(!level.gated());
+        assert!(NoiseSuppressionLevel::Gated.gated());
+        assert_eq!(AudioProcessor::new().mix(), DEFAULT_DENOISE_MIX);
+    }
+
+    #[test]
+    #[cfg(feature = "noise-suppression")]
+    fn test_low_level_closer_to_input_than_very_high() {
+        let frames = noisy_frames(50, RNNOISE_FRAME_SIZE);
+
+        let low = distance_from_input(NoiseSuppressionLevel::Low, &frames);
+        let moderate = distance_from_input(NoiseSuppressionLevel::Moderate, &frames);
+        let very_high = distance_from_input(NoiseSuppressionLevel::VeryHigh, &frames);
+
+        assert!(low < moderate, "low {} >= moderate {}", low, moderate);
+        assert!(
+            moderate < very_high,
+            "moderate {} >= very high {}",
+            moderate,
+            very_high
+        );
+    }
+
+    #[test]
+    fn test_gate_attenuates_non_speech() {
+        let mut processor = AudioProcessor::with_level(NoiseSuppressionLevel::Gated);
+        let frame = vec![1.0f32; processor.frame_size()];
+
+        // 连续低语音概率：增益逐帧衰减到最小值
+        for _ in 0..20 {
+            processor.apply_gate(&mut frame.clone(), 0.0);
+        }
+        assert_eq!(processor.gate_gain, GATE_FLOOR);
+
+        // 检测到语音立即恢复
+        let mut output = frame.clone();
+        processor.apply_gate(&mut output, 0.9);
+        assert_eq!(processor.gate_gain, 1.0);
+        assert_eq!(output, frame);
+    }
+
     #[test]
     fn test_noise_suppression_with_silence() {
         let mut processor = AudioProcessor::new();
@@ -196,4 +597,22 @@ mod tests {
         // 静音应该有低 VAD 概率
         assert!(vad_prob < 0.5);
     }
+
+    #[test]
+    fn test_noise_suppression_info() {
+        let info = NoiseSuppressionInfo::new(true, true);
+        assert!(info.compiled);
+        assert!(info.active);
+        assert_eq!(info.frame_size, 480);
+        assert_eq!(info.sample_rate, 48000);
+
+        let info = NoiseSuppressionInfo::new(true, false);
+        assert!(!info.active);
+
+        // 未编译噪声抑制时不可能处于激活状态
+        let info = NoiseSuppressionInfo::new(false, true);
+        assert!(!info.compiled);
+        assert!(!info.active);
+        assert_eq!(info.frame_size, 480);
+    }
 }
@@ -0,0 +1,92 @@
+//! 采样率变化检测模块
+//!
+//! 采集流重新打开时（如恢复录音时蓝牙耳机切换到通话模式）可能回退到其他采样率，
+//! 按旧采样率创建的重采样器和降噪器会产生错误的音频，需要检测变化并重建
+
+use tokio::sync::watch;
+
+/// 采样率变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateChange {
+    /// 原采样率
+    pub from: u32,
+    /// 新采样率
+    pub to: u32,
+}
+
+/// 采样率监视器
+///
+/// 订阅采集流的实际采样率，消费者处理每个音频块前检查一次
+#[derive(Debug)]
+pub struct SampleRateMonitor {
+    rate_rx: watch::Receiver<u32>,
+    current: u32,
+}
+
+impl SampleRateMonitor {
+    /// 创建监视器，以通道当前值作为初始采样率
+    pub fn new(mut rate_rx: watch::Receiver<u32>) -> Self {
+        let current = *rate_rx.borrow_and_update();
+        Self { rate_rx, current }
+    }
+
+    /// 当前采样率
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// 检查采样率是否变化
+    ///
+    /// 发布的值与当前相同或为零时不视为变化
+    pub fn poll(&mut self) -> Option<RateChange> {
+        if !self.rate_rx.has_changed().unwrap_or(false) {
+            return None;
+        }
+
+        let rate = *self.rate_rx.borrow_and_update();
+        if rate == 0 || rate == self.current {
+            return None;
+        }
+
+        let change = RateChange {
+            from: self.current,
+            to: rate,
+        };
+        self.current = rate;
+        Some(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_rate_transition() {
+        let (rate_tx, rate_rx) = watch::channel(48000);
+        let mut monitor = SampleRateMonitor::new(rate_rx);
+        assert_eq!(monitor.current(), 48000);
+        assert_eq!(monitor.poll(), None);
+
+        // 重新发布相同采样率不算变化
+        rate_tx.send_replace(48000);
+        assert_eq!(monitor.poll(), None);
+
+        // 切换到蓝牙通话模式
+        rate_tx.send_replace(16000);
+        assert_eq!(
+            monitor.poll(),
+            Some(RateChange {
+                from: 48000,
+                to: 16000
+            })
+        );
+        assert_eq!(monitor.current(), 16000);
+        assert_eq!(monitor.poll(), None);
+
+        // 无效采样率被忽略
+        rate_tx.send_replace(0);
+        assert_eq!(monitor.poll(), None);
+        assert_eq!(monitor.current(), 16000);
+    }
+}
@@ -0,0 +1,96 @@
+//! 采集设备故障恢复模块
+//!
+//! 录音中拔出 USB 麦克风时 cpal 只通过错误回调报告，采集随之停止。
+//! 错误回调把故障发送到通道，由应用决定切换到默认设备还是结束录音
+
+use serde::Serialize;
+
+/// 一次录音内最多切换设备的次数，超过后停止录音
+pub const MAX_DEVICE_SWITCHES: u32 = 3;
+
+/// 采集流故障
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum StreamFault {
+    /// 设备已不可用（如被拔出），采集流不会再产生数据
+    DeviceLost,
+    /// 后端报告的其他错误（如缓冲区溢出），采集流通常可以继续
+    Backend(String),
+}
+
+impl StreamFault {
+    /// 采集流是否已无法继续
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::DeviceLost)
+    }
+}
+
+impl From<cpal::StreamError> for StreamFault {
+    fn from(err: cpal::StreamError) -> Self {
+        match err {
+            cpal::StreamError::DeviceNotAvailable => Self::DeviceLost,
+            cpal::StreamError::BackendSpecific { err } => Self::Backend(err.description),
+        }
+    }
+}
+
+/// 收到采集流故障后的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// 非致命错误，继续使用当前采集流
+    Ignore,
+    /// 在默认设备上重新打开采集流，录音继续
+    SwitchToDefault,
+    /// 已多次切换仍失败，停止录音
+    Stop,
+}
+
+/// 决定采集流故障的处理方式
+///
+/// # Arguments
+/// * `fault` - 采集流故障
+/// * `switches` - 本次录音已经切换设备的次数
+pub fn recovery_action(fault: &StreamFault, switches: u32) -> RecoveryAction {
+    if !fault.is_fatal() {
+        RecoveryAction::Ignore
+    } else if switches < MAX_DEVICE_SWITCHES {
+        RecoveryAction::SwitchToDefault
+    } else {
+        RecoveryAction::Stop
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_lost_switches_to_default() {
+        let fault = StreamFault::from(cpal::StreamError::DeviceNotAvailable);
+        assert_eq!(fault, StreamFault::DeviceLost);
+
+        assert_eq!(recovery_action(&fault, 0), RecoveryAction::SwitchToDefault);
+        assert_eq!(
+            recovery_action(&fault, MAX_DEVICE_SWITCHES - 1),
+            RecoveryAction::SwitchToDefault
+        );
+        // 反复丢失设备时停止录音，不无限重试
+        assert_eq!(
+            recovery_action(&fault, MAX_DEVICE_SWITCHES),
+            RecoveryAction::Stop
+        );
+    }
+
+    #[test]
+    fn test_backend_error_ignored() {
+        let fault = StreamFault::from(cpal::StreamError::BackendSpecific {
+            err: cpal::BackendSpecificError {
+                description: "buffer overrun".to_string(),
+            },
+        });
+
+        assert_eq!(fault, StreamFault::Backend("buffer overrun".to_string()));
+        assert!(!fault.is_fatal());
+        assert_eq!(recovery_action(&fault, 0), RecoveryAction::Ignore);
+    }
+}
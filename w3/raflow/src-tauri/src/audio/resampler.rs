@@ -6,8 +6,16 @@ use rubato::{
     FastFixedIn, Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType,
     WindowFunction,
 };
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, info};
+
+/// 自动选择质量时每个候选质量试运行的块数（同时作为所选重采样器的预热）
+pub const CALIBRATION_FRAMES: usize = 8;
+
+/// 自动选择质量时，每块处理耗时不超过该块实时时长的比例才算满足实时要求
+pub const REALTIME_BUDGET_FRACTION: f64 = 0.1;
 
 #[derive(Error, Debug)]
 pub enum ResamplerError {
@@ -24,14 +32,32 @@ pub enum ResamplerError {
 type Result<T> = std::result::Result<T, ResamplerError>;
 
 /// 重采样质量级别
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Quality {
     /// 低质量，快速处理
+    #[default]
     Low,
     /// 中等质量
     Medium,
     /// 高质量，使用 Sinc 插值
     High,
+    /// 创建时试运行各质量，选择满足实时要求的最高质量，之后固定不变
+    AutoOnce,
+}
+
+/// 按试运行耗时选择质量
+///
+/// `timings` 按质量从低到高排列，返回平均每块耗时不超过
+/// `chunk_duration * REALTIME_BUDGET_FRACTION` 的最高质量；都不满足时返回 `Quality::Low`
+pub fn select_quality(timings: &[(Quality, Duration)], chunk_duration: Duration) -> Quality {
+    let budget = chunk_duration.mul_f64(REALTIME_BUDGET_FRACTION);
+    timings
+        .iter()
+        .filter(|(_, elapsed)| *elapsed <= budget)
+        .map(|(quality, _)| *quality)
+        .next_back()
+        .unwrap_or(Quality::Low)
 }
 
 /// 重采样器类型枚举
@@ -46,10 +72,12 @@ pub struct AudioResampler {
     input_buffer: Vec<Vec<f32>>,
     output_buffer: Vec<Vec<f32>>,
     chunk_size: usize,
-    #[allow(dead_code)]
     channels: usize,
     input_rate: u32,
     output_rate: u32,
+    ratio: f64,
+    output_size: usize,
+    quality: Quality,
 }
 
 impl AudioResampler {
@@ -58,9 +86,9 @@ impl AudioResampler {
     /// # Arguments
     /// * `input_rate` - 输入采样率（Hz）
     /// * `output_rate` - 输出采样率（Hz）
-    /// * `chunk_size` - 输入块大小（采样点数量）
+    /// * `chunk_size` - 输入块大小（每个通道的采样点数量）
     /// * `channels` - 通道数
-    /// * `quality` - 重采样质量
+    /// * `quality` - 重采样质量（`AutoOnce` 时先试运行各质量再选择）
     ///
     /// # Example
     /// ```
@@ -76,17 +104,15 @@ impl AudioResampler {
         channels: usize,
         quality: Quality,
     ) -> Result<Self> {
-        let ratio = output_rate as f64 / input_rate as f64;
-        // 为 Sinc 重采样器预留额外空间（过渡带）
-        let output_size = (chunk_size as f64 * ratio * 1.1).ceil() as usize;
+        if quality == Quality::AutoOnce {
+            return Self::calibrate(input_rate, output_rate, chunk_size, channels);
+        }
 
-        debug!(
-            "Creating resampler: {}Hz -> {}Hz, chunk {} -> {}, {} channels, quality: {:?}",
-            input_rate, output_rate, chunk_size, output_size, channels, quality
-        );
+        let ratio = output_rate as f64 / input_rate as f64;
 
         let resampler = match quality {
-            Quality::Low | Quality::Medium => {
+            // AutoOnce 已在上面解析为具体质量
+            Quality::Low | Quality::Medium | Quality::AutoOnce => {
                 // 使用快速插值
                 let degree = match quality {
                     Quality::Low => rubato::PolynomialDegree::Linear,
@@ -115,6 +141,19 @@ impl AudioResampler {
             }
         };
 
+        // 为 Sinc 重采样器预留额外空间（过渡带）；低输出采样率下 10% 余量
+        // 小于 rubato 要求的固定余量，取两者中较大的值
+        let required = match &resampler {
+            ResamplerType::Fast(r) => r.output_frames_next(),
+            ResamplerType::Sinc(r) => r.output_frames_next(),
+        };
+        let output_size = ((chunk_size as f64 * ratio * 1.1).ceil() as usize).max(required);
+
+        debug!(
+            "Creating resampler: {}Hz -> {}Hz, chunk {} -> {}, {} channels, quality: {:?}",
+            input_rate, output_rate, chunk_size, output_size, channels, quality
+        );
+
         Ok(Self {
             resampler,
             input_buffer: vec![vec![0.0; chunk_size]; channels],
@@ -123,16 +162,64 @@ impl AudioResampler {
             channels,
             input_rate,
             output_rate,
+            ratio,
+            output_size,
+            quality,
         })
     }
 
+    /// 试运行各质量的重采样器，返回满足实时要求的最高质量
+    ///
+    /// 每个候选处理 `CALIBRATION_FRAMES` 个静音块并计时，所选重采样器已经过预热，直接返回
+    fn calibrate(
+        input_rate: u32,
+        output_rate: u32,
+        chunk_size: usize,
+        channels: usize,
+    ) -> Result<Self> {
+        let silence = vec![0.0; chunk_size * channels];
+        let chunk_duration =
+            Duration::from_secs_f64(chunk_size as f64 / f64::from(input_rate.max(1)));
+
+        let mut candidates = Vec::new();
+        let mut timings = Vec::new();
+        for quality in [Quality::Low, Quality::Medium, Quality::High] {
+            let mut resampler = Self::new(input_rate, output_rate, chunk_size, channels, quality)?;
+
+            let start = Instant::now();
+            for _ in 0..CALIBRATION_FRAMES {
+                resampler.process(&silence)?;
+            }
+            let per_chunk = start.elapsed() / CALIBRATION_FRAMES as u32;
+
+            debug!(
+                "Resampler calibration: {:?} takes {:?} per chunk",
+                quality, per_chunk
+            );
+            timings.push((quality, per_chunk));
+            candidates.push(resampler);
+        }
+
+        let selected = select_quality(&timings, chunk_duration);
+        info!(
+            "Resampler quality auto-selected: {:?} (chunk {:?}, timings {:?})",
+            selected, chunk_duration, timings
+        );
+
+        candidates
+            .into_iter()
+            .find(|resampler| resampler.quality == selected)
+            .ok_or_else(|| ResamplerError::RubatoError("no calibrated resampler".to_string()))
+    }
+
     /// 处理音频数据并进行重采样
     ///
     /// # Arguments
     /// * `input` - 输入音频数据（交织格式，如立体声为 L, R, L, R, ...）
     ///
     /// # Returns
-    /// * `Ok(Vec<f32>)` - 重采样后的音频数据（单声道格式）
+    /// * `Ok(Vec<f32>)` - 重采样后的音频数据：多通道重采样器为同样通道数的交织格式；
+    ///   单通道重采样器收到立体声输入时先混为单声道
     ///
     /// # Example
     /// ```no_run
@@ -144,6 +231,10 @@ impl AudioResampler {
     /// assert_eq!(output.len(), 160); // 48k * 10ms -> 16k * 10ms
     /// ```
     pub fn process(&mut self, input: &[f32]) -> Result<Vec<f32>> {
+        if self.channels > 1 {
+            return self.process_interleaved(input);
+        }
+
         // 转为单声道（如果是立体声，取平均）
         let mono = if input.len() == self.chunk_size * 2 {
             // 立体声转单声道
@@ -164,6 +255,40 @@ impl AudioResampler {
         // 填充输入缓冲
         self.input_buffer[0].copy_from_slice(&mono);
 
+        let frames_out = self.resample_buffers()?;
+
+        // 返回重采样后的数据
+        Ok(self.output_buffer[0][..frames_out].to_vec())
+    }
+
+    /// 按通道分别重采样交织的多通道输入，输出同样通道数的交织数据
+    fn process_interleaved(&mut self, input: &[f32]) -> Result<Vec<f32>> {
+        let channels = self.channels;
+        if input.len() != self.chunk_size * channels {
+            return Err(ResamplerError::InvalidInputSize {
+                expected: self.chunk_size * channels,
+                actual: input.len(),
+            });
+        }
+
+        // 解交织到各通道的输入缓冲
+        for (i, frame) in input.chunks_exact(channels).enumerate() {
+            for (buffer, &sample) in self.input_buffer.iter_mut().zip(frame) {
+                buffer[i] = sample;
+            }
+        }
+
+        let frames_out = self.resample_buffers()?;
+
+        let mut output = Vec::with_capacity(frames_out * channels);
+        for i in 0..frames_out {
+            output.extend(self.output_buffer.iter().map(|buffer| buffer[i]));
+        }
+        Ok(output)
+    }
+
+    /// 重采样输入缓冲中的一块，返回每个通道输出的采样点数
+    fn resample_buffers(&mut self) -> Result<usize> {
         // 执行重采样（根据不同类型调用对应方法）
         let frames_out = match &mut self.resampler {
             ResamplerType::Fast(r) => {
@@ -180,8 +305,7 @@ impl AudioResampler {
             }
         };
 
-        // 返回重采样后的数据
-        Ok(self.output_buffer[0][..frames_out].to_vec())
+        Ok(frames_out)
     }
 
     /// f32 -> i16 量化
@@ -227,22 +351,111 @@ impl AudioResampler {
         self.output_rate
     }
 
+    /// 获取实际使用的质量（`AutoOnce` 时为试运行选出的质量）
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
     /// 获取块大小
     pub fn chunk_size(&self) -> usize {
         self.chunk_size
     }
+
+    /// 获取通道数
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// 获取重采样比例（输出采样率 / 输入采样率）
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// 获取每块输出缓冲区大小
+    ///
+    /// 为 `chunk_size * ratio` 预留 10% 余量（不小于重采样器要求的长度），实际每块输出长度会在
+    /// `chunk_size * ratio` 附近波动（如 480 @ 48kHz 输出 157~160 个采样点）
+    pub fn expected_output_len(&self) -> usize {
+        self.output_size
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_select_quality_from_timings() {
+        let chunk = Duration::from_millis(10);
+        let timings = |low, medium, high| {
+            [
+                (Quality::Low, Duration::from_micros(low)),
+                (Quality::Medium, Duration::from_micros(medium)),
+                (Quality::High, Duration::from_micros(high)),
+            ]
+        };
+
+        // 预算为 1ms
+        assert_eq!(select_quality(&timings(20, 50, 400), chunk), Quality::High);
+        assert_eq!(select_quality(&timings(20, 50, 1000), chunk), Quality::High);
+        assert_eq!(
+            select_quality(&timings(20, 50, 1001), chunk),
+            Quality::Medium
+        );
+        assert_eq!(
+            select_quality(&timings(20, 1500, 3000), chunk),
+            Quality::Low
+        );
+        // 都超出预算时仍退回最快的质量
+        assert_eq!(
+            select_quality(&timings(2000, 3000, 9000), chunk),
+            Quality::Low
+        );
+        assert_eq!(select_quality(&[], chunk), Quality::Low);
+    }
+
+    #[test]
+    fn test_auto_once_resolves_quality() {
+        let mut resampler = AudioResampler::new(48000, 16000, 480, 1, Quality::AutoOnce).unwrap();
+        assert_ne!(resampler.quality(), Quality::AutoOnce);
+        let output = resampler.process(&[0.0; 480]).unwrap();
+        assert!(output.len() <= resampler.expected_output_len());
+    }
+
     #[test]
     fn test_resampler_creation() {
         let resampler = AudioResampler::new(48000, 16000, 480, 1, Quality::High);
         assert!(resampler.is_ok());
     }
 
+    #[test]
+    fn test_ratio() {
+        let resampler = AudioResampler::new(48000, 16000, 480, 1, Quality::Low).unwrap();
+        assert_eq!(resampler.ratio(), 16000.0 / 48000.0);
+
+        let resampler = AudioResampler::new(44100, 48000, 441, 1, Quality::Low).unwrap();
+        assert_eq!(resampler.ratio(), 48000.0 / 44100.0);
+    }
+
+    #[test]
+    fn test_expected_output_len() {
+        let resampler = AudioResampler::new(48000, 16000, 480, 1, Quality::High).unwrap();
+
+        // 160 * 1.1 向上取整
+        assert_eq!(resampler.expected_output_len(), 176);
+        assert_eq!(resampler.expected_output_len(), resampler.output_buffer[0].len());
+    }
+
+    #[test]
+    fn test_low_output_rate_buffer_is_large_enough() {
+        // 48kHz -> 8kHz 时 10% 余量（88）小于重采样器要求的 90
+        for quality in [Quality::Low, Quality::High] {
+            let mut resampler = AudioResampler::new(48000, 8000, 480, 1, quality).unwrap();
+            assert!(resampler.expected_output_len() >= 90);
+            assert!(resampler.process(&[0.0; 480]).is_ok());
+        }
+    }
+
     #[test]
     fn test_resampling_48k_to_16k() {
         let mut resampler = AudioResampler::new(48000, 16000, 480, 1, Quality::High).unwrap();
@@ -281,6 +494,36 @@ mod tests {
         assert!(output.len() >= 155 && output.len() <= 165);
     }
 
+    #[test]
+    fn test_stereo_resampled_per_channel() {
+        let mut resampler = AudioResampler::new(48000, 16000, 480, 2, Quality::Low).unwrap();
+        assert_eq!(resampler.channels(), 2);
+
+        // 左右声道为不同的常量，重采样后各自保持不变
+        let input: Vec<f32> = (0..480).flat_map(|_| [0.25, -0.5]).collect();
+        for _ in 0..3 {
+            resampler.process(&input).unwrap();
+        }
+        let output = resampler.process(&input).unwrap();
+
+        assert_eq!(output.len() % 2, 0);
+        let frames = output.len() / 2;
+        assert!((155..=165).contains(&frames), "{}", frames);
+        for frame in output.chunks_exact(2) {
+            assert!((frame[0] - 0.25).abs() < 1e-3, "{:?}", frame);
+            assert!((frame[1] + 0.5).abs() < 1e-3, "{:?}", frame);
+        }
+
+        // 多通道重采样器不接受单通道长度的输入
+        assert!(matches!(
+            resampler.process(&[0.0; 480]),
+            Err(ResamplerError::InvalidInputSize {
+                expected: 960,
+                actual: 480
+            })
+        ));
+    }
+
     #[test]
     fn test_quantize_to_i16() {
         let samples = vec![-1.0, -0.5, 0.0, 0.5, 1.0];
@@ -0,0 +1,228 @@
+//! WAV 录音模块
+//!
+//! 把发送到网络的音频另存为 WAV 文件，便于复现和排查转写错误
+//!
+//! 立体声采集时保留双声道（混音前的音频），便于排查声道选择问题
+
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::thread::JoinHandle;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+/// 录音文件的默认采样率（与默认编码 `pcm_16000` 一致）
+pub const WAV_SAMPLE_RATE: u32 = 16000;
+
+/// 16 位 PCM
+const WAV_BITS_PER_SAMPLE: u16 = 16;
+
+/// 文件头长度（RIFF + fmt + data 块头）
+const WAV_HEADER_LEN: u32 = 44;
+
+/// WAV 写入器（16 位 PCM，多声道时采样交错排列）
+///
+/// 创建时写入数据长度为零的文件头，`finalize` 时回填实际长度
+pub struct WavWriter<W: Write + Seek> {
+    inner: W,
+    data_len: u32,
+}
+
+impl WavWriter<BufWriter<File>> {
+    /// 创建 WAV 文件（已存在时覆盖）
+    pub fn create(path: &Path, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), sample_rate, channels)
+    }
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// 在给定的输出上创建写入器并写入文件头
+    ///
+    /// # Arguments
+    /// * `inner` - 输出
+    /// * `sample_rate` - 音频采样率（Hz）
+    /// * `channels` - 声道数
+    pub fn new(mut inner: W, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        if channels == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "WAV channel count must be at least 1",
+            ));
+        }
+        let block_align = channels * WAV_BITS_PER_SAMPLE / 8;
+        let byte_rate = sample_rate * u32::from(block_align);
+
+        inner.write_all(b"RIFF")?;
+        inner.write_all(&(WAV_HEADER_LEN - 8).to_le_bytes())?;
+        inner.write_all(b"WAVE")?;
+        inner.write_all(b"fmt ")?;
+        inner.write_all(&16u32.to_le_bytes())?;
+        inner.write_all(&1u16.to_le_bytes())?; // PCM
+        inner.write_all(&channels.to_le_bytes())?;
+        inner.write_all(&sample_rate.to_le_bytes())?;
+        inner.write_all(&byte_rate.to_le_bytes())?;
+        inner.write_all(&block_align.to_le_bytes())?;
+        inner.write_all(&WAV_BITS_PER_SAMPLE.to_le_bytes())?;
+        inner.write_all(b"data")?;
+        inner.write_all(&0u32.to_le_bytes())?;
+
+        Ok(Self { inner, data_len: 0 })
+    }
+
+    /// 追加采样
+    pub fn write_samples(&mut self, samples: &[i16]) -> io::Result<()> {
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let data_len = u32::try_from(bytes.len())
+            .ok()
+            .and_then(|len| self.data_len.checked_add(len))
+            .filter(|len| *len <= u32::MAX - WAV_HEADER_LEN)
+            .ok_or_else(|| io::Error::other("WAV file exceeds 4GB"))?;
+
+        self.inner.write_all(&bytes)?;
+        self.data_len = data_len;
+        Ok(())
+    }
+
+    /// 回填文件头中的长度并刷新输出
+    pub fn finalize(mut self) -> io::Result<W> {
+        self.inner.seek(SeekFrom::Start(4))?;
+        self.inner
+            .write_all(&(WAV_HEADER_LEN - 8 + self.data_len).to_le_bytes())?;
+        self.inner
+            .seek(SeekFrom::Start(u64::from(WAV_HEADER_LEN - 4)))?;
+        self.inner.write_all(&self.data_len.to_le_bytes())?;
+        self.inner.seek(SeekFrom::End(0))?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// WAV 录音器
+///
+/// 在独立线程写入文件，音频处理不等待磁盘；发送端全部丢弃后写入线程回填文件头并退出
+pub struct WavRecorder {
+    tx: mpsc::UnboundedSender<Vec<i16>>,
+    handle: JoinHandle<io::Result<()>>,
+}
+
+impl WavRecorder {
+    /// 创建录音文件并启动写入线程
+    ///
+    /// # Arguments
+    /// * `path` - 录音文件路径（已存在时覆盖）
+    /// * `sample_rate` - 音频采样率（Hz）
+    /// * `channels` - 声道数（多声道时 `record` 传入交错采样）
+    pub fn spawn(path: &Path, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        let mut writer = WavWriter::create(path, sample_rate, channels)?;
+        let (tx, mut rx) = mpsc::unbounded_channel::<Vec<i16>>();
+        let file_name = path.display().to_string();
+
+        let handle = std::thread::Builder::new()
+            .name("wav-recorder".to_string())
+            .spawn(move || {
+                while let Some(samples) = rx.blocking_recv() {
+                    if let Err(e) = writer.write_samples(&samples) {
+                        error!("Failed to write audio to {}: {}", file_name, e);
+                        return Err(e);
+                    }
+                }
+
+                writer.finalize()?;
+                info!("Audio recording saved to {}", file_name);
+                Ok(())
+            })?;
+
+        Ok(Self { tx, handle })
+    }
+
+    /// 追加一段音频
+    pub fn record(&self, samples: &[i16]) {
+        // 写入线程出错退出后丢弃后续音频
+        let _ = self.tx.send(samples.to_vec());
+    }
+
+    /// 结束录音，返回写入线程句柄（等待它即可确保文件已完成）
+    pub fn finish(self) -> JoinHandle<io::Result<()>> {
+        drop(self.tx);
+        self.handle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    }
+
+    fn assert_header(bytes: &[u8], samples: usize, channels: u16) {
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32_at(bytes, 4) as usize, 36 + samples * 2);
+        assert_eq!(&bytes[8..16], b"WAVEfmt ");
+        assert_eq!(u16_at(bytes, 20), 1); // PCM
+        assert_eq!(u16_at(bytes, 22), channels); // 声道数
+        assert_eq!(u32_at(bytes, 24), 16000); // 采样率
+        assert_eq!(u32_at(bytes, 28), 32000 * u32::from(channels)); // 字节率
+        assert_eq!(u16_at(bytes, 32), 2 * channels); // 块对齐
+        assert_eq!(u16_at(bytes, 34), 16); // 位深
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(u32_at(bytes, 40) as usize, samples * 2);
+        assert_eq!(bytes.len(), 44 + samples * 2);
+    }
+
+    #[test]
+    fn test_writer_finalizes_header() {
+        let mut writer = WavWriter::new(Cursor::new(Vec::new()), WAV_SAMPLE_RATE, 1).unwrap();
+        writer.write_samples(&[1, -1, i16::MAX]).unwrap();
+        writer.write_samples(&[i16::MIN]).unwrap();
+        let bytes = writer.finalize().unwrap().into_inner();
+
+        assert_header(&bytes, 4, 1);
+        assert_eq!(&bytes[44..46], &1i16.to_le_bytes());
+        assert_eq!(&bytes[50..52], &i16::MIN.to_le_bytes());
+    }
+
+    #[test]
+    fn test_recorder_writes_file() {
+        let path =
+            std::env::temp_dir().join(format!("raflow-recording-{}.wav", std::process::id()));
+
+        let recorder = WavRecorder::spawn(&path, WAV_SAMPLE_RATE, 1).unwrap();
+        for _ in 0..3 {
+            recorder.record(&[100; 160]);
+        }
+        recorder.finish().join().unwrap().unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_header(&bytes, 480, 1);
+        assert_eq!(&bytes[44..46], &100i16.to_le_bytes());
+    }
+
+    #[test]
+    fn test_writer_keeps_stereo_interleaved() {
+        let mut writer = WavWriter::new(Cursor::new(Vec::new()), WAV_SAMPLE_RATE, 2).unwrap();
+        writer.write_samples(&[10, -10, 20, -20]).unwrap();
+        let bytes = writer.finalize().unwrap().into_inner();
+
+        assert_header(&bytes, 4, 2);
+        assert_eq!(&bytes[44..46], &10i16.to_le_bytes());
+        assert_eq!(&bytes[46..48], &(-10i16).to_le_bytes());
+    }
+
+    #[test]
+    fn test_writer_rejects_zero_channels() {
+        assert!(WavWriter::new(Cursor::new(Vec::new()), WAV_SAMPLE_RATE, 0).is_err());
+    }
+}
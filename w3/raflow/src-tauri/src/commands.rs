@@ -2,12 +2,22 @@
 //!
 //! 定义前端可以调用的后端命令
 
-use tauri::{command, AppHandle, Manager, State};
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter, Manager, State};
 use tracing::{debug, error, info, warn};
 
 use crate::AppState;
-use crate::config::ConfigManager;
-use crate::state::RecordingState;
+use crate::audio::{AudioDeviceInfo, AudioLevel, NoiseSuppressionInfo};
+use crate::config::{ConfigManager, EffectiveConfig, LatencyBreakdown, estimate_latency_budget};
+use crate::core::{BundleError, BundleManifest, TranscriptEntry};
+use crate::input::{
+    InjectionConfig, InjectionStrategy, InjectorError, TextInjector, cached_layout,
+};
+use crate::logging::LogHandle;
+use crate::network::{API_KEY_CHECK_TIMEOUT, ClientError, MetricsSnapshot, ScribeClient};
+use crate::state::{ControlError, PendingInjection, RecordingState, StartTrigger};
+use crate::system::{HotkeyManager, ParsedHotkey, SetupStatus, WindowInfo, WindowTracker};
 
 // 重导出 AppConfig 为 Config（兼容前端）
 pub use crate::config::AppConfig as Config;
@@ -17,56 +27,216 @@ pub use crate::config::AppConfig as Config;
 pub async fn get_config(app: AppHandle, _state: State<'_, AppState>) -> Result<Config, String> {
     debug!("Getting config");
 
-    ConfigManager::load(&app).map_err(|e| {
+    ConfigManager::load_async(&app).await.map_err(|e| {
         error!("Failed to load config: {}", e);
         e.to_string()
     })
 }
 
+/// 获取实际生效的连接参数（语言代码和当前语言选用的模型）
+#[command]
+pub async fn get_effective_config(app: AppHandle) -> Result<EffectiveConfig, String> {
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(config.effective())
+}
+
 /// 保存配置
+///
+/// `validate_key` 为 true 且 API Key 有变化时，先校验新的 Key，校验失败则不保存；
+/// 保存后按需重新注册热键，注册失败时恢复旧热键和旧配置
 #[command]
 pub async fn save_config(
     app: AppHandle,
     _state: State<'_, AppState>,
     config: Config,
+    validate_key: Option<bool>,
 ) -> Result<(), String> {
     info!("Saving config: language = {}", config.language);
 
-    ConfigManager::save(&app, &config).map_err(|e| {
-        error!("Failed to save config: {}", e);
-        e.to_string()
-    })
+    let stored = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if validate_key.unwrap_or(false)
+        && !config.api_key.is_empty()
+        && config.api_key != stored.api_key
+    {
+        check_api_key(&config).await.map_err(|e| {
+            warn!("API key check failed: {}", e);
+            format!("API Key 校验失败: {}", e)
+        })?;
+    }
+
+    // 先校验再保存，无效配置不会改动已注册的热键
+    config.validate().map_err(|e| e.to_string())?;
+    let changed = HotkeyManager::needs_reregister(&stored, &config).map_err(|e| e.to_string())?;
+
+    ConfigManager::save_async(&app, &config)
+        .await
+        .map_err(|e| {
+            error!("Failed to save config: {}", e);
+            e.to_string()
+        })?;
+
+    // 热键、模式或附加热键改变时立即重新注册；注册失败时旧热键已恢复，配置也回滚为旧配置
+    if changed && let Err(e) = HotkeyManager::reregister(&app, &stored, &config) {
+        error!("Failed to re-register hotkey: {}", e);
+        if let Err(restore) = ConfigManager::save_async(&app, &stored).await {
+            error!("Failed to restore previous config: {}", restore);
+        }
+        return Err(format!("热键注册失败，可能已被其他应用占用: {}", e));
+    }
+
+    Ok(())
+}
+
+/// 校验 API Key
+///
+/// 使用已保存的端点、模型和代理完成一次握手，收到服务器第一条消息后立即断开；
+/// Key 无效时返回 `authentication_failed` 错误
+#[command]
+pub async fn validate_api_key(app: AppHandle, api_key: String) -> Result<(), ClientError> {
+    info!("Validate API key command");
+
+    let config = Config {
+        api_key,
+        ..ConfigManager::load_async(&app).await.unwrap_or_default()
+    };
+    check_api_key(&config).await
+}
+
+/// 按配置的端点和代理校验其中的 API Key
+async fn check_api_key(config: &Config) -> Result<(), ClientError> {
+    ScribeClient::with_config(config.client_config())
+        .check_api_key(API_KEY_CHECK_TIMEOUT)
+        .await
+}
+
+/// 获取首次运行设置状态
+///
+/// 返回尚未完成的设置步骤，供前端引导新用户
+#[command]
+pub async fn get_setup_status(app: AppHandle) -> Result<SetupStatus, String> {
+    debug!("Getting setup status");
+    use crate::audio::AudioCapture;
+
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    let input = AudioCapture::check_input(config.audio_host.as_deref());
+    let hotkey_registered = HotkeyManager::is_registered(&app, &config.hotkey);
+
+    Ok(SetupStatus::from_checks(
+        &config.api_key,
+        input,
+        hotkey_registered,
+    ))
+}
+
+/// 校验热键字符串
+///
+/// 只解析不注册，返回规范化结果或错误描述，供设置界面即时提示
+#[command]
+pub async fn validate_hotkey(hotkey: String) -> Result<ParsedHotkey, String> {
+    debug!("Validating hotkey: {}", hotkey);
+
+    HotkeyManager::validate(&hotkey).map_err(|e| e.to_string())
+}
+
+/// 估算配置带来的延迟
+///
+/// 传入设置界面当前（可能尚未保存）的配置，供界面提示延迟预算
+#[command]
+pub async fn estimate_latency(config: Config) -> Result<LatencyBreakdown, String> {
+    let budget = estimate_latency_budget(&config);
+    debug!("Estimated latency budget: {:?}", budget);
+    Ok(budget)
 }
 
 /// 开始录音
 #[command]
-pub async fn start_recording(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn start_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), ControlError> {
     info!("Start recording command");
 
-    // 检查是否已在录音
-    if state.get_state() == RecordingState::Recording {
+    start_with_trigger(&app, &state, StartTrigger::Hotkey).await
+}
+
+/// 获取当前录音状态（idle / recording / paused / processing）
+#[command]
+pub async fn get_recording_state(state: State<'_, AppState>) -> Result<String, String> {
+    Ok(state.get_state().as_str().to_string())
+}
+
+/// 获取噪声抑制信息（是否编译、帧长、采样率、当前会话是否生效）
+#[command]
+pub async fn get_noise_suppression_info(
+    state: State<'_, AppState>,
+) -> Result<NoiseSuppressionInfo, String> {
+    Ok(NoiseSuppressionInfo::current(
+        state.noise_suppression_active(),
+    ))
+}
+
+/// 获取当前音频电平（RMS/峰值，未录音时为零），供脚本轮询
+#[command]
+pub async fn get_audio_level(state: State<'_, AppState>) -> Result<AudioLevel, String> {
+    Ok(state.current_level())
+}
+
+/// 获取网络指标（连接时长、平均往返延迟、收发消息数），录音结束后保留最后一次的值
+#[command]
+pub async fn get_metrics(state: State<'_, AppState>) -> Result<MetricsSnapshot, String> {
+    Ok(state.metrics())
+}
+
+/// 获取本次（或最近一次）会话的转写历史，从早到晚排列
+#[command]
+pub async fn get_transcript_history(
+    state: State<'_, AppState>,
+) -> Result<Vec<TranscriptEntry>, String> {
+    Ok(state.transcript_history())
+}
+
+/// 开始录音，等待时间按触发来源决定
+pub async fn start_with_trigger(
+    app: &AppHandle,
+    state: &AppState,
+    trigger: StartTrigger,
+) -> Result<(), ControlError> {
+    // 检查是否已在录音（暂停中的会话也视为进行中）
+    if matches!(
+        state.get_state(),
+        RecordingState::Recording | RecordingState::Paused
+    ) {
         warn!("Already recording");
-        return Err("Already recording".to_string());
+        return Err(ControlError::AlreadyRunning);
     }
 
     // 加载配置
-    let config = ConfigManager::load(&app).map_err(|e| e.to_string())?;
+    let config = ConfigManager::load_async(app)
+        .await
+        .map_err(|e| ControlError::StartFailed(e.to_string()))?;
     if config.api_key.is_empty() {
         warn!("API Key not configured");
-        return Err("请先配置 API Key".to_string());
+        return Err(ControlError::NotConfigured("请先配置 API Key".to_string()));
     }
 
     // 发送开始命令到后台控制任务
-    state.start_recording(config).await?;
+    state.start_recording(config, trigger).await?;
 
-    info!("Recording started");
+    info!("Recording started ({:?})", trigger);
 
     Ok(())
 }
 
 /// 停止录音
 #[command]
-pub async fn stop_recording(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn stop_recording(state: State<'_, AppState>) -> Result<(), ControlError> {
     info!("Stop recording command");
 
     // 发送停止命令到后台控制任务
@@ -77,11 +247,105 @@ pub async fn stop_recording(state: State<'_, AppState>) -> Result<(), String> {
     Ok(())
 }
 
+/// 点击悬浮窗停止录音
+///
+/// 不需要热键即可结束录音，停止后隐藏悬浮窗
+#[command]
+pub async fn stop_via_overlay(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), ControlError> {
+    info!("Stop via overlay command");
+
+    if !state.stop_from_overlay().await? {
+        debug!("Overlay clicked while not recording, ignoring");
+        return Ok(());
+    }
+
+    hide_overlay_after_stop(&app, &state).await;
+
+    info!("Recording stopped via overlay");
+
+    Ok(())
+}
+
+/// 取消录音
+///
+/// 丢弃进行中的音频和转写，不注入任何文本
+#[command]
+pub async fn cancel_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), ControlError> {
+    info!("Cancel recording command");
+
+    state.cancel_recording().await?;
+
+    // 隐藏悬浮窗
+    if let Some(overlay) = app.get_webview_window("overlay") {
+        let _ = overlay.hide();
+    }
+
+    info!("Recording cancelled");
+
+    Ok(())
+}
+
+/// 暂停录音
+///
+/// 停止发送音频但保留 WebSocket 会话
+#[command]
+pub async fn pause_recording(state: State<'_, AppState>) -> Result<(), ControlError> {
+    info!("Pause recording command");
+
+    state.pause_recording().await?;
+
+    info!("Recording paused");
+
+    Ok(())
+}
+
+/// 恢复录音
+#[command]
+pub async fn resume_recording(state: State<'_, AppState>) -> Result<(), ControlError> {
+    info!("Resume recording command");
+
+    state.resume_recording().await?;
+
+    info!("Recording resumed");
+
+    Ok(())
+}
+
+/// 立即提交当前语句
+///
+/// 不等静音窗口，先发出缓冲的音频再发送 commit，录音继续进行
+#[command]
+pub async fn commit_now(state: State<'_, AppState>) -> Result<(), ControlError> {
+    info!("Commit now command");
+
+    state.commit_now().await
+}
+
 /// 切换录音状态（热键触发）
+///
+/// 暂停中的会话视为进行中，切换时直接停止
 #[command]
-pub async fn toggle_recording(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn toggle_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), ControlError> {
     info!("Toggle recording command");
 
+    toggle_with_trigger(&app, &state, StartTrigger::Hotkey).await
+}
+
+/// 切换录音状态，开始录音时的等待时间按触发来源决定
+pub async fn toggle_with_trigger(
+    app: &AppHandle,
+    state: &AppState,
+    trigger: StartTrigger,
+) -> Result<(), ControlError> {
     let current_state = state.get_state();
 
     match current_state {
@@ -89,51 +353,234 @@ pub async fn toggle_recording(app: AppHandle, state: State<'_, AppState>) -> Res
             // 当前空闲，开始录音
             info!("Current idle, starting recording");
 
-            // 显示悬浮窗
+            // 显示悬浮窗（先取消上一次停止安排的隐藏）
+            state.cancel_overlay_hide();
             if let Some(overlay) = app.get_webview_window("overlay") {
                 let _ = overlay.show();
             }
 
-            start_recording(app, state).await?;
+            start_with_trigger(app, state, trigger).await?;
         }
-        RecordingState::Recording | RecordingState::Processing => {
-            // 当前录音中，停止录音
+        RecordingState::Recording | RecordingState::Paused | RecordingState::Processing => {
+            // 当前录音中（或已暂停），停止录音
             info!("Current recording, stopping");
 
-            stop_recording(state).await?;
+            state.stop_recording().await?;
 
-            // 隐藏悬浮窗
-            if let Some(overlay) = app.get_webview_window("overlay") {
-                let _ = overlay.hide();
-            }
+            hide_overlay_after_stop(app, state).await;
         }
     }
 
     Ok(())
 }
 
-/// 获取音频设备列表
+/// 停止录音后按配置的延迟隐藏悬浮窗
+///
+/// 延迟期间开始新的录音时不再隐藏
+pub async fn hide_overlay_after_stop(app: &AppHandle, state: &AppState) {
+    let Some(overlay) = app.get_webview_window("overlay") else {
+        return;
+    };
+
+    let delay_ms = ConfigManager::load_async(app)
+        .await
+        .unwrap_or_default()
+        .overlay_hide_delay_ms;
+    let hide = state.schedule_overlay_hide(Duration::from_millis(delay_ms), move || {
+        let _ = overlay.hide();
+    });
+    tauri::async_runtime::spawn(hide);
+}
+
+/// 获取音频设备列表（配置的音频主机上的设备）
 #[command]
-pub async fn list_audio_devices() -> Result<Vec<String>, String> {
+pub async fn list_audio_devices(app: AppHandle) -> Result<Vec<String>, String> {
     debug!("Listing audio devices");
     use crate::audio::AudioCapture;
 
-    AudioCapture::list_devices().map_err(|e| e.to_string())
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    AudioCapture::list_devices(config.audio_host.as_deref()).map_err(|e| e.to_string())
 }
 
-/// 获取黑名单应用列表
+/// 获取可用的音频输入设备详情（是否默认设备、支持的采样率和声道数）
 #[command]
-pub async fn get_blacklist() -> Result<Vec<String>, String> {
-    use crate::system::WindowTracker;
-    Ok(WindowTracker::get_blacklist())
+pub async fn list_audio_devices_detailed(app: AppHandle) -> Result<Vec<AudioDeviceInfo>, String> {
+    debug!("Listing audio devices with details");
+    use crate::audio::AudioCapture;
+
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    AudioCapture::list_devices_detailed(config.audio_host.as_deref()).map_err(|e| e.to_string())
 }
 
-/// 测试文本注入
+/// 设置输入设备并持久化
+///
+/// 设备名称必须在配置的音频主机的设备列表中（大小写不敏感），保存设备列表中的名称；
+/// 传入 None 或空字符串恢复使用默认设备
 #[command]
-pub async fn test_injection(app: AppHandle, text: String) -> Result<(), String> {
-    info!("Testing injection: {} chars", text.len());
+pub async fn set_input_device(app: AppHandle, device: Option<String>) -> Result<(), String> {
+    use crate::audio::{AudioCapture, select_device_name};
+
+    info!("Setting input device: {:?}", device);
+
+    let mut config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let device = match device.filter(|name| !name.trim().is_empty()) {
+        Some(name) => {
+            let devices = AudioCapture::list_devices(config.audio_host.as_deref())
+                .map_err(|e| e.to_string())?;
+            match select_device_name(&name, devices.iter().map(String::as_str)) {
+                Some(matched) => Some(matched.to_string()),
+                None => {
+                    warn!("Input device not found: {}", name);
+                    return Err(format!("Input device not found: {}", name));
+                }
+            }
+        }
+        None => None,
+    };
+
+    config.input_device = device;
+    ConfigManager::save_async(&app, &config)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    use crate::system::WindowTracker;
+/// 获取可用的音频主机列表（如 WASAPI、ASIO、CoreAudio）
+#[command]
+pub async fn list_audio_hosts() -> Result<Vec<String>, String> {
+    debug!("Listing audio hosts");
+    use crate::audio::AudioCapture;
+
+    Ok(AudioCapture::list_hosts())
+}
+
+/// 获取黑名单应用列表（默认条目与用户条目合并后的列表）
+#[command]
+pub async fn get_blacklist(app: AppHandle) -> Result<Vec<String>, String> {
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(config.blacklist)
+}
+
+/// 添加黑名单条目并持久化
+#[command]
+pub async fn add_blacklist_entry(app: AppHandle, entry: String) -> Result<Vec<String>, String> {
+    info!("Adding blacklist entry: {}", entry);
+
+    let mut config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    if config.add_blacklist_entry(&entry) {
+        ConfigManager::save_async(&app, &config)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(config.blacklist)
+}
+
+/// 移除黑名单条目并持久化
+#[command]
+pub async fn remove_blacklist_entry(
+    app: AppHandle,
+    entry: String,
+) -> Result<Vec<String>, String> {
+    info!("Removing blacklist entry: {}", entry);
+
+    let mut config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    if config.remove_blacklist_entry(&entry) {
+        ConfigManager::save_async(&app, &config)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(config.blacklist)
+}
+
+/// 获取用户词典
+#[command]
+pub async fn get_dictionary(app: AppHandle) -> Result<Vec<(String, String)>, String> {
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(config.dictionary)
+}
+
+/// 添加或更新用户词典条目并持久化
+#[command]
+pub async fn add_dictionary_entry(
+    app: AppHandle,
+    pattern: String,
+    replacement: String,
+) -> Result<Vec<(String, String)>, String> {
+    info!("Adding dictionary entry: {} -> {}", pattern, replacement);
+
+    let mut config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    if config.add_dictionary_entry(&pattern, &replacement) {
+        ConfigManager::save_async(&app, &config)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(config.dictionary)
+}
+
+/// 移除用户词典条目并持久化
+#[command]
+pub async fn remove_dictionary_entry(
+    app: AppHandle,
+    pattern: String,
+) -> Result<Vec<(String, String)>, String> {
+    info!("Removing dictionary entry: {}", pattern);
+
+    let mut config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    if config.remove_dictionary_entry(&pattern) {
+        ConfigManager::save_async(&app, &config)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(config.dictionary)
+}
+
+/// 试运行注入的结果，发送到前端
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunResult {
+    /// 将使用的注入策略
+    pub strategy: InjectionStrategy,
+    /// 目标窗口
+    pub window: WindowInfo,
+}
+
+/// 测试文本注入
+///
+/// `dry_run` 为 true 时只执行检查和策略选择，不实际输入文本，
+/// 结果通过 `injection_dry_run` 事件发送并返回
+#[command]
+pub async fn test_injection(
+    app: AppHandle,
+    text: String,
+    dry_run: Option<bool>,
+) -> Result<Option<DryRunResult>, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    info!(
+        "Testing injection: {} chars (dry run: {})",
+        text.chars().count(),
+        dry_run
+    );
 
     // 获取当前活跃窗口
     let window = WindowTracker::get_current_window().map_err(|e| {
@@ -143,46 +590,230 @@ pub async fn test_injection(app: AppHandle, text: String) -> Result<(), String>
 
     info!("Target window: {} - {}", window.app_name, window.title);
 
-    // 检查是否为黑名单应用
-    if window.is_blacklisted() {
-        warn!("Target window is blacklisted: {}", window.app_name);
-        return Err(format!("黑名单应用: {}", window.app_name));
+    // 检查是否为黑名单应用，以及允许列表模式下是否在允许列表中
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    let injection_config = injection_config(config);
+    check_injection_target(&injection_config, &window)?;
+
+    let strategy =
+        inject_into_window(app.clone(), text, window.clone(), injection_config, dry_run).await?;
+    if !dry_run {
+        return Ok(None);
+    }
+
+    let result = DryRunResult { strategy, window };
+    if let Err(e) = app.emit("injection_dry_run", &result) {
+        warn!("Failed to emit injection_dry_run: {}", e);
     }
+    Ok(Some(result))
+}
 
-    // 在单独的线程中执行注入（因为 Enigo 不是 Send）
-    let app_clone = app.clone();
-    let text_clone = text.clone();
-    let window_clone = window.clone();
+/// 将最近一次提交的转写重新注入到当前焦点窗口
+///
+/// 与录音状态无关；目标窗口变化后会重新执行黑名单 / 允许列表检查
+#[command]
+pub async fn reinject_last(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let window = WindowTracker::get_current_window().map_err(|e| {
+        error!("Failed to get current window: {}", e);
+        e.to_string()
+    })?;
 
-    tokio::task::spawn_blocking(move || {
-        use crate::input::{InjectionConfig, TextInjector};
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    // 快捷键触发时焦点可能仍在本应用的窗口上，不能注入到自己
+    let injection_config = InjectionConfig {
+        own_process_id: Some(std::process::id()),
+        ..injection_config(config)
+    };
+    let text = reinjection_text(&state, &injection_config, &window)?;
+
+    info!(
+        "Re-injecting last transcript: {} chars to {}",
+        text.chars().count(),
+        window.app_name
+    );
+
+    inject_into_window(app, text, window, injection_config, false)
+        .await
+        .map(|_| ())
+}
 
+/// 确认并注入等待确认的大段转写
+///
+/// 注入到提交时的目标窗口（确认时焦点在 raflow 自己的窗口上），未记录目标时使用当前焦点窗口；
+/// 目标窗口同样需要通过黑名单 / 允许列表检查，检查失败时保留待确认的注入
+#[command]
+pub async fn confirm_pending_injection(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let config = ConfigManager::load_async(&app)
+        .await
+        .map_err(|e| e.to_string())?;
+    let injection_config = injection_config(config);
+
+    let PendingInjection { text, target } = state
+        .take_pending_injection()
+        .ok_or_else(|| "没有等待确认的注入".to_string())?;
+
+    let window = match target.clone() {
+        Some(window) => Ok(window),
+        None => WindowTracker::get_current_window().map_err(|e| {
+            error!("Failed to get current window: {}", e);
+            e.to_string()
+        }),
+    }
+    .and_then(|window| check_injection_target(&injection_config, &window).map(|()| window));
+    let window = match window {
+        Ok(window) => window,
+        Err(e) => {
+            state.hold_injection(PendingInjection { text, target });
+            return Err(e);
+        }
+    };
+
+    info!(
+        "Injecting confirmed text: {} chars to {}",
+        text.chars().count(),
+        window.app_name
+    );
+
+    inject_into_window(app, text, window, injection_config, false)
+        .await
+        .map(|_| ())
+}
+
+/// 放弃等待确认的大段转写（转写仍可通过重新注入找回）
+#[command]
+pub async fn discard_pending_injection(state: State<'_, AppState>) -> Result<(), String> {
+    if state.take_pending_injection().is_some() {
+        info!("Pending injection discarded");
+    }
+    Ok(())
+}
+
+/// 按用户配置构建手动注入使用的注入配置
+fn injection_config(config: Config) -> InjectionConfig {
+    InjectionConfig {
+        focus_wait_ms: config.focus_wait_ms,
+        enable_blacklist: config.enable_blacklist,
+        blacklist: config.blacklist,
+        allowlist_mode: config.allowlist_mode,
+        allowlist: config.allowlist,
+        keyboard_layout: cached_layout(),
+        clipboard_on_problematic_layout: config.clipboard_on_problematic_layout,
+        append_after_inject: config.append_after_inject,
+        ..Default::default()
+    }
+}
+
+/// 检查目标窗口是否允许注入，返回前端可直接展示的错误信息
+fn check_injection_target(config: &InjectionConfig, window: &WindowInfo) -> Result<(), String> {
+    match config.check_target(window) {
+        Ok(()) => Ok(()),
+        Err(InjectorError::NotAllowlisted(app_name)) => {
+            warn!("Target window is not allowlisted: {}", app_name);
+            Err(format!("不在允许列表中的应用: {}", app_name))
+        }
+        Err(InjectorError::Blacklisted(app_name)) => {
+            warn!("Target window is blacklisted: {}", app_name);
+            Err(format!("黑名单应用: {}", app_name))
+        }
+        Err(InjectorError::OwnWindow(app_name)) => {
+            warn!("Target window belongs to this app: {}", app_name);
+            Err(format!("焦点在本应用窗口上: {}", app_name))
+        }
+        Err(e) => {
+            warn!("Target window rejected: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// 取出可重新注入的转写，并针对新的目标窗口重新检查
+fn reinjection_text(
+    state: &AppState,
+    config: &InjectionConfig,
+    window: &WindowInfo,
+) -> Result<String, String> {
+    let text = state
+        .last_transcript()
+        .ok_or_else(|| "没有可重新注入的转写".to_string())?;
+    check_injection_target(config, window)?;
+    Ok(text)
+}
+
+/// 在单独的线程中执行注入（因为 Enigo 不是 Send）
+async fn inject_into_window(
+    app: AppHandle,
+    text: String,
+    window: WindowInfo,
+    injection_config: InjectionConfig,
+    dry_run: bool,
+) -> Result<InjectionStrategy, String> {
+    tokio::task::spawn_blocking(move || {
         // 创建注入器
-        let mut injector = TextInjector::with_config(app_clone.clone(), InjectionConfig::default())
-            .map_err(|e| {
-                error!("Failed to create injector: {}", e);
-                e.to_string()
-            })?;
+        let mut injector = TextInjector::with_config(app, injection_config).map_err(|e| {
+            error!("Failed to create injector: {}", e);
+            e.to_string()
+        })?;
 
         // 由于 inject 是 async，需要在 runtime 中运行
         let runtime = tokio::runtime::Handle::current();
-        runtime.block_on(async {
-            injector
-                .inject(&text_clone, &window_clone)
-                .await
-                .map_err(|e| {
-                    error!("Injection failed: {}", e);
-                    e.to_string()
-                })
+        let strategy = runtime.block_on(async {
+            injector.inject(&text, &window, dry_run).await.map_err(|e| {
+                error!("Injection failed: {}", e);
+                e.to_string()
+            })
         })?;
 
         info!("Injection successful");
-        Ok::<(), String>(())
+        Ok::<InjectionStrategy, String>(strategy)
     })
     .await
     .map_err(|e| e.to_string())?
 }
 
+/// 获取日志文件路径列表（最新的在最后），用于提交问题报告
+#[command]
+pub async fn get_log_files(log: State<'_, LogHandle>) -> Result<Vec<String>, String> {
+    Ok(log
+        .files()
+        .into_iter()
+        .map(|path| path.display().to_string())
+        .collect())
+}
+
+/// 把最近一次会话的录音和转写记录打包为 zip，用于提交问题报告
+///
+/// 仅当该会话同时启用了录音另存和转写记录时可用
+#[command]
+pub async fn export_session_bundle(state: State<'_, AppState>, path: String) -> Result<(), String> {
+    if state.get_state() != RecordingState::Idle {
+        return Err("Recording in progress".to_string());
+    }
+
+    let artifacts = state.last_session().ok_or(BundleError::NoSession);
+    let manifest = artifacts
+        .and_then(|artifacts| BundleManifest::for_session(&artifacts))
+        .map_err(|e| e.to_string())?;
+
+    let target = path.clone();
+    tokio::task::spawn_blocking(move || manifest.write_zip(std::path::Path::new(&target)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| {
+            error!("Failed to export session bundle: {}", e);
+            e.to_string()
+        })?;
+
+    info!("Exported session bundle to {}", path);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +834,7 @@ mod tests {
             language: "en".to_string(),
             keyboard_max_chars: 20,
             enable_blacklist: false,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -213,16 +845,68 @@ mod tests {
         assert_eq!(deserialized.keyboard_max_chars, 20);
     }
 
-    #[tokio::test]
-    async fn test_list_audio_devices() {
-        let devices = list_audio_devices().await;
+    #[test]
+    fn test_list_audio_devices() {
+        let devices = crate::audio::AudioCapture::list_devices(None);
         // 可能成功也可能失败（取决于环境）
         println!("Audio devices result: {:?}", devices);
     }
 
-    #[tokio::test]
-    async fn test_get_blacklist() {
-        let blacklist = get_blacklist().await.unwrap();
+    fn window(app_name: &str) -> WindowInfo {
+        WindowInfo {
+            app_name: app_name.to_string(),
+            title: String::new(),
+            process_id: 1,
+            position: (0, 0, 0, 0),
+        }
+    }
+
+    #[test]
+    fn test_reinjection_text() {
+        let (state, _control_rx, _state_tx) = AppState::new();
+        let config = InjectionConfig::default();
+
+        // 尚无转写
+        assert!(reinjection_text(&state, &config, &window("Slack")).is_err());
+
+        state.remember_transcript("hello world".to_string());
+        assert_eq!(
+            reinjection_text(&state, &config, &window("Slack")).unwrap(),
+            "hello world"
+        );
+
+        // 新目标窗口命中黑名单时拒绝
+        let err = reinjection_text(&state, &config, &window("1Password 7")).unwrap_err();
+        assert!(err.contains("1Password"));
+
+        // 允许列表同样针对新窗口重新检查
+        let config = InjectionConfig {
+            allowlist_mode: true,
+            allowlist: vec!["Code".to_string()],
+            ..Default::default()
+        };
+        assert!(reinjection_text(&state, &config, &window("Slack")).is_err());
+        assert!(reinjection_text(&state, &config, &window("Visual Studio Code")).is_ok());
+    }
+
+    #[test]
+    fn test_check_injection_target_reports_reason() {
+        let config = InjectionConfig {
+            own_process_id: Some(1),
+            ..Default::default()
+        };
+        let err = check_injection_target(&config, &window("RaFlow")).unwrap_err();
+        assert!(!err.contains("黑名单"), "{}", err);
+        assert!(err.contains("RaFlow"));
+
+        let config = InjectionConfig::default();
+        let err = check_injection_target(&config, &window("1Password 7")).unwrap_err();
+        assert!(err.starts_with("黑名单应用"), "{}", err);
+    }
+
+    #[test]
+    fn test_default_blacklist() {
+        let blacklist = Config::default().blacklist;
         assert!(!blacklist.is_empty());
         assert!(blacklist.contains(&"1Password".to_string()));
     }
@@ -0,0 +1,116 @@
+//! 延迟预算估算
+//!
+//! 按配置估算音频从采集到转写结果的附加延迟，供设置界面提示配置带来的延迟
+
+use super::AppConfig;
+use crate::audio::{RESAMPLE_FRAME_SIZE, RING_BUFFER_CHUNK_FRAMES};
+use serde::Serialize;
+
+/// 估算使用的采集采样率（常见设备为 48kHz）
+pub const NOMINAL_CAPTURE_RATE: u32 = 48_000;
+
+/// 估算使用的网络往返延迟（毫秒）
+pub const ESTIMATED_NETWORK_MS: u64 = 150;
+
+/// 延迟预算明细（毫秒，均为最坏情况）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct LatencyBreakdown {
+    /// 采集缓冲：一个最大的设备回调块在环形缓冲区中等待
+    pub buffer_ms: u64,
+    /// 重采样：凑满一个固定重采样帧
+    pub resample_ms: u64,
+    /// 批量发送：音频在批次中等待发送
+    pub batch_ms: u64,
+    /// 网络往返（估计值）
+    pub network_ms: u64,
+    /// 静音提交：说完后等待自动提交（只影响最终转写）
+    pub commit_ms: u64,
+    /// 部分转写的延迟合计（不含静音提交）
+    pub streaming_ms: u64,
+    /// 最终转写的延迟合计
+    pub total_ms: u64,
+}
+
+/// 按配置估算延迟预算
+///
+/// 采集相关的延迟按 `NOMINAL_CAPTURE_RATE` 换算，精确到采样点后向上取整到毫秒
+pub fn estimate_latency_budget(config: &AppConfig) -> LatencyBreakdown {
+    let buffer_ms = frames_to_ms(RING_BUFFER_CHUNK_FRAMES, NOMINAL_CAPTURE_RATE);
+    let resample_ms = frames_to_ms(RESAMPLE_FRAME_SIZE, NOMINAL_CAPTURE_RATE);
+    let batch_ms = config.batch_interval_ms;
+    let network_ms = ESTIMATED_NETWORK_MS;
+    let commit_ms = config.silence_commit_ms;
+
+    let streaming_ms = buffer_ms
+        .saturating_add(resample_ms)
+        .saturating_add(batch_ms)
+        .saturating_add(network_ms);
+
+    LatencyBreakdown {
+        buffer_ms,
+        resample_ms,
+        batch_ms,
+        network_ms,
+        commit_ms,
+        streaming_ms,
+        total_ms: streaming_ms.saturating_add(commit_ms),
+    }
+}
+
+/// 采样点数换算为毫秒（向上取整）
+fn frames_to_ms(frames: usize, sample_rate: u32) -> u64 {
+    let rate = u64::from(sample_rate.max(1));
+    (frames as u64 * 1000).div_ceil(rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_to_ms_rounds_up() {
+        assert_eq!(frames_to_ms(480, 48_000), 10);
+        assert_eq!(frames_to_ms(481, 48_000), 11);
+        assert_eq!(frames_to_ms(2048, 48_000), 43);
+        assert_eq!(frames_to_ms(441, 44_100), 10);
+        assert_eq!(frames_to_ms(0, 48_000), 0);
+    }
+
+    #[test]
+    fn test_default_budget() {
+        let budget = estimate_latency_budget(&AppConfig::default());
+        assert_eq!(
+            budget,
+            LatencyBreakdown {
+                buffer_ms: 43,
+                resample_ms: 10,
+                batch_ms: 500,
+                network_ms: ESTIMATED_NETWORK_MS,
+                commit_ms: 2000,
+                streaming_ms: 43 + 10 + 500 + ESTIMATED_NETWORK_MS,
+                total_ms: 43 + 10 + 500 + ESTIMATED_NETWORK_MS + 2000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_low_latency_budget() {
+        let config = AppConfig {
+            batch_interval_ms: 100,
+            silence_commit_ms: 800,
+            ..Default::default()
+        };
+        let budget = estimate_latency_budget(&config);
+        assert_eq!(budget.batch_ms, 100);
+        assert_eq!(budget.streaming_ms, 303);
+        assert_eq!(budget.total_ms, 1103);
+
+        // 批量发送和静音提交只影响各自的部分
+        let slower = estimate_latency_budget(&AppConfig {
+            batch_interval_ms: 600,
+            ..config
+        });
+        assert_eq!(slower.streaming_ms - budget.streaming_ms, 500);
+        assert_eq!(slower.commit_ms, budget.commit_ms);
+    }
+}
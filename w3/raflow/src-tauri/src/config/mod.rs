@@ -2,11 +2,31 @@
 //!
 //! 使用 Tauri Store 插件持久化配置
 
+mod latency;
+
+pub use latency::{
+    ESTIMATED_NETWORK_MS, LatencyBreakdown, NOMINAL_CAPTURE_RATE, estimate_latency_budget,
+};
+
+use crate::audio::{
+    AgcConfig, AudioProcessorConfig, CaptureMode, DEFAULT_AGC_MAX_GAIN, DEFAULT_AGC_TARGET_DBFS,
+    NoiseSuppressionLevel, Quality, RING_BUFFER_CHUNK_FRAMES, RING_BUFFER_CHUNKS,
+};
+use crate::core::{DEFAULT_HISTORY_SIZE, PostProcessStep, SpokenSymbol, default_fillers};
+use crate::input::AppendMode;
+use crate::network::{
+    ClientConfig, DEFAULT_BASE_URL, DEFAULT_ENCODING, DEFAULT_MODEL_ID, ProxyConfig, SttProvider,
+    encoding_to_rate, is_known_language, language_code_for, model_for_language,
+    resolve_proxy_url, validate_endpoint,
+};
+use crate::system::{HotkeyManager, WindowTracker};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 use thiserror::Error;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -18,20 +38,190 @@ pub enum ConfigError {
 
     #[error("Store not available")]
     StoreNotAvailable,
+
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    #[error("Invalid config: {0}")]
+    Invalid(String),
 }
 
 type Result<T> = std::result::Result<T, ConfigError>;
 
+/// 键盘策略最大字符数的允许范围
+pub const KEYBOARD_MAX_CHARS_RANGE: std::ops::RangeInclusive<usize> = 1..=1000;
+
+/// 焦点归还等待时间的建议下限（毫秒），低于该值时加载配置会给出警告
+pub const MIN_FOCUS_WAIT_MS: u64 = 50;
+
 const STORE_PATH: &str = "config.json";
 
+/// 热键模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyMode {
+    /// 按一次开始，再按一次停止
+    #[default]
+    Toggle,
+    /// 按住录音，松开立即停止
+    PushToTalk,
+}
+
+/// 附加热键
+///
+/// 主热键（`hotkey`）之外的独立快捷键，未配置的项不注册
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyBindings {
+    /// 切换录音（始终按切换模式工作，与主热键的模式无关）
+    pub toggle: Option<String>,
+    /// 取消录音，丢弃进行中的音频和转写
+    pub cancel: Option<String>,
+    /// 打开设置窗口
+    pub settings: Option<String>,
+    /// 立即提交当前语句（不等静音窗口）
+    pub commit: Option<String>,
+}
+
 /// 应用配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub api_key: String,
     pub hotkey: String,
+    /// 热键模式（切换或按住说话）
+    pub hotkey_mode: HotkeyMode,
+    /// 附加热键（切换、取消、打开设置、手动提交）
+    pub hotkeys: Option<HotkeyBindings>,
     pub language: String,
     pub keyboard_max_chars: usize,
+    /// 注入前隐藏悬浮窗后等待焦点归还的时间（毫秒），按原值使用
+    ///
+    /// 快速机器上可以调低以减少注入延迟；过低时焦点可能尚未归还，
+    /// 需要依赖之后的焦点轮询，低于 `MIN_FOCUS_WAIT_MS` 时加载配置会警告
+    pub focus_wait_ms: u64,
     pub enable_blacklist: bool,
+    /// 黑名单应用名称模式（默认包含常见密码管理器）
+    pub blacklist: Vec<String>,
+    /// 允许列表模式：启用后只向允许列表中的应用注入文本
+    pub allowlist_mode: bool,
+    /// 允许注入的应用名称模式（仅在允许列表模式下生效，黑名单优先）
+    pub allowlist: Vec<String>,
+    /// 音频主机名称（如 WASAPI、ASIO、CoreAudio），None 表示使用系统默认
+    pub audio_host: Option<String>,
+    /// 输入设备名称，None 表示使用默认设备；设备不存在时回退到默认设备
+    pub input_device: Option<String>,
+    /// 重复提交转写的去重窗口（毫秒），0 表示禁用
+    pub duplicate_commit_window_ms: u64,
+    /// 连续无语音多少秒后自动断开，0 表示禁用
+    pub idle_disconnect_secs: u64,
+    /// 连续无语音多少秒后提醒用户（悬浮窗提示，不断开），0 表示禁用
+    pub dead_air_secs: u64,
+    /// 停止录音时，是否将未提交的最后一条部分转写作为结果注入
+    pub promote_partial_on_stop: bool,
+    /// 停止后等待服务器提交的宽限期（毫秒），超时才提升部分转写
+    pub partial_promotion_grace_ms: u64,
+    /// 停止后等待剩余音频发出并收到最终转写的最长时间（毫秒），0 表示不等待
+    pub stop_drain_timeout_ms: u64,
+    /// 最终转写的最低置信度，低于该值时不注入（服务器未返回置信度时视为通过），0 表示不过滤
+    pub min_confidence: f32,
+    /// 同时执行的文本注入任务上限，其余任务排队等待
+    pub max_concurrent_injections: usize,
+    /// 相邻两次注入的最小间隔（毫秒），避免触发目标应用的限流，0 表示不限制
+    pub min_interval_between_injections_ms: u64,
+    /// 键盘布局已知会打错 ASCII 符号时（如德语、法语布局），改用剪贴板注入（默认关闭）
+    pub clipboard_on_problematic_layout: bool,
+    /// 是否保留最近一次提交的转写，供重新注入到其他应用
+    pub remember_last_transcript: bool,
+    /// 会话转写历史最多保留的条数（供"最近听写"面板），0 表示不记录
+    pub transcript_history_size: usize,
+    /// 实时注入部分转写：边说边注入新增的文字，不回退修正，最终结果可能与提交的转写略有出入
+    ///
+    /// 注入的是服务器的原始文本，不能与用户词典、口述符号和后处理同时使用（见 `streams_partials`）
+    pub inject_partials: bool,
+    /// 实时输入：边说边输入部分转写，转写被修订时退格修正，提交时修正为最终结果（优先于 `inject_partials`）
+    pub live_typing: bool,
+    /// 焦点在本应用自己的窗口（如设置窗口）时跳过注入，改为复制到剪贴板并通知前端
+    pub skip_own_windows: bool,
+    /// 提交超过该字符数时先暂存，等待用户确认后再注入；None 表示不确认
+    pub confirm_above_chars: Option<usize>,
+    /// 注入成功后追加空格或回车（剪贴板策略未自动粘贴时不追加）
+    pub append_after_inject: AppendMode,
+    /// 日志级别（trace/debug/info/warn/error），设置 RUST_LOG 时以环境变量为准
+    pub log_level: String,
+    /// 是否裁剪会话开头（开始说话之前）的静音
+    pub trim_leading_silence: bool,
+    /// 连接失败后的最大重试次数（每次重试的等待时间指数增长）
+    pub max_retries: u32,
+    /// 连接保活（ping）间隔（秒）
+    pub keepalive_interval_secs: u64,
+    /// 超过该时长（秒）未收到服务器回应视为连接已断开并重连，0 表示不检测
+    pub pong_timeout_secs: u64,
+    /// 对噪声抑制级别混合比例的缩放（0.0 = 原始音频，1.0 = 按级别降噪）
+    pub denoise_mix: f32,
+    /// 噪声抑制级别（级别越高残留噪声越少，但失真可能更明显）
+    pub noise_suppression_level: NoiseSuppressionLevel,
+    /// 检测到语音后静音门限至少保持打开的时长（毫秒），避免词间停顿被截断
+    pub silence_hold_ms: u64,
+    /// 重采样质量，`auto_once` 表示录音开始时试运行后选择满足实时要求的最高质量
+    pub resampler_quality: Quality,
+    /// 多声道输入的采集方式：混为单声道、只取左/右声道，或保留立体声（不降噪）
+    pub capture_mode: CaptureMode,
+    /// 把每次录音发送的 16kHz 音频另存为 WAV 文件（覆盖旧文件），用于排查转写错误；None 表示不保存
+    pub record_to_file: Option<String>,
+    /// 把每次录音的最终转写另存为 JSON 文件（覆盖旧文件），与录音文件一起可导出为会话包；None 表示不保存
+    pub transcript_log_file: Option<String>,
+    /// 是否启用自动增益，把小声说话提升到目标电平
+    pub agc_enabled: bool,
+    /// 自动增益的目标电平（dBFS）
+    pub agc_target_dbfs: f32,
+    /// 自动增益的最大增益（倍数）
+    pub agc_max_gain: f32,
+    /// 采集预录时长（毫秒），0 表示禁用；启用后空闲时麦克风保持打开，
+    /// 开始录音时带上之前这段音频，避免截掉第一个字
+    pub capture_pre_roll_ms: u64,
+    /// 采集环形缓冲区块数（至少 8）；处理可能长时间落后时调大，避免队列满后丢弃音频
+    pub ring_capacity: usize,
+    /// 采集环形缓冲区每块的帧数（64 到 16384 之间的 2 的幂）；低内存设备可以调小
+    pub ring_chunk_size: usize,
+    /// 语音转写服务商
+    pub provider: SttProvider,
+    /// 默认转写模型
+    pub model_id: String,
+    /// 自定义 WebSocket 端点（区域代理或兼容网关），None 表示使用默认端点
+    pub endpoint: Option<String>,
+    /// 按语言选择的转写模型（界面语言代码 -> 模型 ID），未列出的语言使用 `model_id`
+    pub language_models: HashMap<String, String>,
+    /// 发送音频的编码格式（如 `pcm_16000`），采集音频按其采样率重采样
+    pub audio_encoding: String,
+    /// 连接转写服务使用的代理（`http://` 或 `socks5://`），None 时读取 `HTTPS_PROXY` 环境变量
+    pub proxy_url: Option<String>,
+    /// 热键开始录音后，开始采集前的等待时间（毫秒）
+    pub hotkey_start_grace_ms: u64,
+    /// 托盘点击开始录音后，开始采集前的等待时间（毫秒），避免录进点击声
+    pub tray_start_grace_ms: u64,
+    /// 停止录音后延迟多久隐藏悬浮窗（毫秒），期间开始新的录音时不再隐藏
+    pub overlay_hide_delay_ms: u64,
+    /// 开始录音的超时时间（毫秒），音频初始化或建立连接超过该时间时放弃并报错
+    pub start_timeout_ms: u64,
+    /// 音频批量发送间隔（毫秒，有效范围 100-2000，超出时截断）
+    pub batch_interval_ms: u64,
+    /// 静音多久后自动提交当前语句（毫秒）
+    pub silence_commit_ms: u64,
+    /// 录音中目标窗口切换时立即提交当前语句，之后的语句以新窗口为目标
+    pub commit_on_window_change: bool,
+    /// 音频最大延迟（毫秒），网络阻塞后积压超过该时长的音频直接丢弃以保持实时，0 表示不丢弃
+    pub max_audio_age_ms: u64,
+    /// 用户词典：`(匹配词, 替换词)`，注入前按整词、不区分大小写替换
+    pub dictionary: Vec<(String, String)>,
+    /// 口述符号：把关键词（如 "semicolon"）替换为符号，可配置说出后立即提交当前语句
+    pub spoken_symbols: Vec<SpokenSymbol>,
+    /// 转写后处理步骤，按顺序作用于提交的文本（注入和发送最终转写事件之前）
+    pub post_processing: Vec<PostProcessStep>,
+    /// 注入前去掉语气词（如 "um"、"嗯"），在其他后处理步骤之前执行
+    pub strip_fillers: bool,
+    /// 语气词列表，为空时使用界面语言的默认列表
+    pub filler_words: Vec<String>,
 }
 
 impl Default for AppConfig {
@@ -39,9 +229,367 @@ impl Default for AppConfig {
         Self {
             api_key: String::new(),
             hotkey: "CommandOrControl+Shift+\\".to_string(),
+            hotkey_mode: HotkeyMode::default(),
+            hotkeys: None,
             language: "zh".to_string(),
             keyboard_max_chars: 10,
+            focus_wait_ms: 200,
             enable_blacklist: true,
+            blacklist: WindowTracker::get_blacklist(),
+            allowlist_mode: false,
+            allowlist: Vec::new(),
+            audio_host: None,
+            input_device: None,
+            duplicate_commit_window_ms: 3000,
+            idle_disconnect_secs: 60,
+            dead_air_secs: 0,
+            promote_partial_on_stop: true,
+            partial_promotion_grace_ms: 800,
+            stop_drain_timeout_ms: 3000,
+            min_confidence: 0.0,
+            max_concurrent_injections: 1,
+            min_interval_between_injections_ms: 0,
+            clipboard_on_problematic_layout: false,
+            remember_last_transcript: true,
+            transcript_history_size: DEFAULT_HISTORY_SIZE,
+            inject_partials: false,
+            live_typing: false,
+            skip_own_windows: true,
+            confirm_above_chars: None,
+            append_after_inject: AppendMode::None,
+            log_level: "debug".to_string(),
+            trim_leading_silence: true,
+            max_retries: 3,
+            keepalive_interval_secs: 15,
+            pong_timeout_secs: 30,
+            denoise_mix: 1.0,
+            noise_suppression_level: NoiseSuppressionLevel::default(),
+            silence_hold_ms: 1000,
+            resampler_quality: Quality::Low,
+            capture_mode: CaptureMode::Mono,
+            record_to_file: None,
+            transcript_log_file: None,
+            agc_enabled: false,
+            agc_target_dbfs: DEFAULT_AGC_TARGET_DBFS,
+            agc_max_gain: DEFAULT_AGC_MAX_GAIN,
+            capture_pre_roll_ms: 0,
+            ring_capacity: RING_BUFFER_CHUNKS,
+            ring_chunk_size: RING_BUFFER_CHUNK_FRAMES,
+            provider: SttProvider::default(),
+            model_id: DEFAULT_MODEL_ID.to_string(),
+            endpoint: None,
+            language_models: HashMap::new(),
+            audio_encoding: DEFAULT_ENCODING.to_string(),
+            proxy_url: None,
+            hotkey_start_grace_ms: 0,
+            tray_start_grace_ms: 300,
+            overlay_hide_delay_ms: 500,
+            start_timeout_ms: 10_000,
+            batch_interval_ms: 500,
+            silence_commit_ms: 2000,
+            commit_on_window_change: false,
+            max_audio_age_ms: 3000,
+            dictionary: Vec::new(),
+            spoken_symbols: Vec::new(),
+            post_processing: Vec::new(),
+            strip_fillers: false,
+            filler_words: Vec::new(),
+        }
+    }
+}
+
+/// 实际生效的连接参数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffectiveConfig {
+    /// Scribe 使用的语言代码
+    pub language_code: String,
+    /// 当前语言选用的转写模型
+    pub model_id: String,
+}
+
+impl AppConfig {
+    /// 按当前配置构建转写客户端配置
+    pub fn client_config(&self) -> ClientConfig {
+        ClientConfig {
+            trim_leading_silence: self.trim_leading_silence,
+            max_retries: self.max_retries,
+            keepalive_interval: Duration::from_secs(self.keepalive_interval_secs.max(1)),
+            pong_timeout: Duration::from_secs(self.pong_timeout_secs),
+            model_id: self.resolved_model_id(),
+            base_url: self.resolved_endpoint(),
+            batch_interval: Duration::from_millis(self.batch_interval_ms),
+            silence_commit: Duration::from_millis(self.silence_commit_ms),
+            max_frame_age: Duration::from_millis(self.max_audio_age_ms),
+            encoding: self.audio_encoding.clone(),
+            proxy_url: self.resolved_proxy_url(),
+            final_commit_timeout: Duration::from_millis(self.stop_drain_timeout_ms),
+            ..ClientConfig::with_language(self.api_key.clone(), &self.language)
+        }
+    }
+
+    /// 当前语言使用的转写模型
+    pub fn resolved_model_id(&self) -> String {
+        model_for_language(&self.language_models, &self.language, &self.model_id)
+    }
+
+    /// 实际使用的 WebSocket 端点（未配置或为空时使用默认端点）
+    pub fn resolved_endpoint(&self) -> String {
+        self.endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|endpoint| !endpoint.is_empty())
+            .unwrap_or(DEFAULT_BASE_URL)
+            .to_string()
+    }
+
+    /// 实际使用的代理地址（未配置或为空时读取 `HTTPS_PROXY` 环境变量，端点命中 `NO_PROXY` 时直连）
+    pub fn resolved_proxy_url(&self) -> Option<String> {
+        resolve_proxy_url(
+            self.proxy_url.as_deref(),
+            &self.resolved_endpoint(),
+            |name| std::env::var(name).ok(),
+        )
+    }
+
+    /// 实际执行的后处理步骤
+    ///
+    /// 启用语气词过滤时在配置的步骤之前去掉语气词，未配置语气词时使用当前语言的默认列表
+    pub fn resolved_post_processing(&self) -> Vec<PostProcessStep> {
+        let fillers = self.strip_fillers.then(|| {
+            let fillers = if self.filler_words.is_empty() {
+                default_fillers(&self.language)
+            } else {
+                self.filler_words.clone()
+            };
+            PostProcessStep::StripFillers(fillers)
+        });
+
+        fillers
+            .into_iter()
+            .chain(self.post_processing.iter().cloned())
+            .collect()
+    }
+
+    /// 是否配置了会改写转写文本的处理（用户词典、口述符号、后处理）
+    pub fn transforms_text(&self) -> bool {
+        !self.dictionary.is_empty()
+            || !self.spoken_symbols.is_empty()
+            || !self.resolved_post_processing().is_empty()
+    }
+
+    /// 是否按部分转写实时注入
+    ///
+    /// 实时注入的片段已经输入到目标应用，无法再按词典、口述符号或后处理改写，
+    /// 配置了这些处理时改为提交后整句注入；实时输入优先
+    pub fn streams_partials(&self) -> bool {
+        self.inject_partials && !self.live_typing && !self.transforms_text()
+    }
+
+    /// 检查配置是否可以保存
+    ///
+    /// 自定义端点必须是 `ws://` 或 `wss://` 地址；键盘策略阈值须在
+    /// `KEYBOARD_MAX_CHARS_RANGE` 内，热键须能解析，语言须是支持的代码
+    pub fn validate(&self) -> Result<()> {
+        validate_endpoint(&self.resolved_endpoint())
+            .map_err(|e| ConfigError::InvalidEndpoint(e.to_string()))?;
+
+        if !KEYBOARD_MAX_CHARS_RANGE.contains(&self.keyboard_max_chars) {
+            return Err(ConfigError::Invalid(format!(
+                "keyboard_max_chars must be between {} and {}, got {}",
+                KEYBOARD_MAX_CHARS_RANGE.start(),
+                KEYBOARD_MAX_CHARS_RANGE.end(),
+                self.keyboard_max_chars
+            )));
+        }
+
+        if self.hotkey.trim().is_empty() {
+            return Err(ConfigError::Invalid("hotkey must not be empty".to_string()));
+        }
+        HotkeyManager::validate(&self.hotkey).map_err(|e| {
+            ConfigError::Invalid(format!("hotkey '{}' is invalid: {}", self.hotkey, e))
+        })?;
+        HotkeyManager::bindings(self).map_err(|e| ConfigError::Invalid(e.to_string()))?;
+
+        if !is_known_language(&self.language) {
+            return Err(ConfigError::Invalid(format!(
+                "unsupported language '{}'",
+                self.language
+            )));
+        }
+
+        if encoding_to_rate(&self.audio_encoding).is_none() {
+            return Err(ConfigError::Invalid(format!(
+                "unsupported audio encoding '{}'",
+                self.audio_encoding
+            )));
+        }
+
+        if let Some(proxy_url) = self.proxy_url.as_deref().filter(|url| !url.trim().is_empty()) {
+            ProxyConfig::parse(proxy_url).map_err(|e| ConfigError::Invalid(e.to_string()))?;
+        }
+
+        self.processor_config()
+            .validate()
+            .map_err(|e| ConfigError::Invalid(e.to_string()))?;
+
+        if self.inject_partials && !self.live_typing && self.transforms_text() {
+            return Err(ConfigError::Invalid(
+                "inject_partials cannot be combined with the dictionary, spoken symbols or post-processing"
+                    .to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err(ConfigError::Invalid(format!(
+                "min_confidence must be between 0 and 1, got {}",
+                self.min_confidence
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// 音频处理器配置（自动增益和采集环形缓冲区）
+    ///
+    /// 录音文件路径与会话有关，由调用方填写
+    pub fn processor_config(&self) -> AudioProcessorConfig {
+        AudioProcessorConfig {
+            agc: AgcConfig {
+                enabled: self.agc_enabled,
+                target_dbfs: self.agc_target_dbfs,
+                max_gain: self.agc_max_gain,
+            },
+            record_to_file: None,
+            ring_capacity: self.ring_capacity,
+            ring_chunk_size: self.ring_chunk_size,
+        }
+    }
+
+    /// 实际生效的连接参数
+    pub fn effective(&self) -> EffectiveConfig {
+        EffectiveConfig {
+            language_code: language_code_for(&self.language),
+            model_id: self.resolved_model_id(),
+        }
+    }
+
+    /// 添加黑名单条目
+    ///
+    /// # Returns
+    /// * `true` - 已添加
+    /// * `false` - 条目为空或已存在
+    pub fn add_blacklist_entry(&mut self, entry: &str) -> bool {
+        let entry = entry.trim();
+        if entry.is_empty() || self.blacklist.iter().any(|e| e == entry) {
+            return false;
+        }
+
+        self.blacklist.push(entry.to_string());
+        true
+    }
+
+    /// 移除黑名单条目
+    ///
+    /// # Returns
+    /// * `true` - 已移除
+    /// * `false` - 条目不存在
+    pub fn remove_blacklist_entry(&mut self, entry: &str) -> bool {
+        let entry = entry.trim();
+        let len = self.blacklist.len();
+        self.blacklist.retain(|e| e != entry);
+        self.blacklist.len() != len
+    }
+
+    /// 添加或更新词典条目（匹配词不区分大小写）
+    ///
+    /// # Returns
+    /// * `true` - 已添加或替换词已更新
+    /// * `false` - 条目为空或已存在
+    pub fn add_dictionary_entry(&mut self, pattern: &str, replacement: &str) -> bool {
+        let (pattern, replacement) = (pattern.trim(), replacement.trim());
+        if pattern.is_empty() || replacement.is_empty() {
+            return false;
+        }
+
+        match self
+            .dictionary
+            .iter_mut()
+            .find(|(p, _)| p.to_lowercase() == pattern.to_lowercase())
+        {
+            Some((_, existing)) if existing == replacement => false,
+            Some((_, existing)) => {
+                *existing = replacement.to_string();
+                true
+            }
+            None => {
+                self.dictionary
+                    .push((pattern.to_string(), replacement.to_string()));
+                true
+            }
+        }
+    }
+
+    /// 移除词典条目（匹配词不区分大小写）
+    ///
+    /// # Returns
+    /// * `true` - 已移除
+    /// * `false` - 条目不存在
+    pub fn remove_dictionary_entry(&mut self, pattern: &str) -> bool {
+        let pattern = pattern.trim().to_lowercase();
+        let len = self.dictionary.len();
+        self.dictionary.retain(|(p, _)| p.to_lowercase() != pattern);
+        self.dictionary.len() != len
+    }
+}
+
+/// store 操作的重试策略
+///
+/// 两个窗口同时保存时 store 文件可能被短暂锁定，IO 错误会按间隔重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreRetry {
+    /// 首次失败后的最大重试次数
+    pub retries: u32,
+    /// 每次重试前的等待时间
+    pub delay: Duration,
+}
+
+impl Default for StoreRetry {
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            delay: Duration::from_millis(50),
+        }
+    }
+}
+
+impl StoreRetry {
+    /// 执行 store 操作，IO 错误时重试
+    ///
+    /// 首次成功时直接返回，不产生额外开销；序列化等非 IO 错误不重试。
+    /// 重试间隔会阻塞当前线程，异步上下文应通过 [`ConfigManager::load_async`]
+    /// 或 [`ConfigManager::save_async`] 调用
+    ///
+    /// # Arguments
+    /// * `operation` - 操作名称（用于日志）
+    /// * `op` - store 操作
+    pub fn run<T>(
+        &self,
+        operation: &str,
+        mut op: impl FnMut() -> tauri_plugin_store::Result<T>,
+    ) -> tauri_plugin_store::Result<T> {
+        let mut attempt = 0;
+        loop {
+            match op() {
+                Err(tauri_plugin_store::Error::Io(e)) if attempt < self.retries => {
+                    attempt += 1;
+                    warn!(
+                        "Store {} failed ({}), retrying ({}/{})",
+                        operation, e, attempt, self.retries
+                    );
+                    std::thread::sleep(self.delay);
+                }
+                result => return result,
+            }
         }
     }
 }
@@ -55,10 +603,25 @@ impl ConfigManager {
     /// # Arguments
     /// * `app` - Tauri AppHandle
     pub fn load(app: &AppHandle) -> Result<AppConfig> {
+        Self::load_with_retry(app, StoreRetry::default())
+    }
+
+    /// 在阻塞线程池中加载配置
+    ///
+    /// store 被锁定时的重试会阻塞当前线程，异步上下文中应使用该方法
+    pub async fn load_async(app: &AppHandle) -> Result<AppConfig> {
+        let app = app.clone();
+        tokio::task::spawn_blocking(move || Self::load(&app))
+            .await
+            .map_err(|e| ConfigError::LoadFailed(e.to_string()))?
+    }
+
+    /// 加载配置，store 暂时不可用时按 `retry` 重试
+    pub fn load_with_retry(app: &AppHandle, retry: StoreRetry) -> Result<AppConfig> {
         debug!("Loading config from store");
 
-        let store = app
-            .store(STORE_PATH)
+        let store = retry
+            .run("open", || app.store(STORE_PATH))
             .map_err(|e| ConfigError::LoadFailed(e.to_string()))?;
 
         // 尝试从 store 读取配置
@@ -73,12 +636,21 @@ impl ConfigManager {
             return Ok(AppConfig::default());
         }
 
+        let defaults = AppConfig::default();
         let config = AppConfig {
             api_key,
             hotkey: store
                 .get("hotkey")
                 .and_then(|v| v.as_str().map(|s| s.to_string()))
                 .unwrap_or_else(|| "CommandOrControl+Shift+\\".to_string()),
+            hotkey_mode: store
+                .get("hotkey_mode")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.hotkey_mode),
+            hotkeys: store
+                .get("hotkeys")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.hotkeys),
             language: store
                 .get("language")
                 .and_then(|v| v.as_str().map(|s| s.to_string()))
@@ -87,12 +659,276 @@ impl ConfigManager {
                 .get("keyboard_max_chars")
                 .and_then(|v| v.as_u64())
                 .unwrap_or(10) as usize,
+            focus_wait_ms: store
+                .get("focus_wait_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.focus_wait_ms),
             enable_blacklist: store
                 .get("enable_blacklist")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(true),
+            blacklist: store
+                .get("blacklist")
+                .and_then(|v| {
+                    v.as_array().map(|entries| {
+                        entries
+                            .iter()
+                            .filter_map(|e| e.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                })
+                .unwrap_or(defaults.blacklist),
+            allowlist_mode: store
+                .get("allowlist_mode")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.allowlist_mode),
+            allowlist: store
+                .get("allowlist")
+                .and_then(|v| {
+                    v.as_array().map(|entries| {
+                        entries
+                            .iter()
+                            .filter_map(|e| e.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                })
+                .unwrap_or(defaults.allowlist),
+            audio_host: store
+                .get("audio_host")
+                .and_then(|v| v.as_str().map(|s| s.to_string())),
+            input_device: store
+                .get("input_device")
+                .and_then(|v| v.as_str().map(|s| s.to_string())),
+            duplicate_commit_window_ms: store
+                .get("duplicate_commit_window_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.duplicate_commit_window_ms),
+            idle_disconnect_secs: store
+                .get("idle_disconnect_secs")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.idle_disconnect_secs),
+            dead_air_secs: store
+                .get("dead_air_secs")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.dead_air_secs),
+            promote_partial_on_stop: store
+                .get("promote_partial_on_stop")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.promote_partial_on_stop),
+            partial_promotion_grace_ms: store
+                .get("partial_promotion_grace_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.partial_promotion_grace_ms),
+            stop_drain_timeout_ms: store
+                .get("stop_drain_timeout_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.stop_drain_timeout_ms),
+            min_confidence: store
+                .get("min_confidence")
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(defaults.min_confidence),
+            max_concurrent_injections: store
+                .get("max_concurrent_injections")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or(defaults.max_concurrent_injections),
+            min_interval_between_injections_ms: store
+                .get("min_interval_between_injections_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.min_interval_between_injections_ms),
+            clipboard_on_problematic_layout: store
+                .get("clipboard_on_problematic_layout")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.clipboard_on_problematic_layout),
+            remember_last_transcript: store
+                .get("remember_last_transcript")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.remember_last_transcript),
+            transcript_history_size: store
+                .get("transcript_history_size")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or(defaults.transcript_history_size),
+            inject_partials: store
+                .get("inject_partials")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.inject_partials),
+            live_typing: store
+                .get("live_typing")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.live_typing),
+            skip_own_windows: store
+                .get("skip_own_windows")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.skip_own_windows),
+            confirm_above_chars: store
+                .get("confirm_above_chars")
+                .and_then(|v| v.as_u64())
+                .and_then(|n| usize::try_from(n).ok()),
+            append_after_inject: store
+                .get("append_after_inject")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.append_after_inject),
+            log_level: store
+                .get("log_level")
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or(defaults.log_level),
+            trim_leading_silence: store
+                .get("trim_leading_silence")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.trim_leading_silence),
+            max_retries: store
+                .get("max_retries")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32)
+                .unwrap_or(defaults.max_retries),
+            keepalive_interval_secs: store
+                .get("keepalive_interval_secs")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.keepalive_interval_secs),
+            pong_timeout_secs: store
+                .get("pong_timeout_secs")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.pong_timeout_secs),
+            denoise_mix: store
+                .get("denoise_mix")
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(defaults.denoise_mix),
+            noise_suppression_level: store
+                .get("noise_suppression_level")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.noise_suppression_level),
+            silence_hold_ms: store
+                .get("silence_hold_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.silence_hold_ms),
+            resampler_quality: store
+                .get("resampler_quality")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.resampler_quality),
+            capture_mode: store
+                .get("capture_mode")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.capture_mode),
+            record_to_file: store
+                .get("record_to_file")
+                .and_then(|v| v.as_str().map(|s| s.to_string())),
+            transcript_log_file: store
+                .get("transcript_log_file")
+                .and_then(|v| v.as_str().map(|s| s.to_string())),
+            agc_enabled: store
+                .get("agc_enabled")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.agc_enabled),
+            agc_target_dbfs: store
+                .get("agc_target_dbfs")
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(defaults.agc_target_dbfs),
+            agc_max_gain: store
+                .get("agc_max_gain")
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(defaults.agc_max_gain),
+            capture_pre_roll_ms: store
+                .get("capture_pre_roll_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.capture_pre_roll_ms),
+            ring_capacity: store
+                .get("ring_capacity")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or(defaults.ring_capacity),
+            ring_chunk_size: store
+                .get("ring_chunk_size")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or(defaults.ring_chunk_size),
+            provider: store
+                .get("provider")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.provider),
+            model_id: store
+                .get("model_id")
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or(defaults.model_id),
+            endpoint: store
+                .get("endpoint")
+                .and_then(|v| v.as_str().map(|s| s.to_string())),
+            language_models: store
+                .get("language_models")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.language_models),
+            audio_encoding: store
+                .get("audio_encoding")
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or(defaults.audio_encoding),
+            proxy_url: store
+                .get("proxy_url")
+                .and_then(|v| v.as_str().map(|s| s.to_string())),
+            hotkey_start_grace_ms: store
+                .get("hotkey_start_grace_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.hotkey_start_grace_ms),
+            tray_start_grace_ms: store
+                .get("tray_start_grace_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.tray_start_grace_ms),
+            overlay_hide_delay_ms: store
+                .get("overlay_hide_delay_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.overlay_hide_delay_ms),
+            start_timeout_ms: store
+                .get("start_timeout_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.start_timeout_ms),
+            batch_interval_ms: store
+                .get("batch_interval_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.batch_interval_ms),
+            silence_commit_ms: store
+                .get("silence_commit_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.silence_commit_ms),
+            commit_on_window_change: store
+                .get("commit_on_window_change")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.commit_on_window_change),
+            max_audio_age_ms: store
+                .get("max_audio_age_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(defaults.max_audio_age_ms),
+            dictionary: store
+                .get("dictionary")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.dictionary),
+            spoken_symbols: store
+                .get("spoken_symbols")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.spoken_symbols),
+            post_processing: store
+                .get("post_processing")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.post_processing),
+            strip_fillers: store
+                .get("strip_fillers")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.strip_fillers),
+            filler_words: store
+                .get("filler_words")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or(defaults.filler_words),
         };
 
+        if config.focus_wait_ms < MIN_FOCUS_WAIT_MS {
+            warn!(
+                "focus_wait_ms = {} is below {}ms, injection may start before focus returns",
+                config.focus_wait_ms, MIN_FOCUS_WAIT_MS
+            );
+        }
+
         info!("Config loaded: language = {}", config.language);
         Ok(config)
     }
@@ -103,28 +939,190 @@ impl ConfigManager {
     /// * `app` - Tauri AppHandle
     /// * `config` - 要保存的配置
     pub fn save(app: &AppHandle, config: &AppConfig) -> Result<()> {
+        Self::save_with_retry(app, config, StoreRetry::default())
+    }
+
+    /// 在阻塞线程池中保存配置，异步上下文中应使用该方法
+    pub async fn save_async(app: &AppHandle, config: &AppConfig) -> Result<()> {
+        let app = app.clone();
+        let config = config.clone();
+        tokio::task::spawn_blocking(move || Self::save(&app, &config))
+            .await
+            .map_err(|e| ConfigError::SaveFailed(e.to_string()))?
+    }
+
+    /// 保存配置，store 暂时不可用时按 `retry` 重试
+    pub fn save_with_retry(app: &AppHandle, config: &AppConfig, retry: StoreRetry) -> Result<()> {
         info!("Saving config: language = {}", config.language);
 
-        let store = app
-            .store(STORE_PATH)
+        config.validate()?;
+
+        let store = retry
+            .run("open", || app.store(STORE_PATH))
             .map_err(|e| ConfigError::SaveFailed(e.to_string()))?;
 
         // 保存各个字段
         store.set("api_key", serde_json::json!(config.api_key));
         store.set("hotkey", serde_json::json!(config.hotkey));
+        store.set("hotkey_mode", serde_json::json!(config.hotkey_mode));
+        store.set("hotkeys", serde_json::json!(config.hotkeys));
         store.set("language", serde_json::json!(config.language));
         store.set(
             "keyboard_max_chars",
             serde_json::json!(config.keyboard_max_chars),
         );
+        store.set("focus_wait_ms", serde_json::json!(config.focus_wait_ms));
         store.set(
             "enable_blacklist",
             serde_json::json!(config.enable_blacklist),
         );
+        store.set("blacklist", serde_json::json!(config.blacklist));
+        store.set("allowlist_mode", serde_json::json!(config.allowlist_mode));
+        store.set("allowlist", serde_json::json!(config.allowlist));
+        store.set("audio_host", serde_json::json!(config.audio_host));
+        store.set("input_device", serde_json::json!(config.input_device));
+        store.set(
+            "duplicate_commit_window_ms",
+            serde_json::json!(config.duplicate_commit_window_ms),
+        );
+        store.set(
+            "idle_disconnect_secs",
+            serde_json::json!(config.idle_disconnect_secs),
+        );
+        store.set("dead_air_secs", serde_json::json!(config.dead_air_secs));
+        store.set(
+            "promote_partial_on_stop",
+            serde_json::json!(config.promote_partial_on_stop),
+        );
+        store.set(
+            "partial_promotion_grace_ms",
+            serde_json::json!(config.partial_promotion_grace_ms),
+        );
+        store.set(
+            "stop_drain_timeout_ms",
+            serde_json::json!(config.stop_drain_timeout_ms),
+        );
+        store.set("min_confidence", serde_json::json!(config.min_confidence));
+        store.set(
+            "max_concurrent_injections",
+            serde_json::json!(config.max_concurrent_injections),
+        );
+        store.set(
+            "min_interval_between_injections_ms",
+            serde_json::json!(config.min_interval_between_injections_ms),
+        );
+        store.set(
+            "clipboard_on_problematic_layout",
+            serde_json::json!(config.clipboard_on_problematic_layout),
+        );
+        store.set(
+            "remember_last_transcript",
+            serde_json::json!(config.remember_last_transcript),
+        );
+        store.set(
+            "transcript_history_size",
+            serde_json::json!(config.transcript_history_size),
+        );
+        store.set("inject_partials", serde_json::json!(config.inject_partials));
+        store.set("live_typing", serde_json::json!(config.live_typing));
+        store.set(
+            "skip_own_windows",
+            serde_json::json!(config.skip_own_windows),
+        );
+        store.set(
+            "confirm_above_chars",
+            serde_json::json!(config.confirm_above_chars),
+        );
+        store.set(
+            "append_after_inject",
+            serde_json::json!(config.append_after_inject),
+        );
+        store.set("log_level", serde_json::json!(config.log_level));
+        store.set(
+            "trim_leading_silence",
+            serde_json::json!(config.trim_leading_silence),
+        );
+        store.set("max_retries", serde_json::json!(config.max_retries));
+        store.set(
+            "keepalive_interval_secs",
+            serde_json::json!(config.keepalive_interval_secs),
+        );
+        store.set(
+            "pong_timeout_secs",
+            serde_json::json!(config.pong_timeout_secs),
+        );
+        store.set("denoise_mix", serde_json::json!(config.denoise_mix));
+        store.set(
+            "noise_suppression_level",
+            serde_json::json!(config.noise_suppression_level),
+        );
+        store.set("silence_hold_ms", serde_json::json!(config.silence_hold_ms));
+        store.set(
+            "resampler_quality",
+            serde_json::json!(config.resampler_quality),
+        );
+        store.set("capture_mode", serde_json::json!(config.capture_mode));
+        store.set("record_to_file", serde_json::json!(config.record_to_file));
+        store.set(
+            "transcript_log_file",
+            serde_json::json!(config.transcript_log_file),
+        );
+        store.set("agc_enabled", serde_json::json!(config.agc_enabled));
+        store.set("agc_target_dbfs", serde_json::json!(config.agc_target_dbfs));
+        store.set("agc_max_gain", serde_json::json!(config.agc_max_gain));
+        store.set(
+            "capture_pre_roll_ms",
+            serde_json::json!(config.capture_pre_roll_ms),
+        );
+        store.set("ring_capacity", serde_json::json!(config.ring_capacity));
+        store.set("ring_chunk_size", serde_json::json!(config.ring_chunk_size));
+        store.set("provider", serde_json::json!(config.provider));
+        store.set("model_id", serde_json::json!(config.model_id));
+        store.set("endpoint", serde_json::json!(config.endpoint));
+        store.set(
+            "language_models",
+            serde_json::json!(config.language_models),
+        );
+        store.set("audio_encoding", serde_json::json!(config.audio_encoding));
+        store.set("proxy_url", serde_json::json!(config.proxy_url));
+        store.set(
+            "hotkey_start_grace_ms",
+            serde_json::json!(config.hotkey_start_grace_ms),
+        );
+        store.set(
+            "tray_start_grace_ms",
+            serde_json::json!(config.tray_start_grace_ms),
+        );
+        store.set(
+            "overlay_hide_delay_ms",
+            serde_json::json!(config.overlay_hide_delay_ms),
+        );
+        store.set("start_timeout_ms", serde_json::json!(config.start_timeout_ms));
+        store.set(
+            "batch_interval_ms",
+            serde_json::json!(config.batch_interval_ms),
+        );
+        store.set(
+            "silence_commit_ms",
+            serde_json::json!(config.silence_commit_ms),
+        );
+        store.set(
+            "commit_on_window_change",
+            serde_json::json!(config.commit_on_window_change),
+        );
+        store.set(
+            "max_audio_age_ms",
+            serde_json::json!(config.max_audio_age_ms),
+        );
+        store.set("dictionary", serde_json::json!(config.dictionary));
+        store.set("spoken_symbols", serde_json::json!(config.spoken_symbols));
+        store.set("post_processing", serde_json::json!(config.post_processing));
+        store.set("strip_fillers", serde_json::json!(config.strip_fillers));
+        store.set("filler_words", serde_json::json!(config.filler_words));
 
         // 持久化到磁盘
-        store
-            .save()
+        retry
+            .run("save", || store.save())
             .map_err(|e| ConfigError::SaveFailed(e.to_string()))?;
 
         info!("Config saved successfully");
@@ -144,6 +1142,244 @@ mod tests {
         assert_eq!(config.keyboard_max_chars, 10);
         assert!(config.enable_blacklist);
         assert_eq!(config.hotkey, "CommandOrControl+Shift+\\");
+        assert_eq!(config.duplicate_commit_window_ms, 3000);
+        assert_eq!(config.max_concurrent_injections, 1);
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.denoise_mix, 1.0);
+        assert_eq!(config.hotkey_mode, HotkeyMode::Toggle);
+    }
+
+    fn locked() -> tauri_plugin_store::Error {
+        std::io::Error::new(std::io::ErrorKind::WouldBlock, "store file is locked").into()
+    }
+
+    #[test]
+    fn test_store_retry_recovers_from_lock() {
+        let retry = StoreRetry {
+            retries: 2,
+            delay: Duration::from_millis(1),
+        };
+
+        // 模拟 store：第一次被锁定，第二次成功
+        let mut calls = 0;
+        let result = retry.run("save", || {
+            calls += 1;
+            if calls == 1 { Err(locked()) } else { Ok(calls) }
+        });
+        assert_eq!(result.unwrap(), 2);
+
+        // 超过重试次数后返回最后一次的错误
+        let mut calls = 0;
+        let result: tauri_plugin_store::Result<()> = retry.run("save", || {
+            calls += 1;
+            Err(locked())
+        });
+        assert!(matches!(result, Err(tauri_plugin_store::Error::Io(_))));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_store_retry_skips_non_io_errors() {
+        let mut calls = 0;
+        let result: tauri_plugin_store::Result<()> = StoreRetry::default().run("open", || {
+            calls += 1;
+            Err(tauri_plugin_store::Error::SerializeFunctionNotFound(
+                "json".to_string(),
+            ))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_endpoint_validation() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.resolved_endpoint(), DEFAULT_BASE_URL);
+        assert!(config.validate().is_ok());
+
+        config.endpoint = Some("  ".to_string());
+        assert_eq!(config.resolved_endpoint(), DEFAULT_BASE_URL);
+
+        config.endpoint = Some("wss://eu-proxy.example.com/realtime".to_string());
+        assert_eq!(
+            config.resolved_endpoint(),
+            "wss://eu-proxy.example.com/realtime"
+        );
+        assert!(config.validate().is_ok());
+
+        config.endpoint = Some("https://eu-proxy.example.com/realtime".to_string());
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidEndpoint(_))
+        ));
+    }
+
+    fn invalid_reason(config: &AppConfig) -> String {
+        match config.validate() {
+            Err(ConfigError::Invalid(reason)) => reason,
+            other => panic!("Expected invalid config, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_keyboard_max_chars_validation() {
+        let mut config = AppConfig {
+            keyboard_max_chars: 0,
+            ..Default::default()
+        };
+        assert!(invalid_reason(&config).contains("keyboard_max_chars"));
+
+        config.keyboard_max_chars = 1001;
+        assert!(invalid_reason(&config).contains("keyboard_max_chars"));
+
+        config.keyboard_max_chars = 1000;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_hotkey_validation() {
+        let mut config = AppConfig {
+            hotkey: "  ".to_string(),
+            ..Default::default()
+        };
+        assert!(invalid_reason(&config).contains("must not be empty"));
+
+        config.hotkey = "Ctrl+Shift+NoSuchKey".to_string();
+        assert!(invalid_reason(&config).contains("Ctrl+Shift+NoSuchKey"));
+
+        config.hotkey = "Ctrl+Alt+Space".to_string();
+        assert!(config.validate().is_ok());
+
+        // 附加热键不能与主热键重复
+        config.hotkeys = Some(HotkeyBindings {
+            cancel: Some("ctrl+alt+space".to_string()),
+            ..Default::default()
+        });
+        assert!(invalid_reason(&config).contains("bound to both"));
+    }
+
+    #[test]
+    fn test_language_validation() {
+        let mut config = AppConfig {
+            language: "klingon".to_string(),
+            ..Default::default()
+        };
+        assert!(invalid_reason(&config).contains("klingon"));
+
+        config.language = String::new();
+        assert!(invalid_reason(&config).contains("language"));
+
+        // 界面语言代码和 ISO 639-3 代码都可以
+        config.language = "en".to_string();
+        assert!(config.validate().is_ok());
+        config.language = "cmn".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_audio_encoding_validation() {
+        let mut config = AppConfig {
+            audio_encoding: "pcm_8000".to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.audio_encoding = "opus".to_string();
+        assert!(invalid_reason(&config).contains("opus"));
+    }
+
+    #[test]
+    fn test_proxy_url_validation() {
+        let mut config = AppConfig {
+            proxy_url: Some("socks5://127.0.0.1:1080".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        // 空值表示回退到环境变量
+        config.proxy_url = Some(" ".to_string());
+        assert!(config.validate().is_ok());
+
+        config.proxy_url = Some("ftp://proxy.corp".to_string());
+        assert!(invalid_reason(&config).contains("ftp"));
+    }
+
+    #[test]
+    fn test_ring_buffer_validation() {
+        let mut config = AppConfig {
+            ring_capacity: 8,
+            ring_chunk_size: 64,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.processor_config().ring_capacity, 8);
+        assert_eq!(config.processor_config().ring_chunk_size, 64);
+
+        config.ring_capacity = 4;
+        assert!(invalid_reason(&config).contains("capacity"));
+
+        config.ring_capacity = 64;
+        config.ring_chunk_size = 1000;
+        assert!(invalid_reason(&config).contains("chunk size"));
+    }
+
+    #[test]
+    fn test_min_confidence_validation() {
+        let mut config = AppConfig {
+            min_confidence: 1.0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        for invalid in [f32::NAN, -0.1, 1.5] {
+            config.min_confidence = invalid;
+            assert!(invalid_reason(&config).contains("min_confidence"));
+        }
+    }
+
+    #[test]
+    fn test_inject_partials_excludes_text_transforms() {
+        let mut config = AppConfig {
+            inject_partials: true,
+            ..Default::default()
+        };
+        assert!(config.streams_partials());
+        assert!(config.validate().is_ok());
+
+        // 流式片段无法经过词典修正：不能保存，已保存的配置也改为整句注入
+        config.dictionary = vec![("github".to_string(), "GitHub".to_string())];
+        assert!(!config.streams_partials());
+        assert!(invalid_reason(&config).contains("inject_partials"));
+
+        config.dictionary.clear();
+        config.strip_fillers = true;
+        assert!(!config.streams_partials());
+        assert!(invalid_reason(&config).contains("inject_partials"));
+
+        // 实时输入会在提交时修正为处理后的文本，可以同时使用
+        config.live_typing = true;
+        assert!(!config.streams_partials());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_app_config_missing_fields_use_defaults() {
+        let json = r#"{"api_key": "k", "hotkey": "Ctrl+A", "language": "en"}"#;
+        let config: AppConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.api_key, "k");
+        assert_eq!(config.keyboard_max_chars, 10);
+        assert_eq!(config.duplicate_commit_window_ms, 3000);
+    }
+
+    #[test]
+    fn test_hotkey_mode_serialization() {
+        let json = r#"{"hotkey_mode": "push_to_talk"}"#;
+        let config: AppConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.hotkey_mode, HotkeyMode::PushToTalk);
+
+        let value = serde_json::to_value(HotkeyMode::Toggle).unwrap();
+        assert_eq!(value, "toggle");
     }
 
     #[test]
@@ -151,9 +1387,84 @@ mod tests {
         let config = AppConfig {
             api_key: "test-key-123".to_string(),
             hotkey: "Cmd+Shift+A".to_string(),
+            hotkey_mode: HotkeyMode::PushToTalk,
+            hotkeys: Some(HotkeyBindings {
+                cancel: Some("Ctrl+Alt+C".to_string()),
+                ..Default::default()
+            }),
             language: "en".to_string(),
             keyboard_max_chars: 20,
+            focus_wait_ms: 80,
             enable_blacklist: false,
+            blacklist: vec!["Banking".to_string()],
+            allowlist_mode: true,
+            allowlist: vec!["Code".to_string()],
+            audio_host: Some("ASIO".to_string()),
+            input_device: Some("USB Microphone".to_string()),
+            duplicate_commit_window_ms: 1000,
+            idle_disconnect_secs: 0,
+            dead_air_secs: 10,
+            promote_partial_on_stop: false,
+            partial_promotion_grace_ms: 500,
+            stop_drain_timeout_ms: 1500,
+            min_confidence: 0.6,
+            max_concurrent_injections: 2,
+            min_interval_between_injections_ms: 250,
+            clipboard_on_problematic_layout: true,
+            remember_last_transcript: false,
+            transcript_history_size: 10,
+            inject_partials: true,
+            live_typing: true,
+            skip_own_windows: false,
+            confirm_above_chars: Some(2000),
+            append_after_inject: AppendMode::Newline,
+            log_level: "warn".to_string(),
+            trim_leading_silence: false,
+            max_retries: 5,
+            keepalive_interval_secs: 5,
+            pong_timeout_secs: 0,
+            denoise_mix: 0.5,
+            noise_suppression_level: NoiseSuppressionLevel::Low,
+            silence_hold_ms: 300,
+            resampler_quality: Quality::AutoOnce,
+            capture_mode: CaptureMode::RightOnly,
+            record_to_file: Some("/tmp/raflow.wav".to_string()),
+            transcript_log_file: Some("/tmp/raflow.json".to_string()),
+            agc_enabled: true,
+            agc_target_dbfs: -18.0,
+            agc_max_gain: 4.0,
+            capture_pre_roll_ms: 300,
+            ring_capacity: 32,
+            ring_chunk_size: 512,
+            provider: SttProvider::Scribe,
+            model_id: "custom-model".to_string(),
+            endpoint: Some("wss://proxy.example.com/realtime".to_string()),
+            language_models: HashMap::from([("en".to_string(), "en-model".to_string())]),
+            audio_encoding: "pcm_24000".to_string(),
+            proxy_url: Some("http://proxy.corp:3128".to_string()),
+            hotkey_start_grace_ms: 20,
+            tray_start_grace_ms: 500,
+            overlay_hide_delay_ms: 1200,
+            start_timeout_ms: 15_000,
+            batch_interval_ms: 250,
+            silence_commit_ms: 1500,
+            commit_on_window_change: true,
+            max_audio_age_ms: 0,
+            dictionary: vec![("github".to_string(), "GitHub".to_string())],
+            spoken_symbols: vec![SpokenSymbol {
+                keyword: "semicolon".to_string(),
+                symbol: ";".to_string(),
+                commit: true,
+            }],
+            post_processing: vec![
+                PostProcessStep::Trim,
+                PostProcessStep::CustomReplacements(vec![(
+                    "gonna".to_string(),
+                    "going to".to_string(),
+                )]),
+            ],
+            strip_fillers: true,
+            filler_words: vec!["like".to_string()],
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -162,7 +1473,167 @@ mod tests {
         assert_eq!(deserialized.api_key, config.api_key);
         assert_eq!(deserialized.language, "en");
         assert_eq!(deserialized.keyboard_max_chars, 20);
+        assert_eq!(deserialized.focus_wait_ms, 80);
         assert!(!deserialized.enable_blacklist);
+        assert_eq!(deserialized.audio_host.as_deref(), Some("ASIO"));
+        assert_eq!(
+            deserialized.input_device.as_deref(),
+            Some("USB Microphone")
+        );
+        assert_eq!(deserialized.blacklist, vec!["Banking".to_string()]);
+        assert!(deserialized.allowlist_mode);
+        assert_eq!(deserialized.allowlist, vec!["Code".to_string()]);
+        assert_eq!(deserialized.max_concurrent_injections, 2);
+        assert_eq!(deserialized.min_interval_between_injections_ms, 250);
+        assert!(deserialized.clipboard_on_problematic_layout);
+        assert!(!deserialized.remember_last_transcript);
+        assert_eq!(deserialized.transcript_history_size, 10);
+        assert!(deserialized.inject_partials);
+        assert!(deserialized.live_typing);
+        assert!(!deserialized.skip_own_windows);
+        assert_eq!(deserialized.confirm_above_chars, Some(2000));
+        assert_eq!(deserialized.append_after_inject, AppendMode::Newline);
+        assert_eq!(deserialized.hotkey_mode, HotkeyMode::PushToTalk);
+        assert_eq!(deserialized.hotkeys, config.hotkeys);
+        assert_eq!(deserialized.log_level, "warn");
+        assert!(!deserialized.trim_leading_silence);
+        assert_eq!(deserialized.max_retries, 5);
+        assert_eq!(deserialized.keepalive_interval_secs, 5);
+        assert_eq!(deserialized.pong_timeout_secs, 0);
+        assert_eq!(deserialized.denoise_mix, 0.5);
+        assert_eq!(
+            deserialized.noise_suppression_level,
+            NoiseSuppressionLevel::Low
+        );
+        assert_eq!(deserialized.dead_air_secs, 10);
+        assert_eq!(deserialized.stop_drain_timeout_ms, 1500);
+        assert_eq!(deserialized.min_confidence, 0.6);
+        assert_eq!(deserialized.silence_hold_ms, 300);
+        assert_eq!(deserialized.resampler_quality, Quality::AutoOnce);
+        assert_eq!(deserialized.capture_mode, CaptureMode::RightOnly);
+        assert_eq!(
+            deserialized.record_to_file.as_deref(),
+            Some("/tmp/raflow.wav")
+        );
+        assert_eq!(
+            deserialized.transcript_log_file.as_deref(),
+            Some("/tmp/raflow.json")
+        );
+        assert!(deserialized.agc_enabled);
+        assert_eq!(deserialized.agc_target_dbfs, -18.0);
+        assert_eq!(deserialized.agc_max_gain, 4.0);
+        assert_eq!(deserialized.capture_pre_roll_ms, 300);
+        assert_eq!(deserialized.ring_capacity, 32);
+        assert_eq!(deserialized.ring_chunk_size, 512);
+        assert_eq!(deserialized.provider, SttProvider::Scribe);
+        assert_eq!(deserialized.model_id, "custom-model");
+        assert_eq!(
+            deserialized.endpoint.as_deref(),
+            Some("wss://proxy.example.com/realtime")
+        );
+        assert_eq!(deserialized.language_models, config.language_models);
+        assert_eq!(deserialized.audio_encoding, "pcm_24000");
+        assert_eq!(
+            deserialized.proxy_url.as_deref(),
+            Some("http://proxy.corp:3128")
+        );
+        assert_eq!(deserialized.hotkey_start_grace_ms, 20);
+        assert_eq!(deserialized.tray_start_grace_ms, 500);
+        assert_eq!(deserialized.overlay_hide_delay_ms, 1200);
+        assert_eq!(deserialized.start_timeout_ms, 15_000);
+        assert_eq!(deserialized.batch_interval_ms, 250);
+        assert_eq!(deserialized.silence_commit_ms, 1500);
+        assert!(deserialized.commit_on_window_change);
+        assert_eq!(deserialized.max_audio_age_ms, 0);
+        assert_eq!(deserialized.dictionary, config.dictionary);
+        assert_eq!(deserialized.spoken_symbols, config.spoken_symbols);
+        assert_eq!(deserialized.post_processing, config.post_processing);
+        assert!(deserialized.strip_fillers);
+        assert_eq!(deserialized.filler_words, config.filler_words);
+    }
+
+    #[test]
+    fn test_effective_model_per_language() {
+        let mut config = AppConfig {
+            language: "en".to_string(),
+            language_models: HashMap::from([("en".to_string(), "en-model".to_string())]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.effective(),
+            EffectiveConfig {
+                language_code: "eng".to_string(),
+                model_id: "en-model".to_string(),
+            }
+        );
+
+        // 切换到未映射的语言时回退到默认模型
+        config.language = "zh".to_string();
+        assert_eq!(config.effective().model_id, DEFAULT_MODEL_ID);
+        assert_eq!(config.effective().language_code, "cmn");
+    }
+
+    #[test]
+    fn test_resolved_post_processing_fillers() {
+        let mut config = AppConfig {
+            language: "en".to_string(),
+            post_processing: vec![PostProcessStep::Trim],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.resolved_post_processing(),
+            vec![PostProcessStep::Trim]
+        );
+
+        config.strip_fillers = true;
+        assert_eq!(
+            config.resolved_post_processing(),
+            vec![
+                PostProcessStep::StripFillers(default_fillers("en")),
+                PostProcessStep::Trim,
+            ]
+        );
+
+        // 自定义列表优先于语言默认列表
+        config.filler_words = vec!["like".to_string()];
+        assert_eq!(
+            config.resolved_post_processing()[0],
+            PostProcessStep::StripFillers(vec!["like".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_blacklist_entries() {
+        let mut config = AppConfig::default();
+        assert!(config.blacklist.contains(&"1Password".to_string()));
+
+        assert!(config.add_blacklist_entry(" Internal Banking "));
+        assert!(!config.add_blacklist_entry("Internal Banking"));
+        assert!(!config.add_blacklist_entry(""));
+        assert!(config.blacklist.contains(&"Internal Banking".to_string()));
+
+        assert!(config.remove_blacklist_entry("1Password"));
+        assert!(!config.remove_blacklist_entry("1Password"));
+        assert!(!config.blacklist.contains(&"1Password".to_string()));
+    }
+
+    #[test]
+    fn test_dictionary_entries() {
+        let mut config = AppConfig::default();
+        assert!(config.dictionary.is_empty());
+
+        assert!(config.add_dictionary_entry(" github ", "GitHub"));
+        assert!(!config.add_dictionary_entry("GitHub", "GitHub"));
+        assert!(!config.add_dictionary_entry("raflow", " "));
+        assert!(config.add_dictionary_entry("GITHUB", "Github"));
+        assert_eq!(
+            config.dictionary,
+            vec![("github".to_string(), "Github".to_string())]
+        );
+
+        assert!(config.remove_dictionary_entry("GitHub"));
+        assert!(!config.remove_dictionary_entry("github"));
+        assert!(config.dictionary.is_empty());
     }
 
     // 实际的 load/save 测试需要 Tauri 运行时
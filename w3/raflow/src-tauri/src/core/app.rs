@@ -2,14 +2,43 @@
 //!
 //! 整合音频、网络、输入等所有模块，实现完整的录音-转写-注入流程
 
-use crate::audio::AudioManager;
+use super::bundle::{SessionArtifacts, TranscriptLogEntry, TranscriptLogWriter};
+use super::dictionary::UserDictionary;
+use super::history::TranscriptEntry;
+use super::idle::{DeadAirTimer, IdleTimer};
+use super::injection::InjectionQueue;
+use super::postprocess::{PostProcessPipeline, TranscriptPostProcessor};
+use super::shutdown::event_task_stop_limit;
+use super::spoken::SpokenSymbols;
+use super::transcript::{
+    CancelFlag, CommitDeduplicator, EventChannels, LiveEdit, LiveTyper, PartialStreamer,
+    StopReason, TranscriptEventHandler, meets_confidence, run_event_loop,
+};
+use super::window_watch::{
+    InjectionTarget, TargetWindow, WindowChangeWatcher, WindowWatchAction, choose_injection_target,
+};
+use crate::audio::{
+    AudioCapture, AudioFrame, AudioLevel, AudioManager, AudioManagerConfig, AudioProcessorConfig,
+    BufferStats, CaptureError, CaptureMode, DEAD_MIC_TIMEOUT, DEFAULT_OUTPUT_SAMPLE_RATE,
+    LevelThrottle, PipelineStop, RecoveryAction, StreamFault, VAD_EMIT_INTERVAL, recovery_action,
+};
 use crate::config::AppConfig;
-use crate::input::{InjectionConfig, TextInjector};
-use crate::network::{NetworkManager, ServerMessage};
-use crate::system::WindowTracker;
+use crate::input::{
+    AppendMode, InjectionConfig, InjectionResult, InjectionStrategy, InjectorError, TextInjector,
+    cached_layout,
+};
+use crate::network::{
+    DrainState, MetricsReader, NetworkManager, ServerMessage, backend_for, encoding_to_rate,
+};
+use crate::state::{ControlError, RecordingSession};
+use crate::system::{WindowInfo, WindowTracker};
+use crate::AppState;
+use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tracing::{debug, error, info, warn};
 
 #[derive(Error, Debug)]
@@ -30,8 +59,74 @@ pub enum AppError {
     AlreadyRunning,
 }
 
+impl From<AppError> for ControlError {
+    fn from(error: AppError) -> Self {
+        match error {
+            AppError::AlreadyRunning => ControlError::AlreadyRunning,
+            AppError::NotConfigured(reason) => ControlError::NotConfigured(reason),
+            other => ControlError::Failed(other.to_string()),
+        }
+    }
+}
+
 type Result<T> = std::result::Result<T, AppError>;
 
+/// 待命采集
+///
+/// 两次录音之间保持音频流运行，持续保留最近的预录音频，
+/// 开始录音时交给 `AppController`
+pub struct StandbyCapture {
+    capture: AudioCapture,
+    audio_host: Option<String>,
+    input_device: Option<String>,
+    pre_roll_ms: u64,
+    capture_mode: CaptureMode,
+}
+
+impl StandbyCapture {
+    /// 按配置启动待命采集
+    ///
+    /// 未启用预录（`capture_pre_roll_ms` 为 0）或启动失败时返回 None
+    pub async fn arm(config: &AppConfig) -> Option<Self> {
+        if config.capture_pre_roll_ms == 0 {
+            return None;
+        }
+
+        let result = async {
+            let mut capture = AudioCapture::with_host_and_device(
+                config.audio_host.as_deref(),
+                config.input_device.as_deref(),
+            )?;
+            capture.set_capture_mode(config.capture_mode);
+            capture.start_pre_roll(config.capture_pre_roll_ms).await?;
+            Ok::<_, CaptureError>(capture)
+        }
+        .await;
+
+        match result {
+            Ok(capture) => Some(Self {
+                capture,
+                audio_host: config.audio_host.clone(),
+                input_device: config.input_device.clone(),
+                pre_roll_ms: config.capture_pre_roll_ms,
+                capture_mode: config.capture_mode,
+            }),
+            Err(e) => {
+                warn!("Failed to start pre-roll capture: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 待命采集是否仍符合配置（主机、设备、预录时长或采集方式变化后需要重新创建）
+    fn matches(&self, config: &AppConfig) -> bool {
+        self.audio_host == config.audio_host
+            && self.input_device == config.input_device
+            && self.pre_roll_ms == config.capture_pre_roll_ms
+            && self.capture_mode == config.capture_mode
+    }
+}
+
 /// 应用控制器
 ///
 /// 管理整个应用的生命周期和数据流
@@ -39,8 +134,17 @@ type Result<T> = std::result::Result<T, AppError>;
 pub struct AppController {
     app: AppHandle,
     config: AppConfig,
+    /// 正在预录的采集器，开始录音时使用
+    standby: Option<AudioCapture>,
     audio_manager: Option<AudioManager>,
-    stop_tx: Option<mpsc::Sender<()>>,
+    stop_tx: Option<mpsc::Sender<StopReason>>,
+    /// 网络会话的取消信号
+    cancel_tx: Option<watch::Sender<bool>>,
+    /// 强制提交当前语句的信号（发送任务收到后先发出缓冲的音频再发送 commit）
+    commit_tx: Option<mpsc::Sender<()>>,
+    /// 开始录音时的焦点窗口（悬浮窗抢走焦点之前），作为注入目标
+    target_window: Option<WindowInfo>,
+    event_task: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl AppController {
@@ -49,11 +153,26 @@ impl AppController {
         Self {
             app,
             config,
+            standby: None,
             audio_manager: None,
             stop_tx: None,
+            cancel_tx: None,
+            commit_tx: None,
+            target_window: None,
+            event_task: None,
         }
     }
 
+    /// 使用待命采集，录音开头会带上预录音频
+    ///
+    /// 待命采集与当前配置不符时丢弃，改为重新打开设备
+    pub fn with_standby(mut self, standby: Option<StandbyCapture>) -> Self {
+        self.standby = standby
+            .filter(|s| s.matches(&self.config))
+            .map(|s| s.capture);
+        self
+    }
+
     /// 启动录音流程
     ///
     /// 完整流程：
@@ -74,30 +193,131 @@ impl AppController {
             return Err(AppError::NotConfigured("API Key not set".to_string()));
         }
 
+        // 在悬浮窗抢走焦点、用户切换窗口之前记下注入目标
+        self.target_window = WindowTracker::get_current_window()
+            .ok()
+            .filter(|window| window.process_id != std::process::id());
+        match &self.target_window {
+            Some(window) => info!("Injection target: {}", window.app_name),
+            None => debug!("No external window focused at start, injecting into current window"),
+        }
+
+        // 新会话清空上一次的转写历史
+        let state = self.app.state::<AppState>();
+        state.reset_transcript_history(self.config.transcript_history_size);
+        emit_history(&self.app, &state);
+
         // 创建停止信号通道
-        let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
+        let (stop_tx, mut stop_rx) = mpsc::channel::<StopReason>(1);
         self.stop_tx = Some(stop_tx);
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        self.cancel_tx = Some(cancel_tx);
 
         // 创建通道
-        let (audio_tx, audio_rx) = mpsc::channel::<Vec<i16>>(100);
+        let (audio_tx, audio_rx) = mpsc::channel::<AudioFrame>(100);
         let (event_tx, mut event_rx) = mpsc::channel::<ServerMessage>(100);
+        let (commit_tx, commit_rx) = mpsc::channel::<()>(1);
+        self.commit_tx = Some(commit_tx.clone());
 
         // 启动音频管理器
-        let mut audio_manager =
-            AudioManager::new(audio_tx).map_err(|e| AppError::Audio(e.to_string()))?;
+        let audio_config = AudioManagerConfig {
+            audio_host: self.config.audio_host.clone(),
+            input_device: self.config.input_device.clone(),
+            denoise_mix: self.config.denoise_mix,
+            noise_suppression_level: self.config.noise_suppression_level,
+            processor: AudioProcessorConfig {
+                record_to_file: debug_file_path(&self.config.record_to_file),
+                ..self.config.processor_config()
+            },
+            silence_hold: Duration::from_millis(self.config.silence_hold_ms),
+            resampler_quality: self.config.resampler_quality,
+            capture_mode: self.config.capture_mode,
+            output_sample_rate: encoding_to_rate(&self.config.audio_encoding)
+                .unwrap_or(DEFAULT_OUTPUT_SAMPLE_RATE),
+            ..Default::default()
+        };
+        // 记录本次会话的调试文件，供导出会话包
+        self.app
+            .state::<AppState>()
+            .set_last_session(SessionArtifacts {
+                audio: debug_file_path(&self.config.record_to_file),
+                transcript: debug_file_path(&self.config.transcript_log_file),
+            });
+
+        let mut audio_manager = match self.standby.take() {
+            Some(capture) => AudioManager::with_capture(audio_tx, audio_config, capture),
+            None => AudioManager::with_config(audio_tx, audio_config)
+                .map_err(|e| AppError::Audio(e.to_string()))?,
+        }
+        .with_level_meter(self.app.state::<AppState>().level_meter());
 
         audio_manager
             .start()
+            .await
             .map_err(|e| AppError::Audio(e.to_string()))?;
 
         info!("Audio manager started");
 
+        let voice_rx = audio_manager.voice_activity();
+        if self.config.dead_air_secs > 0 {
+            tokio::spawn(Self::watch_dead_air(
+                self.app.clone(),
+                audio_manager.voice_activity(),
+                Duration::from_secs(self.config.dead_air_secs),
+            ));
+        }
+
+        if let Some(level_rx) = audio_manager.take_level_receiver() {
+            tokio::spawn(Self::forward_levels(self.app.clone(), level_rx));
+        }
+        tokio::spawn(Self::forward_vad(
+            self.app.clone(),
+            audio_manager.voice_probability(),
+        ));
+        tokio::spawn(Self::forward_buffer_stats(
+            self.app.clone(),
+            audio_manager.buffer_stats(),
+        ));
+        tokio::spawn(Self::forward_sample_rate_changes(
+            self.app.clone(),
+            audio_manager.sample_rate_changes(),
+        ));
+        tokio::spawn(Self::track_noise_suppression(
+            self.app.clone(),
+            audio_manager.noise_suppression_active(),
+        ));
+        tokio::spawn(Self::forward_mic_silent(
+            self.app.clone(),
+            audio_manager.mic_silent(),
+        ));
+        tokio::spawn(Self::watch_pipeline(
+            self.app.clone(),
+            audio_manager.pipeline_stopped(),
+        ));
+        if let Some(fault_rx) = audio_manager.take_fault_receiver() {
+            tokio::spawn(Self::watch_device(self.app.clone(), fault_rx));
+        }
+
         // 保存 audio_manager（拥有所有权）
         self.audio_manager = Some(audio_manager);
 
         // 启动网络管理器
-        let mut network_manager =
-            NetworkManager::new(self.config.api_key.clone(), audio_rx, event_tx);
+        let client_config = self.config.client_config();
+        let client_model = client_config.model_id.clone();
+        let backend = backend_for(self.config.provider, client_config.clone());
+        let mut network_manager = NetworkManager::with_config(client_config, audio_rx, event_tx)
+            .with_backend(backend)
+            .with_cancel(cancel_rx)
+            .with_commit_trigger(commit_rx);
+        tokio::spawn(Self::forward_commit_interval(
+            self.app.clone(),
+            network_manager.commit_interval(),
+        ));
+        tokio::spawn(Self::forward_metrics(
+            self.app.clone(),
+            network_manager.metrics(),
+        ));
+        let mut drain_rx = network_manager.drain_state();
 
         tokio::spawn(async move {
             if let Err(e) = network_manager.run().await {
@@ -105,22 +325,37 @@ impl AppController {
             }
         });
 
-        info!("Network manager started");
+        info!(
+            "Network manager started (provider = {:?}, model = {})",
+            self.config.provider, client_model
+        );
+
+        // 目标窗口切换后由监视任务发送新窗口，事件处理器在强制提交的语句注入后改用它
+        let (target_tx, target_rx) = watch::channel(self.target_window.clone());
+        if self.config.commit_on_window_change {
+            tokio::spawn(Self::watch_target_window(
+                self.app.clone(),
+                commit_tx.clone(),
+                target_tx,
+            ));
+        }
 
         // 启动事件处理任务
         let app_clone = self.app.clone();
         let config_clone = self.config.clone();
+        let idle_timeout = Duration::from_secs(self.config.idle_disconnect_secs);
 
-        tokio::spawn(async move {
+        let event_task = tokio::spawn(async move {
             tokio::select! {
-                _ = Self::handle_events(app_clone, config_clone, &mut event_rx) => {
+                _ = Self::handle_events(app_clone.clone(), config_clone, target_rx, commit_tx, &mut event_rx, &mut stop_rx, &mut drain_rx) => {
                     info!("Event handler finished");
                 }
-                _ = stop_rx.recv() => {
-                    info!("Stop signal received");
+                _ = Self::wait_for_idle(voice_rx, idle_timeout) => {
+                    Self::idle_disconnect(app_clone, idle_timeout).await;
                 }
             }
         });
+        self.event_task = Some(event_task);
 
         info!("Event handler started");
 
@@ -128,9 +363,74 @@ impl AppController {
     }
 
     /// 停止录音流程
+    ///
+    /// 停止采集后等待事件处理任务收尾：发送任务发出剩余音频和最终 commit，
+    /// 收到最终转写（或超时）后才返回，避免丢掉最后几个字
     pub async fn stop_recording(&mut self) -> Result<()> {
         info!("Stopping recording flow");
 
+        self.end_session(StopReason::Stop).await;
+        self.wait_for_event_task().await;
+
+        // 发送停止事件
+        self.app
+            .emit("recording_stopped", ())
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        info!("Recording stopped");
+
+        Ok(())
+    }
+
+    /// 取消录音
+    ///
+    /// 丢弃尚未发送的音频，不发送 commit，之后到达的转写也不会注入
+    pub async fn cancel_recording(&mut self) -> Result<()> {
+        info!("Cancelling recording flow");
+
+        // 先通知网络会话取消，再停止采集，避免音频通道关闭时发送剩余音频和 commit
+        if let Some(cancel_tx) = self.cancel_tx.take() {
+            let _ = cancel_tx.send(true);
+        }
+
+        self.end_session(StopReason::Cancel).await;
+        // 取消后事件处理任务会很快结束，同样按上限等待，避免与下一次录音的任务并存
+        self.wait_for_event_task().await;
+
+        self.app
+            .emit("recording_cancelled", ())
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        info!("Recording cancelled");
+
+        Ok(())
+    }
+
+    /// 立即提交当前语句
+    ///
+    /// 不等静音窗口，发送任务先发出缓冲的音频再发送 commit；
+    /// 已有提交请求在排队时直接返回
+    pub fn commit_now(&mut self) -> Result<()> {
+        let commit_tx = self
+            .commit_tx
+            .as_ref()
+            .ok_or_else(|| AppError::Network("Not recording".to_string()))?;
+
+        match commit_tx.try_send(()) {
+            Ok(()) | Err(mpsc::error::TrySendError::Full(())) => {
+                info!("Manual commit requested");
+                Ok(())
+            }
+            Err(mpsc::error::TrySendError::Closed(())) => {
+                Err(AppError::Network("Session closed".to_string()))
+            }
+        }
+    }
+
+    /// 停止音频采集并通知事件处理任务
+    async fn end_session(&mut self, reason: StopReason) {
+        self.commit_tx = None;
+
         // 停止音频采集
         if let Some(mut audio_manager) = self.audio_manager.take() {
             audio_manager.stop();
@@ -139,166 +439,1103 @@ impl AppController {
 
         // 发送停止信号
         if let Some(stop_tx) = self.stop_tx.take() {
-            let _ = stop_tx.send(()).await;
+            let _ = stop_tx.send(reason).await;
         }
+    }
+
+    /// 暂停录音
+    ///
+    /// 停止音频采集，网络连接和事件处理任务保持运行，以便恢复后继续同一会话
+    pub async fn pause_recording(&mut self) -> Result<()> {
+        let audio_manager = self
+            .audio_manager
+            .as_mut()
+            .ok_or_else(|| AppError::Audio("Not recording".to_string()))?;
+
+        audio_manager.pause();
 
-        // 发送停止事件
         self.app
-            .emit("recording_stopped", ())
+            .emit("recording_paused", ())
             .map_err(|e| AppError::Network(e.to_string()))?;
 
-        info!("Recording stopped");
+        info!("Recording paused");
+
+        Ok(())
+    }
+
+    /// 恢复录音
+    ///
+    /// 重新启动音频采集，复用现有的网络会话
+    pub async fn resume_recording(&mut self) -> Result<()> {
+        let audio_manager = self
+            .audio_manager
+            .as_mut()
+            .ok_or_else(|| AppError::Audio("Not recording".to_string()))?;
+
+        audio_manager
+            .resume()
+            .await
+            .map_err(|e| AppError::Audio(e.to_string()))?;
+
+        self.app
+            .emit("recording_resumed", ())
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        info!("Recording resumed");
 
         Ok(())
     }
 
+    /// 切换到默认输入设备（当前设备故障时由设备监视任务通过控制任务调用）
+    ///
+    /// # Returns
+    /// 新的输入设备名称
+    pub async fn switch_to_default_device(&mut self) -> Result<String> {
+        let audio_manager = self
+            .audio_manager
+            .as_mut()
+            .ok_or_else(|| AppError::Audio("Not recording".to_string()))?;
+
+        audio_manager
+            .switch_to_default_device()
+            .await
+            .map_err(|e| AppError::Audio(e.to_string()))
+    }
+
+    /// 退出前停止录音
+    ///
+    /// 与 `stop_recording` 相同，事件处理任务处理完最后的转写后返回
+    pub async fn shutdown(&mut self) -> Result<()> {
+        self.stop_recording().await
+    }
+
+    /// 等待事件处理任务结束
+    ///
+    /// 收尾等待和部分转写提升都有各自的超时，这里再加一层上限，避免停止命令卡住
+    async fn wait_for_event_task(&mut self) {
+        let Some(mut event_task) = self.event_task.take() else {
+            return;
+        };
+
+        let limit = event_task_stop_limit(
+            self.config.stop_drain_timeout_ms,
+            self.config.partial_promotion_grace_ms,
+        );
+        match tokio::time::timeout(limit, &mut event_task).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("Event handler task failed: {}", e),
+            Err(_) => {
+                warn!("Event handler did not finish within {:?}, aborting", limit);
+                event_task.abort();
+            }
+        }
+    }
+
     /// 检查是否正在运行
     pub fn is_running(&self) -> bool {
         self.audio_manager.is_some()
     }
 
-    /// 处理服务器事件
-    async fn handle_events(
+    /// 将音频电平限流后转发到前端
+    ///
+    /// 音频管理器停止后通道关闭，任务随之结束
+    async fn forward_levels(app: AppHandle, mut level_rx: mpsc::Receiver<AudioLevel>) {
+        let mut throttle = LevelThrottle::default();
+
+        while let Some(level) = level_rx.recv().await {
+            if throttle.should_emit(Instant::now())
+                && let Err(e) = app.emit("audio_level", level)
+            {
+                warn!("Failed to emit audio_level: {}", e);
+            }
+        }
+    }
+
+    /// 将语音概率转发到前端（`vad` 事件，约 15Hz），供界面显示"正在说话"
+    ///
+    /// 音频管理器释放后通道关闭，任务随之结束，结束前发送一次 0 让指示熄灭
+    async fn forward_vad(app: AppHandle, mut vad_rx: watch::Receiver<f32>) {
+        let mut throttle = LevelThrottle::new(VAD_EMIT_INTERVAL);
+
+        while vad_rx.changed().await.is_ok() {
+            let vad = *vad_rx.borrow_and_update();
+            if throttle.should_emit(Instant::now())
+                && let Err(e) = app.emit("vad", vad)
+            {
+                warn!("Failed to emit vad: {}", e);
+            }
+        }
+
+        if let Err(e) = app.emit("vad", 0.0f32) {
+            warn!("Failed to emit vad: {}", e);
+        }
+    }
+
+    /// 将缓冲区统计转发到前端，供界面提示过载
+    ///
+    /// 音频管理器释放后通道关闭，任务随之结束
+    async fn forward_buffer_stats(app: AppHandle, mut stats_rx: watch::Receiver<BufferStats>) {
+        while stats_rx.changed().await.is_ok() {
+            let stats = *stats_rx.borrow_and_update();
+            if let Err(e) = app.emit("buffer_stats", stats) {
+                warn!("Failed to emit buffer_stats: {}", e);
+            }
+        }
+    }
+
+    /// 把采集采样率变化推送给前端
+    async fn forward_sample_rate_changes(app: AppHandle, mut rate_rx: watch::Receiver<u32>) {
+        while rate_rx.changed().await.is_ok() {
+            let rate = *rate_rx.borrow_and_update();
+            if let Err(e) = app.emit("sample_rate_changed", rate) {
+                warn!("Failed to emit sample_rate_changed: {}", e);
+            }
+        }
+    }
+
+    /// 把生效的静音提交窗口（毫秒）推送给前端，供调试提交限流
+    async fn forward_commit_interval(app: AppHandle, mut interval_rx: watch::Receiver<Duration>) {
+        while interval_rx.changed().await.is_ok() {
+            let interval = *interval_rx.borrow_and_update();
+            debug!("Effective silence commit window: {:?}", interval);
+            if let Err(e) = app.emit("commit_interval", interval.as_millis() as u64) {
+                warn!("Failed to emit commit_interval: {}", e);
+            }
+        }
+    }
+
+    /// 定时把网络指标推送给前端并保存到应用状态
+    ///
+    /// 网络管理器停止后保存最后一次的指标并结束（连接时长保留断开前的值）
+    async fn forward_metrics(app: AppHandle, metrics: MetricsReader) {
+        let state = app.state::<AppState>();
+        let mut ticker = tokio::time::interval(METRICS_INTERVAL);
+        let mut connection_duration_ms = 0;
+
+        loop {
+            ticker.tick().await;
+            let closed = metrics.is_closed();
+            let mut snapshot = metrics.snapshot().await;
+
+            if closed {
+                snapshot.connection_duration_ms = connection_duration_ms;
+                state.set_metrics(snapshot);
+                break;
+            }
+
+            connection_duration_ms = snapshot.connection_duration_ms;
+            state.set_metrics(snapshot);
+            if let Err(e) = app.emit("metrics", snapshot) {
+                warn!("Failed to emit metrics: {}", e);
+            }
+        }
+    }
+
+    /// 把降噪是否生效同步到应用状态
+    ///
+    /// 音频处理停止后通道关闭，状态重置为未降噪
+    async fn track_noise_suppression(app: AppHandle, mut active_rx: watch::Receiver<bool>) {
+        let state = app.state::<AppState>();
+        // 订阅前消费者任务可能已发布初始状态，先同步一次当前值
+        loop {
+            state.set_noise_suppression_active(*active_rx.borrow_and_update());
+            if active_rx.changed().await.is_err() {
+                break;
+            }
+        }
+        state.set_noise_suppression_active(false);
+    }
+
+    /// 麦克风持续无输入时提醒前端（如系统层面静音），收到输入后清除提醒
+    async fn forward_mic_silent(app: AppHandle, mut silent_rx: watch::Receiver<bool>) {
+        while silent_rx.changed().await.is_ok() {
+            let result = if *silent_rx.borrow_and_update() {
+                app.emit("mic_silent", DEAD_MIC_TIMEOUT.as_secs())
+            } else {
+                app.emit("mic_silent_cleared", ())
+            };
+            if let Err(e) = result {
+                warn!("Failed to emit microphone input state: {}", e);
+            }
+        }
+    }
+
+    /// 音频流水线意外停止时通知前端并停止录音
+    ///
+    /// 正常停止时消费者任务退出、通道关闭，任务随之结束
+    async fn watch_pipeline(
         app: AppHandle,
-        config: AppConfig,
-        event_rx: &mut mpsc::Receiver<ServerMessage>,
+        mut pipeline_rx: watch::Receiver<Option<PipelineStop>>,
     ) {
-        info!("Event handler started");
+        let Some(reason) = pipeline_rx
+            .wait_for(Option::is_some)
+            .await
+            .ok()
+            .and_then(|reason| *reason)
+        else {
+            return;
+        };
+        error!("Audio pipeline stopped unexpectedly: {:?}", reason);
+
+        if let Err(e) = app.emit("pipeline_stopped", reason) {
+            warn!("Failed to emit pipeline_stopped: {}", e);
+        }
+
+        if let Some(overlay) = app.get_webview_window("overlay") {
+            let _ = overlay.hide();
+        }
+
+        // 通过控制任务停止，保证录音状态同步更新
+        let state = app.state::<AppState>().inner().clone();
+        if let Err(e) = state.stop_recording().await {
+            error!("Failed to stop recording after pipeline stop: {}", e);
+        }
+    }
+
+    /// 处理采集流故障
+    ///
+    /// 设备被拔出时切换到默认设备继续录音并发送 `device_changed`（新设备名称）；
+    /// 没有可用设备或多次切换后仍失败时发送 `device_error` 并停止录音。
+    /// 音频管理器释放后故障通道关闭，任务随之结束
+    async fn watch_device(app: AppHandle, mut fault_rx: mpsc::Receiver<StreamFault>) {
+        let state = app.state::<AppState>().inner().clone();
+        let mut switches = 0;
 
-        while let Some(message) = event_rx.recv().await {
-            debug!("Received server message: {:?}", message);
-
-            match message {
-                ServerMessage::PartialTranscript { text, .. } => {
-                    // 发送部分转写到前端
-                    if let Err(e) = app.emit(
-                        "transcript_update",
-                        serde_json::json!({
-                            "text": text,
-                            "is_final": false,
-                        }),
-                    ) {
-                        warn!("Failed to emit partial transcript: {}", e);
+        while let Some(fault) = fault_rx.recv().await {
+            let error = match recovery_action(&fault, switches) {
+                RecoveryAction::Ignore => {
+                    debug!("Ignoring non-fatal audio stream error: {:?}", fault);
+                    continue;
+                }
+                RecoveryAction::SwitchToDefault => {
+                    switches += 1;
+                    warn!("Audio device lost, switching to default device");
+                    match state.switch_audio_device().await {
+                        Ok(device) => {
+                            if let Err(e) = app.emit("device_changed", device) {
+                                warn!("Failed to emit device_changed: {}", e);
+                            }
+                            continue;
+                        }
+                        Err(e) => e.to_string(),
                     }
                 }
+                RecoveryAction::Stop => format!("Audio device lost {} times", switches + 1),
+            };
+
+            error!("Audio device unavailable: {}", error);
+            if let Err(e) = app.emit("device_error", &error) {
+                warn!("Failed to emit device_error: {}", e);
+            }
+
+            if let Some(overlay) = app.get_webview_window("overlay") {
+                let _ = overlay.hide();
+            }
+
+            if let Err(e) = state.stop_recording().await {
+                error!("Failed to stop recording after device loss: {}", e);
+            }
+            break;
+        }
+    }
+
+    /// 录音中监视焦点窗口，目标窗口切换时强制提交当前语句
+    ///
+    /// 切换前说的话作为一句提交，不会和切换后为新窗口说的话混在同一句里；录音继续进行。
+    /// 网络会话结束后提交通道关闭，任务随之结束
+    async fn watch_target_window(
+        app: AppHandle,
+        commit_tx: mpsc::Sender<()>,
+        target_tx: watch::Sender<Option<WindowInfo>>,
+    ) {
+        let mut watcher = WindowChangeWatcher::new(Some(std::process::id()));
+        let mut ticker = tokio::time::interval(WINDOW_WATCH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = commit_tx.closed() => break,
+                _ = ticker.tick() => {}
+            }
+
+            let window = WindowTracker::get_current_window().ok();
+            let process_id = window.as_ref().map(|window| window.process_id);
+            if let WindowWatchAction::ForceCommit { from, to } = watcher.observe(process_id) {
+                info!(
+                    "Target window changed ({} -> {}), committing utterance",
+                    from, to
+                );
+                if commit_tx.try_send(()).is_err() {
+                    debug!("Force commit already pending");
+                }
+                if let Err(e) = app.emit("target_window_changed", to) {
+                    warn!("Failed to emit target_window_changed: {}", e);
+                }
+                target_tx.send_replace(window);
+            }
+        }
+    }
+
+    /// 等待空闲超时
+    ///
+    /// 超时时间内未检测到语音时返回；超时为零或音频管理器已停止时永不返回
+    async fn wait_for_idle(mut voice_rx: watch::Receiver<Instant>, timeout: Duration) {
+        if timeout.is_zero() {
+            return std::future::pending().await;
+        }
+
+        let mut timer = IdleTimer::new(timeout, Instant::now());
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
 
-                ServerMessage::CommittedTranscript { text, confidence } => {
-                    info!(
-                        "Committed transcript: {} (confidence: {:?})",
-                        text, confidence
-                    );
-
-                    // 发送最终转写到前端
-                    if let Err(e) = app.emit(
-                        "transcript_update",
-                        serde_json::json!({
-                            "text": text,
-                            "is_final": true,
-                            "confidence": confidence.unwrap_or(1.0),
-                        }),
-                    ) {
-                        warn!("Failed to emit committed transcript: {}", e);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if timer.is_idle(Instant::now()) {
+                        return;
                     }
+                }
+                changed = voice_rx.changed() => {
+                    if changed.is_err() {
+                        return std::future::pending().await;
+                    }
+                    timer.record_voice(*voice_rx.borrow_and_update());
+                }
+            }
+        }
+    }
 
-                    // 执行文本注入
-                    let app_for_injection = app.clone();
-                    let text_for_injection = text.clone();
+    /// 静音超时后提醒用户麦克风仍在录音，不停止会话
+    ///
+    /// 发送 `dead_air`（静音秒数）供悬浮窗提示，之后检测到语音时发送 `dead_air_cleared`；
+    /// 音频管理器停止后语音活动通道关闭，任务随之结束
+    async fn watch_dead_air(
+        app: AppHandle,
+        mut voice_rx: watch::Receiver<Instant>,
+        timeout: Duration,
+    ) {
+        let mut timer = DeadAirTimer::new(timeout, Instant::now());
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
 
-                    // 先隐藏 overlay（在异步任务外）
-                    if let Some(overlay) = app.get_webview_window("overlay") {
-                        if let Err(e) = overlay.hide() {
-                            error!("Failed to hide overlay: {}", e);
-                        } else {
-                            debug!("Overlay hidden before window detection");
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if timer.poll(Instant::now()) {
+                        info!("No speech for {:?}, prompting user", timeout);
+                        if let Err(e) = app.emit("dead_air", timeout.as_secs()) {
+                            warn!("Failed to emit dead_air: {}", e);
                         }
                     }
+                }
+                changed = voice_rx.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                    if timer.record_voice(*voice_rx.borrow_and_update())
+                        && let Err(e) = app.emit("dead_air_cleared", ())
+                    {
+                        warn!("Failed to emit dead_air_cleared: {}", e);
+                    }
+                }
+            }
+        }
+    }
 
-                    tokio::task::spawn_blocking(move || {
-                        // 等待焦点切换完成
-                        std::thread::sleep(std::time::Duration::from_millis(300));
+    /// 空闲超时后自动停止录音
+    async fn idle_disconnect(app: AppHandle, timeout: Duration) {
+        info!("No speech for {:?}, auto-disconnecting", timeout);
 
-                        // 现在获取当前窗口（应该是目标窗口了）
-                        let window = match WindowTracker::get_current_window() {
-                            Ok(w) => w,
-                            Err(e) => {
-                                error!("Failed to get current window: {}", e);
-                                return;
-                            }
-                        };
-
-                        // 创建注入配置
-                        let injection_config = InjectionConfig {
-                            keyboard_max_chars: config.keyboard_max_chars,
-                            enable_blacklist: config.enable_blacklist,
-                            ..Default::default()
-                        };
-
-                        // 创建注入器并注入
-                        let mut injector = match TextInjector::with_config(
-                            app_for_injection.clone(),
-                            injection_config,
-                        ) {
-                            Ok(i) => i,
-                            Err(e) => {
-                                error!("Failed to create injector: {}", e);
-                                return;
-                            }
-                        };
-
-                        // 执行注入
-                        let runtime = tokio::runtime::Handle::current();
-                        if let Err(e) = runtime
-                            .block_on(async { injector.inject(&text_for_injection, &window).await })
-                        {
-                            error!("Injection failed: {}", e);
-                        } else {
-                            info!("Text injected successfully");
-                        }
-                    });
+        if let Err(e) = app.emit("idle_disconnect", timeout.as_secs()) {
+            warn!("Failed to emit idle_disconnect: {}", e);
+        }
+
+        if let Some(overlay) = app.get_webview_window("overlay") {
+            let _ = overlay.hide();
+        }
+
+        // 通过控制任务停止，保证录音状态同步更新；
+        // 停止会等待事件处理任务结束，而本函数运行在该任务中，不能在这里等待
+        let state = app.state::<AppState>().inner().clone();
+        tokio::spawn(async move {
+            if let Err(e) = state.stop_recording().await {
+                error!("Failed to stop recording after idle timeout: {}", e);
+            }
+        });
+    }
+
+    /// 处理服务器事件（停止、取消和部分转写提升见 `run_event_loop`）
+    ///
+    /// `target_rx` 的初始值是开始录音时记下的目标窗口，之后收到目标窗口切换
+    async fn handle_events(
+        app: AppHandle,
+        config: AppConfig,
+        mut target_rx: watch::Receiver<Option<WindowInfo>>,
+        commit_tx: mpsc::Sender<()>,
+        event_rx: &mut mpsc::Receiver<ServerMessage>,
+        stop_rx: &mut mpsc::Receiver<StopReason>,
+        drain_rx: &mut watch::Receiver<DrainState>,
+    ) {
+        info!("Event handler started");
+
+        let (live_results_tx, live_results_rx) = mpsc::unbounded_channel();
+        let target_window = target_rx.borrow_and_update().clone();
+        let mut handler = EventHandler::new(app, config, target_window, commit_tx, live_results_tx);
+        let channels = EventChannels {
+            target_rx,
+            live_results_rx,
+            event_rx,
+            stop_rx,
+            drain_rx,
+        };
+        run_event_loop(&mut handler, channels).await;
+
+        // 等待转写记录写完，之后导出的会话包含完整记录
+        if let Some(log) = handler.transcript_log.take()
+            && !matches!(
+                tokio::task::spawn_blocking(move || log.finish().join()).await,
+                Ok(Ok(()))
+            )
+        {
+            warn!("Transcript log writer failed");
+        }
+
+        info!("Event handler stopped");
+    }
+}
+
+impl RecordingSession for AppController {
+    async fn start(&mut self) -> std::result::Result<(), ControlError> {
+        // 开始录音的其余失败统一报告为启动失败
+        self.start_recording()
+            .await
+            .map_err(|e| match ControlError::from(e) {
+                ControlError::Failed(reason) => ControlError::StartFailed(reason),
+                other => other,
+            })
+    }
+
+    async fn cancel(&mut self) -> std::result::Result<(), ControlError> {
+        Ok(self.cancel_recording().await?)
+    }
+
+    async fn pause(&mut self) -> std::result::Result<(), ControlError> {
+        Ok(self.pause_recording().await?)
+    }
+
+    async fn resume(&mut self) -> std::result::Result<(), ControlError> {
+        Ok(self.resume_recording().await?)
+    }
+}
+
+/// 配置中的调试文件路径（未设置或为空时为 None）
+fn debug_file_path(path: &Option<String>) -> Option<PathBuf> {
+    path.as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 把转写历史作为 `history_updated` 事件发送到前端
+fn emit_history(app: &AppHandle, state: &AppState) {
+    if let Err(e) = app.emit("history_updated", state.transcript_history()) {
+        warn!("Failed to emit history_updated: {}", e);
+    }
+}
+
+/// 把注入结果作为 `injection_result` 事件发送到前端
+async fn forward_injection_results(
+    app: AppHandle,
+    mut results_rx: mpsc::UnboundedReceiver<InjectionResult>,
+) {
+    while let Some(result) = results_rx.recv().await {
+        if let Err(e) = app.emit("injection_result", &result) {
+            warn!("Failed to emit injection_result: {}", e);
+        }
+    }
+}
+
+/// 推送网络指标的间隔
+const METRICS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 录音中检查焦点窗口的间隔
+const WINDOW_WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 注入任务的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InjectionKind {
+    /// 最终提交的完整文本：按配置追加空格或回车
+    Committed,
+    /// 部分转写实时注入的片段（包括提交时补上的尾部）：不追加
+    Streamed,
+    /// 实时输入的修改：连续的小步输入，不等待焦点切换的固定延迟
+    LiveEdit,
+}
+
+/// 实时输入修改的执行结果，由注入任务发回事件处理器
+#[derive(Debug, Clone, Copy)]
+struct LiveEditResult {
+    /// 修改所属语句的序号（见 `LiveTyper::sentence`）
+    sentence: u64,
+    /// 修改是否已执行
+    applied: bool,
+}
+
+/// 实时输入修改的结果回报
+///
+/// 注入任务结束时发送；没有标记为已执行就被丢弃时（跳过修改、无法确定目标窗口等）回报失败
+struct LiveEditReport {
+    result: LiveEditResult,
+    tx: mpsc::UnboundedSender<LiveEditResult>,
+}
+
+impl Drop for LiveEditReport {
+    fn drop(&mut self) {
+        let _ = self.tx.send(self.result);
+    }
+}
+
+/// 发送到前端的转写更新
+///
+/// 借用转写文本，发送事件时不必复制
+#[derive(Clone, Serialize)]
+struct TranscriptUpdate<'a> {
+    text: &'a str,
+    /// 后处理之前的原始转写（仅最终转写，供调试）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw: Option<&'a str>,
+    is_final: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidence: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    promoted: Option<bool>,
+}
+
+/// 服务器事件处理器
+///
+/// 将服务器消息转发到前端，并对已提交的转写执行文本注入
+struct EventHandler {
+    app: AppHandle,
+    config: AppConfig,
+    /// 注入配置（每次注入复制它，而不是整个应用配置）
+    injection_config: InjectionConfig,
+    dedup: CommitDeduplicator,
+    dictionary: UserDictionary,
+    spoken: SpokenSymbols,
+    /// 转写后处理流水线
+    post_processor: PostProcessPipeline,
+    /// 强制提交信号发送端（发送任务收到后立即提交当前语句）
+    commit_tx: mpsc::Sender<()>,
+    /// 注入目标窗口：开始录音时记下，切换窗口并强制提交后更新
+    target: TargetWindow<WindowInfo>,
+    /// 当前语句是否已经请求过强制提交
+    commit_forced: bool,
+    injections: InjectionQueue,
+    /// 注入结果发送端（注入任务完成后发送，由转发任务发送到前端）
+    results_tx: mpsc::UnboundedSender<InjectionResult>,
+    /// 实时输入修改结果的发送端（注入任务完成后发回事件处理器）
+    live_results_tx: mpsc::UnboundedSender<LiveEditResult>,
+    cancel: CancelFlag,
+    /// 部分转写实时注入（未启用时为 None）
+    streamer: Option<PartialStreamer>,
+    /// 实时输入（未启用时为 None）
+    live: Option<LiveTyper>,
+    /// 转写记录及其文件路径（未启用时为 None）
+    transcript_log: Option<TranscriptLogWriter>,
+    /// 会话开始时间，转写记录中的时间以此为起点
+    started: Instant,
+}
+
+impl EventHandler {
+    fn new(
+        app: AppHandle,
+        config: AppConfig,
+        target_window: Option<WindowInfo>,
+        commit_tx: mpsc::Sender<()>,
+        live_results_tx: mpsc::UnboundedSender<LiveEditResult>,
+    ) -> Self {
+        let dedup =
+            CommitDeduplicator::new(Duration::from_millis(config.duplicate_commit_window_ms));
+
+        let dictionary = UserDictionary::new(&config.dictionary);
+        let spoken = SpokenSymbols::new(&config.spoken_symbols);
+        let post_processor = PostProcessPipeline::new(&config.resolved_post_processing());
+
+        let injection_config = InjectionConfig {
+            keyboard_max_chars: config.keyboard_max_chars,
+            focus_wait_ms: config.focus_wait_ms,
+            enable_blacklist: config.enable_blacklist,
+            blacklist: config.blacklist.clone(),
+            allowlist_mode: config.allowlist_mode,
+            allowlist: config.allowlist.clone(),
+            keyboard_layout: cached_layout(),
+            clipboard_on_problematic_layout: config.clipboard_on_problematic_layout,
+            own_process_id: config.skip_own_windows.then(std::process::id),
+            confirm_above_chars: config.confirm_above_chars,
+            append_after_inject: config.append_after_inject,
+            ..Default::default()
+        };
+
+        // 实时输入的退格修正和流式注入的增量后缀都依赖注入顺序，只能逐个执行
+        let max_concurrent = if config.live_typing || config.streams_partials() {
+            1
+        } else {
+            config.max_concurrent_injections
+        };
+        let injections = InjectionQueue::with_min_interval(
+            max_concurrent,
+            Duration::from_millis(config.min_interval_between_injections_ms),
+        );
+
+        // 注入结果转发任务在所有注入任务结束后退出
+        let (results_tx, results_rx) = mpsc::unbounded_channel();
+        tokio::spawn(forward_injection_results(app.clone(), results_rx));
+
+        let transcript_log = debug_file_path(&config.transcript_log_file).and_then(|path| {
+            TranscriptLogWriter::spawn(path.clone())
+                .inspect_err(|e| {
+                    warn!("Failed to start transcript log {}: {}", path.display(), e);
+                })
+                .ok()
+        });
+
+        Self {
+            transcript_log,
+            started: Instant::now(),
+            streamer: config.streams_partials().then(PartialStreamer::default),
+            live: config.live_typing.then(LiveTyper::default),
+            app,
+            config,
+            injection_config,
+            dedup,
+            dictionary,
+            spoken,
+            post_processor,
+            commit_tx,
+            target: TargetWindow::new(target_window),
+            commit_forced: false,
+            injections,
+            results_tx,
+            live_results_tx,
+            cancel: CancelFlag::default(),
+        }
+    }
+
+    /// 注入提交的文本；超过确认阈值时暂存并通知前端等待确认
+    fn inject_or_hold(&self, text: String) {
+        let state = self.app.state::<AppState>();
+        let target = self.target.current().cloned();
+        let Some(text) = state.hold_for_confirmation(&self.injection_config, text, target) else {
+            if let Err(e) = self.app.emit("injection_pending_confirm", ()) {
+                warn!("Failed to emit injection_pending_confirm: {}", e);
+            }
+            return;
+        };
+
+        self.inject(text, InjectionKind::Committed);
+    }
+
+    /// 通过注入队列把文本注入到当前焦点窗口
+    fn inject(&self, text: String, kind: InjectionKind) {
+        self.submit_injection(kind, move |injector, window, runtime| {
+            let result = runtime.block_on(async { injector.inject(&text, window, false).await });
+            match &result {
+                Ok(_) => info!("Text injected successfully"),
+                Err(e) => error!("Injection failed: {}", e),
+            }
+            Some(result)
+        });
+    }
+
+    /// 通过注入队列在当前焦点窗口执行实时输入修改
+    ///
+    /// 目标窗口不支持实时输入（黑名单、终端、布局有问题）时跳过修改；
+    /// 提交时传入 `commit`，此时改为按普通方式注入完整的提交文本
+    ///
+    /// 部分转写的修改结果发回事件处理器（见 `on_live_result`）；
+    /// 修改是连续的小步输入，不再等待焦点切换的固定延迟
+    fn live_type(&self, edit: LiveEdit, commit: Option<String>) {
+        // 提交的修改之后已经开始下一句，不再回报
+        let mut report = match (&commit, &self.live) {
+            (None, Some(live)) => Some(LiveEditReport {
+                result: LiveEditResult {
+                    sentence: live.sentence(),
+                    applied: false,
+                },
+                tx: self.live_results_tx.clone(),
+            }),
+            _ => None,
+        };
+
+        self.submit_injection(InjectionKind::LiveEdit, move |injector, window, runtime| {
+            // 实时输入的修改通过键盘模拟完成
+            let result = match (injector.config().check_live_typing(window), commit) {
+                (Ok(()), _) => runtime
+                    .block_on(async { injector.retype(edit.backspaces, &edit.text, window).await })
+                    .map(|()| InjectionStrategy::Keyboard),
+                (Err(e), Some(text)) => {
+                    debug!("Live typing unavailable ({}), injecting committed text", e);
+                    runtime.block_on(async { injector.inject(&text, window, false).await })
+                }
+                (Err(e), None) => {
+                    debug!("Skipping live edit: {}", e);
+                    return None;
                 }
+            };
 
-                ServerMessage::SessionStarted { session_id, .. } => {
-                    info!("Session started: {}", session_id);
-                    if let Err(e) = app.emit("session_started", session_id) {
-                        warn!("Failed to emit session_started: {}", e);
+            if let Err(e) = &result {
+                error!("Live typing failed: {}", e);
+            }
+            if let Some(report) = report.as_mut() {
+                report.result.applied = result.is_ok();
+            }
+            Some(result)
+        });
+    }
+
+    /// 提交注入任务：等待焦点切换后确定目标窗口、创建注入器，再执行 `job`
+    ///
+    /// 记下的目标窗口仍然存在时以它为目标，否则使用当前焦点窗口（见 `choose_injection_target`）
+    ///
+    /// `kind` 决定是否等待焦点切换的固定延迟、是否追加空格或回车；
+    /// `job` 返回注入结果（跳过注入时返回 None），结果连同目标应用名称发送到前端
+    fn submit_injection<F>(&self, kind: InjectionKind, job: F)
+    where
+        F: FnOnce(
+                &mut TextInjector,
+                &WindowInfo,
+                &tokio::runtime::Handle,
+            ) -> Option<std::result::Result<InjectionStrategy, InjectorError>>
+            + Send
+            + 'static,
+    {
+        let app = &self.app;
+        let app_for_injection = app.clone();
+        let mut injection_config = self.injection_config.clone();
+        let results_tx = self.results_tx.clone();
+        let remembered = self.target.current().cloned();
+
+        // 只在最终提交的文本之后追加，流式片段之间不插入空格或回车
+        if kind == InjectionKind::Streamed {
+            injection_config.append_after_inject = AppendMode::None;
+        }
+
+        // 先隐藏 overlay（在异步任务外），并告知注入器焦点可能仍在本应用
+        if let Some(overlay) = app.get_webview_window("overlay") {
+            injection_config.overlay_was_shown =
+                overlay.is_visible().unwrap_or(false) || overlay.is_focused().unwrap_or(false);
+            if let Err(e) = overlay.hide() {
+                error!("Failed to hide overlay: {}", e);
+            } else {
+                debug!("Overlay hidden before window detection");
+            }
+        }
+
+        // 通过有界队列执行，突发的转写会排队而不是同时注入
+        let runtime = tokio::runtime::Handle::current();
+        // 焦点切换由注入器按 `focus_wait_ms` 等待，这里不再额外休眠
+        self.injections.submit(move || {
+            let current = WindowTracker::get_current_window();
+            let remembered_pid = remembered.as_ref().map(|w| w.process_id);
+            let target = choose_injection_target(
+                remembered_pid,
+                remembered_pid.is_some_and(WindowTracker::is_process_alive),
+            );
+            let window = match (target, remembered, current) {
+                (InjectionTarget::Remembered, Some(window), _) => window,
+                (_, remembered, Ok(window)) => {
+                    if remembered.is_some() {
+                        debug!("Remembered target window gone, using {}", window.app_name);
                     }
+                    window
+                }
+                (_, _, Err(e)) => {
+                    error!("Failed to get current window: {}", e);
+                    let _ = results_tx.send(InjectionResult::failure(String::new(), e.to_string()));
+                    return;
                 }
+            };
 
-                ServerMessage::InputError { error_message } => {
-                    error!("Input error from server: {}", error_message);
-                    if let Err(e) = app.emit("api_error", error_message) {
-                        warn!("Failed to emit api_error: {}", e);
+            // 创建注入器并注入
+            let mut injector =
+                match TextInjector::with_config(app_for_injection.clone(), injection_config) {
+                    Ok(i) => i,
+                    Err(e) => {
+                        error!("Failed to create injector: {}", e);
+                        let _ = results_tx
+                            .send(InjectionResult::failure(window.app_name, e.to_string()));
+                        return;
                     }
+                };
+
+            if let Some(outcome) = job(&mut injector, &window, &runtime) {
+                let _ = results_tx.send(InjectionResult::from_outcome(&window, &outcome));
+            }
+        });
+    }
+}
+
+impl TranscriptEventHandler for EventHandler {
+    type Window = WindowInfo;
+    type LiveResult = LiveEditResult;
+
+    fn drain_timeout(&self) -> Duration {
+        Duration::from_millis(self.config.stop_drain_timeout_ms)
+    }
+
+    fn promotion_grace(&self) -> Option<Duration> {
+        self.config
+            .promote_partial_on_stop
+            .then(|| Duration::from_millis(self.config.partial_promotion_grace_ms))
+    }
+
+    fn cancel(&mut self) {
+        self.cancel.cancel();
+    }
+
+    fn on_window_changed(&mut self, window: WindowInfo) {
+        self.target.on_window_changed(window);
+    }
+
+    /// 处理实时输入修改的执行结果：当前语句的修改失败或被跳过时放弃跟踪，
+    /// 之后的部分转写重新输入完整文本，而不是按未生效的修改继续退格
+    fn on_live_result(&mut self, result: LiveEditResult) {
+        if result.applied {
+            return;
+        }
+        if let Some(live) = self.live.as_mut()
+            && live.on_failed(result.sentence)
+        {
+            debug!("Live edit not applied, restarting live typing for this sentence");
+        }
+    }
+
+    /// 处理单条服务器消息
+    ///
+    /// # Returns
+    /// * `false` - 应停止处理后续消息（认证失败）
+    fn handle_message(&mut self, message: ServerMessage) -> bool {
+        debug!("Received server message: {:?}", message);
+
+        // 取消录音后到达的转写不发送到前端，也不注入
+        if self.cancel.should_discard(&message) {
+            info!("Recording cancelled, discarding transcript");
+            return true;
+        }
+
+        let app = &self.app;
+
+        match message {
+            ServerMessage::PartialTranscript { ref text, .. } => {
+                // 发送部分转写到前端
+                let update = TranscriptUpdate {
+                    text,
+                    raw: None,
+                    is_final: false,
+                    confidence: None,
+                    promoted: None,
+                };
+                if let Err(e) = app.emit("transcript_update", update) {
+                    warn!("Failed to emit partial transcript: {}", e);
                 }
 
-                ServerMessage::AuthError { error } => {
-                    error!("Authentication error: {}", error);
-                    if let Err(e) = app.emit("auth_error", error) {
-                        warn!("Failed to emit auth_error: {}", e);
+                // 说出需要立即提交的符号关键词时，不等静音直接提交当前语句
+                if !self.commit_forced && self.spoken.process(text).force_commit {
+                    self.commit_forced = true;
+                    if self.commit_tx.try_send(()).is_err() {
+                        debug!("Force commit already pending");
                     }
-                    break; // 认证失败，停止处理
                 }
 
-                ServerMessage::CommitThrottled { error } => {
-                    // 提交被限制，这是一个警告，不影响继续运行
-                    warn!("Commit throttled by server: {}", error);
-                    // 可选：发送到前端供调试
-                    if let Err(e) = app.emit("commit_throttled", error) {
-                        warn!("Failed to emit commit_throttled: {}", e);
-                    }
+                // 实时输入模式：退格修正被修订的部分，再输入新的结尾
+                if let Some(edit) = self.live.as_mut().and_then(|l| l.on_partial(text)) {
+                    self.live_type(edit, None);
                 }
 
-                ServerMessage::SessionEnded { reason } => {
-                    info!("Session ended: {}", reason);
-                    if let Err(e) = app.emit("session_ended", reason) {
-                        warn!("Failed to emit session_ended: {}", e);
-                    }
-                    break;
+                // 实时注入模式：只注入新增的尾部
+                if let Some(suffix) = self.streamer.as_mut().and_then(|s| s.on_partial(text)) {
+                    self.inject(suffix, InjectionKind::Streamed);
+                }
+            }
+
+            ServerMessage::CommittedTranscript { confidence, .. } => {
+                // 消费消息，把文本直接转移给注入任务
+                if let Some(text) = message.into_text() {
+                    self.handle_committed(text, confidence, false);
+                }
+                // 切换窗口前的语句已交给注入队列，之后的语句以新窗口为目标
+                self.target.on_committed();
+            }
+
+            ServerMessage::SessionStarted { session_id, .. } => {
+                info!("Session started: {}", session_id);
+                if let Err(e) = app.emit("session_started", session_id) {
+                    warn!("Failed to emit session_started: {}", e);
+                }
+            }
+
+            ServerMessage::InputError { error_message } => {
+                error!("Input error from server: {}", error_message);
+                if let Err(e) = app.emit("api_error", error_message) {
+                    warn!("Failed to emit api_error: {}", e);
+                }
+            }
+
+            ServerMessage::AuthError { error } => {
+                error!("Authentication error: {}", error);
+                if let Err(e) = app.emit("auth_error", error) {
+                    warn!("Failed to emit auth_error: {}", e);
+                }
+                return false; // 认证失败，停止处理
+            }
+
+            ServerMessage::CommitThrottled { error } => {
+                // 提交被限制，这是一个警告，不影响继续运行
+                warn!("Commit throttled by server: {}", error);
+                // 可选：发送到前端供调试
+                if let Err(e) = app.emit("commit_throttled", error) {
+                    warn!("Failed to emit commit_throttled: {}", e);
+                }
+            }
+
+            ServerMessage::SessionEnded { reason } => {
+                // 录音仍在进行时网络管理器会重新建立会话，继续处理新会话的事件
+                info!("Session ended: {}", reason);
+                if let Err(e) = app.emit("session_ended", reason) {
+                    warn!("Failed to emit session_ended: {}", e);
                 }
             }
         }
 
-        info!("Event handler stopped");
+        true
+    }
+
+    /// 处理已提交的转写：发送到前端并注入文本
+    ///
+    /// # Arguments
+    /// * `text` - 转写文本
+    /// * `confidence` - 置信度
+    /// * `promoted` - 是否由停止时未提交的部分转写提升而来
+    fn handle_committed(&mut self, text: String, confidence: Option<f32>, promoted: bool) {
+        info!(
+            "Committed transcript: {} (confidence: {:?}, promoted: {})",
+            text, confidence, promoted
+        );
+
+        self.commit_forced = false;
+
+        // 重连/重放可能导致服务器重复发送相同的转写
+        if !self.dedup.should_inject(&text, Instant::now()) {
+            warn!("Suppressing duplicate committed transcript: {}", text);
+            if let Some(live) = self.live.as_mut() {
+                live.reset();
+            }
+            if let Some(streamer) = self.streamer.as_mut() {
+                streamer.reset();
+            }
+            return;
+        }
+
+        // 保留服务器返回的原始转写，随最终转写事件发送
+        let raw = text.clone();
+
+        // 按用户词典修正专有名词
+        let text = if self.dictionary.is_empty() {
+            text
+        } else {
+            self.dictionary.apply(&text)
+        };
+
+        // 把口述的符号关键词替换为符号
+        let text = if self.spoken.is_empty() {
+            text
+        } else {
+            self.spoken.process(&text).text
+        };
+
+        // 按配置的后处理步骤规范化文本
+        let text = if self.post_processor.is_empty() {
+            text
+        } else {
+            self.post_processor.process(&text)
+        };
+
+        // 每次提交后重写转写记录，会话被空闲断开时也不会丢失
+        if let Some(log) = &self.transcript_log {
+            log.push(TranscriptLogEntry {
+                text: text.clone(),
+                raw: raw.clone(),
+                confidence,
+                promoted,
+                offset_ms: self.started.elapsed().as_millis() as u64,
+            });
+        }
+
+        let app = &self.app;
+        let state = app.state::<AppState>();
+
+        // 记录到会话历史，供前端的"最近听写"面板重新复制
+        if self.config.transcript_history_size > 0 {
+            state.push_transcript_history(TranscriptEntry::new(
+                text.clone(),
+                confidence,
+                !promoted,
+            ));
+            emit_history(app, &state);
+        }
+
+        // 保留最近一次转写，供重新注入到其他应用
+        if self.config.remember_last_transcript {
+            state.remember_transcript(text.clone());
+        } else {
+            state.clear_last_transcript();
+        }
+
+        // 发送最终转写到前端
+        let update = TranscriptUpdate {
+            text: &text,
+            raw: Some(&raw),
+            is_final: true,
+            confidence: Some(confidence.unwrap_or(1.0)),
+            promoted: Some(promoted),
+        };
+        if let Err(e) = app.emit("transcript_update", update) {
+            warn!("Failed to emit committed transcript: {}", e);
+        }
+
+        // 置信度过低的转写不注入（提升的部分转写使用固定置信度，不参与过滤）
+        if !promoted && !meets_confidence(confidence, self.config.min_confidence) {
+            warn!(
+                "Skipping low-confidence transcript ({:?} < {}): {}",
+                confidence, self.config.min_confidence, text
+            );
+            if let Err(e) = app.emit("low_confidence_skipped", &text) {
+                warn!("Failed to emit low_confidence_skipped: {}", e);
+            }
+            // 退格删除实时输入或实时注入已输入的部分，下一句重新开始
+            if let Some(edit) = self.live.as_mut().and_then(LiveTyper::erase) {
+                self.live_type(edit, None);
+            }
+            if let Some(live) = self.live.as_mut() {
+                live.reset();
+            }
+            if let Some(edit) = self.streamer.as_mut().and_then(PartialStreamer::erase) {
+                self.live_type(edit, None);
+            }
+            return;
+        }
+
+        // 实时输入模式：把已输入的文本修正为最终结果
+        if let Some(live) = self.live.as_mut() {
+            let edit = live.on_commit(&text).unwrap_or_default();
+            self.live_type(edit, Some(text));
+            return;
+        }
+
+        // 实时注入模式下，部分转写已注入的内容不再重复注入
+        // （实时注入时没有文本处理，提交的文本即服务器原文）
+        match self.streamer.as_mut().map(|s| s.on_commit(&text)) {
+            Some(Some(tail)) => self.inject(tail, InjectionKind::Streamed),
+            Some(None) => {}
+            None => self.inject_or_hold(text),
+        }
     }
 }
 
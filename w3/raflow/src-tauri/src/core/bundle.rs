@@ -0,0 +1,292 @@
+//! 会话导出模块
+//!
+//! 同时启用录音另存和转写记录时，最近一次会话的 WAV 和转写 JSON 可以打包成一个 zip，
+//! 方便附在问题报告中复现转写错误
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tracing::warn;
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// 压缩包中录音文件的名称
+pub const BUNDLE_AUDIO_NAME: &str = "audio.wav";
+
+/// 压缩包中转写记录的名称
+pub const BUNDLE_TRANSCRIPT_NAME: &str = "transcript.json";
+
+/// 会话导出错误
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("No recording session to export")]
+    NoSession,
+
+    #[error("Session was recorded without {0}")]
+    FeatureDisabled(&'static str),
+
+    #[error("Zip error: {0}")]
+    Zip(#[from] ZipError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// 单条转写记录
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranscriptLogEntry {
+    /// 后处理后的转写（即注入的文本）
+    pub text: String,
+    /// 服务器返回的原始转写
+    pub raw: String,
+    pub confidence: Option<f32>,
+    /// 是否由停止时未提交的部分转写提升而来
+    pub promoted: bool,
+    /// 相对录音开始的时间（毫秒）
+    pub offset_ms: u64,
+}
+
+/// 一次会话的转写记录
+///
+/// 每次提交后重写 JSON 文件（覆盖旧文件），与录音文件一起用于排查转写错误
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TranscriptLog {
+    pub entries: Vec<TranscriptLogEntry>,
+}
+
+impl TranscriptLog {
+    /// 追加一条转写
+    pub fn push(&mut self, entry: TranscriptLogEntry) {
+        self.entries.push(entry);
+    }
+
+    /// 写入 JSON 文件（已存在时覆盖）
+    pub fn write_to(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()
+    }
+}
+
+/// 转写记录的写入线程
+///
+/// 提交处理只发送条目，JSON 文件在单独线程中重写，不在异步任务里等待磁盘 IO
+pub struct TranscriptLogWriter {
+    tx: mpsc::UnboundedSender<TranscriptLogEntry>,
+    handle: JoinHandle<()>,
+}
+
+impl TranscriptLogWriter {
+    /// 启动写入线程，先写入空记录覆盖上一次会话的文件
+    ///
+    /// # Arguments
+    /// * `path` - 转写记录文件路径
+    pub fn spawn(path: PathBuf) -> io::Result<Self> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<TranscriptLogEntry>();
+
+        let handle = std::thread::Builder::new()
+            .name("transcript-log".to_string())
+            .spawn(move || {
+                let mut log = TranscriptLog::default();
+                if let Err(e) = log.write_to(&path) {
+                    warn!("Failed to create transcript log {}: {}", path.display(), e);
+                }
+
+                while let Some(entry) = rx.blocking_recv() {
+                    log.push(entry);
+                    // 积压的条目合并成一次写入
+                    while let Ok(entry) = rx.try_recv() {
+                        log.push(entry);
+                    }
+                    if let Err(e) = log.write_to(&path) {
+                        warn!("Failed to write transcript log {}: {}", path.display(), e);
+                    }
+                }
+            })?;
+
+        Ok(Self { tx, handle })
+    }
+
+    /// 追加一条转写
+    pub fn push(&self, entry: TranscriptLogEntry) {
+        let _ = self.tx.send(entry);
+    }
+
+    /// 结束记录，返回写入线程句柄（等待它即可确保文件已写完）
+    pub fn finish(self) -> JoinHandle<()> {
+        drop(self.tx);
+        self.handle
+    }
+}
+
+/// 最近一次会话生成的调试文件
+///
+/// 对应功能未启用时为 None
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionArtifacts {
+    /// 录音另存的 WAV 文件
+    pub audio: Option<PathBuf>,
+    /// 转写记录 JSON 文件
+    pub transcript: Option<PathBuf>,
+}
+
+/// 压缩包中的一个文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleEntry {
+    /// 磁盘上的源文件
+    pub source: PathBuf,
+    /// 压缩包中的名称
+    pub name: &'static str,
+}
+
+/// 压缩包清单
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleManifest {
+    pub entries: Vec<BundleEntry>,
+}
+
+impl BundleManifest {
+    /// 按会话的调试文件生成清单
+    ///
+    /// 只有录音另存和转写记录在该会话中都已启用时才能导出
+    pub fn for_session(artifacts: &SessionArtifacts) -> Result<Self, BundleError> {
+        let audio = artifacts
+            .audio
+            .clone()
+            .ok_or(BundleError::FeatureDisabled("audio recording"))?;
+        let transcript = artifacts
+            .transcript
+            .clone()
+            .ok_or(BundleError::FeatureDisabled("transcript logging"))?;
+
+        Ok(Self {
+            entries: vec![
+                BundleEntry {
+                    source: audio,
+                    name: BUNDLE_AUDIO_NAME,
+                },
+                BundleEntry {
+                    source: transcript,
+                    name: BUNDLE_TRANSCRIPT_NAME,
+                },
+            ],
+        })
+    }
+
+    /// 把清单中的文件写入 zip（已存在时覆盖）
+    ///
+    /// WAV 压缩率很低，文件以不压缩（stored）方式写入
+    pub fn write_zip(&self, path: &Path) -> Result<(), BundleError> {
+        let mut zip = ZipWriter::new(BufWriter::new(File::create(path)?));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+        for entry in &self.entries {
+            let mut source = File::open(&entry.source)?;
+            zip.start_file(entry.name, options)?;
+            io::copy(&mut source, &mut zip)?;
+        }
+
+        zip.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_requires_both_artifacts() {
+        let artifacts = SessionArtifacts {
+            audio: Some(PathBuf::from("/tmp/raflow.wav")),
+            transcript: Some(PathBuf::from("/tmp/raflow.json")),
+        };
+        let manifest = BundleManifest::for_session(&artifacts).unwrap();
+        let names: Vec<_> = manifest.entries.iter().map(|e| e.name).collect();
+        assert_eq!(names, vec![BUNDLE_AUDIO_NAME, BUNDLE_TRANSCRIPT_NAME]);
+        assert_eq!(manifest.entries[0].source, PathBuf::from("/tmp/raflow.wav"));
+
+        // 任一调试功能未启用时不能导出
+        let audio_only = SessionArtifacts {
+            transcript: None,
+            ..artifacts.clone()
+        };
+        assert!(matches!(
+            BundleManifest::for_session(&audio_only),
+            Err(BundleError::FeatureDisabled("transcript logging"))
+        ));
+        assert!(matches!(
+            BundleManifest::for_session(&SessionArtifacts::default()),
+            Err(BundleError::FeatureDisabled("audio recording"))
+        ));
+    }
+
+    #[test]
+    fn test_transcript_log_writer() {
+        let path =
+            std::env::temp_dir().join(format!("raflow-transcript-{}.json", std::process::id()));
+
+        let writer = TranscriptLogWriter::spawn(path.clone()).unwrap();
+        for (i, text) in ["你好", "世界"].into_iter().enumerate() {
+            writer.push(TranscriptLogEntry {
+                text: text.to_string(),
+                raw: text.to_string(),
+                confidence: None,
+                promoted: false,
+                offset_ms: i as u64 * 1000,
+            });
+        }
+        writer.finish().join().unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let texts: Vec<_> = json["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(texts, vec!["你好", "世界"]);
+    }
+
+    #[test]
+    fn test_write_zip() {
+        let dir = std::env::temp_dir().join(format!("raflow-bundle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let audio = dir.join("session.wav");
+        let transcript = dir.join("session.json");
+        std::fs::write(&audio, [1u8, 2, 3]).unwrap();
+        std::fs::write(&transcript, b"{}").unwrap();
+
+        let manifest = BundleManifest::for_session(&SessionArtifacts {
+            audio: Some(audio),
+            transcript: Some(transcript),
+        })
+        .unwrap();
+        let bundle = dir.join("bundle.zip");
+        manifest.write_zip(&bundle).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+        let mut read = |name: &str| {
+            let mut data = Vec::new();
+            io::Read::read_to_end(&mut archive.by_name(name).unwrap(), &mut data).unwrap();
+            data
+        };
+        assert_eq!(read(BUNDLE_AUDIO_NAME), vec![1, 2, 3]);
+        assert_eq!(read(BUNDLE_TRANSCRIPT_NAME), b"{}".to_vec());
+
+        // 源文件缺失时返回 IO 错误
+        std::fs::remove_file(&manifest.entries[0].source).unwrap();
+        assert!(matches!(
+            manifest.write_zip(&bundle),
+            Err(BundleError::Io(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
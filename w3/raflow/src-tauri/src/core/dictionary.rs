@@ -0,0 +1,179 @@
+//! 用户词典模块
+//!
+//! 在注入前按用户词典修正转写结果中的专有名词（如 "github" -> "GitHub"）
+
+/// 用户词典
+///
+/// 按整词、不区分大小写匹配。同一位置有多个条目匹配时使用最长的匹配，
+/// 替换结果不会被再次匹配
+#[derive(Debug, Clone, Default)]
+pub struct UserDictionary {
+    entries: Vec<(String, String)>,
+}
+
+impl UserDictionary {
+    /// 创建用户词典
+    ///
+    /// # Arguments
+    /// * `entries` - `(匹配词, 替换词)` 列表，匹配词为空的条目会被忽略
+    pub fn new(entries: &[(String, String)]) -> Self {
+        let entries = entries
+            .iter()
+            .filter(|(pattern, _)| !pattern.trim().is_empty())
+            .map(|(pattern, replacement)| (pattern.trim().to_string(), replacement.clone()))
+            .collect();
+
+        Self { entries }
+    }
+
+    /// 词典是否为空
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 对文本应用词典替换
+    pub fn apply(&self, text: &str) -> String {
+        if self.entries.is_empty() {
+            return text.to_string();
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut prev: Option<char> = None;
+        let mut pos = 0;
+
+        while let Some(c) = text[pos..].chars().next() {
+            if let Some((len, replacement)) = self.longest_match(text, pos, prev) {
+                output.push_str(replacement);
+                prev = text[..pos + len].chars().next_back();
+                pos += len;
+                continue;
+            }
+
+            output.push(c);
+            prev = Some(c);
+            pos += c.len_utf8();
+        }
+
+        output
+    }
+
+    /// 查找从 `pos` 开始的最长整词匹配，返回匹配的字节长度和替换词
+    fn longest_match(&self, text: &str, pos: usize, prev: Option<char>) -> Option<(usize, &str)> {
+        self.entries
+            .iter()
+            .filter_map(|(pattern, replacement)| {
+                let len = match_whole_word(&text[pos..], pattern, prev)?;
+                Some((len, replacement.as_str()))
+            })
+            .max_by_key(|(len, _)| *len)
+    }
+}
+
+/// 在 `text` 开头不区分大小写地匹配 `pattern`，返回匹配的字节长度
+///
+/// 匹配词首尾是单词字符时，前后相邻的字符不能是单词字符，避免替换单词的一部分
+pub(super) fn match_whole_word(text: &str, pattern: &str, prev: Option<char>) -> Option<usize> {
+    let first = pattern.chars().next()?;
+    if is_word_char(first) && prev.is_some_and(is_word_char) {
+        return None;
+    }
+
+    let mut chars = text.char_indices();
+    for p in pattern.chars() {
+        let (_, c) = chars.next()?;
+        if !c.to_lowercase().eq(p.to_lowercase()) {
+            return None;
+        }
+    }
+
+    let (len, next) = match chars.next() {
+        Some((index, c)) => (index, Some(c)),
+        None => (text.len(), None),
+    };
+
+    let last = pattern.chars().next_back()?;
+    if is_word_char(last) && next.is_some_and(is_word_char) {
+        return None;
+    }
+
+    Some(len)
+}
+
+/// 是否为构成单词的字符
+///
+/// 中日韩文字不以空格分词，视为单词边界，使 "用github提交" 中的 "github" 也能匹配
+fn is_word_char(c: char) -> bool {
+    (c.is_alphanumeric() || c == '_') && !is_cjk(c)
+}
+
+/// 是否为中日韩文字
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}' // 平假名、片假名
+            | '\u{3400}'..='\u{4DBF}' // CJK 扩展 A
+            | '\u{4E00}'..='\u{9FFF}' // CJK 统一表意文字
+            | '\u{AC00}'..='\u{D7AF}' // 韩文音节
+            | '\u{F900}'..='\u{FAFF}' // CJK 兼容表意文字
+            | '\u{20000}'..='\u{2FA1F}' // CJK 扩展 B 及以后
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary(entries: &[(&str, &str)]) -> UserDictionary {
+        let entries: Vec<(String, String)> = entries
+            .iter()
+            .map(|(p, r)| (p.to_string(), r.to_string()))
+            .collect();
+        UserDictionary::new(&entries)
+    }
+
+    #[test]
+    fn test_whole_word_case_insensitive() {
+        let dict = dictionary(&[("github", "GitHub"), ("raflow", "RAFlow")]);
+
+        assert_eq!(
+            dict.apply("push raflow to Github, then GITHUB."),
+            "push RAFlow to GitHub, then GitHub."
+        );
+        assert_eq!(dict.apply("github"), "GitHub");
+    }
+
+    #[test]
+    fn test_substrings_not_replaced() {
+        let dict = dictionary(&[("git", "Git"), ("raflow", "RAFlow")]);
+
+        assert_eq!(dict.apply("github digit git_hub"), "github digit git_hub");
+        assert_eq!(dict.apply("raflows myraflow"), "raflows myraflow");
+        assert_eq!(dict.apply("use git."), "use Git.");
+    }
+
+    #[test]
+    fn test_cjk_neighbours_are_boundaries() {
+        let dict = dictionary(&[("github", "GitHub")]);
+        assert_eq!(dict.apply("我用github提交代码"), "我用GitHub提交代码");
+    }
+
+    #[test]
+    fn test_longest_match_without_cascading() {
+        let dict = dictionary(&[
+            ("open ai", "OpenAI"),
+            ("open", "Open"),
+            ("openai", "open ai"),
+        ]);
+
+        assert_eq!(dict.apply("open ai and open"), "OpenAI and Open");
+        // 替换结果不会被再次匹配
+        assert_eq!(dict.apply("openai"), "open ai");
+    }
+
+    #[test]
+    fn test_empty_patterns_ignored() {
+        let dict = dictionary(&[("  ", "x"), ("", "y")]);
+        assert!(dict.is_empty());
+        assert_eq!(dict.apply("hello world"), "hello world");
+    }
+}
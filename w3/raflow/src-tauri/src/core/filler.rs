@@ -0,0 +1,167 @@
+//! 语气词过滤模块
+//!
+//! 去掉最终转写中的语气词（如 "um"、"uh"、"嗯"、"那个"），让口述的文字更干净
+
+/// 英文默认语气词
+const ENGLISH_FILLERS: &[&str] = &["um", "umm", "uh", "uhh", "er", "erm", "hmm"];
+
+/// 中文默认语气词
+const CHINESE_FILLERS: &[&str] = &["嗯", "嗯嗯", "呃", "额", "那个"];
+
+/// 界面语言对应的默认语气词
+///
+/// 中文口述常夹杂英文，中文同时使用英文语气词；没有默认列表的语言返回空列表
+pub fn default_fillers(language: &str) -> Vec<String> {
+    let lists: &[&[&str]] = match language.trim().to_ascii_lowercase().as_str() {
+        "zh" | "cmn" => &[CHINESE_FILLERS, ENGLISH_FILLERS],
+        "en" | "eng" => &[ENGLISH_FILLERS],
+        _ => &[],
+    };
+
+    lists
+        .iter()
+        .flat_map(|list| list.iter().map(|filler| filler.to_string()))
+        .collect()
+}
+
+/// 去掉文本中的语气词
+///
+/// 语气词必须独立成词（不区分大小写）：前后是文本边界、空白或标点。
+/// 中文不以空格分词，因此 "那个人" 中的 "那个" 不会被去掉，只有 "那个，" 这样
+/// 单独出现时才去掉。语气词后紧跟的逗号和空白一并去掉，避免留下多余的标点
+///
+/// # Arguments
+/// * `text` - 转写文本
+/// * `fillers` - 语气词列表，空白条目会被忽略
+pub fn strip_fillers(text: &str, fillers: &[String]) -> String {
+    let fillers: Vec<&str> = fillers
+        .iter()
+        .map(|filler| filler.trim())
+        .filter(|filler| !filler.is_empty())
+        .collect();
+    if fillers.is_empty() {
+        return text.to_string();
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut prev: Option<char> = None;
+    let mut pos = 0;
+
+    while let Some(c) = text[pos..].chars().next() {
+        let matched = if prev.is_some_and(is_word_char) {
+            None
+        } else {
+            fillers
+                .iter()
+                .filter_map(|filler| match_filler(&text[pos..], filler))
+                .max()
+        };
+
+        let Some(len) = matched else {
+            output.push(c);
+            prev = Some(c);
+            pos += c.len_utf8();
+            continue;
+        };
+
+        // 跳过语气词、紧跟的逗号和之后的空白
+        pos += len;
+        if let Some(comma) = text[pos..].chars().next().filter(|c| is_comma(*c)) {
+            pos += comma.len_utf8();
+        }
+        pos += text[pos..].len() - text[pos..].trim_start().len();
+
+        // 语气词位于句末或标点前时，去掉它前面留下的空白
+        if text[pos..].chars().next().is_none_or(|c| !is_word_char(c)) {
+            output.truncate(output.trim_end().len());
+        }
+        prev = output.chars().next_back();
+    }
+
+    output
+}
+
+/// 在 `text` 开头不区分大小写地匹配语气词，后面紧跟单词字符时不匹配
+fn match_filler(text: &str, filler: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    for f in filler.chars() {
+        let (_, c) = chars.next()?;
+        if !c.to_lowercase().eq(f.to_lowercase()) {
+            return None;
+        }
+    }
+
+    match chars.next() {
+        Some((_, c)) if is_word_char(c) => None,
+        Some((index, _)) => Some(index),
+        None => Some(text.len()),
+    }
+}
+
+/// 是否为构成单词的字符
+///
+/// 与用户词典不同，中日韩文字也算单词字符，语气词必须单独出现；
+/// 连字符和撇号也算，避免拆开 "uh-oh"、"um's" 这类写法
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '\'')
+}
+
+/// 语气词之后一并去掉的逗号
+fn is_comma(c: char) -> bool {
+    matches!(c, ',' | '，' | '、')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_english_fillers() {
+        let fillers = default_fillers("en");
+
+        assert_eq!(
+            strip_fillers("Um, I think we should, uh, ship it", &fillers),
+            "I think we should, ship it"
+        );
+        assert_eq!(
+            strip_fillers("so UMM the plan works", &fillers),
+            "so the plan works"
+        );
+        assert_eq!(strip_fillers("we are done um.", &fillers), "we are done.");
+        assert_eq!(strip_fillers("uh", &fillers), "");
+
+        // 包含语气词的正常单词不受影响
+        assert_eq!(
+            strip_fillers("an umbrella, a summer hum, her uh-oh moment", &fillers),
+            "an umbrella, a summer hum, her uh-oh moment"
+        );
+    }
+
+    #[test]
+    fn test_strip_chinese_fillers() {
+        let fillers = default_fillers("zh");
+
+        assert_eq!(strip_fillers("嗯，我觉得可以", &fillers), "我觉得可以");
+        assert_eq!(
+            strip_fillers("我们明天，那个，再讨论一下", &fillers),
+            "我们明天，再讨论一下"
+        );
+        // "那个" 作为指示代词时保留
+        assert_eq!(strip_fillers("那个人说嗯好的", &fillers), "那个人说嗯好的");
+        // 中文同时过滤英文语气词
+        assert_eq!(strip_fillers("呃 um 好吧", &fillers), "好吧");
+    }
+
+    #[test]
+    fn test_custom_and_disabled_fillers() {
+        let fillers = vec!["like".to_string(), " ".to_string()];
+        assert_eq!(
+            strip_fillers("it was, like, fine", &fillers),
+            "it was, fine"
+        );
+        assert_eq!(strip_fillers("I like it", &fillers), "I it");
+
+        assert!(default_fillers("fr").is_empty());
+        assert_eq!(strip_fillers("um, bonjour", &[]), "um, bonjour");
+    }
+}
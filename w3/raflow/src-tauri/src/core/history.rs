@@ -0,0 +1,157 @@
+//! 转写历史模块
+//!
+//! 记录本次会话提交的转写，供前端的"最近听写"面板展示和重新复制
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 默认保留的转写条数
+pub const DEFAULT_HISTORY_SIZE: usize = 50;
+
+/// 一条转写历史
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranscriptEntry {
+    /// 后处理后的转写（即注入的文本）
+    pub text: String,
+    /// 提交时间（Unix 毫秒）
+    pub timestamp: u64,
+    pub confidence: Option<f32>,
+    /// 是否为服务器提交的转写（false 表示由停止时未提交的部分转写提升而来）
+    pub committed: bool,
+}
+
+impl TranscriptEntry {
+    /// 创建一条以当前时间为提交时间的记录
+    pub fn new(text: String, confidence: Option<f32>, committed: bool) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+
+        Self {
+            text,
+            timestamp,
+            confidence,
+            committed,
+        }
+    }
+}
+
+/// 会话转写历史
+///
+/// 只保留最近 `capacity` 条，超出时丢弃最早的记录；容量为 0 表示不记录
+#[derive(Debug, Clone)]
+pub struct TranscriptHistory {
+    entries: VecDeque<TranscriptEntry>,
+    capacity: usize,
+}
+
+impl TranscriptHistory {
+    /// 创建空的转写历史
+    ///
+    /// # Arguments
+    /// * `capacity` - 最多保留的条数
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity.min(DEFAULT_HISTORY_SIZE)),
+            capacity,
+        }
+    }
+
+    /// 追加一条转写，超出容量时丢弃最早的记录
+    pub fn push(&mut self, entry: TranscriptEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// 清空历史并使用新的容量（新会话开始时调用）
+    pub fn reset(&mut self, capacity: usize) {
+        self.entries.clear();
+        self.capacity = capacity;
+    }
+
+    /// 按提交顺序（从早到晚）返回所有记录
+    pub fn entries(&self) -> Vec<TranscriptEntry> {
+        self.entries.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for TranscriptHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str) -> TranscriptEntry {
+        TranscriptEntry::new(text.to_string(), Some(0.9), true)
+    }
+
+    #[test]
+    fn test_history_append() {
+        let mut history = TranscriptHistory::default();
+        assert!(history.is_empty());
+
+        history.push(entry("你好"));
+        history.push(TranscriptEntry::new("世界".to_string(), None, false));
+
+        let entries = history.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].text, "你好");
+        assert_eq!(entries[0].confidence, Some(0.9));
+        assert!(entries[0].committed);
+        assert_eq!(entries[1].text, "世界");
+        assert!(!entries[1].committed);
+        assert!(entries[0].timestamp > 0);
+        assert!(entries[0].timestamp <= entries[1].timestamp);
+    }
+
+    #[test]
+    fn test_history_evicts_oldest_over_capacity() {
+        let mut history = TranscriptHistory::new(3);
+        for text in ["a", "b", "c", "d", "e"] {
+            history.push(entry(text));
+        }
+
+        let texts: Vec<_> = history.entries().into_iter().map(|e| e.text).collect();
+        assert_eq!(texts, ["c", "d", "e"]);
+
+        // 容量为 0 时不记录
+        let mut disabled = TranscriptHistory::new(0);
+        disabled.push(entry("a"));
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn test_history_reset_clears_entries() {
+        let mut history = TranscriptHistory::new(3);
+        history.push(entry("a"));
+        history.push(entry("b"));
+
+        history.reset(1);
+        assert!(history.is_empty());
+
+        history.push(entry("c"));
+        history.push(entry("d"));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.entries()[0].text, "d");
+    }
+}
@@ -0,0 +1,135 @@
+//! 空闲检测模块
+//!
+//! 长时间未检测到语音时自动断开，避免持续发送静音产生 API 费用；
+//! 也可以在较短的静音后提醒用户麦克风仍在录音（不断开）
+
+use std::time::{Duration, Instant};
+
+/// 空闲计时器
+///
+/// 记录最近一次语音活动的时间，超过超时时间未检测到语音即视为空闲
+#[derive(Debug)]
+pub struct IdleTimer {
+    timeout: Duration,
+    last_voice: Instant,
+}
+
+impl IdleTimer {
+    /// 创建空闲计时器
+    ///
+    /// # Arguments
+    /// * `timeout` - 空闲超时时间
+    /// * `now` - 计时起点
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_voice: now,
+        }
+    }
+
+    /// 记录语音活动，重置计时
+    ///
+    /// # Returns
+    /// 是否重置了计时（早于上次语音的活动时间被忽略）
+    pub fn record_voice(&mut self, at: Instant) -> bool {
+        let newer = at > self.last_voice;
+        if newer {
+            self.last_voice = at;
+        }
+        newer
+    }
+
+    /// 检查是否已空闲超时
+    pub fn is_idle(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_voice) >= self.timeout
+    }
+}
+
+/// 静音提醒计时器
+///
+/// 与空闲断开不同，静音超时后只提醒一次，会话继续；再次检测到语音后重新计时
+#[derive(Debug)]
+pub struct DeadAirTimer {
+    idle: IdleTimer,
+    prompted: bool,
+}
+
+impl DeadAirTimer {
+    /// 创建静音提醒计时器
+    ///
+    /// # Arguments
+    /// * `timeout` - 静音多久后提醒
+    /// * `now` - 计时起点
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            idle: IdleTimer::new(timeout, now),
+            prompted: false,
+        }
+    }
+
+    /// 记录语音活动，重置计时
+    ///
+    /// # Returns
+    /// 已经提醒过、应撤销提醒时返回 true
+    pub fn record_voice(&mut self, at: Instant) -> bool {
+        self.idle.record_voice(at) && std::mem::take(&mut self.prompted)
+    }
+
+    /// 检查是否应提醒；同一段静音只返回一次 true
+    pub fn poll(&mut self, now: Instant) -> bool {
+        if self.prompted || !self.idle.is_idle(now) {
+            return false;
+        }
+
+        self.prompted = true;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_after_sustained_silence() {
+        let start = Instant::now();
+        let timer = IdleTimer::new(Duration::from_secs(30), start);
+
+        assert!(!timer.is_idle(start + Duration::from_secs(10)));
+        assert!(!timer.is_idle(start + Duration::from_secs(29)));
+        assert!(timer.is_idle(start + Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_voice_resets_timer() {
+        let start = Instant::now();
+        let mut timer = IdleTimer::new(Duration::from_secs(30), start);
+
+        timer.record_voice(start + Duration::from_secs(20));
+        assert!(!timer.is_idle(start + Duration::from_secs(40)));
+        assert!(timer.is_idle(start + Duration::from_secs(50)));
+
+        // 过期的活动时间不会回退计时
+        assert!(!timer.record_voice(start));
+        assert!(timer.is_idle(start + Duration::from_secs(50)));
+    }
+
+    #[test]
+    fn test_dead_air_prompts_once_and_resets_on_voice() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut timer = DeadAirTimer::new(Duration::from_secs(10), start);
+
+        assert!(!timer.poll(at(9)));
+        assert!(timer.poll(at(10)));
+        // 同一段静音不重复提醒
+        assert!(!timer.poll(at(15)));
+
+        // 检测到语音：撤销提醒并重新计时
+        assert!(timer.record_voice(at(16)));
+        assert!(!timer.poll(at(20)));
+        assert!(!timer.record_voice(at(21)));
+        assert!(!timer.poll(at(30)));
+        assert!(timer.poll(at(31)));
+    }
+}
@@ -0,0 +1,160 @@
+//! 文本注入调度模块
+//!
+//! 限制同时执行的注入任务数量，避免突发的转写（如重连重放）耗尽阻塞线程池，
+//! 并可限制注入频率，避免目标应用（如聊天软件）触发自身的限流或刷屏检测
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Semaphore, mpsc};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// 注入任务队列
+///
+/// 任务按提交顺序启动，同时运行的任务数不超过上限，其余任务排队等待；
+/// 相邻任务的启动时间至少间隔 `min_interval`。
+/// 队列被丢弃后，已提交的任务仍会执行完毕
+pub struct InjectionQueue {
+    tx: mpsc::UnboundedSender<Job>,
+}
+
+impl InjectionQueue {
+    /// 创建注入队列（必须在 tokio 运行时内调用）
+    ///
+    /// # Arguments
+    /// * `max_concurrent` - 并发上限，为零时按 1 处理
+    pub fn new(max_concurrent: usize) -> Self {
+        Self::with_min_interval(max_concurrent, Duration::ZERO)
+    }
+
+    /// 创建限制注入频率的注入队列（必须在 tokio 运行时内调用）
+    ///
+    /// # Arguments
+    /// * `max_concurrent` - 并发上限，为零时按 1 处理
+    /// * `min_interval` - 相邻注入启动的最小间隔，为零时不限制
+    pub fn with_min_interval(max_concurrent: usize, min_interval: Duration) -> Self {
+        let permits = Arc::new(Semaphore::new(max_concurrent.max(1)));
+        let (tx, mut rx) = mpsc::unbounded_channel::<Job>();
+
+        tokio::spawn(async move {
+            let mut next_start = Instant::now();
+
+            while let Some(job) = rx.recv().await {
+                // 先获取许可再启动阻塞任务，保证排队任务不占用阻塞线程
+                let Ok(permit) = permits.clone().acquire_owned().await else {
+                    break;
+                };
+
+                // 与上一次注入保持最小间隔
+                if next_start > Instant::now() {
+                    debug!("Delaying injection to respect minimum interval");
+                    tokio::time::sleep_until(next_start).await;
+                }
+                next_start = Instant::now() + min_interval;
+
+                tokio::task::spawn_blocking(move || {
+                    job();
+                    drop(permit);
+                });
+            }
+        });
+
+        Self { tx }
+    }
+
+    /// 提交注入任务
+    pub fn submit<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if self.tx.send(Box::new(job)).is_err() {
+            warn!("Injection queue closed, dropping injection task");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn run_jobs(max_concurrent: usize, jobs: usize) -> (usize, Vec<usize>) {
+        let queue = InjectionQueue::new(max_concurrent);
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+
+        for i in 0..jobs {
+            let active = active.clone();
+            let peak = peak.clone();
+            let order = order.clone();
+            let done_tx = done_tx.clone();
+
+            queue.submit(move || {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                order.lock().unwrap().push(i);
+
+                std::thread::sleep(Duration::from_millis(20));
+
+                active.fetch_sub(1, Ordering::SeqCst);
+                done_tx.send(()).unwrap();
+            });
+        }
+
+        for _ in 0..jobs {
+            done_rx.recv().await.unwrap();
+        }
+
+        let order = order.lock().unwrap().clone();
+        (peak.load(Ordering::SeqCst), order)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_serializes_injections() {
+        let (peak, order) = run_jobs(1, 5).await;
+
+        assert_eq!(peak, 1);
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_bounded_concurrency() {
+        let (peak, order) = run_jobs(2, 6).await;
+
+        assert!(peak <= 2);
+        assert_eq!(order.len(), 6);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_min_interval_between_injections() {
+        let interval = Duration::from_millis(100);
+        let queue = InjectionQueue::with_min_interval(1, interval);
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+
+        // 突发提交的任务只记录启动时间，立即完成
+        for _ in 0..3 {
+            let done_tx = done_tx.clone();
+            queue.submit(move || done_tx.send(Instant::now()).unwrap());
+        }
+
+        let mut started = Vec::new();
+        for _ in 0..3 {
+            started.push(done_rx.recv().await.unwrap());
+        }
+
+        for pair in started.windows(2) {
+            assert!(pair[1] - pair[0] >= interval);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zero_limit_treated_as_one() {
+        let (peak, _) = run_jobs(0, 3).await;
+        assert_eq!(peak, 1);
+    }
+}
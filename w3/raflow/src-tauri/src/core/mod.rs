@@ -3,5 +3,35 @@
 //! 包含应用主控制器和完整的数据流集成
 
 pub mod app;
+pub mod bundle;
+pub mod dictionary;
+pub mod filler;
+pub mod history;
+pub mod idle;
+pub mod injection;
+pub mod postprocess;
+pub mod shutdown;
+pub mod spoken;
+pub mod transcript;
+pub mod window_watch;
 
-pub use app::{AppController, AppError};
+pub use app::{AppController, AppError, StandbyCapture};
+pub use bundle::{
+    BundleEntry, BundleError, BundleManifest, SessionArtifacts, TranscriptLog, TranscriptLogEntry,
+    TranscriptLogWriter,
+};
+pub use dictionary::UserDictionary;
+pub use filler::{default_fillers, strip_fillers};
+pub use history::{DEFAULT_HISTORY_SIZE, TranscriptEntry, TranscriptHistory};
+pub use idle::{DeadAirTimer, IdleTimer};
+pub use injection::InjectionQueue;
+pub use postprocess::{
+    CapitalizeSentences, CollapseWhitespace, CustomReplacements, PostProcessPipeline,
+    PostProcessStep, StripFillers, TranscriptPostProcessor, Trim,
+};
+pub use shutdown::{ExitGuard, ShutdownOutcome};
+pub use spoken::{SpokenOutput, SpokenSymbol, SpokenSymbols};
+pub use transcript::{CancelFlag, CommitDeduplicator, PartialStreamer, PartialTracker};
+pub use window_watch::{
+    InjectionTarget, WindowChangeWatcher, WindowWatchAction, choose_injection_target,
+};
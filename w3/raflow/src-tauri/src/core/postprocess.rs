@@ -0,0 +1,314 @@
+//! 转写后处理模块
+//!
+//! 在注入和发送最终转写事件之前规范化提交的文本（去除首尾空白、合并空白、
+//! 句首大写、自定义替换、去除语气词），步骤及其顺序由配置决定
+
+use super::filler::strip_fillers;
+use serde::{Deserialize, Serialize};
+
+/// 转写后处理器
+pub trait TranscriptPostProcessor: Send + Sync {
+    /// 处理一段提交的转写文本
+    fn process(&self, text: &str) -> String;
+}
+
+/// 后处理步骤配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessStep {
+    /// 去除首尾空白
+    Trim,
+    /// 把连续空白合并为一个空格
+    CollapseWhitespace,
+    /// 句首字母大写
+    CapitalizeSentences,
+    /// 按 `(原文, 替换)` 顺序做子串替换（区分大小写）
+    CustomReplacements(Vec<(String, String)>),
+    /// 去掉独立成词的语气词（不区分大小写）
+    StripFillers(Vec<String>),
+}
+
+impl PostProcessStep {
+    /// 创建对应的处理器
+    fn processor(&self) -> Box<dyn TranscriptPostProcessor> {
+        match self {
+            Self::Trim => Box::new(Trim),
+            Self::CollapseWhitespace => Box::new(CollapseWhitespace),
+            Self::CapitalizeSentences => Box::new(CapitalizeSentences),
+            Self::CustomReplacements(replacements) => {
+                Box::new(CustomReplacements::new(replacements))
+            }
+            Self::StripFillers(fillers) => Box::new(StripFillers::new(fillers)),
+        }
+    }
+}
+
+/// 去除首尾空白
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Trim;
+
+impl TranscriptPostProcessor for Trim {
+    fn process(&self, text: &str) -> String {
+        text.trim().to_string()
+    }
+}
+
+/// 把连续空白（含换行）合并为一个空格，首尾空白保留为一个空格
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CollapseWhitespace;
+
+impl TranscriptPostProcessor for CollapseWhitespace {
+    fn process(&self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut in_whitespace = false;
+
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !in_whitespace {
+                    output.push(' ');
+                }
+                in_whitespace = true;
+            } else {
+                output.push(c);
+                in_whitespace = false;
+            }
+        }
+
+        output
+    }
+}
+
+/// 句首字母大写
+///
+/// 文本开头，以及 `.`、`!`、`?` 后跟空白、或中文句末标点（`。！？`）之后的第一个字母大写。
+/// 句末标点后先出现其他字符（如 `3.5`、`example.com`）时不视为新句子
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CapitalizeSentences;
+
+/// 句首大写的扫描状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SentenceState {
+    /// 句子中间
+    Inside,
+    /// 刚遇到英文句末标点，需要空白才算新句子
+    AfterTerminator,
+    /// 等待新句子的第一个字母
+    Start,
+}
+
+impl TranscriptPostProcessor for CapitalizeSentences {
+    fn process(&self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut state = SentenceState::Start;
+
+        for c in text.chars() {
+            if state == SentenceState::Start && c.is_alphabetic() {
+                output.extend(c.to_uppercase());
+                state = SentenceState::Inside;
+                continue;
+            }
+
+            state = match c {
+                '.' | '!' | '?' => SentenceState::AfterTerminator,
+                '。' | '！' | '？' => SentenceState::Start,
+                c if c.is_whitespace() && state != SentenceState::Inside => SentenceState::Start,
+                _ => SentenceState::Inside,
+            };
+            output.push(c);
+        }
+
+        output
+    }
+}
+
+/// 自定义子串替换
+///
+/// 按配置顺序依次替换，后面的替换作用于前面替换的结果；原文为空的条目会被忽略
+#[derive(Debug, Clone, Default)]
+pub struct CustomReplacements {
+    replacements: Vec<(String, String)>,
+}
+
+impl CustomReplacements {
+    /// 创建替换处理器
+    pub fn new(replacements: &[(String, String)]) -> Self {
+        let replacements = replacements
+            .iter()
+            .filter(|(from, _)| !from.is_empty())
+            .cloned()
+            .collect();
+
+        Self { replacements }
+    }
+}
+
+impl TranscriptPostProcessor for CustomReplacements {
+    fn process(&self, text: &str) -> String {
+        self.replacements
+            .iter()
+            .fold(text.to_string(), |text, (from, to)| text.replace(from, to))
+    }
+}
+
+/// 去除语气词
+///
+/// 匹配规则见 [`strip_fillers`]
+#[derive(Debug, Clone, Default)]
+pub struct StripFillers {
+    fillers: Vec<String>,
+}
+
+impl StripFillers {
+    /// 创建语气词处理器
+    pub fn new(fillers: &[String]) -> Self {
+        Self {
+            fillers: fillers.to_vec(),
+        }
+    }
+}
+
+impl TranscriptPostProcessor for StripFillers {
+    fn process(&self, text: &str) -> String {
+        strip_fillers(text, &self.fillers)
+    }
+}
+
+/// 后处理流水线
+///
+/// 按配置顺序依次执行各步骤
+#[derive(Default)]
+pub struct PostProcessPipeline {
+    steps: Vec<Box<dyn TranscriptPostProcessor>>,
+}
+
+impl PostProcessPipeline {
+    /// 按配置创建流水线
+    pub fn new(steps: &[PostProcessStep]) -> Self {
+        Self {
+            steps: steps.iter().map(PostProcessStep::processor).collect(),
+        }
+    }
+
+    /// 是否没有任何步骤
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl TranscriptPostProcessor for PostProcessPipeline {
+    fn process(&self, text: &str) -> String {
+        self.steps
+            .iter()
+            .fold(text.to_string(), |text, step| step.process(&text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacements(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_trim() {
+        assert_eq!(Trim.process("  hello world \n"), "hello world");
+        assert_eq!(Trim.process("   "), "");
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        assert_eq!(
+            CollapseWhitespace.process("hello   world\n\tagain"),
+            "hello world again"
+        );
+        assert_eq!(CollapseWhitespace.process("  padded  "), " padded ");
+        assert_eq!(CollapseWhitespace.process("你好  世界"), "你好 世界");
+    }
+
+    #[test]
+    fn test_capitalize_sentences() {
+        assert_eq!(
+            CapitalizeSentences.process("hello. how are you? fine!  thanks"),
+            "Hello. How are you? Fine!  Thanks"
+        );
+        // 中文句末标点之后不需要空白
+        assert_eq!(CapitalizeSentences.process("好的。okay"), "好的。Okay");
+        // 小数和域名中的点不是句末
+        assert_eq!(
+            CapitalizeSentences.process("version 3.5 is on example.com"),
+            "Version 3.5 is on example.com"
+        );
+        // 开头的非字母字符结束句首
+        assert_eq!(
+            CapitalizeSentences.process("\"quoted\" text"),
+            "\"quoted\" text"
+        );
+    }
+
+    #[test]
+    fn test_custom_replacements() {
+        let processor = CustomReplacements::new(&replacements(&[
+            ("", "ignored"),
+            ("gonna", "going to"),
+            ("going to", "will"),
+        ]));
+        // 后面的替换作用于前面替换的结果
+        assert_eq!(processor.process("I'm gonna go"), "I'm will go");
+        assert_eq!(processor.process("Gonna"), "Gonna");
+    }
+
+    #[test]
+    fn test_pipeline_order() {
+        let text = "  hello   world.  bye ";
+
+        let pipeline = PostProcessPipeline::new(&[
+            PostProcessStep::CollapseWhitespace,
+            PostProcessStep::Trim,
+            PostProcessStep::CapitalizeSentences,
+        ]);
+        assert_eq!(pipeline.process(text), "Hello world. Bye");
+
+        // 先替换再大写，替换出的句首也会大写
+        let pipeline = PostProcessPipeline::new(&[
+            PostProcessStep::CustomReplacements(replacements(&[("bye", "see you")])),
+            PostProcessStep::CapitalizeSentences,
+        ]);
+        assert_eq!(pipeline.process("ok. bye"), "Ok. See you");
+
+        // 顺序相反时替换结果不再大写
+        let pipeline = PostProcessPipeline::new(&[
+            PostProcessStep::CapitalizeSentences,
+            PostProcessStep::CustomReplacements(replacements(&[("Bye", "see you")])),
+        ]);
+        assert_eq!(pipeline.process("ok. bye"), "Ok. see you");
+
+        // 去掉语气词后再大写，新的句首也会大写
+        let pipeline = PostProcessPipeline::new(&[
+            PostProcessStep::StripFillers(vec!["um".to_string()]),
+            PostProcessStep::CapitalizeSentences,
+        ]);
+        assert_eq!(pipeline.process("um, ok. Um so"), "Ok. So");
+
+        assert!(PostProcessPipeline::new(&[]).is_empty());
+        assert_eq!(PostProcessPipeline::default().process(text), text);
+    }
+
+    #[test]
+    fn test_step_serialization() {
+        let steps = vec![
+            PostProcessStep::Trim,
+            PostProcessStep::CustomReplacements(replacements(&[("a", "b")])),
+        ];
+        let json = serde_json::to_string(&steps).unwrap();
+        assert_eq!(json, r#"["trim",{"custom_replacements":[["a","b"]]}]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<PostProcessStep>>(&json).unwrap(),
+            steps
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! 应用退出处理模块
+//!
+//! 退出前先优雅地停止录音（提交、关闭连接、处理最后的转写），超时后强制退出
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 等待事件处理任务结束时，在收尾等待和部分转写提升之外的额外余量
+pub const EVENT_TASK_STOP_MARGIN: Duration = Duration::from_secs(1);
+
+/// 退出时在停止录音的上限之外留出的余量（停止采集、关闭连接等）
+pub const SHUTDOWN_MARGIN: Duration = Duration::from_secs(1);
+
+/// 停止录音时等待事件处理任务的上限
+///
+/// # Arguments
+/// * `drain_timeout_ms` - 等待最终转写的时间（`stop_drain_timeout_ms`）
+/// * `promotion_grace_ms` - 部分转写提升前的等待（`partial_promotion_grace_ms`）
+pub fn event_task_stop_limit(drain_timeout_ms: u64, promotion_grace_ms: u64) -> Duration {
+    Duration::from_millis(drain_timeout_ms.saturating_add(promotion_grace_ms))
+        .saturating_add(EVENT_TASK_STOP_MARGIN)
+}
+
+/// 退出时优雅停止的超时，不短于停止录音本身的上限，保证最后的转写能注入
+pub fn shutdown_timeout(drain_timeout_ms: u64, promotion_grace_ms: u64) -> Duration {
+    event_task_stop_limit(drain_timeout_ms, promotion_grace_ms).saturating_add(SHUTDOWN_MARGIN)
+}
+
+/// 优雅停止的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShutdownOutcome {
+    /// 已正常停止
+    Stopped,
+    /// 停止失败
+    Failed(String),
+    /// 超时未完成
+    TimedOut,
+}
+
+/// 退出守卫
+///
+/// 第一次退出请求会被拦截以执行优雅停止，停止完成后再次发起的退出直接放行
+#[derive(Debug, Default)]
+pub struct ExitGuard {
+    shutting_down: AtomicBool,
+}
+
+impl ExitGuard {
+    /// 创建退出守卫
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始退出流程
+    ///
+    /// # Returns
+    /// * `true` - 首次调用，应拦截退出并执行优雅停止
+    /// * `false` - 已在退出流程中，直接放行
+    pub fn begin(&self) -> bool {
+        !self.shutting_down.swap(true, Ordering::SeqCst)
+    }
+}
+
+/// 在超时时间内执行停止操作
+///
+/// # Arguments
+/// * `stop` - 停止录音的 future
+/// * `timeout` - 最长等待时间
+pub async fn graceful_shutdown<F>(stop: F, timeout: Duration) -> ShutdownOutcome
+where
+    F: Future<Output = Result<(), String>>,
+{
+    info!("Shutting down, stopping recording (timeout: {:?})", timeout);
+
+    match tokio::time::timeout(timeout, stop).await {
+        Ok(Ok(())) => {
+            info!("Recording stopped gracefully");
+            ShutdownOutcome::Stopped
+        }
+        Ok(Err(e)) => {
+            warn!("Failed to stop recording on exit: {}", e);
+            ShutdownOutcome::Failed(e)
+        }
+        Err(_) => {
+            warn!("Timed out stopping recording on exit");
+            ShutdownOutcome::TimedOut
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_exit_guard_intercepts_once() {
+        let guard = ExitGuard::new();
+        assert!(guard.begin());
+        assert!(!guard.begin());
+    }
+
+    #[test]
+    fn test_shutdown_timeout_covers_stop_limit() {
+        assert_eq!(
+            event_task_stop_limit(3000, 800),
+            Duration::from_millis(4800)
+        );
+        assert!(shutdown_timeout(3000, 800) > event_task_stop_limit(3000, 800));
+
+        // 极大的配置值不会溢出
+        assert!(event_task_stop_limit(u64::MAX, u64::MAX) >= Duration::from_millis(u64::MAX));
+        assert!(shutdown_timeout(u64::MAX, u64::MAX) >= Duration::from_millis(u64::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_stop_completes_before_exit() {
+        let steps = Arc::new(Mutex::new(Vec::new()));
+
+        let stop_steps = steps.clone();
+        let stop = async move {
+            stop_steps.lock().unwrap().push("commit");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            stop_steps.lock().unwrap().push("close");
+            Ok(())
+        };
+
+        let outcome = graceful_shutdown(stop, Duration::from_secs(1)).await;
+        steps.lock().unwrap().push("exit");
+
+        assert_eq!(outcome, ShutdownOutcome::Stopped);
+        assert_eq!(*steps.lock().unwrap(), vec!["commit", "close", "exit"]);
+    }
+
+    #[tokio::test]
+    async fn test_hung_stop_times_out() {
+        let stop = std::future::pending::<Result<(), String>>();
+
+        let outcome = graceful_shutdown(stop, Duration::from_millis(20)).await;
+        assert_eq!(outcome, ShutdownOutcome::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_stop_failure_is_reported() {
+        let stop = async { Err("Control channel closed".to_string()) };
+
+        let outcome = graceful_shutdown(stop, Duration::from_secs(1)).await;
+        assert_eq!(
+            outcome,
+            ShutdownOutcome::Failed("Control channel closed".to_string())
+        );
+    }
+}
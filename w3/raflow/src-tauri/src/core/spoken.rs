@@ -0,0 +1,165 @@
+//! 口述符号模块
+//!
+//! 把口述的符号关键词（如 "semicolon"、"分号"）替换为符号本身。
+//! 可按关键词配置替换后立即提交当前语句，让符号及时注入而不必等待静音
+
+use super::dictionary::match_whole_word;
+use serde::{Deserialize, Serialize};
+
+/// 口述符号配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpokenSymbol {
+    /// 口述关键词（整词、不区分大小写匹配）
+    pub keyword: String,
+    /// 替换成的符号
+    pub symbol: String,
+    /// 说出该关键词后是否立即提交当前语句
+    #[serde(default)]
+    pub commit: bool,
+}
+
+/// 口述符号处理结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpokenOutput {
+    /// 替换后的文本
+    pub text: String,
+    /// 是否替换了需要立即提交的关键词
+    pub force_commit: bool,
+}
+
+/// 口述符号处理器
+///
+/// 匹配规则与用户词典相同：整词、不区分大小写，同一位置取最长的匹配。
+/// 符号紧贴前面的文字，关键词前的空白会被去掉
+#[derive(Debug, Clone, Default)]
+pub struct SpokenSymbols {
+    entries: Vec<SpokenSymbol>,
+}
+
+impl SpokenSymbols {
+    /// 创建口述符号处理器
+    ///
+    /// # Arguments
+    /// * `symbols` - 口述符号配置，关键词为空的条目会被忽略
+    pub fn new(symbols: &[SpokenSymbol]) -> Self {
+        let entries = symbols
+            .iter()
+            .filter(|symbol| !symbol.keyword.trim().is_empty())
+            .map(|symbol| SpokenSymbol {
+                keyword: symbol.keyword.trim().to_string(),
+                ..symbol.clone()
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// 是否没有配置任何符号
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 替换文本中的符号关键词
+    pub fn process(&self, text: &str) -> SpokenOutput {
+        if self.entries.is_empty() {
+            return SpokenOutput {
+                text: text.to_string(),
+                force_commit: false,
+            };
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut force_commit = false;
+        let mut prev: Option<char> = None;
+        let mut pos = 0;
+
+        while let Some(c) = text[pos..].chars().next() {
+            if let Some((len, entry)) = self.longest_match(text, pos, prev) {
+                output.truncate(output.trim_end().len());
+                output.push_str(&entry.symbol);
+                force_commit |= entry.commit;
+                prev = text[..pos + len].chars().next_back();
+                pos += len;
+                continue;
+            }
+
+            output.push(c);
+            prev = Some(c);
+            pos += c.len_utf8();
+        }
+
+        SpokenOutput {
+            text: output,
+            force_commit,
+        }
+    }
+
+    /// 查找从 `pos` 开始的最长关键词匹配，返回匹配的字节长度和对应条目
+    fn longest_match(
+        &self,
+        text: &str,
+        pos: usize,
+        prev: Option<char>,
+    ) -> Option<(usize, &SpokenSymbol)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                let len = match_whole_word(&text[pos..], &entry.keyword, prev)?;
+                Some((len, entry))
+            })
+            .max_by_key(|(len, _)| *len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(keyword: &str, symbol: &str, commit: bool) -> SpokenSymbol {
+        SpokenSymbol {
+            keyword: keyword.to_string(),
+            symbol: symbol.to_string(),
+            commit,
+        }
+    }
+
+    #[test]
+    fn test_commit_keyword_substitutes_and_forces_commit() {
+        let spoken = SpokenSymbols::new(&[
+            symbol("semicolon", ";", true),
+            symbol("分号", "；", true),
+            symbol("comma", ",", false),
+        ]);
+
+        let output = spoken.process("let x equals one Semicolon");
+        assert_eq!(output.text, "let x equals one;");
+        assert!(output.force_commit);
+
+        let output = spoken.process("好的分号");
+        assert_eq!(output.text, "好的；");
+        assert!(output.force_commit);
+
+        // 未配置提交的关键词只替换
+        let output = spoken.process("a comma b");
+        assert_eq!(output.text, "a, b");
+        assert!(!output.force_commit);
+
+        // 单词的一部分不匹配
+        let output = spoken.process("semicolons");
+        assert_eq!(output.text, "semicolons");
+        assert!(!output.force_commit);
+    }
+
+    #[test]
+    fn test_longest_keyword_wins() {
+        let spoken =
+            SpokenSymbols::new(&[symbol("colon", ":", false), symbol("semi colon", ";", true)]);
+        assert_eq!(spoken.process("a semi colon").text, "a;");
+        assert_eq!(spoken.process("key colon value").text, "key: value");
+
+        // 空关键词被忽略
+        let spoken = SpokenSymbols::new(&[symbol(" ", "?", false)]);
+        assert!(spoken.is_empty());
+        assert_eq!(spoken.process("a b").text, "a b");
+    }
+}
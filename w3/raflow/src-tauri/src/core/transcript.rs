@@ -0,0 +1,846 @@
+//! 转写结果处理模块
+//!
+//! 在注入前对服务器返回的转写结果进行过滤
+
+use crate::network::{DrainState, ServerMessage};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info};
+
+/// 由部分转写提升而来的结果使用的置信度
+pub const PROMOTED_CONFIDENCE: f32 = 0.5;
+
+/// 已提交转写去重器
+///
+/// 服务器在重连或重放时可能重复发送相同的 committed_transcript，
+/// 在时间窗口内与上一条相同的文本会被视为重复
+#[derive(Debug)]
+pub struct CommitDeduplicator {
+    window: Duration,
+    last: Option<(String, Instant)>,
+}
+
+impl CommitDeduplicator {
+    /// 创建去重器
+    ///
+    /// # Arguments
+    /// * `window` - 去重时间窗口，为零时禁用去重
+    pub fn new(window: Duration) -> Self {
+        Self { window, last: None }
+    }
+
+    /// 检查文本是否应该注入
+    ///
+    /// # Returns
+    /// * `true` - 新文本，或相同文本已超出时间窗口（合法的重复）
+    /// * `false` - 时间窗口内的重复文本
+    pub fn should_inject(&mut self, text: &str, now: Instant) -> bool {
+        let text = text.trim();
+
+        if let Some((last_text, last_at)) = &self.last
+            && !self.window.is_zero()
+            && last_text == text
+            && now.saturating_duration_since(*last_at) < self.window
+        {
+            return false;
+        }
+
+        self.last = Some((text.to_string(), now));
+        true
+    }
+}
+
+/// 录音取消标记
+///
+/// 取消录音后，之后到达的转写既不发送到前端也不注入
+#[derive(Debug, Default)]
+pub struct CancelFlag {
+    cancelled: bool,
+}
+
+impl CancelFlag {
+    /// 标记为已取消
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    /// 是否已取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// 是否应丢弃该消息（取消后的转写消息）
+    pub fn should_discard(&self, message: &ServerMessage) -> bool {
+        self.cancelled && message.is_transcript()
+    }
+}
+
+/// 部分转写追踪器
+///
+/// 记录最近一条尚未被提交的部分转写
+#[derive(Debug, Default)]
+pub struct PartialTracker {
+    pending: Option<String>,
+}
+
+impl PartialTracker {
+    /// 根据服务器消息更新状态
+    pub fn observe(&mut self, message: &ServerMessage) {
+        match message {
+            ServerMessage::PartialTranscript { text, .. } => {
+                self.pending = (!text.trim().is_empty()).then(|| text.clone());
+            }
+            ServerMessage::CommittedTranscript { .. } => {
+                self.pending = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// 是否有未提交的部分转写
+    pub fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// 取出未提交的部分转写
+    pub fn take_pending(&mut self) -> Option<String> {
+        self.pending.take()
+    }
+}
+
+/// 部分转写实时注入器
+///
+/// 每条部分转写只注入相对已注入内容新增的尾部，已注入的内容不再修正；
+/// 提交时补上剩余的尾部，然后开始下一句
+#[derive(Debug, Default)]
+pub struct PartialStreamer {
+    /// 当前语句已注入的字符数
+    injected_chars: usize,
+}
+
+impl PartialStreamer {
+    /// 收到部分转写，返回需要注入的新增文本
+    ///
+    /// 部分转写变短或只修改了已注入的内容时返回 None
+    pub fn on_partial(&mut self, text: &str) -> Option<String> {
+        self.take_suffix(text)
+    }
+
+    /// 收到提交的转写，返回尚未注入的剩余文本，并重置为下一句
+    pub fn on_commit(&mut self, text: &str) -> Option<String> {
+        let suffix = self.take_suffix(text);
+        self.injected_chars = 0;
+        suffix
+    }
+
+    /// 删除当前语句已注入的全部文本（如提交的转写置信度过低），返回所需的修改，并重置为下一句
+    pub fn erase(&mut self) -> Option<LiveEdit> {
+        let backspaces = std::mem::take(&mut self.injected_chars);
+        (backspaces > 0).then(|| LiveEdit {
+            backspaces,
+            text: String::new(),
+        })
+    }
+
+    /// 放弃当前语句（已注入的文本保持不变）
+    pub fn reset(&mut self) {
+        self.injected_chars = 0;
+    }
+
+    /// 按字符数取出超过已注入长度的部分
+    fn take_suffix(&mut self, text: &str) -> Option<String> {
+        let suffix: String = text.chars().skip(self.injected_chars).collect();
+        if suffix.is_empty() {
+            return None;
+        }
+
+        self.injected_chars += suffix.chars().count();
+        Some(suffix)
+    }
+}
+
+/// 实时输入的一次修改：先退格删除，再输入新文本
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiveEdit {
+    /// 需要退格删除的字符数
+    pub backspaces: usize,
+    /// 删除后输入的文本
+    pub text: String,
+}
+
+/// 实时输入跟踪器
+///
+/// 记录当前语句已输入的文本；部分转写被修订时，
+/// 退格删除分歧点之后的内容，再输入新的结尾（按字符计数）
+#[derive(Debug, Default)]
+pub struct LiveTyper {
+    /// 当前语句已输入的文本
+    typed: String,
+    /// 当前语句的序号（提交或放弃跟踪后递增）
+    sentence: u64,
+}
+
+impl LiveTyper {
+    /// 收到部分转写，返回把已输入文本修正为该转写所需的修改
+    ///
+    /// 与已输入文本相同时返回 None
+    pub fn on_partial(&mut self, text: &str) -> Option<LiveEdit> {
+        self.retype(text)
+    }
+
+    /// 收到提交的转写，返回最终修改，并重置为下一句
+    pub fn on_commit(&mut self, text: &str) -> Option<LiveEdit> {
+        let edit = self.retype(text);
+        self.reset();
+        edit
+    }
+
+    /// 删除当前语句已输入的全部文本，返回所需的修改（仍属于当前语句，之后由调用方 `reset`）
+    pub fn erase(&mut self) -> Option<LiveEdit> {
+        self.retype("")
+    }
+
+    /// 放弃当前语句的跟踪（已输入的文本保持不变）
+    pub fn reset(&mut self) {
+        self.typed.clear();
+        self.sentence = self.sentence.wrapping_add(1);
+    }
+
+    /// 当前语句的序号，用于把修改的执行结果对应到语句
+    pub fn sentence(&self) -> u64 {
+        self.sentence
+    }
+
+    /// 第 `sentence` 句的修改没有执行成功
+    ///
+    /// 已输入的内容无法确定，仍是当前语句时放弃跟踪；之前语句的结果忽略
+    ///
+    /// # Returns
+    /// * `true` - 放弃了当前语句的跟踪
+    pub fn on_failed(&mut self, sentence: u64) -> bool {
+        if sentence != self.sentence {
+            return false;
+        }
+        self.reset();
+        true
+    }
+
+    /// 计算从已输入文本到目标文本的修改
+    fn retype(&mut self, text: &str) -> Option<LiveEdit> {
+        let common = self
+            .typed
+            .chars()
+            .zip(text.chars())
+            .take_while(|(typed, new)| typed == new)
+            .count();
+        let backspaces = self.typed.chars().count() - common;
+        let suffix: String = text.chars().skip(common).collect();
+        if backspaces == 0 && suffix.is_empty() {
+            return None;
+        }
+
+        self.typed = text.to_string();
+        Some(LiveEdit {
+            backspaces,
+            text: suffix,
+        })
+    }
+}
+
+/// 最终转写的置信度是否达到注入阈值
+///
+/// 服务器没有返回置信度时视为通过；阈值为 0 时不过滤
+pub fn meets_confidence(confidence: Option<f32>, min_confidence: f32) -> bool {
+    confidence.is_none_or(|confidence| confidence >= min_confidence)
+}
+
+/// 停止录音后等待最终提交
+///
+/// 宽限期内收到的服务器消息交给 `on_message` 处理；
+/// 宽限期结束仍未收到提交时，返回需要提升为已提交的部分转写
+pub async fn flush_on_stop<F>(
+    tracker: &mut PartialTracker,
+    event_rx: &mut mpsc::Receiver<ServerMessage>,
+    grace: Duration,
+    mut on_message: F,
+) -> Option<String>
+where
+    F: FnMut(ServerMessage),
+{
+    let deadline = tokio::time::Instant::now() + grace;
+
+    while tracker.has_pending() {
+        match tokio::time::timeout_at(deadline, event_rx.recv()).await {
+            Ok(Some(message)) => {
+                tracker.observe(&message);
+                on_message(message);
+            }
+            Ok(None) | Err(_) => break,
+        }
+    }
+
+    tracker.take_pending()
+}
+
+/// 停止录音后等待发送任务收尾和最终转写
+///
+/// 先等待发送任务发出剩余音频和最终 commit，发送了 commit 时再等待之后的已提交转写；
+/// 等待期间收到的服务器消息交给 `on_message` 处理，最多等待 `timeout`
+///
+/// # Returns
+/// * `true` - 收到了最终 commit 对应的转写
+pub async fn drain_after_stop<F>(
+    drain_rx: &mut watch::Receiver<DrainState>,
+    event_rx: &mut mpsc::Receiver<ServerMessage>,
+    timeout: Duration,
+    mut on_message: F,
+) -> bool
+where
+    F: FnMut(ServerMessage),
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut flushed = None;
+
+    loop {
+        if flushed == Some(false) {
+            return false;
+        }
+        if flushed.is_none()
+            && let DrainState::Flushed { committed } = *drain_rx.borrow_and_update()
+        {
+            flushed = Some(committed);
+            continue;
+        }
+
+        tokio::select! {
+            message = event_rx.recv() => {
+                let Some(message) = message else {
+                    return false;
+                };
+                let is_commit = matches!(message, ServerMessage::CommittedTranscript { .. });
+                on_message(message);
+                if is_commit && flushed == Some(true) {
+                    return true;
+                }
+            }
+            // 发送任务已结束且未报告收尾时只等待转写
+            Ok(()) = drain_rx.changed(), if flushed.is_none() => {}
+            _ = tokio::time::sleep_until(deadline) => return false,
+        }
+    }
+}
+
+/// 停止原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// 正常停止：等待并注入最后的转写
+    Stop,
+    /// 取消：丢弃未完成的转写
+    Cancel,
+}
+
+/// 录音期间的转写事件处理方（见 `run_event_loop`）
+pub trait TranscriptEventHandler {
+    /// 注入目标窗口
+    type Window: Clone;
+    /// 实时输入修改的执行结果
+    type LiveResult;
+
+    /// 停止后等待剩余音频发出和最终转写的最长时间，0 表示不等待
+    fn drain_timeout(&self) -> Duration;
+
+    /// 停止后等待服务器提交的宽限期，None 表示不提升部分转写
+    fn promotion_grace(&self) -> Option<Duration>;
+
+    /// 处理单条服务器消息，返回 false 时停止处理后续消息
+    fn handle_message(&mut self, message: ServerMessage) -> bool;
+
+    /// 处理提交的转写（`promoted` 表示由部分转写提升而来）
+    fn handle_committed(&mut self, text: String, confidence: Option<f32>, promoted: bool);
+
+    /// 录音已取消，之后的转写都应丢弃
+    fn cancel(&mut self);
+
+    /// 注入目标窗口已切换
+    fn on_window_changed(&mut self, window: Self::Window);
+
+    /// 实时输入修改执行完毕
+    fn on_live_result(&mut self, result: Self::LiveResult);
+}
+
+/// 事件循环的输入通道
+pub struct EventChannels<'a, W, R> {
+    /// 注入目标窗口（初始值已由处理方读取）
+    pub target_rx: watch::Receiver<Option<W>>,
+    /// 实时输入修改的执行结果
+    pub live_results_rx: mpsc::UnboundedReceiver<R>,
+    /// 服务器消息
+    pub event_rx: &'a mut mpsc::Receiver<ServerMessage>,
+    /// 停止信号
+    pub stop_rx: &'a mut mpsc::Receiver<StopReason>,
+    /// 发送任务的收尾状态
+    pub drain_rx: &'a mut watch::Receiver<DrainState>,
+}
+
+/// 处理录音期间的转写事件，直到停止、取消或服务器消息结束
+///
+/// 停止信号优先于同时到达的服务器消息：取消后不再处理任何转写；
+/// 正常停止时先处理已到达的消息，再等待剩余音频发出和最终转写，启用了部分转写提升时，
+/// 宽限期内仍未提交则将最后的部分转写作为低置信度结果处理
+pub async fn run_event_loop<H>(
+    handler: &mut H,
+    channels: EventChannels<'_, H::Window, H::LiveResult>,
+) where
+    H: TranscriptEventHandler,
+{
+    let EventChannels {
+        mut target_rx,
+        mut live_results_rx,
+        event_rx,
+        stop_rx,
+        drain_rx,
+    } = channels;
+    let mut partials = PartialTracker::default();
+
+    loop {
+        tokio::select! {
+            biased;
+
+            reason = stop_rx.recv() => {
+                info!("Stop signal received ({:?})", reason);
+
+                if reason == Some(StopReason::Cancel) {
+                    // 取消时丢弃未提交的部分转写，不再提升
+                    handler.cancel();
+                    break;
+                }
+
+                // 先处理停止前已经到达的消息
+                while let Ok(message) = event_rx.try_recv() {
+                    partials.observe(&message);
+                    handler.handle_message(message);
+                }
+
+                // 等待剩余音频发出并收到最终 commit 对应的转写
+                let drain_timeout = handler.drain_timeout();
+                if !drain_timeout.is_zero() {
+                    let received = drain_after_stop(drain_rx, event_rx, drain_timeout, |message| {
+                        partials.observe(&message);
+                        handler.handle_message(message);
+                    })
+                    .await;
+                    debug!("Drain finished (final transcript received: {})", received);
+                }
+
+                if let Some(grace) = handler.promotion_grace() {
+                    let promoted = flush_on_stop(&mut partials, event_rx, grace, |message| {
+                        handler.handle_message(message);
+                    })
+                    .await;
+
+                    if let Some(text) = promoted {
+                        info!("No commit after stop, promoting last partial: {}", text);
+                        handler.handle_committed(text, Some(PROMOTED_CONFIDENCE), true);
+                    }
+                }
+
+                break;
+            }
+            Ok(()) = target_rx.changed() => {
+                if let Some(window) = target_rx.borrow_and_update().clone() {
+                    handler.on_window_changed(window);
+                }
+            }
+            Some(result) = live_results_rx.recv() => {
+                handler.on_live_result(result);
+            }
+            message = event_rx.recv() => {
+                let Some(message) = message else {
+                    break;
+                };
+
+                partials.observe(&message);
+                if !handler.handle_message(message) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(text: &str) -> ServerMessage {
+        ServerMessage::PartialTranscript {
+            text: text.to_string(),
+            created_at_ms: None,
+        }
+    }
+
+    fn committed(text: &str) -> ServerMessage {
+        ServerMessage::CommittedTranscript {
+            text: text.to_string(),
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_partial_tracker() {
+        let mut tracker = PartialTracker::default();
+        assert!(!tracker.has_pending());
+
+        tracker.observe(&partial("hello"));
+        assert!(tracker.has_pending());
+
+        tracker.observe(&committed("hello"));
+        assert!(!tracker.has_pending());
+
+        // 空白的部分转写不视为待提交
+        tracker.observe(&partial("  "));
+        assert!(!tracker.has_pending());
+    }
+
+    #[tokio::test]
+    async fn test_partial_promoted_on_stop() {
+        let (_event_tx, mut event_rx) = mpsc::channel(10);
+        let mut tracker = PartialTracker::default();
+        tracker.observe(&partial("short utterance"));
+
+        let promoted = flush_on_stop(
+            &mut tracker,
+            &mut event_rx,
+            Duration::from_millis(50),
+            |_| {},
+        )
+        .await;
+
+        assert_eq!(promoted.as_deref(), Some("short utterance"));
+        assert!(!tracker.has_pending());
+    }
+
+    #[tokio::test]
+    async fn test_commit_during_grace_not_promoted() {
+        let (event_tx, mut event_rx) = mpsc::channel(10);
+        let mut tracker = PartialTracker::default();
+        tracker.observe(&partial("hello"));
+
+        event_tx.send(committed("hello world")).await.unwrap();
+
+        let mut handled = Vec::new();
+        let promoted = flush_on_stop(&mut tracker, &mut event_rx, Duration::from_secs(5), |m| {
+            handled.push(m)
+        })
+        .await;
+
+        assert!(promoted.is_none());
+        assert_eq!(handled, vec![committed("hello world")]);
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_final_commit() {
+        let (drain_tx, mut drain_rx) = watch::channel(DrainState::Sending);
+        let (event_tx, mut event_rx) = mpsc::channel(10);
+
+        // 收尾之前的提交属于之前的语句，不结束等待
+        event_tx.send(committed("earlier")).await.unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drain_tx.send_replace(DrainState::Flushed { committed: true });
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            event_tx.send(committed("last words")).await.unwrap();
+            // 保持通道打开，确认是收到最终转写而不是通道关闭结束等待
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let mut handled = Vec::new();
+        let received =
+            drain_after_stop(&mut drain_rx, &mut event_rx, Duration::from_secs(2), |m| {
+                handled.push(m)
+            })
+            .await;
+
+        assert!(received);
+        assert_eq!(handled, vec![committed("earlier"), committed("last words")]);
+    }
+
+    #[tokio::test]
+    async fn test_drain_without_commit_returns_immediately() {
+        let (_drain_tx, mut drain_rx) = watch::channel(DrainState::Flushed { committed: false });
+        let (_event_tx, mut event_rx) = mpsc::channel(10);
+
+        let started = Instant::now();
+        let received =
+            drain_after_stop(&mut drain_rx, &mut event_rx, Duration::from_secs(2), |_| {}).await;
+
+        assert!(!received);
+        assert!(started.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_streamer_injects_appended_text() {
+        let mut streamer = PartialStreamer::default();
+
+        assert_eq!(streamer.on_partial("hel").as_deref(), Some("hel"));
+        assert_eq!(streamer.on_partial("hello").as_deref(), Some("lo"));
+        assert_eq!(streamer.on_partial("hello").as_deref(), None);
+        assert_eq!(streamer.on_partial("hello wor").as_deref(), Some(" wor"));
+
+        // 修改已注入的内容不回退，只注入超出的部分
+        assert_eq!(streamer.on_partial("hello wo").as_deref(), None);
+        assert_eq!(streamer.on_partial("help wanted").as_deref(), Some("ed"));
+
+        // 提交时补上剩余部分，下一句从头开始
+        assert_eq!(streamer.on_commit("help wanted.").as_deref(), Some("."));
+        assert_eq!(streamer.on_partial("你好").as_deref(), Some("你好"));
+        assert_eq!(streamer.on_partial("你好世界").as_deref(), Some("世界"));
+        assert_eq!(streamer.on_commit("你好世界"), None);
+    }
+
+    #[test]
+    fn test_streamer_erase_removes_injected_text() {
+        let mut streamer = PartialStreamer::default();
+        assert_eq!(streamer.erase(), None);
+
+        streamer.on_partial("你好 wor");
+        assert_eq!(
+            streamer.erase(),
+            Some(LiveEdit {
+                backspaces: 6,
+                text: String::new(),
+            })
+        );
+
+        // 删除后下一句从头开始
+        assert_eq!(streamer.erase(), None);
+        assert_eq!(streamer.on_partial("ok").as_deref(), Some("ok"));
+    }
+
+    #[test]
+    fn test_live_typer_backspaces_revisions() {
+        let edit = |backspaces, text: &str| {
+            Some(LiveEdit {
+                backspaces,
+                text: text.to_string(),
+            })
+        };
+        let mut typer = LiveTyper::default();
+
+        assert_eq!(typer.on_partial("hel"), edit(0, "hel"));
+        assert_eq!(typer.on_partial("hello"), edit(0, "lo"));
+        assert_eq!(typer.on_partial("hello"), None);
+
+        // 修订：删除分歧点之后的内容再输入新结尾
+        assert_eq!(typer.on_partial("help"), edit(2, "p"));
+        assert_eq!(typer.on_partial("help wanted"), edit(0, " wanted"));
+        assert_eq!(typer.on_partial("help want"), edit(2, ""));
+
+        // 提交时修正为最终文本，下一句从头开始
+        assert_eq!(typer.on_commit("Help wanted."), edit(9, "Help wanted."));
+        assert_eq!(typer.on_partial("你好"), edit(0, "你好"));
+        assert_eq!(typer.on_partial("你们好"), edit(1, "们好"));
+        assert_eq!(typer.on_commit("你们好"), None);
+
+        // 放弃跟踪后重新开始
+        typer.on_partial("abc");
+        typer.reset();
+        assert_eq!(typer.on_partial("abd"), edit(0, "abd"));
+    }
+
+    #[test]
+    fn test_live_typer_failed_edit_resets_current_sentence() {
+        let mut typer = LiveTyper::default();
+
+        typer.on_partial("hel");
+        let first = typer.sentence();
+        assert!(typer.on_failed(first));
+        // 放弃跟踪后重新输入完整的转写
+        assert_eq!(
+            typer.on_partial("hello").map(|e| e.text),
+            Some("hello".into())
+        );
+
+        // 之前语句的失败结果不影响当前语句
+        let second = typer.sentence();
+        typer.on_commit("hello");
+        typer.on_partial("wor");
+        assert!(!typer.on_failed(second));
+        assert_eq!(typer.on_partial("world").map(|e| e.text), Some("ld".into()));
+    }
+
+    #[test]
+    fn test_live_typer_erase_removes_typed_text() {
+        let mut typer = LiveTyper::default();
+        assert_eq!(typer.erase(), None);
+
+        typer.on_partial("你好 world");
+        let sentence = typer.sentence();
+        assert_eq!(
+            typer.erase(),
+            Some(LiveEdit {
+                backspaces: 8,
+                text: String::new(),
+            })
+        );
+        // 删除仍属于当前语句，之后没有可删除的内容
+        assert_eq!(typer.sentence(), sentence);
+        assert_eq!(typer.erase(), None);
+    }
+
+    /// 记录收到的事件的测试处理方
+    #[derive(Default)]
+    struct RecordingHandler {
+        messages: Vec<ServerMessage>,
+        committed: Vec<(String, bool)>,
+        cancel: CancelFlag,
+    }
+
+    impl TranscriptEventHandler for RecordingHandler {
+        type Window = String;
+        type LiveResult = ();
+
+        fn drain_timeout(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        fn promotion_grace(&self) -> Option<Duration> {
+            Some(Duration::from_millis(100))
+        }
+
+        fn handle_message(&mut self, message: ServerMessage) -> bool {
+            if !self.cancel.should_discard(&message) {
+                if let ServerMessage::CommittedTranscript { text, .. } = &message {
+                    self.committed.push((text.clone(), false));
+                }
+                self.messages.push(message);
+            }
+            true
+        }
+
+        fn handle_committed(&mut self, text: String, _confidence: Option<f32>, promoted: bool) {
+            self.committed.push((text, promoted));
+        }
+
+        fn cancel(&mut self) {
+            self.cancel.cancel();
+        }
+
+        fn on_window_changed(&mut self, _window: String) {}
+
+        fn on_live_result(&mut self, _result: ()) {}
+    }
+
+    /// 按顺序放入停止信号和服务器消息后运行事件循环
+    async fn run_with(stop: StopReason, messages: Vec<ServerMessage>) -> RecordingHandler {
+        let (_target_tx, target_rx) = watch::channel(None);
+        let (_live_tx, live_results_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::channel(10);
+        let (stop_tx, mut stop_rx) = mpsc::channel(1);
+        let (_drain_tx, mut drain_rx) = watch::channel(DrainState::default());
+
+        stop_tx.send(stop).await.unwrap();
+        for message in messages {
+            event_tx.send(message).await.unwrap();
+        }
+
+        let mut handler = RecordingHandler::default();
+        let channels = EventChannels {
+            target_rx,
+            live_results_rx,
+            event_rx: &mut event_rx,
+            stop_rx: &mut stop_rx,
+            drain_rx: &mut drain_rx,
+        };
+        run_event_loop(&mut handler, channels).await;
+        handler
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_commit_after_cancel_not_handled() {
+        // 取消之后才处理到的提交既不处理也不提升部分转写
+        let handler = run_with(StopReason::Cancel, vec![partial("hel"), committed("hello")]).await;
+
+        assert!(handler.cancel.is_cancelled());
+        assert!(handler.messages.is_empty());
+        assert!(handler.committed.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stop_handles_remaining_messages() {
+        // 正常停止时剩余消息照常处理，没有提交的部分转写被提升
+        let handler = run_with(StopReason::Stop, vec![committed("hello"), partial("wor")]).await;
+
+        assert!(!handler.cancel.is_cancelled());
+        assert_eq!(
+            handler.committed,
+            vec![("hello".to_string(), false), ("wor".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn test_commit_discarded_after_cancel() {
+        let mut flag = CancelFlag::default();
+        assert!(!flag.should_discard(&committed("hello")));
+
+        flag.cancel();
+        assert!(flag.is_cancelled());
+        assert!(flag.should_discard(&committed("hello")));
+        assert!(flag.should_discard(&partial("hel")));
+
+        // 非转写消息照常处理
+        let ended = ServerMessage::SessionEnded {
+            reason: "cancelled".to_string(),
+        };
+        assert!(!flag.should_discard(&ended));
+    }
+
+    #[test]
+    fn test_duplicate_within_window() {
+        let mut dedup = CommitDeduplicator::new(Duration::from_millis(2000));
+        let start = Instant::now();
+
+        assert!(dedup.should_inject("hello world", start));
+        assert!(!dedup.should_inject("hello world", start + Duration::from_millis(500)));
+        assert!(!dedup.should_inject(" hello world ", start + Duration::from_millis(800)));
+    }
+
+    #[test]
+    fn test_repeat_after_window() {
+        let mut dedup = CommitDeduplicator::new(Duration::from_millis(2000));
+        let start = Instant::now();
+
+        assert!(dedup.should_inject("yes", start));
+        assert!(dedup.should_inject("yes", start + Duration::from_millis(2500)));
+    }
+
+    #[test]
+    fn test_different_text_not_deduped() {
+        let mut dedup = CommitDeduplicator::new(Duration::from_millis(2000));
+        let start = Instant::now();
+
+        assert!(dedup.should_inject("first", start));
+        assert!(dedup.should_inject("second", start + Duration::from_millis(100)));
+        assert!(dedup.should_inject("first", start + Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn test_confidence_threshold() {
+        assert!(meets_confidence(Some(0.9), 0.6));
+        assert!(meets_confidence(Some(0.6), 0.6));
+        assert!(!meets_confidence(Some(0.3), 0.6));
+
+        // 没有置信度时视为通过，阈值为 0 时不过滤
+        assert!(meets_confidence(None, 0.6));
+        assert!(meets_confidence(Some(0.0), 0.0));
+    }
+
+    #[test]
+    fn test_zero_window_disables_dedup() {
+        let mut dedup = CommitDeduplicator::new(Duration::ZERO);
+        let start = Instant::now();
+
+        assert!(dedup.should_inject("same", start));
+        assert!(dedup.should_inject("same", start));
+    }
+}
@@ -0,0 +1,212 @@
+//! 目标窗口监视模块
+//!
+//! 录音过程中用户切换到其他应用时，继续把同一句话注入到新窗口会让人困惑。
+//! 监视器记录录音的目标窗口，检测到切换时要求立即提交当前语句，之后的语句以新窗口为目标。
+//!
+//! 注入时优先使用开始录音时记下的目标窗口，而不是等待焦点归还后才去获取的当前窗口：
+//! 后者在悬浮窗尚未交还焦点或获取失败时会得到错误的目标
+
+/// 观察到焦点窗口后应执行的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowWatchAction {
+    /// 目标窗口未变化
+    None,
+    /// 目标窗口已切换：立即提交当前语句，录音继续
+    ForceCommit {
+        /// 切换前的目标窗口进程 ID
+        from: u32,
+        /// 新的目标窗口进程 ID
+        to: u32,
+    },
+}
+
+/// 目标窗口切换检测
+///
+/// 按进程 ID 区分窗口：同一应用内切换标签页或文档不算切换。
+/// 本应用自己的窗口（悬浮窗、设置窗口）和无法获取的窗口不改变目标
+#[derive(Debug, Clone, Default)]
+pub struct WindowChangeWatcher {
+    own_process_id: Option<u32>,
+    target: Option<u32>,
+}
+
+impl WindowChangeWatcher {
+    /// 创建监视器
+    ///
+    /// # Arguments
+    /// * `own_process_id` - 本应用的进程 ID，其窗口获得焦点时忽略
+    pub fn new(own_process_id: Option<u32>) -> Self {
+        Self {
+            own_process_id,
+            target: None,
+        }
+    }
+
+    /// 当前目标窗口的进程 ID（尚未观察到目标时为 None）
+    pub fn target(&self) -> Option<u32> {
+        self.target
+    }
+
+    /// 记录一次焦点窗口观察结果
+    ///
+    /// 第一次观察到的外部窗口作为目标，不触发提交
+    ///
+    /// # Arguments
+    /// * `process_id` - 当前焦点窗口的进程 ID，无法获取时为 None
+    pub fn observe(&mut self, process_id: Option<u32>) -> WindowWatchAction {
+        let Some(current) = process_id.filter(|pid| Some(*pid) != self.own_process_id) else {
+            return WindowWatchAction::None;
+        };
+
+        match self.target.replace(current) {
+            Some(previous) if previous != current => WindowWatchAction::ForceCommit {
+                from: previous,
+                to: current,
+            },
+            _ => WindowWatchAction::None,
+        }
+    }
+}
+
+/// 事件处理器记下的注入目标
+///
+/// 录音中切换窗口时，切换前说的那句话仍属于原窗口：新窗口先作为待定目标，
+/// 强制提交的语句注入后才成为之后语句的目标
+#[derive(Debug, Clone)]
+pub struct TargetWindow<W> {
+    current: Option<W>,
+    pending: Option<W>,
+}
+
+impl<W> TargetWindow<W> {
+    /// 以开始录音时记下的窗口为目标
+    pub fn new(window: Option<W>) -> Self {
+        Self {
+            current: window,
+            pending: None,
+        }
+    }
+
+    /// 当前语句的注入目标
+    pub fn current(&self) -> Option<&W> {
+        self.current.as_ref()
+    }
+
+    /// 检测到目标窗口切换，下一次提交之后生效
+    pub fn on_window_changed(&mut self, window: W) {
+        self.pending = Some(window);
+    }
+
+    /// 一句话已提交并交给注入队列，待定的新窗口成为目标
+    pub fn on_committed(&mut self) {
+        if let Some(window) = self.pending.take() {
+            self.current = Some(window);
+        }
+    }
+}
+
+/// 注入时使用的目标窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectionTarget {
+    /// 开始录音时记下的窗口
+    Remembered,
+    /// 注入时的焦点窗口
+    Current,
+}
+
+/// 选择注入目标
+///
+/// 记下的窗口所属进程仍在运行时始终使用它，即使焦点暂时落在其他应用上
+/// （用户有意切换窗口时由 `TargetWindow` 更新记下的窗口）；
+/// 只有记下的应用已经关闭，或开始时没有记下窗口，才退回当前焦点窗口
+///
+/// # Arguments
+/// * `remembered` - 记下的目标窗口进程 ID
+/// * `remembered_alive` - 记下的窗口所属进程是否仍在运行
+pub fn choose_injection_target(remembered: Option<u32>, remembered_alive: bool) -> InjectionTarget {
+    match remembered {
+        Some(_) if remembered_alive => InjectionTarget::Remembered,
+        _ => InjectionTarget::Current,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_change_forces_commit() {
+        let mut watcher = WindowChangeWatcher::new(Some(1));
+
+        // 第一个外部窗口成为目标
+        assert_eq!(watcher.observe(Some(10)), WindowWatchAction::None);
+        assert_eq!(watcher.observe(Some(10)), WindowWatchAction::None);
+
+        // 切换到其他应用时提交当前语句
+        assert_eq!(
+            watcher.observe(Some(20)),
+            WindowWatchAction::ForceCommit { from: 10, to: 20 }
+        );
+
+        // 录音继续，新窗口成为目标，不再重复提交
+        assert_eq!(watcher.target(), Some(20));
+        assert_eq!(watcher.observe(Some(20)), WindowWatchAction::None);
+    }
+
+    #[test]
+    fn test_own_and_unknown_windows_ignored() {
+        let mut watcher = WindowChangeWatcher::new(Some(1));
+
+        // 悬浮窗获得焦点、无法获取窗口都不算切换
+        assert_eq!(watcher.observe(Some(1)), WindowWatchAction::None);
+        assert_eq!(watcher.target(), None);
+        assert_eq!(watcher.observe(Some(10)), WindowWatchAction::None);
+        assert_eq!(watcher.observe(Some(1)), WindowWatchAction::None);
+        assert_eq!(watcher.observe(None), WindowWatchAction::None);
+        assert_eq!(watcher.observe(Some(10)), WindowWatchAction::None);
+        assert_eq!(watcher.target(), Some(10));
+    }
+
+    #[test]
+    fn test_target_switches_after_forced_commit() {
+        let mut target = TargetWindow::new(Some(10));
+        assert_eq!(target.current(), Some(&10));
+
+        // 切换前说的话仍注入到原窗口
+        target.on_window_changed(20);
+        assert_eq!(target.current(), Some(&10));
+
+        // 强制提交的语句注入后，之后的语句以新窗口为目标
+        target.on_committed();
+        assert_eq!(target.current(), Some(&20));
+        target.on_committed();
+        assert_eq!(target.current(), Some(&20));
+
+        // 开始时没有记下窗口
+        let mut target = TargetWindow::new(None);
+        target.on_window_changed(30);
+        assert_eq!(target.current(), None);
+        target.on_committed();
+        assert_eq!(target.current(), Some(&30));
+    }
+
+    #[test]
+    fn test_remembered_window_preferred_while_present() {
+        // 记下的应用仍在运行，不论焦点在哪里都以它为目标
+        assert_eq!(
+            choose_injection_target(Some(10), true),
+            InjectionTarget::Remembered
+        );
+
+        // 记下的应用已关闭
+        assert_eq!(
+            choose_injection_target(Some(10), false),
+            InjectionTarget::Current
+        );
+        // 开始时没有记下窗口
+        assert_eq!(
+            choose_injection_target(None, false),
+            InjectionTarget::Current
+        );
+    }
+}
@@ -4,6 +4,7 @@
 
 use super::keyboard::KeyboardInjector;
 use tauri::AppHandle;
+use tauri::image::Image;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use thiserror::Error;
 use tokio::time::{Duration, sleep};
@@ -23,6 +24,138 @@ pub enum ClipboardError {
 
 type Result<T> = std::result::Result<T, ClipboardError>;
 
+/// 剪贴板内容快照
+///
+/// 注入前保存、注入后恢复。HTML 等富文本只能以其纯文本形式保存
+#[derive(Debug, Clone)]
+pub enum ClipboardSnapshot {
+    /// 文本内容
+    Text(String),
+    /// 图片内容（RGBA）
+    Image(Image<'static>),
+    /// 空剪贴板（或无法读取的格式）
+    Empty,
+}
+
+impl ClipboardSnapshot {
+    /// 内容类型名称（用于日志）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "text",
+            Self::Image(_) => "image",
+            Self::Empty => "empty",
+        }
+    }
+}
+
+/// 剪贴板读写接口
+///
+/// 注入流程只依赖该接口，测试中可以替换为内存实现
+trait ClipboardAccess {
+    /// 读取文本内容
+    fn read_text(&self) -> Result<String>;
+
+    /// 写入文本内容
+    fn write_text(&self, text: &str) -> Result<()>;
+
+    /// 读取图片内容
+    fn read_image(&self) -> Result<Image<'static>>;
+
+    /// 写入图片内容
+    fn write_image(&self, image: &Image<'_>) -> Result<()>;
+
+    /// 清空剪贴板
+    fn clear(&self) -> Result<()>;
+
+    /// 保存当前剪贴板内容（优先文本，其次图片）
+    fn snapshot(&self) -> ClipboardSnapshot {
+        if let Ok(text) = self.read_text() {
+            return ClipboardSnapshot::Text(text);
+        }
+
+        match self.read_image() {
+            Ok(image) => ClipboardSnapshot::Image(image),
+            Err(_) => ClipboardSnapshot::Empty,
+        }
+    }
+
+    /// 将剪贴板恢复为快照中的内容
+    fn restore(&self, snapshot: ClipboardSnapshot) -> Result<()> {
+        match snapshot {
+            ClipboardSnapshot::Text(text) => self.write_text(&text),
+            ClipboardSnapshot::Image(image) => self.write_image(&image),
+            ClipboardSnapshot::Empty => self.clear(),
+        }
+    }
+}
+
+impl ClipboardAccess for AppHandle {
+    fn read_text(&self) -> Result<String> {
+        self.clipboard()
+            .read_text()
+            .map_err(|e| ClipboardError::ReadFailed(e.to_string()))
+    }
+
+    fn write_text(&self, text: &str) -> Result<()> {
+        self.clipboard()
+            .write_text(text)
+            .map_err(|e| ClipboardError::WriteFailed(e.to_string()))
+    }
+
+    fn read_image(&self) -> Result<Image<'static>> {
+        self.clipboard()
+            .read_image()
+            .map(Image::to_owned)
+            .map_err(|e| ClipboardError::ReadFailed(e.to_string()))
+    }
+
+    fn write_image(&self, image: &Image<'_>) -> Result<()> {
+        self.clipboard()
+            .write_image(image)
+            .map_err(|e| ClipboardError::WriteFailed(e.to_string()))
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.clipboard()
+            .clear()
+            .map_err(|e| ClipboardError::WriteFailed(e.to_string()))
+    }
+}
+
+/// 写入文本、执行粘贴、等待后恢复旧剪贴板内容
+///
+/// 粘贴失败时不等待，但仍会恢复旧内容后再返回错误
+async fn paste_and_restore<C, P>(clipboard: &C, text: &str, paste: P, wait: Duration) -> Result<()>
+where
+    C: ClipboardAccess,
+    P: FnOnce() -> Result<()>,
+{
+    // 1. 保存当前剪贴板内容
+    let snapshot = clipboard.snapshot();
+    debug!("Saved old clipboard content: {}", snapshot.kind());
+
+    // 2. 写入新文本到剪贴板
+    clipboard.write_text(text)?;
+    debug!("Wrote new text to clipboard");
+
+    // 3. 模拟粘贴快捷键
+    let result = paste();
+
+    // 4. 等待粘贴完成
+    if result.is_ok() {
+        sleep(wait).await;
+    }
+
+    // 5. 恢复旧剪贴板内容（粘贴失败时同样恢复）
+    if let Err(e) = clipboard.restore(snapshot) {
+        warn!("Failed to restore old clipboard: {}", e);
+    } else {
+        debug!("Restored old clipboard content");
+    }
+
+    result
+}
+
 /// 剪贴板注入器
 ///
 /// 使用剪贴板策略注入文本（适合长文本）
@@ -42,11 +175,11 @@ impl ClipboardInjector {
     /// 通过剪贴板注入文本
     ///
     /// # 流程
-    /// 1. 保存当前剪贴板内容
+    /// 1. 保存当前剪贴板内容（文本或图片）
     /// 2. 写入新文本到剪贴板
     /// 3. (可选) 模拟 Cmd+V/Ctrl+V 粘贴
     /// 4. 等待粘贴完成
-    /// 5. 恢复旧剪贴板内容
+    /// 5. 恢复旧剪贴板内容（粘贴失败时同样恢复）
     ///
     /// # Arguments
     /// * `text` - 要注入的文本
@@ -64,44 +197,33 @@ impl ClipboardInjector {
     pub async fn inject_via_clipboard(&self, text: &str, auto_paste: bool) -> Result<()> {
         debug!("Injecting via clipboard: {} chars", text.len());
 
-        // 1. 保存当前剪贴板内容
-        let old_content = self.app.clipboard().read_text().ok();
-
-        if old_content.is_some() {
-            debug!("Saved old clipboard content");
-        }
-
-        // 2. 写入新文本到剪贴板
-        self.app
-            .clipboard()
-            .write_text(text)
-            .map_err(|e| ClipboardError::WriteFailed(e.to_string()))?;
-
-        debug!("Wrote new text to clipboard");
-
-        // 3. 模拟粘贴快捷键（如果启用）
-        if auto_paste {
-            let mut keyboard = KeyboardInjector::new()?;
-            keyboard.simulate_paste()?;
-            debug!("Simulated paste shortcut");
-        } else {
-            debug!("Skipped paste simulation (auto_paste=false)");
-        }
+        // 模拟粘贴快捷键（如果启用）
+        let paste = || {
+            if auto_paste {
+                let mut keyboard = KeyboardInjector::new()?;
+                keyboard.simulate_paste()?;
+                debug!("Simulated paste shortcut");
+            } else {
+                debug!("Skipped paste simulation (auto_paste=false)");
+            }
+            Ok(())
+        };
 
-        // 4. 等待粘贴完成（根据文本长度动态调整）
+        // 等待时间根据文本长度动态调整
         let wait_time = (text.len() / 100).clamp(1, 5); // 1-5 秒
-        sleep(Duration::from_millis(wait_time as u64 * 100)).await;
+        let wait = Duration::from_millis(wait_time as u64 * 100);
 
-        // 5. 恢复旧剪贴板内容
-        if let Some(old) = old_content {
-            if let Err(e) = self.app.clipboard().write_text(&old) {
-                warn!("Failed to restore old clipboard: {}", e);
-            } else {
-                debug!("Restored old clipboard content");
-            }
-        }
+        paste_and_restore(&self.app, text, paste, wait).await
+    }
 
-        Ok(())
+    /// 保存当前剪贴板内容
+    pub fn snapshot(&self) -> ClipboardSnapshot {
+        ClipboardAccess::snapshot(&self.app)
+    }
+
+    /// 将剪贴板恢复为快照中的内容
+    pub fn restore(&self, snapshot: ClipboardSnapshot) -> Result<()> {
+        ClipboardAccess::restore(&self.app, snapshot)
     }
 
     /// 读取当前剪贴板内容
@@ -132,9 +254,142 @@ impl ClipboardInjector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::KeyboardError;
+    use std::sync::Mutex;
 
-    // 注意：剪贴板测试需要 Tauri AppHandle，在单元测试中无法创建
-    // 这些测试应该在集成测试或 E2E 测试中进行
+    // 注意：系统剪贴板测试需要 Tauri AppHandle，在单元测试中无法创建，
+    // 应该在集成测试或 E2E 测试中进行；注入流程使用内存剪贴板测试
+
+    /// 内存剪贴板，记录每次写入
+    struct MockClipboard {
+        content: Mutex<ClipboardSnapshot>,
+        writes: Mutex<Vec<String>>,
+    }
+
+    impl MockClipboard {
+        fn new(content: ClipboardSnapshot) -> Self {
+            Self {
+                content: Mutex::new(content),
+                writes: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn content(&self) -> ClipboardSnapshot {
+            self.content.lock().unwrap().clone()
+        }
+
+        fn writes(&self) -> Vec<String> {
+            self.writes.lock().unwrap().clone()
+        }
+
+        fn set(&self, content: ClipboardSnapshot) {
+            self.writes.lock().unwrap().push(match &content {
+                ClipboardSnapshot::Text(text) => text.clone(),
+                other => other.kind().to_string(),
+            });
+            *self.content.lock().unwrap() = content;
+        }
+    }
+
+    impl ClipboardAccess for MockClipboard {
+        fn read_text(&self) -> Result<String> {
+            match self.content() {
+                ClipboardSnapshot::Text(text) => Ok(text),
+                _ => Err(ClipboardError::ReadFailed("not text".to_string())),
+            }
+        }
+
+        fn write_text(&self, text: &str) -> Result<()> {
+            self.set(ClipboardSnapshot::Text(text.to_string()));
+            Ok(())
+        }
+
+        fn read_image(&self) -> Result<Image<'static>> {
+            match self.content() {
+                ClipboardSnapshot::Image(image) => Ok(image),
+                _ => Err(ClipboardError::ReadFailed("not image".to_string())),
+            }
+        }
+
+        fn write_image(&self, image: &Image<'_>) -> Result<()> {
+            self.set(ClipboardSnapshot::Image(image.clone().to_owned()));
+            Ok(())
+        }
+
+        fn clear(&self) -> Result<()> {
+            self.set(ClipboardSnapshot::Empty);
+            Ok(())
+        }
+    }
+
+    fn text(content: &str) -> ClipboardSnapshot {
+        ClipboardSnapshot::Text(content.to_string())
+    }
+
+    fn image() -> ClipboardSnapshot {
+        ClipboardSnapshot::Image(Image::new_owned(vec![255, 0, 0, 255, 0, 255, 0, 255], 2, 1))
+    }
+
+    #[test]
+    fn test_snapshot_restore_round_trip() {
+        // 文本
+        let clipboard = MockClipboard::new(text("original"));
+        let snapshot = clipboard.snapshot();
+        assert!(matches!(&snapshot, ClipboardSnapshot::Text(t) if t == "original"));
+        clipboard.write_text("transcript").unwrap();
+        clipboard.restore(snapshot).unwrap();
+        assert!(matches!(clipboard.content(), ClipboardSnapshot::Text(t) if t == "original"));
+
+        // 空剪贴板恢复后仍为空，而不是残留转写文本
+        let clipboard = MockClipboard::new(ClipboardSnapshot::Empty);
+        let snapshot = clipboard.snapshot();
+        assert!(matches!(snapshot, ClipboardSnapshot::Empty));
+        clipboard.write_text("transcript").unwrap();
+        clipboard.restore(snapshot).unwrap();
+        assert!(matches!(clipboard.content(), ClipboardSnapshot::Empty));
+    }
+
+    #[tokio::test]
+    async fn test_restore_after_paste() {
+        let clipboard = MockClipboard::new(text("original"));
+
+        paste_and_restore(&clipboard, "transcript", || Ok(()), Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(clipboard.writes(), vec!["transcript", "original"]);
+        assert!(matches!(clipboard.content(), ClipboardSnapshot::Text(t) if t == "original"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_after_paste_failure() {
+        let clipboard = MockClipboard::new(text("original"));
+        let paste = || Err(ClipboardError::Keyboard(KeyboardError::InitFailed));
+
+        let result = paste_and_restore(&clipboard, "transcript", paste, Duration::ZERO).await;
+
+        assert!(matches!(result, Err(ClipboardError::Keyboard(_))));
+        assert_eq!(clipboard.writes(), vec!["transcript", "original"]);
+        assert!(matches!(clipboard.content(), ClipboardSnapshot::Text(t) if t == "original"));
+    }
+
+    #[tokio::test]
+    async fn test_image_clipboard_restored() {
+        let clipboard = MockClipboard::new(image());
+
+        paste_and_restore(&clipboard, "transcript", || Ok(()), Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(clipboard.writes(), vec!["transcript", "image"]);
+        match clipboard.content() {
+            ClipboardSnapshot::Image(restored) => {
+                assert_eq!((restored.width(), restored.height()), (2, 1));
+                assert_eq!(restored.rgba(), &[255, 0, 0, 255, 0, 255, 0, 255]);
+            }
+            other => panic!("Expected image, got {:?}", other),
+        }
+    }
 
     #[test]
     fn test_clipboard_error_types() {
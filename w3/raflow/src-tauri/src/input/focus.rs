@@ -2,6 +2,7 @@
 //!
 //! 管理悬浮窗和目标应用之间的焦点切换
 
+use crate::system::{WindowInfo, WindowTracker};
 use tauri::{AppHandle, Manager};
 use thiserror::Error;
 use tokio::time::{Duration, sleep};
@@ -24,30 +25,116 @@ pub enum FocusError {
 
 type Result<T> = std::result::Result<T, FocusError>;
 
+/// 焦点归还的轮询策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusRetry {
+    /// 最多检查焦点的次数
+    pub attempts: u32,
+    /// 第一次重试前的等待时间，之后每次翻倍
+    pub initial_backoff: Duration,
+    /// 单次等待的上限
+    pub max_backoff: Duration,
+}
+
+impl Default for FocusRetry {
+    fn default() -> Self {
+        Self {
+            attempts: 6,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_millis(400),
+        }
+    }
+}
+
+/// 隐藏悬浮窗后等待系统把焦点归还给目标应用
+///
+/// 按调用方配置的时长等待，不再强制下限：等待过短时慢速机器上焦点可能尚未归还，
+/// 由之后的焦点轮询兜底；等待过长则每次注入都多出相应的延迟
+async fn wait_for_focus_return(wait_ms: u64) {
+    sleep(Duration::from_millis(wait_ms)).await;
+    debug!(
+        "Focus should be on target window now (waited {}ms)",
+        wait_ms
+    );
+}
+
+/// 焦点是否已离开本应用
+///
+/// 无法获取当前窗口时无法判断，视为已归还（与不检查时的行为一致）
+fn focus_left_own_process(window: Option<&WindowInfo>, own_process_id: u32) -> bool {
+    window.is_none_or(|window| window.process_id != own_process_id)
+}
+
+/// 轮询当前焦点窗口，直到焦点离开本应用
+///
+/// 每次检查之间按 `retry` 退避等待；检查 `retry.attempts` 次后焦点仍在本应用时返回 `FocusFailed`
+async fn wait_for_focus_to_leave<F>(
+    retry: FocusRetry,
+    own_process_id: u32,
+    mut current_window: F,
+) -> Result<()>
+where
+    F: FnMut() -> Option<WindowInfo>,
+{
+    let mut backoff = retry.initial_backoff;
+
+    for attempt in 1..=retry.attempts.max(1) {
+        let window = current_window();
+        if focus_left_own_process(window.as_ref(), own_process_id) {
+            debug!("Focus returned to target window after {} check(s)", attempt);
+            return Ok(());
+        }
+
+        if attempt < retry.attempts {
+            debug!("Focus still on this app, retrying in {:?}", backoff);
+            sleep(backoff).await;
+            backoff = backoff.saturating_mul(2).min(retry.max_backoff);
+        }
+    }
+
+    Err(FocusError::FocusFailed(format!(
+        "focus did not leave this app after {} checks",
+        retry.attempts.max(1)
+    )))
+}
+
 /// 焦点管理器
 ///
 /// 确保文本注入时焦点在正确的窗口
 pub struct FocusManager {
     app: AppHandle,
+    retry: FocusRetry,
 }
 
 impl FocusManager {
     /// 创建新的焦点管理器
     pub fn new(app: AppHandle) -> Self {
-        Self { app }
+        Self {
+            app,
+            retry: FocusRetry::default(),
+        }
     }
 
     /// 隐藏悬浮窗并等待焦点归还
     ///
-    /// 隐藏悬浮窗后，系统会自动将焦点归还给之前的活跃窗口
+    /// 隐藏悬浮窗后，系统会自动将焦点归还给之前的活跃窗口。
+    /// 悬浮窗原本可见时，等待后还会轮询当前窗口，直到焦点离开本应用（按进程 ID 判断），
+    /// 慢速机器上焦点迟迟未归还时按退避重试，始终未归还返回 `FocusFailed`。
+    /// 点击悬浮窗停止录音时悬浮窗持有焦点，同样需要等待焦点归还。
+    /// 调用方可能已经先隐藏了悬浮窗，此时由 `overlay_was_shown` 告知
     ///
     /// # Arguments
-    /// * `wait_ms` - 等待焦点归还的时间（毫秒）
-    pub async fn ensure_target_focused(&self, wait_ms: u64) -> Result<()> {
+    /// * `wait_ms` - 等待焦点归还的时间（毫秒），按原值使用
+    /// * `overlay_was_shown` - 调用方注入前是否隐藏过可见的悬浮窗
+    pub async fn ensure_target_focused(&self, wait_ms: u64, overlay_was_shown: bool) -> Result<()> {
         debug!("Ensuring target window has focus");
 
         // 获取 overlay 窗口
+        let mut overlay_was_visible = overlay_was_shown;
         if let Some(overlay) = self.app.get_webview_window("overlay") {
+            let focused = overlay.is_focused().unwrap_or(false);
+            overlay_was_visible |= overlay.is_visible().unwrap_or(true) || focused;
+
             // 隐藏悬浮窗
             overlay
                 .hide()
@@ -59,11 +146,15 @@ impl FocusManager {
         }
 
         // 等待系统将焦点归还给目标应用
-        // 增加等待时间，确保焦点真正切换
-        let extended_wait = wait_ms.max(200); // 至少等待 200ms
-        sleep(Duration::from_millis(extended_wait)).await;
-
-        debug!("Focus should be on target window now (waited {}ms)", extended_wait);
+        wait_for_focus_return(wait_ms).await;
+
+        // 悬浮窗没有占用焦点时（如从设置窗口测试注入）不要求焦点离开本应用
+        if overlay_was_visible {
+            wait_for_focus_to_leave(self.retry, std::process::id(), || {
+                WindowTracker::get_current_window().ok()
+            })
+            .await?;
+        }
 
         Ok(())
     }
@@ -177,6 +268,66 @@ mod tests {
         assert!(err.to_string().contains("Failed to show window"));
     }
 
+    fn window(process_id: u32) -> WindowInfo {
+        WindowInfo {
+            app_name: format!("app-{}", process_id),
+            title: String::new(),
+            process_id,
+            position: (0, 0, 0, 0),
+        }
+    }
+
+    fn immediate(attempts: u32) -> FocusRetry {
+        FocusRetry {
+            attempts,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_focus_left_own_process() {
+        assert!(focus_left_own_process(Some(&window(2)), 1));
+        assert!(!focus_left_own_process(Some(&window(1)), 1));
+        assert!(focus_left_own_process(None, 1));
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_focus_leaves() {
+        // 前两次检查焦点仍在本应用，第三次归还
+        let mut windows = vec![window(1), window(1), window(7)].into_iter();
+        let mut checks = 0;
+        let result = wait_for_focus_to_leave(immediate(5), 1, || {
+            checks += 1;
+            windows.next()
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(checks, 3);
+    }
+
+    #[tokio::test]
+    async fn test_wait_gives_up_when_focus_stays() {
+        let mut checks = 0;
+        let result = wait_for_focus_to_leave(immediate(4), 1, || {
+            checks += 1;
+            Some(window(1))
+        })
+        .await;
+
+        assert!(matches!(result, Err(FocusError::FocusFailed(_))));
+        assert_eq!(checks, 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_focus_wait_used_verbatim() {
+        // 配置的 80ms 不会被抬高到 200ms
+        let started = tokio::time::Instant::now();
+        wait_for_focus_return(80).await;
+        assert_eq!(started.elapsed(), Duration::from_millis(80));
+    }
+
     // 实际的焦点管理测试需要 Tauri 运行时环境
     // 应该在集成测试或 E2E 测试中进行
 }
@@ -6,9 +6,11 @@ use super::{
     clipboard::{ClipboardError, ClipboardInjector},
     focus::{FocusError, FocusManager},
     keyboard::{KeyboardError, KeyboardInjector},
+    layout::is_problematic_layout,
 };
-use crate::system::WindowInfo;
-use tauri::AppHandle;
+use crate::system::{WindowInfo, WindowTracker};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
@@ -26,14 +28,27 @@ pub enum InjectorError {
     #[error("Target window is blacklisted: {0}")]
     Blacklisted(String),
 
+    #[error("Target window is not allowlisted: {0}")]
+    NotAllowlisted(String),
+
+    #[error("Target window belongs to this app: {0}")]
+    OwnWindow(String),
+
     #[error("Text too long: {0} chars (max: {1})")]
     TextTooLong(usize, usize),
+
+    #[error("Keyboard layout may mistype ASCII text: {0}")]
+    ProblematicLayout(String),
+
+    #[error("Live typing is not supported in: {0}")]
+    LiveTypingUnsupported(String),
 }
 
 type Result<T> = std::result::Result<T, InjectorError>;
 
 /// 注入策略
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InjectionStrategy {
     /// 键盘模拟（适合短文本）
     Keyboard,
@@ -41,6 +56,69 @@ pub enum InjectionStrategy {
     Clipboard,
 }
 
+/// 一次注入的结果
+///
+/// 通过 `injection_result` 事件发送到前端，悬浮窗据此提示注入到了哪个应用或为何被拒绝
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InjectionResult {
+    pub success: bool,
+    /// 成功时使用的注入策略
+    pub strategy: Option<InjectionStrategy>,
+    /// 目标应用名称（无法获取当前窗口时为空）
+    pub app_name: String,
+    /// 失败原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl InjectionResult {
+    /// 由注入结果和目标窗口生成
+    pub fn from_outcome(window: &WindowInfo, outcome: &Result<InjectionStrategy>) -> Self {
+        match outcome {
+            Ok(strategy) => Self {
+                success: true,
+                strategy: Some(*strategy),
+                app_name: window.app_name.clone(),
+                error: None,
+            },
+            Err(e) => Self::failure(window.app_name.clone(), e.to_string()),
+        }
+    }
+
+    /// 注入失败
+    pub fn failure(app_name: String, error: String) -> Self {
+        Self {
+            success: false,
+            strategy: None,
+            app_name,
+            error: Some(error),
+        }
+    }
+}
+
+/// 一次注入的执行方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InjectionPlan {
+    pub strategy: InjectionStrategy,
+    /// 注入后追加的按键
+    pub append: AppendMode,
+    /// 键盘模拟时每个字符的输入延迟（毫秒）
+    pub typing_delay_ms: u64,
+}
+
+/// 注入成功后追加的按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppendMode {
+    /// 不追加
+    #[default]
+    None,
+    /// 追加空格，分隔相邻两段口述
+    Space,
+    /// 按回车（如在聊天应用中直接发送）
+    Newline,
+}
+
 /// 注入配置
 #[derive(Debug, Clone)]
 pub struct InjectionConfig {
@@ -48,14 +126,36 @@ pub struct InjectionConfig {
     pub keyboard_max_chars: usize,
     /// 每个字符的输入延迟（毫秒）
     pub typing_delay_ms: u64,
-    /// 焦点归还等待时间（毫秒）
+    /// 焦点归还等待时间（毫秒），按原值使用，不设下限
     pub focus_wait_ms: u64,
     /// 是否启用黑名单检查
     pub enable_blacklist: bool,
+    /// 黑名单应用名称模式
+    pub blacklist: Vec<String>,
+    /// 是否启用允许列表模式（只向允许列表中的应用注入）
+    pub allowlist_mode: bool,
+    /// 允许列表应用名称模式
+    pub allowlist: Vec<String>,
     /// 最大文本长度限制
     pub max_text_length: usize,
     /// 是否自动模拟粘贴快捷键（false 则只写入剪贴板，不自动粘贴）
     pub auto_paste: bool,
+    /// 当前键盘布局标识（启动时检测，None 表示未知）
+    pub keyboard_layout: Option<String>,
+    /// 布局已知有问题时，ASCII 文本改用剪贴板注入（默认关闭，不动用户的剪贴板）
+    pub clipboard_on_problematic_layout: bool,
+    /// 本应用的进程 ID，焦点在本应用自己的窗口（如设置窗口）时不注入；None 表示不检查
+    pub own_process_id: Option<u32>,
+    /// 超过该字符数的提交先等待用户确认再注入，避免误把大段文本粘贴到错误位置；None 表示不确认
+    pub confirm_above_chars: Option<usize>,
+    /// 注入成功后追加的按键
+    pub append_after_inject: AppendMode,
+    /// 向终端注入时把多行文本合并为一行、优先键盘模拟并且不按回车，避免逐行执行命令
+    pub terminal_safe: bool,
+    /// 终端安全模式下每个字符的输入延迟（毫秒），终端处理输入较慢
+    pub terminal_typing_delay_ms: u64,
+    /// 调用方在注入前已隐藏了可见的悬浮窗，焦点可能仍在本应用，注入前需要等待焦点离开
+    pub overlay_was_shown: bool,
 }
 
 impl Default for InjectionConfig {
@@ -65,8 +165,179 @@ impl Default for InjectionConfig {
             typing_delay_ms: 5,
             focus_wait_ms: 50,
             enable_blacklist: true,
+            blacklist: WindowTracker::get_blacklist(),
+            allowlist_mode: false,
+            allowlist: Vec::new(),
             max_text_length: 10000,
             auto_paste: false, // 默认禁用自动粘贴，避免 enigo 导致程序退出
+            keyboard_layout: None,
+            clipboard_on_problematic_layout: false,
+            own_process_id: None,
+            confirm_above_chars: None,
+            append_after_inject: AppendMode::None,
+            terminal_safe: true,
+            terminal_typing_delay_ms: 15,
+            overlay_was_shown: false,
+        }
+    }
+}
+
+/// 把多行文本合并为一行：换行（含 CRLF）及其两侧的空白替换为一个空格
+///
+/// 粘贴或输入到终端时，换行会让 shell 逐行执行
+pub fn single_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 文本的字符数
+///
+/// 长度阈值都按字符而不是 UTF-8 字节计算，否则 5 个汉字（15 字节）就会被当作长文本
+pub fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
+
+impl InjectionConfig {
+    /// 选择注入策略
+    ///
+    /// 短文本使用键盘模拟，长文本使用剪贴板；
+    /// 键盘布局有问题且启用了回退时，ASCII 文本也改用剪贴板
+    pub fn select_strategy(&self, text: &str) -> InjectionStrategy {
+        if char_count(text) > self.keyboard_max_chars {
+            return InjectionStrategy::Clipboard;
+        }
+
+        match self.check_layout(text) {
+            Err(e) if self.clipboard_on_problematic_layout => {
+                warn!("{}, using clipboard", e);
+                InjectionStrategy::Clipboard
+            }
+            Err(e) => {
+                warn!("{}", e);
+                InjectionStrategy::Keyboard
+            }
+            Ok(()) => InjectionStrategy::Keyboard,
+        }
+    }
+
+    /// 目标窗口是否按终端安全模式注入
+    pub fn is_terminal_safe_target(&self, window: &WindowInfo) -> bool {
+        self.terminal_safe && WindowTracker::is_terminal(window)
+    }
+
+    /// 决定注入方式
+    ///
+    /// 终端安全模式下（`terminal` 为 true）文本应已合并为一行：优先键盘模拟（布局有问题时仍用剪贴板），
+    /// 使用较大的输入延迟，并且不追加回车
+    pub fn plan(&self, text: &str, terminal: bool) -> InjectionPlan {
+        if !terminal {
+            let strategy = self.select_strategy(text);
+            return InjectionPlan {
+                strategy,
+                append: self.append_for(strategy),
+                typing_delay_ms: self.typing_delay_ms,
+            };
+        }
+
+        let strategy = match self.check_layout(text) {
+            Err(e) if self.clipboard_on_problematic_layout => {
+                warn!("{}, using clipboard", e);
+                InjectionStrategy::Clipboard
+            }
+            _ => InjectionStrategy::Keyboard,
+        };
+        let append = match self.append_for(strategy) {
+            AppendMode::Newline => AppendMode::None,
+            append => append,
+        };
+
+        InjectionPlan {
+            strategy,
+            append,
+            typing_delay_ms: self.typing_delay_ms.max(self.terminal_typing_delay_ms),
+        }
+    }
+
+    /// 按注入策略决定实际追加的按键
+    ///
+    /// 剪贴板策略未启用自动粘贴时文本并未输入到目标窗口，不追加
+    pub fn append_for(&self, strategy: InjectionStrategy) -> AppendMode {
+        match strategy {
+            InjectionStrategy::Clipboard if !self.auto_paste => AppendMode::None,
+            _ => self.append_after_inject,
+        }
+    }
+
+    /// 检查目标窗口是否允许注入
+    ///
+    /// 黑名单优先：即使应用在允许列表中，命中黑名单也会被拒绝；
+    /// 允许列表模式下，未在允许列表中的应用一律拒绝；
+    /// 设置了 `own_process_id` 时，本应用自己的窗口也会被拒绝
+    pub fn check_target(&self, window: &WindowInfo) -> Result<()> {
+        if self.own_process_id == Some(window.process_id) {
+            return Err(InjectorError::OwnWindow(window.app_name.clone()));
+        }
+
+        if self.enable_blacklist && window.is_blacklisted(&self.blacklist) {
+            return Err(InjectorError::Blacklisted(window.app_name.clone()));
+        }
+
+        if self.allowlist_mode && !window.is_allowlisted(&self.allowlist) {
+            return Err(InjectorError::NotAllowlisted(window.app_name.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// 文本是否需要用户确认后再注入（按字符数计算）
+    pub fn needs_confirmation(&self, text: &str) -> bool {
+        self.confirm_above_chars
+            .is_some_and(|limit| char_count(text) > limit)
+    }
+
+    /// 检查文本是否超过最大长度（按字符数计算）
+    pub fn check_length(&self, text: &str) -> Result<()> {
+        let chars = char_count(text);
+        if chars > self.max_text_length {
+            return Err(InjectorError::TextTooLong(chars, self.max_text_length));
+        }
+        Ok(())
+    }
+
+    /// 检查当前键盘布局能否正确模拟输入文本
+    ///
+    /// 非美式布局下 enigo 可能把 ASCII 符号转写成其他字符，
+    /// 布局已知有问题时拒绝键盘模拟；布局未知时放行
+    pub fn check_layout(&self, text: &str) -> Result<()> {
+        match &self.keyboard_layout {
+            Some(layout) if text.is_ascii() && is_problematic_layout(layout) => {
+                Err(InjectorError::ProblematicLayout(layout.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 检查目标窗口能否实时输入
+    ///
+    /// 实时输入依赖退格修正已输入的文本：终端会把退格当作命令行编辑，
+    /// 布局有问题时也无法改用剪贴板修正，这两种情况都不支持
+    pub fn check_live_typing(&self, window: &WindowInfo) -> Result<()> {
+        self.check_target(window)?;
+
+        if WindowTracker::is_terminal(window) {
+            return Err(InjectorError::LiveTypingUnsupported(
+                window.app_name.clone(),
+            ));
+        }
+
+        match &self.keyboard_layout {
+            Some(layout) if is_problematic_layout(layout) => {
+                Err(InjectorError::ProblematicLayout(layout.clone()))
+            }
+            _ => Ok(()),
         }
     }
 }
@@ -75,6 +346,7 @@ impl Default for InjectionConfig {
 ///
 /// 智能选择注入策略并执行文本注入
 pub struct TextInjector {
+    app: AppHandle,
     keyboard: KeyboardInjector,
     clipboard: ClipboardInjector,
     focus: FocusManager,
@@ -88,6 +360,7 @@ impl TextInjector {
     /// * `app` - Tauri AppHandle
     pub fn new(app: AppHandle) -> Result<Self> {
         Ok(Self {
+            app: app.clone(),
             keyboard: KeyboardInjector::new()?,
             clipboard: ClipboardInjector::new(app.clone()),
             focus: FocusManager::new(app),
@@ -98,6 +371,7 @@ impl TextInjector {
     /// 使用自定义配置创建注入器
     pub fn with_config(app: AppHandle, config: InjectionConfig) -> Result<Self> {
         Ok(Self {
+            app: app.clone(),
             keyboard: KeyboardInjector::new()?,
             clipboard: ClipboardInjector::new(app.clone()),
             focus: FocusManager::new(app),
@@ -110,9 +384,10 @@ impl TextInjector {
     /// # Arguments
     /// * `text` - 要注入的文本
     /// * `window` - 目标窗口信息
+    /// * `dry_run` - 只执行检查（长度、黑名单、焦点）和策略选择，不模拟键盘、不写剪贴板
     ///
     /// # Returns
-    /// * `Ok(InjectionStrategy)` - 成功注入，返回使用的策略
+    /// * `Ok(InjectionStrategy)` - 成功注入（或试运行通过），返回使用的策略
     /// * `Err(InjectorError)` - 注入失败
     ///
     /// # Example
@@ -123,79 +398,146 @@ impl TextInjector {
     /// async fn inject_text(app: tauri::AppHandle) {
     ///     let mut injector = TextInjector::new(app).unwrap();
     ///     let window = WindowTracker::get_current_window().unwrap();
-    ///     injector.inject("Hello, world!", &window).await.unwrap();
+    ///     injector.inject("Hello, world!", &window, false).await.unwrap();
     /// }
     /// ```
-    pub async fn inject(&mut self, text: &str, window: &WindowInfo) -> Result<InjectionStrategy> {
+    pub async fn inject(
+        &mut self,
+        text: &str,
+        window: &WindowInfo,
+        dry_run: bool,
+    ) -> Result<InjectionStrategy> {
         info!(
-            "Injecting text: {} chars to {}",
-            text.len(),
-            window.app_name
+            "Injecting text: {} chars to {}{}",
+            char_count(text),
+            window.app_name,
+            if dry_run { " (dry run)" } else { "" }
         );
 
         // 1. 检查文本长度
-        if text.len() > self.config.max_text_length {
-            return Err(InjectorError::TextTooLong(
-                text.len(),
-                self.config.max_text_length,
-            ));
-        }
-
-        // 2. 黑名单检查
-        if self.config.enable_blacklist && window.is_blacklisted() {
-            warn!("Target window is blacklisted: {}", window.app_name);
-            return Err(InjectorError::Blacklisted(window.app_name.clone()));
+        self.config.check_length(text)?;
+
+        // 2. 黑名单 / 允许列表检查（试运行时不写剪贴板）
+        match self.config.check_target(window) {
+            Ok(()) => {}
+            Err(e) if dry_run => {
+                warn!("Target window rejected: {}", e);
+                return Err(e);
+            }
+            Err(InjectorError::NotAllowlisted(app_name)) => {
+                warn!("Target window is not allowlisted: {}", app_name);
+                self.fallback_to_clipboard(text, "not_allowlisted", &app_name);
+                return Err(InjectorError::NotAllowlisted(app_name));
+            }
+            Err(InjectorError::OwnWindow(app_name)) => {
+                warn!("Focus is on our own window, skipping injection");
+                self.fallback_to_clipboard(text, "own_window_focused", &app_name);
+                return Err(InjectorError::OwnWindow(app_name));
+            }
+            Err(e) => {
+                warn!("Target window rejected: {}", e);
+                return Err(e);
+            }
         }
 
         // 3. 确保焦点在目标窗口
         self.focus
-            .ensure_target_focused(self.config.focus_wait_ms)
+            .ensure_target_focused(self.config.focus_wait_ms, self.config.overlay_was_shown)
             .await?;
 
-        // 4. 选择注入策略
-        let strategy = self.select_strategy(text);
+        // 4. 选择注入策略（终端中合并为一行，避免换行触发命令执行）
+        let terminal = self.config.is_terminal_safe_target(window);
+        let terminal_text;
+        let text = if terminal {
+            debug!("Terminal target, injecting as a single line");
+            terminal_text = single_line(text);
+            terminal_text.as_str()
+        } else {
+            text
+        };
+        let plan = self.config.plan(text, terminal);
+        let strategy = plan.strategy;
         debug!("Selected strategy: {:?}", strategy);
 
-        // 5. 执行注入
-        match strategy {
-            InjectionStrategy::Keyboard => {
-                self.inject_via_keyboard(text).await?;
-            }
-            InjectionStrategy::Clipboard => {
-                self.inject_via_clipboard(text).await?;
-            }
-        }
+        // 5. 执行注入并追加空格或回车
+        perform(self, text, &plan, dry_run).await?;
 
-        info!("Text injected successfully using {:?}", strategy);
+        if dry_run {
+            info!("Dry run passed, would inject using {:?}", strategy);
+        } else {
+            info!("Text injected successfully using {:?}", strategy);
+        }
 
         Ok(strategy)
     }
 
-    /// 选择注入策略
+    /// 实时输入：退格删除指定数量的字符，再通过键盘模拟输入新文本
     ///
-    /// 根据文本长度自动选择最佳策略
-    fn select_strategy(&self, text: &str) -> InjectionStrategy {
-        if text.len() <= self.config.keyboard_max_chars {
-            InjectionStrategy::Keyboard
-        } else {
-            InjectionStrategy::Clipboard
+    /// # Arguments
+    /// * `backspaces` - 需要删除的字符数
+    /// * `text` - 删除后输入的文本
+    /// * `window` - 目标窗口
+    pub async fn retype(
+        &mut self,
+        backspaces: usize,
+        text: &str,
+        window: &WindowInfo,
+    ) -> Result<()> {
+        self.config.check_live_typing(window)?;
+
+        if backspaces == 0 && text.is_empty() {
+            return Ok(());
+        }
+
+        self.focus
+            .ensure_target_focused(self.config.focus_wait_ms, self.config.overlay_was_shown)
+            .await?;
+
+        debug!(
+            "Live typing: {} backspaces, {} chars",
+            backspaces,
+            text.chars().count()
+        );
+        for _ in 0..backspaces {
+            self.keyboard.simulate_backspace()?;
+        }
+        if !text.is_empty() {
+            self.inject_via_keyboard(text, self.config.typing_delay_ms)
+                .await?;
         }
+        Ok(())
     }
 
     /// 通过键盘模拟注入（短文本）
-    async fn inject_via_keyboard(&mut self, text: &str) -> Result<()> {
-        debug!("Injecting via keyboard: {} chars", text.len());
-        self.keyboard.type_text(text).await?;
+    async fn inject_via_keyboard(&mut self, text: &str, delay_ms: u64) -> Result<()> {
+        debug!("Injecting via keyboard: {} chars", char_count(text));
+
+        if delay_ms > 0 {
+            self.keyboard.type_text_delayed(text, delay_ms).await?;
+        } else {
+            self.keyboard.type_text(text).await?;
+        }
         Ok(())
     }
 
     /// 通过剪贴板注入（长文本）
     async fn inject_via_clipboard(&self, text: &str) -> Result<()> {
-        debug!("Injecting via clipboard: {} chars", text.len());
+        debug!("Injecting via clipboard: {} chars", char_count(text));
         self.clipboard.inject_via_clipboard(text, self.config.auto_paste).await?;
         Ok(())
     }
 
+    /// 目标被拒绝时，只把文本写入剪贴板供用户手动粘贴，并通过 `event` 通知前端
+    fn fallback_to_clipboard(&self, text: &str, event: &str, app_name: &str) {
+        if let Err(e) = self.clipboard.write(text) {
+            warn!("Failed to copy rejected text to clipboard: {}", e);
+        }
+
+        if let Err(e) = self.app.emit(event, app_name) {
+            warn!("Failed to emit {}: {}", event, e);
+        }
+    }
+
     /// 更新配置
     pub fn set_config(&mut self, config: InjectionConfig) {
         self.config = config;
@@ -207,6 +549,56 @@ impl TextInjector {
     }
 }
 
+/// 注入的实际输出（键盘模拟和剪贴板）
+trait InjectionOutput {
+    /// 模拟键盘输入文本，每个字符间隔 `delay_ms` 毫秒
+    async fn type_text(&mut self, text: &str, delay_ms: u64) -> Result<()>;
+    /// 通过剪贴板注入文本
+    async fn paste(&mut self, text: &str) -> Result<()>;
+    /// 按回车
+    fn press_enter(&mut self) -> Result<()>;
+}
+
+impl InjectionOutput for TextInjector {
+    async fn type_text(&mut self, text: &str, delay_ms: u64) -> Result<()> {
+        self.inject_via_keyboard(text, delay_ms).await
+    }
+
+    async fn paste(&mut self, text: &str) -> Result<()> {
+        self.inject_via_clipboard(text).await
+    }
+
+    fn press_enter(&mut self) -> Result<()> {
+        Ok(self.keyboard.simulate_enter()?)
+    }
+}
+
+/// 按策略输出文本并追加按键；试运行时不产生任何输出
+async fn perform<O: InjectionOutput>(
+    output: &mut O,
+    text: &str,
+    plan: &InjectionPlan,
+    dry_run: bool,
+) -> Result<()> {
+    if dry_run {
+        return Ok(());
+    }
+
+    let delay_ms = plan.typing_delay_ms;
+    match plan.strategy {
+        InjectionStrategy::Keyboard => output.type_text(text, delay_ms).await?,
+        InjectionStrategy::Clipboard => output.paste(text).await?,
+    }
+
+    match plan.append {
+        AppendMode::None => {}
+        AppendMode::Space => output.type_text(" ", delay_ms).await?,
+        AppendMode::Newline => output.press_enter()?,
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -218,6 +610,7 @@ mod tests {
         assert_eq!(config.typing_delay_ms, 5);
         assert_eq!(config.focus_wait_ms, 50);
         assert!(config.enable_blacklist);
+        assert_eq!(config.blacklist.len(), 6);
     }
 
     #[test]
@@ -242,6 +635,40 @@ mod tests {
         assert_eq!(strategy, InjectionStrategy::Clipboard);
     }
 
+    #[test]
+    fn test_strategy_counts_chars_not_bytes() {
+        let config = InjectionConfig::default();
+
+        // 5 个汉字是 15 字节，按字节计算会超过 10 的阈值
+        let cjk = "你好，世界";
+        assert!(cjk.len() > config.keyboard_max_chars);
+        assert_eq!(char_count(cjk), 5);
+        assert_eq!(config.select_strategy(cjk), InjectionStrategy::Keyboard);
+
+        // 中英混合：10 个字符，18 字节
+        let mixed = "Ra语音听写flow";
+        assert_eq!(char_count(mixed), 10);
+        assert_eq!(config.select_strategy(mixed), InjectionStrategy::Keyboard);
+        assert_eq!(
+            config.select_strategy("RAFlow语音听写工具"),
+            InjectionStrategy::Clipboard
+        );
+    }
+
+    #[test]
+    fn test_length_limit_counts_chars() {
+        let config = InjectionConfig {
+            max_text_length: 4,
+            ..Default::default()
+        };
+
+        assert!(config.check_length("语音听写").is_ok());
+        assert!(matches!(
+            config.check_length("语音听写ok"),
+            Err(InjectorError::TextTooLong(6, 4))
+        ));
+    }
+
     #[test]
     fn test_injector_error_types() {
         let err = InjectorError::Blacklisted("1Password".to_string());
@@ -251,6 +678,323 @@ mod tests {
         assert!(err.to_string().contains("too long"));
     }
 
+    fn window(app_name: &str) -> WindowInfo {
+        WindowInfo {
+            app_name: app_name.to_string(),
+            title: String::new(),
+            process_id: 1,
+            position: (0, 0, 0, 0),
+        }
+    }
+
+    #[test]
+    fn test_allowlist_disabled_allows_all() {
+        let config = InjectionConfig::default();
+        assert!(config.check_target(&window("Visual Studio Code")).is_ok());
+        assert!(config.check_target(&window("Slack")).is_ok());
+    }
+
+    #[test]
+    fn test_allowlist_enforced() {
+        let config = InjectionConfig {
+            allowlist_mode: true,
+            allowlist: vec!["Code".to_string(), "Obsidian".to_string()],
+            ..Default::default()
+        };
+
+        assert!(config.check_target(&window("Visual Studio Code")).is_ok());
+        assert!(config.check_target(&window("Obsidian")).is_ok());
+        assert!(matches!(
+            config.check_target(&window("Slack")),
+            Err(InjectorError::NotAllowlisted(app)) if app == "Slack"
+        ));
+
+        // 空允许列表拒绝所有应用
+        let config = InjectionConfig {
+            allowlist_mode: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.check_target(&window("Visual Studio Code")),
+            Err(InjectorError::NotAllowlisted(_))
+        ));
+    }
+
+    #[test]
+    fn test_blacklist_wins_over_allowlist() {
+        let config = InjectionConfig {
+            allowlist_mode: true,
+            allowlist: vec!["1Password".to_string(), "Code".to_string()],
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            config.check_target(&window("1Password 7")),
+            Err(InjectorError::Blacklisted(_))
+        ));
+        assert!(config.check_target(&window("Visual Studio Code")).is_ok());
+
+        // 禁用黑名单后只看允许列表
+        let config = InjectionConfig {
+            enable_blacklist: false,
+            ..config
+        };
+        assert!(config.check_target(&window("1Password 7")).is_ok());
+    }
+
+    #[test]
+    fn test_blacklisted_window_injection_result() {
+        let config = InjectionConfig::default();
+        let target = window("1Password 7");
+
+        let outcome = config
+            .check_target(&target)
+            .map(|_| InjectionStrategy::Keyboard);
+        let result = InjectionResult::from_outcome(&target, &outcome);
+        assert!(!result.success);
+        assert_eq!(result.strategy, None);
+        assert_eq!(result.app_name, "1Password 7");
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Target window is blacklisted: 1Password 7")
+        );
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["success"], false);
+        assert!(json["error"].as_str().unwrap().contains("blacklisted"));
+
+        let target = window("Visual Studio Code");
+        let result = InjectionResult::from_outcome(&target, &Ok(InjectionStrategy::Clipboard));
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["strategy"], "clipboard");
+        assert!(json.get("error").is_none());
+    }
+
+    #[test]
+    fn test_own_window_suppressed() {
+        let own = WindowInfo {
+            process_id: std::process::id(),
+            ..window("raflow")
+        };
+
+        // 默认不检查（手动测试注入时目标就是设置窗口）
+        assert!(InjectionConfig::default().check_target(&own).is_ok());
+
+        let config = InjectionConfig {
+            own_process_id: Some(std::process::id()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.check_target(&own),
+            Err(InjectorError::OwnWindow(app_name)) if app_name == "raflow"
+        ));
+        assert!(matches!(
+            config.check_live_typing(&own),
+            Err(InjectorError::OwnWindow(_))
+        ));
+        assert!(config.check_target(&window("Visual Studio Code")).is_ok());
+    }
+
+    #[test]
+    fn test_check_layout() {
+        let config = InjectionConfig {
+            keyboard_layout: Some("de".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.check_layout("a@b.c"),
+            Err(InjectorError::ProblematicLayout(layout)) if layout == "de"
+        ));
+        // 非 ASCII 文本不做判断
+        assert!(config.check_layout("你好").is_ok());
+
+        let config = InjectionConfig {
+            keyboard_layout: Some("us".to_string()),
+            ..Default::default()
+        };
+        assert!(config.check_layout("a@b.c").is_ok());
+
+        // 布局未知时放行
+        assert!(InjectionConfig::default().check_layout("a@b.c").is_ok());
+    }
+
+    #[test]
+    fn test_check_live_typing() {
+        let config = InjectionConfig::default();
+        assert!(
+            config
+                .check_live_typing(&window("Visual Studio Code"))
+                .is_ok()
+        );
+        assert!(matches!(
+            config.check_live_typing(&window("iTerm2")),
+            Err(InjectorError::LiveTypingUnsupported(_))
+        ));
+        assert!(matches!(
+            config.check_live_typing(&window("1Password 7")),
+            Err(InjectorError::Blacklisted(_))
+        ));
+
+        // 布局有问题时不论文本内容都不支持
+        let config = InjectionConfig {
+            keyboard_layout: Some("de".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.check_live_typing(&window("Visual Studio Code")),
+            Err(InjectorError::ProblematicLayout(_))
+        ));
+    }
+
+    #[test]
+    fn test_append_for_strategy() {
+        for mode in [AppendMode::None, AppendMode::Space, AppendMode::Newline] {
+            let config = InjectionConfig {
+                append_after_inject: mode,
+                ..Default::default()
+            };
+            assert_eq!(config.append_for(InjectionStrategy::Keyboard), mode);
+            // 只写入剪贴板、没有粘贴时不追加
+            assert_eq!(
+                config.append_for(InjectionStrategy::Clipboard),
+                AppendMode::None
+            );
+
+            let config = InjectionConfig {
+                auto_paste: true,
+                ..config
+            };
+            assert_eq!(config.append_for(InjectionStrategy::Keyboard), mode);
+            assert_eq!(config.append_for(InjectionStrategy::Clipboard), mode);
+        }
+    }
+
+    /// 记录调用、不产生实际输出的注入输出
+    #[derive(Default)]
+    struct RecordingOutput {
+        calls: Vec<String>,
+    }
+
+    impl InjectionOutput for RecordingOutput {
+        async fn type_text(&mut self, text: &str, delay_ms: u64) -> Result<()> {
+            self.calls.push(format!("type:{}@{}ms", text, delay_ms));
+            Ok(())
+        }
+
+        async fn paste(&mut self, text: &str) -> Result<()> {
+            self.calls.push(format!("paste:{}", text));
+            Ok(())
+        }
+
+        fn press_enter(&mut self) -> Result<()> {
+            self.calls.push("enter".to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_select_strategy() {
+        let config = InjectionConfig {
+            keyboard_layout: Some("com.apple.keylayout.German".to_string()),
+            clipboard_on_problematic_layout: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.select_strategy("Hello"),
+            InjectionStrategy::Clipboard
+        );
+        assert_eq!(
+            config.select_strategy("This is a very long text"),
+            InjectionStrategy::Clipboard
+        );
+
+        let config = InjectionConfig {
+            clipboard_on_problematic_layout: false,
+            ..config
+        };
+        assert_eq!(config.select_strategy("Hello"), InjectionStrategy::Keyboard);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_produces_no_output() {
+        let config = InjectionConfig {
+            append_after_inject: AppendMode::Newline,
+            auto_paste: true,
+            ..Default::default()
+        };
+
+        for text in [
+            "Hello",
+            "This is a very long text that should use clipboard",
+        ] {
+            let plan = config.plan(text, false);
+
+            let mut output = RecordingOutput::default();
+            perform(&mut output, text, &plan, true).await.unwrap();
+            assert!(output.calls.is_empty(), "{:?}", output.calls);
+        }
+
+        // 非试运行时按同样的决定输出
+        let mut output = RecordingOutput::default();
+        let plan = config.plan("Hello", false);
+        perform(&mut output, "Hello", &plan, false).await.unwrap();
+        assert_eq!(output.calls, vec!["type:Hello@5ms", "enter"]);
+    }
+
+    #[test]
+    fn test_single_line() {
+        assert_eq!(single_line("ls -la"), "ls -la");
+        assert_eq!(single_line("git status\ngit diff"), "git status git diff");
+        assert_eq!(single_line("echo 1\r\n\r\n  echo 2 \n"), "echo 1 echo 2");
+        assert_eq!(single_line(""), "");
+    }
+
+    #[tokio::test]
+    async fn test_terminal_target_uses_safe_plan() {
+        let config = InjectionConfig {
+            append_after_inject: AppendMode::Newline,
+            auto_paste: true,
+            ..Default::default()
+        };
+        let terminal = window("iTerm2");
+        let editor = window("Visual Studio Code");
+        let text = "This is a very long text\nthat should use clipboard";
+
+        assert!(config.is_terminal_safe_target(&terminal));
+        assert!(!config.is_terminal_safe_target(&editor));
+
+        // 终端：合并为一行，键盘模拟，放慢输入，不按回车
+        let plan = config.plan(&single_line(text), true);
+        assert_eq!(
+            plan,
+            InjectionPlan {
+                strategy: InjectionStrategy::Keyboard,
+                append: AppendMode::None,
+                typing_delay_ms: 15,
+            }
+        );
+        let mut output = RecordingOutput::default();
+        perform(&mut output, &single_line(text), &plan, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            output.calls,
+            vec!["type:This is a very long text that should use clipboard@15ms"]
+        );
+
+        // 其他应用不受影响
+        let plan = config.plan(text, false);
+        assert_eq!(plan.strategy, InjectionStrategy::Clipboard);
+        assert_eq!(plan.append, AppendMode::Newline);
+
+        // 关闭终端安全模式后终端按普通应用处理
+        let config = InjectionConfig {
+            terminal_safe: false,
+            ..config
+        };
+        assert!(!config.is_terminal_safe_target(&terminal));
+    }
+
     // 实际的注入测试需要 Tauri 运行时和 GUI 环境
     // 应该在集成测试中进行
 }
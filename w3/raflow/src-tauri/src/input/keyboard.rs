@@ -5,7 +5,10 @@
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
 use thiserror::Error;
 use tokio::time::{Duration, sleep};
-use tracing::{debug, error};
+#[cfg(target_os = "macos")]
+use tracing::error;
+use tracing::debug;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Error, Debug)]
 pub enum KeyboardError {
@@ -62,6 +65,29 @@ impl KeyboardInjector {
         Ok(())
     }
 
+    /// 逐字输入文本
+    ///
+    /// 按字素簇（grapheme cluster）逐个输入，每个之间等待 `delay_ms` 毫秒，
+    /// 避免终端、带自动补全的 IDE 等在输入过快时丢字。
+    /// emoji 和组合字符作为一个整体输入，不会被拆开
+    ///
+    /// # Arguments
+    /// * `text` - 要输入的文本
+    /// * `delay_ms` - 每个字素簇之间的延迟（毫秒）
+    pub async fn type_text_delayed(&mut self, text: &str, delay_ms: u64) -> Result<()> {
+        debug!("Typing text with {}ms delay: {} chars", delay_ms, text.len());
+
+        for grapheme in graphemes(text) {
+            self.enigo
+                .text(grapheme)
+                .map_err(|e| KeyboardError::TypeFailed(e.to_string()))?;
+
+            sleep(Duration::from_millis(delay_ms)).await;
+        }
+
+        Ok(())
+    }
+
     /// 模拟粘贴快捷键
     ///
     /// - macOS: Cmd+V
@@ -133,6 +159,11 @@ impl KeyboardInjector {
     }
 }
 
+/// 将文本拆分为字素簇
+fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    text.graphemes(true)
+}
+
 impl Default for KeyboardInjector {
     fn default() -> Self {
         Self::new().unwrap()
@@ -158,6 +189,24 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_graphemes_not_split() {
+        let clusters: Vec<&str> = graphemes("a你好").collect();
+        assert_eq!(clusters, vec!["a", "你", "好"]);
+
+        // 带肤色修饰的 emoji 和 ZWJ 序列
+        let clusters: Vec<&str> = graphemes("👍🏽👨‍👩‍👧").collect();
+        assert_eq!(clusters, vec!["👍🏽", "👨‍👩‍👧"]);
+
+        // 组合字符：e + U+0301
+        let clusters: Vec<&str> = graphemes("e\u{301}x").collect();
+        assert_eq!(clusters, vec!["e\u{301}", "x"]);
+
+        // 拼接后与原文一致
+        let text = "Hi 👋🏻 café\u{301}!";
+        assert_eq!(graphemes(text).collect::<String>(), text);
+    }
+
     #[test]
     #[ignore] // 需要 GUI 环境
     fn test_simulate_paste() {
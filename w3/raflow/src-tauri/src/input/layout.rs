@@ -0,0 +1,285 @@
+//! 键盘布局检测模块
+//!
+//! 非美式布局下，键盘模拟输入的部分符号可能被转写成其他字符
+//! （如 QWERTZ/AZERTY 的符号键、US International 的死键）。
+//! 检测当前布局，已知有问题的布局改用剪贴板注入
+
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// 布局检测结果的缓存时间（检测需要启动子进程；切换布局后最迟这么久生效）
+const LAYOUT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// 最近一次布局检测的结果
+static LAYOUT_CACHE: Mutex<LayoutCache> = Mutex::new(LayoutCache::new());
+
+/// 已知有问题的布局名称片段（macOS 输入源 ID、布局名称）
+const PROBLEMATIC_NAMES: &[&str] = &[
+    "german",
+    "french",
+    "belgian",
+    "swiss",
+    "czech",
+    "slovak",
+    "turkish",
+    "hungarian",
+    "qwertz",
+    "azerty",
+    "international",
+    "intl",
+];
+
+/// 已知有问题的 XKB 布局代码（Linux）
+const PROBLEMATIC_XKB: &[&str] = &["de", "fr", "be", "ch", "cz", "sk", "tr", "hu"];
+
+/// 已知有问题的键盘布局标识（Windows KLID）
+const PROBLEMATIC_KLID: &[&str] = &[
+    "00000407", // 德语
+    "0000040c", // 法语
+    "0000080c", // 比利时法语
+    "00000807", // 瑞士德语
+    "0000100c", // 瑞士法语
+    "00000405", // 捷克语
+    "0000041b", // 斯洛伐克语
+    "0000041f", // 土耳其语 Q
+    "0000040e", // 匈牙利语
+    "00020409", // 美式国际
+];
+
+/// 布局是否已知会导致键盘模拟输入错误的字符
+///
+/// # Arguments
+/// * `layout` - 布局标识：macOS 输入源 ID（如 `com.apple.keylayout.German`）、
+///   XKB 布局（如 `de`、`us(intl)`）或 Windows KLID（如 `00000407`）
+pub fn is_problematic_layout(layout: &str) -> bool {
+    let layout = layout.trim().to_lowercase();
+
+    if PROBLEMATIC_KLID.contains(&layout.as_str()) {
+        return true;
+    }
+
+    // XKB 格式：布局(变体)
+    let (base, variant) = match layout.split_once('(') {
+        Some((base, variant)) => (base, variant.trim_end_matches(')')),
+        None => (layout.as_str(), ""),
+    };
+    if PROBLEMATIC_XKB.contains(&base) || variant.contains("intl") {
+        return true;
+    }
+
+    // macOS 输入源 ID 取最后一段名称
+    let name = layout.rsplit('.').next().unwrap_or(&layout);
+    PROBLEMATIC_NAMES
+        .iter()
+        .any(|pattern| name.contains(pattern))
+}
+
+/// 检测当前键盘布局
+///
+/// 通过系统命令读取，失败时返回 None。Windows 上读取的是默认布局
+pub fn detect_layout() -> Option<String> {
+    let layout = query_layout();
+    debug!("Detected keyboard layout: {:?}", layout);
+    layout
+}
+
+/// 当前键盘布局，缓存 `LAYOUT_CACHE_TTL` 内的检测结果
+///
+/// 每次录音会话和手动注入都需要布局，避免每次都启动子进程
+pub fn cached_layout() -> Option<String> {
+    match LAYOUT_CACHE.lock() {
+        Ok(mut cache) => cache.get_or_detect(Instant::now(), detect_layout),
+        Err(_) => detect_layout(),
+    }
+}
+
+/// 带有效期的布局检测结果
+struct LayoutCache {
+    detected_at: Option<Instant>,
+    layout: Option<String>,
+}
+
+impl LayoutCache {
+    const fn new() -> Self {
+        Self {
+            detected_at: None,
+            layout: None,
+        }
+    }
+
+    /// 缓存未过期时返回缓存的结果，否则调用 `detect` 重新检测
+    fn get_or_detect(
+        &mut self,
+        now: Instant,
+        detect: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        let fresh = self
+            .detected_at
+            .is_some_and(|at| now.saturating_duration_since(at) < LAYOUT_CACHE_TTL);
+        if !fresh {
+            self.layout = detect();
+            self.detected_at = Some(now);
+        }
+        self.layout.clone()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn query_layout() -> Option<String> {
+    let output = run(
+        "defaults",
+        &[
+            "read",
+            "com.apple.HIToolbox",
+            "AppleCurrentKeyboardLayoutInputSourceID",
+        ],
+    )?;
+    let layout = output.trim();
+    (!layout.is_empty()).then(|| layout.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn query_layout() -> Option<String> {
+    let output = run(
+        "reg",
+        &["query", r"HKCU\Keyboard Layout\Preload", "/v", "1"],
+    )?;
+    parse_reg_query(&output)
+}
+
+#[cfg(target_os = "linux")]
+fn query_layout() -> Option<String> {
+    let output = run("setxkbmap", &["-query"])?;
+    parse_setxkbmap(&output)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn query_layout() -> Option<String> {
+    None
+}
+
+/// 执行命令并返回标准输出
+#[cfg_attr(
+    not(any(target_os = "macos", target_os = "windows", target_os = "linux")),
+    allow(dead_code)
+)]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        debug!("{} exited with {}", program, output.status);
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// 解析 `setxkbmap -query` 输出，返回 `布局` 或 `布局(变体)`（多个布局时取第一个）
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn parse_setxkbmap(output: &str) -> Option<String> {
+    let field = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().split(',').next().unwrap_or("").to_string())
+        })
+    };
+
+    let layout = field("layout").filter(|layout| !layout.is_empty())?;
+    match field("variant").filter(|variant| !variant.is_empty()) {
+        Some(variant) => Some(format!("{}({})", layout, variant)),
+        None => Some(layout),
+    }
+}
+
+/// 解析 `reg query` 输出中的 KLID
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+fn parse_reg_query(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let (_, kind, value) = (parts.next()?, parts.next()?, parts.next()?);
+        (kind == "REG_SZ").then(|| value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_cache_expires() {
+        let mut cache = LayoutCache::new();
+        let start = Instant::now();
+        let mut detections = 0;
+        let mut detect = |layout: &str| {
+            detections += 1;
+            Some(layout.to_string())
+        };
+
+        assert_eq!(
+            cache.get_or_detect(start, || detect("de")).as_deref(),
+            Some("de")
+        );
+        // 有效期内不再检测
+        assert_eq!(
+            cache
+                .get_or_detect(start + Duration::from_secs(30), || detect("us"))
+                .as_deref(),
+            Some("de")
+        );
+        // 过期后重新检测
+        assert_eq!(
+            cache
+                .get_or_detect(start + LAYOUT_CACHE_TTL, || detect("us"))
+                .as_deref(),
+            Some("us")
+        );
+        assert_eq!(detections, 2);
+    }
+
+    #[test]
+    fn test_problematic_layouts() {
+        // macOS 输入源 ID
+        assert!(is_problematic_layout("com.apple.keylayout.German"));
+        assert!(is_problematic_layout("com.apple.keylayout.ABC-AZERTY"));
+        assert!(is_problematic_layout(
+            "com.apple.keylayout.USInternational-PC"
+        ));
+        // XKB
+        assert!(is_problematic_layout("de"));
+        assert!(is_problematic_layout("fr(oss)"));
+        assert!(is_problematic_layout("us(intl)"));
+        // Windows KLID
+        assert!(is_problematic_layout("00000407"));
+        assert!(is_problematic_layout("00020409"));
+    }
+
+    #[test]
+    fn test_safe_layouts() {
+        assert!(!is_problematic_layout("com.apple.keylayout.US"));
+        assert!(!is_problematic_layout("com.apple.keylayout.ABC"));
+        assert!(!is_problematic_layout("com.apple.keylayout.British"));
+        assert!(!is_problematic_layout("us"));
+        assert!(!is_problematic_layout("gb"));
+        assert!(!is_problematic_layout("00000409"));
+        assert!(!is_problematic_layout(""));
+    }
+
+    #[test]
+    fn test_parse_setxkbmap() {
+        let output =
+            "rules:      evdev\nmodel:      pc105\nlayout:     de,us\nvariant:    nodeadkeys,\n";
+        assert_eq!(parse_setxkbmap(output).as_deref(), Some("de(nodeadkeys)"));
+
+        let output = "rules:      evdev\nlayout:     us\n";
+        assert_eq!(parse_setxkbmap(output).as_deref(), Some("us"));
+
+        assert_eq!(parse_setxkbmap("rules: evdev\n"), None);
+    }
+
+    #[test]
+    fn test_parse_reg_query() {
+        let output = "\r\nHKEY_CURRENT_USER\\Keyboard Layout\\Preload\r\n    1    REG_SZ    00000407\r\n\r\n";
+        assert_eq!(parse_reg_query(output).as_deref(), Some("00000407"));
+        assert_eq!(parse_reg_query("ERROR: not found"), None);
+    }
+}
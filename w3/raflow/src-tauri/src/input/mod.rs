@@ -6,8 +6,13 @@ pub mod clipboard;
 pub mod focus;
 pub mod injector;
 pub mod keyboard;
+pub mod layout;
 
-pub use clipboard::{ClipboardError, ClipboardInjector};
+pub use clipboard::{ClipboardError, ClipboardInjector, ClipboardSnapshot};
 pub use focus::{FocusError, FocusManager};
-pub use injector::{InjectionConfig, InjectionStrategy, InjectorError, TextInjector};
+pub use injector::{
+    AppendMode, InjectionConfig, InjectionPlan, InjectionResult, InjectionStrategy, InjectorError,
+    TextInjector, char_count, single_line,
+};
 pub use keyboard::{KeyboardError, KeyboardInjector};
+pub use layout::{cached_layout, detect_layout, is_problematic_layout};
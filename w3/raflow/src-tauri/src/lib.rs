@@ -3,13 +3,13 @@ mod commands;
 pub mod config;
 pub mod core;
 pub mod input;
+mod logging;
 pub mod network;
 mod state;
 pub mod system;
 
 use anyhow::Result;
-use tauri::Manager;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tauri::{Emitter, Manager, RunEvent};
 
 pub use state::{AppState, RecordingState};
 
@@ -30,15 +30,6 @@ pub fn run() -> Result<()> {
         eprintln!("=============");
     }));
 
-    // 初始化 tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "raflow=debug,tokio=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
     let app_path = dirs::data_local_dir()
         .ok_or_else(|| anyhow::anyhow!("Failed to get data local dir"))?
         .join(APP_PATH);
@@ -47,21 +38,52 @@ pub fn run() -> Result<()> {
         std::fs::create_dir_all(&app_path)?;
     }
 
+    // 初始化 tracing（滚动日志文件 + 调试构建的终端输出）
+    let log_handle = logging::init(&app_path)?;
+
     let (state, control_rx, state_tx) = AppState::new();
 
-    tauri::Builder::default()
+    let app = tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .invoke_handler(tauri::generate_handler![
             commands::get_config,
             commands::save_config,
+            commands::validate_api_key,
+            commands::get_effective_config,
+            commands::get_setup_status,
+            commands::validate_hotkey,
+            commands::estimate_latency,
             commands::start_recording,
             commands::stop_recording,
+            commands::stop_via_overlay,
+            commands::cancel_recording,
+            commands::pause_recording,
+            commands::resume_recording,
+            commands::commit_now,
             commands::toggle_recording,
             commands::list_audio_devices,
+            commands::list_audio_devices_detailed,
+            commands::set_input_device,
+            commands::list_audio_hosts,
             commands::get_blacklist,
+            commands::add_blacklist_entry,
+            commands::remove_blacklist_entry,
+            commands::get_dictionary,
+            commands::add_dictionary_entry,
+            commands::remove_dictionary_entry,
+            commands::get_recording_state,
+            commands::get_noise_suppression_info,
+            commands::get_audio_level,
+            commands::get_metrics,
+            commands::get_transcript_history,
             commands::test_injection,
+            commands::reinject_last,
+            commands::confirm_pending_injection,
+            commands::discard_pending_injection,
+            commands::get_log_files,
+            commands::export_session_bundle,
         ])
         .setup(move |app| {
             use config::ConfigManager;
@@ -73,44 +95,70 @@ pub fn run() -> Result<()> {
             // 加载配置
             let config = ConfigManager::load(app.handle()).unwrap_or_default();
 
+            // 应用配置中的日志级别
+            log_handle.set_level(&config.log_level);
+            app.manage(log_handle);
+
+            // 记录键盘布局，便于排查键盘注入打错字符的问题
+            match input::cached_layout() {
+                Some(layout) if input::is_problematic_layout(&layout) => tracing::warn!(
+                    "Keyboard layout {} may mistype ASCII symbols via keyboard injection",
+                    layout
+                ),
+                Some(layout) => tracing::info!("Keyboard layout: {}", layout),
+                None => tracing::info!("Keyboard layout: unknown"),
+            }
+
             // 注册全局热键
-            if let Err(e) = HotkeyManager::register(app.handle(), &config.hotkey) {
+            if let Err(e) = HotkeyManager::register(app.handle(), &config) {
                 tracing::warn!("Failed to register hotkey: {}", e);
             }
 
             // 启动后台控制任务（使用 LocalSet 支持非 Send future）
             let app_handle = app.handle().clone();
+            let mut standby_config = config.clone();
 
             std::thread::spawn(move || {
-                use crate::core::AppController;
-                use crate::state::ControlCommand;
+                use crate::core::{AppController, StandbyCapture};
+                use crate::state::{
+                    ControlCommand, ControlError, handle_pause, handle_resume, handle_start,
+                };
 
                 let rt = tokio::runtime::Runtime::new().unwrap();
 
                 rt.block_on(async move {
                     let mut controller: Option<AppController> = None;
                     let mut control_rx = control_rx;
+                    // 空闲时的预录采集（未启用预录时为 None）
+                    let mut standby = StandbyCapture::arm(&standby_config).await;
 
                     while let Some(cmd) = control_rx.recv().await {
                         match cmd {
-                            ControlCommand::Start { config, response } => {
+                            ControlCommand::Start { config, abort, response } => {
                                 tracing::info!("Control task: Start");
 
-                                if controller.is_some() {
-                                    let _ = response.send(Err("Already running".to_string()));
-                                    continue;
-                                }
-
-                                let mut ctrl = AppController::new(app_handle.clone(), config);
-                                match ctrl.start_recording().await {
-                                    Ok(()) => {
+                                let running = controller.is_some();
+                                let started = handle_start(
+                                    running,
+                                    || {
+                                        standby_config = (*config).clone();
+                                        AppController::new(app_handle.clone(), *config)
+                                            .with_standby(standby.take())
+                                    },
+                                    abort,
+                                    response,
+                                )
+                                .await;
+                                match started {
+                                    Some(ctrl) => {
                                         controller = Some(ctrl);
-                                        let _ = response.send(Ok(()));
                                         let _ = state_tx.send(RecordingState::Recording);
                                     }
-                                    Err(e) => {
-                                        let _ = response.send(Err(e.to_string()));
+                                    // 启动失败或被放弃，采集器已随控制器丢弃，重新开始预录
+                                    None if !running => {
+                                        standby = StandbyCapture::arm(&standby_config).await;
                                     }
+                                    None => {}
                                 }
                             }
 
@@ -118,19 +166,106 @@ pub fn run() -> Result<()> {
                                 tracing::info!("Control task: Stop");
 
                                 if let Some(mut ctrl) = controller.take() {
+                                    // 等待剩余音频发出和最终转写期间处于处理中状态
+                                    let _ = state_tx.send(RecordingState::Processing);
                                     match ctrl.stop_recording().await {
                                         Ok(()) => {
                                             let _ = response.send(Ok(()));
                                             let _ = state_tx.send(RecordingState::Idle);
+                                            // 重新开始预录，上一次录音的音频已随采集器丢弃
+                                            standby = StandbyCapture::arm(&standby_config).await;
                                         }
                                         Err(e) => {
-                                            let _ = response.send(Err(e.to_string()));
+                                            // 控制器已取走，采集和会话都已结束，同样恢复预录
+                                            let _ = response.send(Err(e.into()));
+                                            let _ = state_tx.send(RecordingState::Idle);
+                                            standby = StandbyCapture::arm(&standby_config).await;
                                         }
                                     }
                                 } else {
                                     let _ = response.send(Ok(())); // 已停止
                                 }
                             }
+
+                            ControlCommand::Cancel { response } => {
+                                tracing::info!("Control task: Cancel");
+
+                                if let Some(mut ctrl) = controller.take() {
+                                    match ctrl.cancel_recording().await {
+                                        Ok(()) => {
+                                            let _ = response.send(Ok(()));
+                                            let _ = state_tx.send(RecordingState::Idle);
+                                            standby = StandbyCapture::arm(&standby_config).await;
+                                        }
+                                        Err(e) => {
+                                            // 控制器已取走，同停止失败一样回到空闲并恢复预录
+                                            let _ = response.send(Err(e.into()));
+                                            let _ = state_tx.send(RecordingState::Idle);
+                                            standby = StandbyCapture::arm(&standby_config).await;
+                                        }
+                                    }
+                                } else {
+                                    let _ = response.send(Ok(())); // 未在录音
+                                }
+                            }
+
+                            ControlCommand::Pause { response } => {
+                                tracing::info!("Control task: Pause");
+
+                                let result = handle_pause(controller.as_mut(), &state_tx).await;
+                                let _ = response.send(result);
+                            }
+
+                            ControlCommand::Resume { response } => {
+                                tracing::info!("Control task: Resume");
+
+                                let result = handle_resume(controller.as_mut(), &state_tx).await;
+                                let _ = response.send(result);
+                            }
+
+                            ControlCommand::Commit { response } => {
+                                tracing::info!("Control task: Commit");
+
+                                let result = match controller.as_mut() {
+                                    Some(ctrl)
+                                        if matches!(
+                                            *state_tx.borrow(),
+                                            RecordingState::Recording | RecordingState::Paused
+                                        ) =>
+                                    {
+                                        ctrl.commit_now().map_err(ControlError::from)
+                                    }
+                                    _ => Err(ControlError::NotRecording),
+                                };
+                                let _ = response.send(result);
+                            }
+
+                            ControlCommand::SwitchDevice { response } => {
+                                tracing::info!("Control task: SwitchDevice");
+
+                                let result = match controller.as_mut() {
+                                    Some(ctrl) if *state_tx.borrow() == RecordingState::Recording => {
+                                        ctrl.switch_to_default_device().await.map_err(ControlError::from)
+                                    }
+                                    _ => Err(ControlError::NotRecording),
+                                };
+                                let _ = response.send(result);
+                            }
+
+                            ControlCommand::Shutdown { response } => {
+                                tracing::info!("Control task: Shutdown");
+
+                                drop(standby.take());
+                                let result = match controller.take() {
+                                    Some(mut ctrl) => {
+                                        ctrl.shutdown().await.map_err(ControlError::from)
+                                    }
+                                    None => Ok(()),
+                                };
+                                let _ = state_tx.send(RecordingState::Idle);
+                                let _ = response.send(result);
+                                break;
+                            }
                         }
                     }
 
@@ -138,11 +273,52 @@ pub fn run() -> Result<()> {
                 });
             });
 
+            // 把控制任务、热键、托盘引起的状态变化推送到前端
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(state::forward_state_changes(
+                state.subscribe(),
+                move |recording_state| {
+                    let name = recording_state.as_str();
+                    if let Err(e) = app_handle.emit("recording_state_changed", name) {
+                        tracing::warn!("Failed to emit recording_state_changed: {}", e);
+                    }
+                },
+            ));
+
             app.manage(state);
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())?;
+
+    // 托盘退出和关闭最后一个窗口都会触发 ExitRequested：
+    // 先拦截退出，优雅停止录音后再真正退出
+    use config::ConfigManager;
+    use core::shutdown::{ExitGuard, graceful_shutdown, shutdown_timeout};
+
+    let exit_guard = ExitGuard::new();
+    app.run(move |app_handle, event| {
+        if let RunEvent::ExitRequested { api, code, .. } = event
+            && exit_guard.begin()
+        {
+            api.prevent_exit();
+
+            let app_handle = app_handle.clone();
+            let state = app_handle.state::<AppState>().inner().clone();
+            tauri::async_runtime::spawn(async move {
+                // 超时按配置的收尾等待计算，不能比停止录音本身更早放弃
+                let config = ConfigManager::load_async(&app_handle)
+                    .await
+                    .unwrap_or_default();
+                let timeout = shutdown_timeout(
+                    config.stop_drain_timeout_ms,
+                    config.partial_promotion_grace_ms,
+                );
+                let stop = async move { state.shutdown().await.map_err(String::from) };
+                graceful_shutdown(stop, timeout).await;
+                app_handle.exit(code.unwrap_or(0));
+            });
+        }
+    });
 
     Ok(())
 }
@@ -0,0 +1,207 @@
+//! 日志初始化模块
+//!
+//! 日志写入应用数据目录下按天滚动的日志文件，调试构建同时输出到终端
+
+use std::path::{Path, PathBuf};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    EnvFilter, Registry, layer::SubscriberExt, reload, util::SubscriberInitExt,
+};
+
+/// 日志目录名称
+const LOG_DIR: &str = "logs";
+
+/// 日志文件名前缀
+const LOG_FILE_PREFIX: &str = "raflow";
+
+/// 日志文件名后缀
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// 最多保留的日志文件数（每天一个文件，超出后删除最旧的）
+const MAX_LOG_FILES: usize = 7;
+
+/// 默认日志级别
+pub const DEFAULT_LOG_LEVEL: &str = "debug";
+
+/// 日志句柄
+///
+/// 用于在配置加载后调整日志级别（设置了 `RUST_LOG` 环境变量时以环境变量为准），
+/// 以及查找日志文件供用户提交问题报告
+pub struct LogHandle {
+    filter: reload::Handle<EnvFilter, Registry>,
+    from_env: bool,
+    dir: PathBuf,
+}
+
+impl LogHandle {
+    /// 设置日志级别
+    pub fn set_level(&self, level: &str) {
+        if self.from_env {
+            return;
+        }
+
+        let directives = filter_directives(level);
+        if let Err(e) = self
+            .filter
+            .modify(|filter| *filter = EnvFilter::new(&directives))
+        {
+            tracing::warn!("Failed to update log level: {}", e);
+        }
+    }
+
+    /// 列出当前的日志文件
+    pub fn files(&self) -> Vec<PathBuf> {
+        log_files(&self.dir)
+    }
+}
+
+/// 解析日志目录
+///
+/// # Arguments
+/// * `app_path` - 应用数据目录
+pub fn log_dir(app_path: &Path) -> PathBuf {
+    app_path.join(LOG_DIR)
+}
+
+/// 根据日志级别生成过滤规则，无效级别回退到默认级别
+pub fn filter_directives(level: &str) -> String {
+    let level = level.trim().to_ascii_lowercase();
+    let level = match level.as_str() {
+        "trace" | "debug" | "info" | "warn" | "error" | "off" => level.as_str(),
+        _ => DEFAULT_LOG_LEVEL,
+    };
+
+    format!("raflow={},tokio=info", level)
+}
+
+/// 判断文件名是否为滚动日志文件（如 `raflow.2025-01-01.log`）
+pub fn is_log_file(file_name: &str) -> bool {
+    file_name
+        .strip_prefix(LOG_FILE_PREFIX)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .and_then(|rest| rest.strip_suffix(LOG_FILE_SUFFIX))
+        .and_then(|date| date.strip_suffix('.'))
+        .is_some_and(|date| !date.is_empty())
+}
+
+/// 列出日志目录中的日志文件（按文件名排序，最新的在最后）
+pub fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(is_log_file)
+        })
+        .collect();
+
+    files.sort();
+    files
+}
+
+/// 创建滚动日志写入器
+fn file_appender(dir: &Path) -> anyhow::Result<RollingFileAppender> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)?;
+
+    Ok(appender)
+}
+
+/// 初始化日志
+///
+/// # Arguments
+/// * `app_path` - 应用数据目录，日志写入其下的 `logs` 目录
+pub fn init(app_path: &Path) -> anyhow::Result<LogHandle> {
+    let env_filter = EnvFilter::try_from_default_env().ok();
+    let from_env = env_filter.is_some();
+    let filter =
+        env_filter.unwrap_or_else(|| EnvFilter::new(filter_directives(DEFAULT_LOG_LEVEL)));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let dir = log_dir(app_path);
+    let appender = file_appender(&dir)?;
+
+    // 调试构建同时输出到终端
+    let stdout_layer = cfg!(debug_assertions).then(tracing_subscriber::fmt::layer);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(appender),
+        )
+        .with(stdout_layer)
+        .init();
+
+    Ok(LogHandle {
+        filter: handle,
+        from_env,
+        dir,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("raflow-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_log_dir() {
+        let dir = log_dir(Path::new("/data/raflow"));
+        assert_eq!(dir, PathBuf::from("/data/raflow/logs"));
+    }
+
+    #[test]
+    fn test_filter_directives() {
+        assert_eq!(filter_directives("info"), "raflow=info,tokio=info");
+        assert_eq!(filter_directives(" WARN "), "raflow=warn,tokio=info");
+        assert_eq!(filter_directives("verbose"), "raflow=debug,tokio=info");
+    }
+
+    #[test]
+    fn test_is_log_file() {
+        assert!(is_log_file("raflow.2025-01-01.log"));
+        assert!(!is_log_file("raflow.log"));
+        assert!(!is_log_file("raflow..log"));
+        assert!(!is_log_file("other.2025-01-01.log"));
+        assert!(!is_log_file("raflow.2025-01-01.txt"));
+    }
+
+    #[test]
+    fn test_appender_file_naming() {
+        let dir = temp_dir("logs");
+
+        let mut appender = file_appender(&dir).unwrap();
+        writeln!(appender, "hello").unwrap();
+        appender.flush().unwrap();
+
+        let files = log_files(&dir);
+        assert_eq!(files.len(), 1);
+
+        let content = std::fs::read_to_string(&files[0]).unwrap();
+        assert_eq!(content, "hello\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_log_files_missing_dir() {
+        assert!(log_files(Path::new("/nonexistent/raflow/logs")).is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+//! 语音转写后端模块
+//!
+//! 不同服务商的连接方式和消息格式不同，收发循环只通过 `SttBackend` 编解码，
+//! 与具体协议无关
+
+use super::client::{ClientConfig, ClientError, ScribeClient, WsSink, WsStream};
+use super::protocol::{ClientMessage, ServerMessage, pcm_bytes};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::error;
+
+/// 转写事件
+///
+/// 各后端都把服务器消息解码为该结构，应用的其余部分与后端无关
+pub type TranscriptEvent = ServerMessage;
+
+/// 语音转写服务商
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SttProvider {
+    /// ElevenLabs Scribe v2 实时转写
+    #[default]
+    Scribe,
+}
+
+/// 语音转写后端
+///
+/// 负责建立连接以及音频、控制消息和服务器消息的编解码
+pub trait SttBackend: Send + Sync {
+    /// 建立 WebSocket 连接
+    fn connect(&self) -> BoxFuture<'_, Result<(WsSink, WsStream), ClientError>>;
+
+    /// 编码一批 16kHz 单声道音频
+    fn encode_audio(&self, pcm_data: &[i16]) -> serde_json::Result<Message>;
+
+    /// 编码提交当前语句的控制消息
+    fn encode_commit(&self) -> serde_json::Result<Message>;
+
+    /// 编码应用层保活消息
+    fn encode_keepalive(&self) -> serde_json::Result<Message>;
+
+    /// 解码服务器发送的文本帧，无法识别的消息返回 None
+    fn decode_message(&self, text: &str) -> Option<TranscriptEvent>;
+}
+
+/// 按服务商创建转写后端
+pub fn backend_for(provider: SttProvider, config: ClientConfig) -> Box<dyn SttBackend> {
+    match provider {
+        SttProvider::Scribe => Box::new(ScribeBackend::new(config)),
+    }
+}
+
+/// ElevenLabs Scribe v2 后端
+pub struct ScribeBackend {
+    client: ScribeClient,
+}
+
+impl ScribeBackend {
+    /// 使用客户端配置创建后端
+    pub fn new(config: ClientConfig) -> Self {
+        Self {
+            client: ScribeClient::with_config(config),
+        }
+    }
+}
+
+impl SttBackend for ScribeBackend {
+    fn connect(&self) -> BoxFuture<'_, Result<(WsSink, WsStream), ClientError>> {
+        Box::pin(self.client.connect())
+    }
+
+    /// 二进制模式直接发送小端 i16 字节，否则发送 Base64 JSON 文本
+    fn encode_audio(&self, pcm_data: &[i16]) -> serde_json::Result<Message> {
+        let frame = if self.client.config().binary_audio {
+            Message::Binary(pcm_bytes(pcm_data).into())
+        } else {
+            Message::Text(ClientMessage::audio_chunk(pcm_data).to_json()?.into())
+        };
+
+        Ok(frame)
+    }
+
+    fn encode_commit(&self) -> serde_json::Result<Message> {
+        Ok(Message::Text(ClientMessage::commit().to_json()?.into()))
+    }
+
+    fn encode_keepalive(&self) -> serde_json::Result<Message> {
+        Ok(Message::Text(ClientMessage::KeepAlive.to_json()?.into()))
+    }
+
+    fn decode_message(&self, text: &str) -> Option<TranscriptEvent> {
+        ServerMessage::from_json(text)
+            .inspect_err(|e| error!("Failed to parse server message: {}", e))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioFrame;
+    use crate::network::NetworkManager;
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::{Duration, Instant};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// 行协议的测试后端：音频编码为 `audio:<采样数>`，服务器发送 `partial:<文本>` 或 `final:<文本>`
+    struct LineBackend {
+        url: String,
+    }
+
+    impl SttBackend for LineBackend {
+        fn connect(&self) -> BoxFuture<'_, Result<(WsSink, WsStream), ClientError>> {
+            Box::pin(async {
+                let (ws, _) = tokio_tungstenite::connect_async(self.url.as_str())
+                    .await
+                    .map_err(|e| ClientError::ConnectionFailed(e.to_string()))?;
+                Ok(ws.split())
+            })
+        }
+
+        fn encode_audio(&self, pcm_data: &[i16]) -> serde_json::Result<Message> {
+            Ok(Message::text(format!("audio:{}", pcm_data.len())))
+        }
+
+        fn encode_commit(&self) -> serde_json::Result<Message> {
+            Ok(Message::text("commit"))
+        }
+
+        fn encode_keepalive(&self) -> serde_json::Result<Message> {
+            Ok(Message::text("ping"))
+        }
+
+        fn decode_message(&self, text: &str) -> Option<TranscriptEvent> {
+            let (kind, text) = text.split_once(':')?;
+            let text = text.to_string();
+            match kind {
+                "partial" => Some(ServerMessage::PartialTranscript {
+                    text,
+                    created_at_ms: None,
+                }),
+                "final" => Some(ServerMessage::CommittedTranscript {
+                    text,
+                    confidence: None,
+                }),
+                _ => None,
+            }
+        }
+    }
+
+    /// 网络管理器通过自定义后端连接、编码音频并解码服务器消息
+    #[tokio::test]
+    async fn test_manager_uses_custom_backend() {
+        // 本地行协议服务器：收到音频后先回一条无法识别的消息，再回提交的转写
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            loop {
+                match ws.next().await {
+                    Some(Ok(Message::Text(text))) if text.starts_with("audio:") => {
+                        ws.send(Message::text("unknown")).await.unwrap();
+                        ws.send(Message::text("final:你好")).await.unwrap();
+                        return text.to_string();
+                    }
+                    Some(Ok(_)) => {}
+                    other => panic!("Expected audio frame, got {:?}", other),
+                }
+            }
+        });
+
+        let (audio_tx, audio_rx) = mpsc::channel(10);
+        let (event_tx, mut event_rx) = mpsc::channel(10);
+        let config = ClientConfig {
+            trim_leading_silence: false,
+            batch_interval: Duration::from_millis(100),
+            keepalive_interval: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let mut manager = NetworkManager::with_config(config, audio_rx, event_tx)
+            .with_backend(Box::new(LineBackend { url }));
+        let run = tokio::spawn(async move { manager.run().await });
+
+        audio_tx
+            .send(AudioFrame::new(vec![1000; 1600], Instant::now()))
+            .await
+            .unwrap();
+
+        let sent = tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(sent.starts_with("audio:"), "sent {}", sent);
+
+        // 无法识别的消息被丢弃，提交的转写转发给事件通道
+        let event = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match event_rx.recv().await {
+                    Some(event @ ServerMessage::CommittedTranscript { .. }) => return event,
+                    Some(_) => {}
+                    None => panic!("Event channel closed"),
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            event,
+            ServerMessage::CommittedTranscript {
+                text: "你好".to_string(),
+                confidence: None,
+            }
+        );
+
+        run.abort();
+    }
+
+    #[test]
+    fn test_scribe_backend_codec() {
+        let backend = backend_for(SttProvider::Scribe, ClientConfig::default());
+        match backend.encode_commit().unwrap() {
+            Message::Text(text) => assert!(text.as_str().contains(r#""commit":true"#)),
+            other => panic!("Expected text frame, got {:?}", other),
+        }
+
+        let message = r#"{"message_type":"committed_transcript","text":"hello"}"#;
+        assert!(matches!(
+            backend.decode_message(message),
+            Some(ServerMessage::CommittedTranscript { .. })
+        ));
+        assert_eq!(backend.decode_message("not json"), None);
+    }
+}
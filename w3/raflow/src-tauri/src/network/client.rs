@@ -2,12 +2,20 @@
 //!
 //! 实现与 ElevenLabs Scribe v2 API 的 WebSocket 连接
 
-use futures_util::{StreamExt, stream::SplitSink, stream::SplitStream};
+use futures_util::{SinkExt, StreamExt, stream::SplitSink, stream::SplitStream};
+use super::protocol::ServerMessage;
+use super::proxy::{ProxyConfig, ProxyError};
+use super::silence::DEFAULT_PRE_ROLL_MS;
+use super::state_machine::DEFAULT_MAX_RETRIES;
+use std::collections::HashMap;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{client::IntoClientRequest, Message},
+    client_async_tls, connect_async,
+    tungstenite::{
+        self, Message, client::IntoClientRequest, handshake::client::Request, http::StatusCode,
+    },
     MaybeTlsStream, WebSocketStream,
 };
 use tracing::{debug, info};
@@ -25,44 +33,345 @@ pub enum ClientError {
 
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
+
+    #[error(transparent)]
+    Proxy(#[from] ProxyError),
+}
+
+impl ClientError {
+    /// 稳定的错误代码（发送到前端）
+    pub fn code(&self) -> &'static str {
+        match self {
+            ClientError::ConnectionFailed(_) => "connection_failed",
+            ClientError::WebSocketError(_) => "websocket_error",
+            ClientError::InvalidUrl(_) => "invalid_url",
+            ClientError::AuthenticationFailed(_) => "authentication_failed",
+            ClientError::Proxy(_) => "proxy_error",
+        }
+    }
+
+    /// 是否值得重试
+    ///
+    /// 认证失败、URL 无效和代理拒绝时重试也不会成功
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::AuthenticationFailed(_) | ClientError::InvalidUrl(_) => false,
+            ClientError::Proxy(error) => error.is_retryable(),
+            _ => true,
+        }
+    }
+}
+
+impl serde::Serialize for ClientError {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut error = serializer.serialize_struct("ClientError", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
 }
 
 type Result<T> = std::result::Result<T, ClientError>;
 
+/// 校验 API Key 时等待服务器第一条消息的最长时间
+pub const API_KEY_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 默认转写模型
+pub const DEFAULT_MODEL_ID: &str = "scribe_v2_realtime";
+
+/// 默认编码格式（16kHz 单声道 16 位 PCM）
+pub const DEFAULT_ENCODING: &str = "pcm_16000";
+
+/// 支持的 PCM 编码及其采样率
+const PCM_ENCODINGS: &[(&str, u32)] = &[
+    ("pcm_8000", 8000),
+    ("pcm_16000", 16000),
+    ("pcm_22050", 22050),
+    ("pcm_24000", 24000),
+    ("pcm_44100", 44100),
+    ("pcm_48000", 48000),
+];
+
+/// 默认 WebSocket 端点
+pub const DEFAULT_BASE_URL: &str = "wss://api.elevenlabs.io/v1/speech-to-text/realtime";
+
 /// WebSocket 发送端类型别名
 pub type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
 
 /// WebSocket 接收端类型别名
 pub type WsStream = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
 
+/// 连接保活方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeepAlive {
+    /// WebSocket ping 帧
+    #[default]
+    Ping,
+    /// 应用层 `keep_alive` 消息（服务商要求时使用）
+    Message,
+}
+
 /// Scribe v2 客户端配置
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
     /// API Key
     pub api_key: String,
+    /// WebSocket 端点（不含查询参数），可指向区域代理或兼容网关
+    pub base_url: String,
     /// 模型 ID
     pub model_id: String,
     /// 语言代码
     pub language_code: String,
-    /// 编码格式
+    /// 编码格式（决定发送音频的采样率，见 `encoding_to_rate`）
     pub encoding: String,
+    /// 保活方式
+    pub keepalive: KeepAlive,
+    /// 保活间隔
+    pub keepalive_interval: Duration,
+    /// ping 保活时，超过该时长未收到服务器任何数据（含 pong）视为连接已断开，零表示不检测
+    pub pong_timeout: Duration,
+    /// 是否裁剪会话开头（检测到语音之前）的静音
+    pub trim_leading_silence: bool,
+    /// 裁剪时在语音起点前保留的预录时长（毫秒）
+    pub pre_roll_ms: u64,
+    /// 使用二进制帧发送原始 PCM（小端 i16），而不是 Base64 JSON
+    ///
+    /// commit 等控制消息仍使用 JSON 文本帧
+    pub binary_audio: bool,
+    /// 连接失败后的最大重试次数
+    pub max_retries: u32,
+    /// 音频批量发送间隔（累积该时长的音频后发送一次）
+    pub batch_interval: Duration,
+    /// 静音超过该时长后自动发送 commit
+    pub silence_commit: Duration,
+    /// 音频帧的最大帧龄，网络阻塞后超过该时长的积压音频直接丢弃，零表示不丢弃
+    pub max_frame_age: Duration,
+    /// 代理地址（`http://`、`socks5://`），None 表示直连
+    pub proxy_url: Option<String>,
+    /// 停止录音发出最终 commit 后等待服务器回应的最长时间，收到回应或超时后才关闭连接
+    pub final_commit_timeout: Duration,
 }
 
 impl Default for ClientConfig {
     fn default() -> Self {
         Self {
             api_key: String::new(),
-            model_id: "scribe_v2_realtime".to_string(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model_id: DEFAULT_MODEL_ID.to_string(),
             language_code: "cmn".to_string(), // 使用 ISO 639-3 普通话代码
-            encoding: "pcm_16000".to_string(),
+            encoding: DEFAULT_ENCODING.to_string(),
+            keepalive: KeepAlive::default(),
+            keepalive_interval: Duration::from_secs(15),
+            pong_timeout: Duration::from_secs(30),
+            trim_leading_silence: true,
+            pre_roll_ms: DEFAULT_PRE_ROLL_MS,
+            binary_audio: false,
+            max_retries: DEFAULT_MAX_RETRIES,
+            batch_interval: Duration::from_millis(500),
+            silence_commit: Duration::from_millis(2000),
+            max_frame_age: Duration::from_secs(3),
+            proxy_url: None,
+            final_commit_timeout: Duration::from_secs(3),
         }
     }
 }
 
+impl ClientConfig {
+    /// 使用界面语言创建配置
+    ///
+    /// # Arguments
+    /// * `api_key` - ElevenLabs API Key
+    /// * `language` - 界面语言代码（如 `zh`、`en`）
+    pub fn with_language(api_key: String, language: &str) -> Self {
+        Self {
+            api_key,
+            language_code: language_code_for(language),
+            ..Default::default()
+        }
+    }
+
+    /// 编码格式对应的采样率
+    ///
+    /// 编码应已在配置校验时检查过，未知编码按默认的 16kHz 处理
+    pub fn sample_rate(&self) -> u32 {
+        encoding_to_rate(&self.encoding).unwrap_or(16000)
+    }
+}
+
+/// 将编码格式映射为音频采样率（Hz）
+///
+/// 只支持 PCM 编码，不区分大小写；未知编码返回 None
+///
+/// # Example
+/// ```
+/// use raflow_lib::network::encoding_to_rate;
+///
+/// assert_eq!(encoding_to_rate("pcm_16000"), Some(16000));
+/// assert_eq!(encoding_to_rate("ulaw_8000"), None);
+/// ```
+pub fn encoding_to_rate(encoding: &str) -> Option<u32> {
+    let encoding = encoding.trim();
+    PCM_ENCODINGS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(encoding))
+        .map(|(_, rate)| *rate)
+}
+
+/// 检查 WebSocket 端点是否有效
+///
+/// 只接受带主机名的 `ws://` 或 `wss://` 地址
+///
+/// # Example
+/// ```
+/// use raflow_lib::network::validate_endpoint;
+///
+/// assert!(validate_endpoint("wss://proxy.example.com/v1/realtime").is_ok());
+/// assert!(validate_endpoint("https://proxy.example.com").is_err());
+/// ```
+pub fn validate_endpoint(url: &str) -> Result<()> {
+    let url = url.trim();
+    let lower = url.to_ascii_lowercase();
+    let host = lower
+        .strip_prefix("wss://")
+        .or_else(|| lower.strip_prefix("ws://"))
+        .ok_or_else(|| ClientError::InvalidUrl(format!("{} (expected ws:// or wss://)", url)))?;
+
+    if host.split(['/', '?', '#']).next().is_none_or(str::is_empty) {
+        return Err(ClientError::InvalidUrl(format!("{} (missing host)", url)));
+    }
+
+    Ok(())
+}
+
+/// 界面语言代码（ISO 639-1）与 Scribe 使用的 ISO 639-3 代码
+const LANGUAGE_CODES: &[(&str, &str)] = &[
+    ("zh", "cmn"),
+    ("en", "eng"),
+    ("ja", "jpn"),
+    ("ko", "kor"),
+    ("fr", "fra"),
+    ("de", "deu"),
+    ("es", "spa"),
+];
+
+/// 将界面语言代码（ISO 639-1）映射为 Scribe 使用的 ISO 639-3 代码
+///
+/// 未知的代码原样返回
+///
+/// # Example
+/// ```
+/// use raflow_lib::network::language_code_for;
+///
+/// assert_eq!(language_code_for("zh"), "cmn");
+/// assert_eq!(language_code_for("en"), "eng");
+/// ```
+pub fn language_code_for(language: &str) -> String {
+    let language = language.trim();
+    LANGUAGE_CODES
+        .iter()
+        .find(|(ui, _)| ui.eq_ignore_ascii_case(language))
+        .map_or(language, |(_, code)| code)
+        .to_string()
+}
+
+/// 是否为支持的语言代码（界面语言代码或对应的 ISO 639-3 代码，不区分大小写）
+pub fn is_known_language(language: &str) -> bool {
+    let language = language.trim();
+    LANGUAGE_CODES
+        .iter()
+        .any(|(ui, code)| ui.eq_ignore_ascii_case(language) || code.eq_ignore_ascii_case(language))
+}
+
+/// 根据界面语言选择转写模型
+///
+/// 优先使用 `models` 中该语言（大小写不敏感）的映射，
+/// 没有映射时使用 `default_model`，其为空时使用 `DEFAULT_MODEL_ID`
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use raflow_lib::network::model_for_language;
+///
+/// let models = HashMap::from([("en".to_string(), "scribe_en".to_string())]);
+/// assert_eq!(model_for_language(&models, "EN", "scribe_v2_realtime"), "scribe_en");
+/// assert_eq!(model_for_language(&models, "zh", "scribe_v2_realtime"), "scribe_v2_realtime");
+/// ```
+pub fn model_for_language(
+    models: &HashMap<String, String>,
+    language: &str,
+    default_model: &str,
+) -> String {
+    let language = language.trim();
+    let mapped = models
+        .iter()
+        .find(|(lang, model)| {
+            lang.trim().eq_ignore_ascii_case(language) && !model.trim().is_empty()
+        })
+        .map(|(_, model)| model.trim());
+
+    match mapped {
+        Some(model) => model.to_string(),
+        None if default_model.trim().is_empty() => DEFAULT_MODEL_ID.to_string(),
+        None => default_model.trim().to_string(),
+    }
+}
+
+/// 将握手失败转换为客户端错误
+///
+/// 服务器返回 401/403 时视为认证失败，其余错误视为可重试的连接失败
+fn handshake_error(error: tungstenite::Error) -> ClientError {
+    if let tungstenite::Error::Http(response) = &error {
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return ClientError::AuthenticationFailed(format!("HTTP {}", status));
+        }
+        return ClientError::ConnectionFailed(format!("HTTP {}", status));
+    }
+
+    ClientError::ConnectionFailed(error.to_string())
+}
+
+/// 根据握手后服务器的第一条消息判断 API Key 是否有效
+///
+/// 会话开始（或任何转写消息）说明 Key 有效；认证错误返回 `AuthenticationFailed`；
+/// 输入错误、会话结束或未收到任何消息就断开视为连接失败
+pub fn check_first_message(message: Option<ServerMessage>) -> Result<()> {
+    match message {
+        Some(ServerMessage::AuthError { error }) => Err(ClientError::AuthenticationFailed(error)),
+        Some(ServerMessage::InputError { error_message }) => {
+            Err(ClientError::ConnectionFailed(error_message))
+        }
+        Some(ServerMessage::SessionEnded { reason }) => Err(ClientError::ConnectionFailed(
+            format!("session ended: {}", reason),
+        )),
+        Some(_) => Ok(()),
+        None => Err(ClientError::ConnectionFailed(
+            "connection closed before the session started".to_string(),
+        )),
+    }
+}
+
+/// 读取服务器的第一条消息（跳过 ping 等控制帧），连接关闭时返回 None
+async fn first_server_message(stream: &mut WsStream) -> Option<ServerMessage> {
+    while let Some(message) = stream.next().await {
+        match message {
+            Ok(Message::Text(text)) => return ServerMessage::from_json(&text).ok(),
+            Ok(Message::Close(_)) | Err(_) => return None,
+            Ok(_) => continue,
+        }
+    }
+
+    None
+}
+
 /// ElevenLabs Scribe v2 WebSocket 客户端
 pub struct ScribeClient {
     config: ClientConfig,
-    base_url: String,
 }
 
 impl ScribeClient {
@@ -86,10 +395,7 @@ impl ScribeClient {
 
     /// 使用自定义配置创建客户端
     pub fn with_config(config: ClientConfig) -> Self {
-        Self {
-            config,
-            base_url: "wss://api.elevenlabs.io/v1/speech-to-text/realtime".to_string(),
-        }
+        Self { config }
     }
 
     /// 建立 WebSocket 连接
@@ -109,11 +415,70 @@ impl ScribeClient {
     /// }
     /// ```
     pub async fn connect(&self) -> Result<(WsSink, WsStream)> {
+        let request = self.connect_request()?;
+
+        // 连接（配置了代理时先建立隧道，再在隧道上进行 TLS 和 WebSocket 握手）
+        let (ws_stream, response) = match &self.config.proxy_url {
+            Some(proxy_url) => {
+                let proxy = ProxyConfig::parse(proxy_url)?;
+                let uri = request.uri();
+                let host = uri
+                    .host()
+                    .ok_or_else(|| ClientError::InvalidUrl(uri.to_string()))?
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string();
+                let default_port = if uri.scheme_str() == Some("ws") {
+                    80
+                } else {
+                    443
+                };
+                let port = uri.port_u16().unwrap_or(default_port);
+                let stream = proxy.connect(&host, port).await?;
+                client_async_tls(request, stream)
+                    .await
+                    .map_err(handshake_error)?
+            }
+            None => connect_async(request).await.map_err(handshake_error)?,
+        };
+
+        info!("WebSocket connected: status = {}", response.status());
+
+        // 分离发送和接收端
+        let (sink, stream) = ws_stream.split();
+
+        Ok((sink, stream))
+    }
+
+    /// 校验 API Key
+    ///
+    /// 完成一次握手并等待服务器第一条消息后立即断开，不发送任何音频。
+    /// 握手返回 401/403 或服务器返回认证错误时为 `AuthenticationFailed`
+    ///
+    /// # Arguments
+    /// * `timeout` - 握手后等待第一条消息的最长时间
+    pub async fn check_api_key(&self, timeout: Duration) -> Result<()> {
+        let (mut sink, mut stream) = self.connect().await?;
+
+        let first = tokio::time::timeout(timeout, first_server_message(&mut stream))
+            .await
+            .map_err(|_| {
+                ClientError::ConnectionFailed(format!("no response from server in {:?}", timeout))
+            })?;
+
+        if let Err(e) = sink.close().await {
+            debug!("Failed to close API key check connection: {}", e);
+        }
+
+        check_first_message(first)
+    }
+
+    /// 构建握手请求（端点 URL 和 API Key header）
+    pub fn connect_request(&self) -> Result<Request> {
+        validate_endpoint(&self.config.base_url)?;
+
         // 构建 URL
-        let url = format!(
-            "{}?model_id={}&encoding={}",
-            self.base_url, self.config.model_id, self.config.encoding
-        );
+        let url = self.request_url();
 
         debug!("Connecting to: {}", url);
 
@@ -128,17 +493,18 @@ impl ScribeClient {
 
         debug!("Request headers: {:?}", request.headers());
 
-        // 连接
-        let (ws_stream, response) = connect_async(request)
-            .await
-            .map_err(|e| ClientError::ConnectionFailed(e.to_string()))?;
-
-        info!("WebSocket connected: status = {}", response.status());
-
-        // 分离发送和接收端
-        let (sink, stream) = ws_stream.split();
+        Ok(request)
+    }
 
-        Ok((sink, stream))
+    /// 构建连接 URL（包含模型、编码和语言参数）
+    pub fn request_url(&self) -> String {
+        format!(
+            "{}?model_id={}&encoding={}&language_code={}",
+            self.config.base_url.trim(),
+            self.config.model_id,
+            self.config.encoding,
+            self.config.language_code
+        )
     }
 
     /// 设置语言代码
@@ -161,20 +527,37 @@ mod tests {
         let client = ScribeClient::new("test-api-key".to_string());
         assert_eq!(client.config.api_key, "test-api-key");
         assert_eq!(client.config.model_id, "scribe_v2_realtime");
+        assert_eq!(client.config.keepalive, KeepAlive::Ping);
     }
 
     #[test]
     fn test_custom_config() {
         let config = ClientConfig {
             api_key: "custom-key".to_string(),
+            base_url: "wss://proxy.example.com/scribe".to_string(),
             model_id: "custom-model".to_string(),
             language_code: "en".to_string(),
             encoding: "pcm_8000".to_string(),
+            keepalive: KeepAlive::Message,
+            keepalive_interval: Duration::from_secs(5),
+            pong_timeout: Duration::from_secs(10),
+            trim_leading_silence: false,
+            pre_roll_ms: 0,
+            binary_audio: true,
+            max_retries: 5,
+            batch_interval: Duration::from_millis(250),
+            silence_commit: Duration::from_millis(1500),
+            max_frame_age: Duration::ZERO,
+            proxy_url: Some("socks5://127.0.0.1:1080".to_string()),
+            final_commit_timeout: Duration::from_secs(1),
         };
 
         let client = ScribeClient::with_config(config);
         assert_eq!(client.config.language_code, "en");
         assert_eq!(client.config.encoding, "pcm_8000");
+        assert_eq!(client.config.keepalive, KeepAlive::Message);
+        assert!(client.config.binary_audio);
+        assert_eq!(client.config.max_retries, 5);
     }
 
     #[test]
@@ -184,6 +567,215 @@ mod tests {
         assert_eq!(client.config.language_code, "fr");
     }
 
+    #[test]
+    fn test_request_url_contains_language() {
+        let client = ScribeClient::with_config(ClientConfig::with_language(
+            "test-key".to_string(),
+            "en",
+        ));
+        let url = client.request_url();
+
+        assert!(url.contains("model_id=scribe_v2_realtime"));
+        assert!(url.contains("encoding=pcm_16000"));
+        assert!(url.contains("language_code=eng"));
+    }
+
+    #[test]
+    fn test_custom_endpoint_in_connect_request() {
+        let config = ClientConfig {
+            base_url: "wss://eu-proxy.example.com/v1/speech-to-text/realtime".to_string(),
+            ..ClientConfig::with_language("test-key".to_string(), "en")
+        };
+        let request = ScribeClient::with_config(config).connect_request().unwrap();
+
+        let uri = request.uri();
+        assert_eq!(uri.scheme_str(), Some("wss"));
+        assert_eq!(uri.host(), Some("eu-proxy.example.com"));
+        assert_eq!(uri.path(), "/v1/speech-to-text/realtime");
+        assert!(uri.query().unwrap().contains("language_code=eng"));
+        assert_eq!(request.headers()["xi-api-key"], "test-key");
+
+        // 默认端点
+        let request = ScribeClient::new("test-key".to_string())
+            .connect_request()
+            .unwrap();
+        assert_eq!(request.uri().host(), Some("api.elevenlabs.io"));
+    }
+
+    #[test]
+    fn test_validate_endpoint() {
+        assert!(validate_endpoint(DEFAULT_BASE_URL).is_ok());
+        assert!(validate_endpoint("ws://localhost:8080/realtime").is_ok());
+        assert!(validate_endpoint("WSS://Proxy.Example.com").is_ok());
+
+        for url in [
+            "https://api.elevenlabs.io",
+            "api.elevenlabs.io",
+            "wss://",
+            "ws:///path",
+            "",
+        ] {
+            assert!(
+                matches!(validate_endpoint(url), Err(ClientError::InvalidUrl(_))),
+                "{} should be rejected",
+                url
+            );
+        }
+
+        // 无效端点在连接前被拒绝，且不重试
+        let config = ClientConfig {
+            base_url: "http://proxy.example.com".to_string(),
+            ..Default::default()
+        };
+        let err = ScribeClient::with_config(config)
+            .connect_request()
+            .unwrap_err();
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_model_for_language() {
+        let models = HashMap::from([
+            ("en".to_string(), "scribe_en_fast".to_string()),
+            ("ja".to_string(), " ".to_string()),
+        ]);
+
+        assert_eq!(
+            model_for_language(&models, "en", "custom"),
+            "scribe_en_fast"
+        );
+        assert_eq!(
+            model_for_language(&models, " EN ", "custom"),
+            "scribe_en_fast"
+        );
+        // 没有映射或映射为空时回退到默认模型
+        assert_eq!(model_for_language(&models, "zh", "custom"), "custom");
+        assert_eq!(model_for_language(&models, "ja", "custom"), "custom");
+        assert_eq!(model_for_language(&models, "zh", ""), DEFAULT_MODEL_ID);
+    }
+
+    #[test]
+    fn test_request_url_uses_language_model() {
+        let models = HashMap::from([("en".to_string(), "scribe_en_fast".to_string())]);
+
+        let config = ClientConfig {
+            model_id: model_for_language(&models, "en", DEFAULT_MODEL_ID),
+            ..ClientConfig::with_language("test-key".to_string(), "en")
+        };
+        let url = ScribeClient::with_config(config).request_url();
+        assert!(url.contains("model_id=scribe_en_fast"));
+
+        let config = ClientConfig {
+            model_id: model_for_language(&models, "zh", DEFAULT_MODEL_ID),
+            ..ClientConfig::with_language("test-key".to_string(), "zh")
+        };
+        let url = ScribeClient::with_config(config).request_url();
+        assert!(url.contains("model_id=scribe_v2_realtime"));
+    }
+
+    #[test]
+    fn test_language_code_for() {
+        assert_eq!(language_code_for("zh"), "cmn");
+        assert_eq!(language_code_for("en"), "eng");
+        assert_eq!(language_code_for("ja"), "jpn");
+        assert_eq!(language_code_for("EN"), "eng");
+        // 未知代码原样返回
+        assert_eq!(language_code_for("yue"), "yue");
+
+        assert!(is_known_language("zh"));
+        assert!(is_known_language(" JA "));
+        assert!(is_known_language("eng"));
+        assert!(!is_known_language("yue"));
+        assert!(!is_known_language(""));
+    }
+
+    #[test]
+    fn test_encoding_to_rate() {
+        assert_eq!(encoding_to_rate("pcm_8000"), Some(8000));
+        assert_eq!(encoding_to_rate("pcm_16000"), Some(16000));
+        assert_eq!(encoding_to_rate(" PCM_24000 "), Some(24000));
+        assert_eq!(encoding_to_rate("pcm_12345"), None);
+        assert_eq!(encoding_to_rate("mp3"), None);
+        assert_eq!(encoding_to_rate(""), None);
+
+        let config = ClientConfig {
+            encoding: "pcm_24000".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.sample_rate(), 24000);
+        assert_eq!(ClientConfig::default().sample_rate(), 16000);
+    }
+
+    fn http_error(status: StatusCode) -> tungstenite::Error {
+        let response = tungstenite::http::Response::builder()
+            .status(status)
+            .body(None)
+            .unwrap();
+        tungstenite::Error::Http(Box::new(response))
+    }
+
+    #[test]
+    fn test_handshake_auth_errors_not_retryable() {
+        for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            let error = handshake_error(http_error(status));
+            assert!(matches!(error, ClientError::AuthenticationFailed(_)));
+            assert!(!error.is_retryable());
+        }
+    }
+
+    #[test]
+    fn test_handshake_other_errors_retryable() {
+        let error = handshake_error(http_error(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(matches!(error, ClientError::ConnectionFailed(_)));
+        assert!(error.is_retryable());
+
+        let error = handshake_error(tungstenite::Error::ConnectionClosed);
+        assert!(matches!(error, ClientError::ConnectionFailed(_)));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_first_message_decides_api_key_check() {
+        let started =
+            ServerMessage::from_json(r#"{"message_type":"session_started","session_id":"abc"}"#)
+                .unwrap();
+        assert!(check_first_message(Some(started)).is_ok());
+
+        let auth =
+            ServerMessage::from_json(r#"{"message_type":"auth_error","error":"invalid api key"}"#)
+                .unwrap();
+        let error = check_first_message(Some(auth)).unwrap_err();
+        assert!(matches!(error, ClientError::AuthenticationFailed(_)));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "authentication_failed",
+                "message": "Authentication failed: invalid api key",
+            })
+        );
+
+        let closed = check_first_message(None).unwrap_err();
+        assert!(matches!(closed, ClientError::ConnectionFailed(_)));
+        assert!(closed.is_retryable());
+    }
+
+    // 集成测试需要真实的 API Key
+    #[tokio::test]
+    #[ignore]
+    async fn test_real_api_key_check() {
+        let api_key = std::env::var("ELEVENLABS_API_KEY").expect("ELEVENLABS_API_KEY not set");
+
+        let client = ScribeClient::new(api_key);
+        client.check_api_key(API_KEY_CHECK_TIMEOUT).await.unwrap();
+
+        let client = ScribeClient::new("invalid-key".to_string());
+        let error = client
+            .check_api_key(API_KEY_CHECK_TIMEOUT)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::AuthenticationFailed(_)));
+    }
+
     // 集成测试需要真实的 API Key
     #[tokio::test]
     #[ignore]
@@ -3,15 +3,22 @@
 //! 整合 WebSocket 连接、状态管理和消息处理
 
 use super::{
-    client::{ClientError, ScribeClient, WsSink, WsStream},
-    protocol::{ClientMessage, ServerMessage},
-    state_machine::{ConnectionState, StateMachine},
+    backend::{ScribeBackend, SttBackend},
+    client::{ClientConfig, ClientError, KeepAlive, WsSink, WsStream},
+    metrics::{MetricsReader, NetworkMetrics},
+    protocol::ServerMessage,
+    scheduler::{SendAction, SendScheduler},
+    state_machine::{ConnectionState, DEFAULT_RETRY_DELAY, StateMachine},
+    throttle::CommitBackoff,
 };
-use futures_util::{SinkExt, StreamExt};
+use crate::audio::AudioFrame;
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::{RwLock, mpsc};
-use tokio_tungstenite::tungstenite::Message;
+use tokio::sync::{RwLock, mpsc, watch};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::{self, Message};
 use tracing::{debug, error, info, warn};
 
 #[derive(Error, Debug)]
@@ -31,14 +38,163 @@ pub enum ManagerError {
 
 type Result<T> = std::result::Result<T, ManagerError>;
 
+/// 音频批量发送间隔下限
+const MIN_BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 音频批量发送间隔上限
+const MAX_BATCH_INTERVAL: Duration = Duration::from_millis(2000);
+
+/// 将批量发送间隔限制在合理范围内
+///
+/// 间隔过短会产生大量小帧，过长则转写延迟明显
+fn clamp_batch_interval(interval: Duration) -> Duration {
+    let clamped = interval.clamp(MIN_BATCH_INTERVAL, MAX_BATCH_INTERVAL);
+    if clamped != interval {
+        warn!(
+            "Batch interval {:?} out of range, clamped to {:?}",
+            interval, clamped
+        );
+    }
+    clamped
+}
+
+/// 停止录音后发送任务的收尾状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DrainState {
+    /// 仍在发送音频
+    #[default]
+    Sending,
+    /// 音频输入已关闭，剩余音频已发送；`committed` 表示是否发送了最终 commit
+    Flushed { committed: bool },
+}
+
+/// 发送任务的结束方式
+#[derive(Debug)]
+enum SendExit {
+    /// 录音已结束（音频输入关闭或取消），不再需要连接
+    Finished,
+    /// 连接中断或服务器结束了会话，录音仍在进行；交回输入通道供新会话继续使用
+    Interrupted {
+        audio_rx: mpsc::Receiver<AudioFrame>,
+        commit_rx: mpsc::Receiver<()>,
+        /// 已从输入通道读出但尚未发送的音频，由新会话先发送
+        pending: Vec<i16>,
+    },
+}
+
+/// 发送任务结束后的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AfterSend {
+    /// 录音仍在进行而服务器结束了会话：按重试延迟重新建立会话
+    ResumeSession,
+    /// 按错误重试策略决定是否重连
+    Retry,
+    /// 录音已结束，停止网络管理器
+    Stop,
+}
+
+/// 连续重建会话的次数上限
+const MAX_CONSECUTIVE_RESUMES: u32 = 3;
+
+/// 会话持续超过该时长后，重建会话不再算作连续重建
+const STABLE_SESSION: Duration = Duration::from_secs(30);
+
+/// 服务器结束会话后重建会话的次数限制
+///
+/// 会话刚建立就被结束（如服务器持续拒绝）时不会无限重连；
+/// 正常持续的会话因超时结束时计数重新开始
+#[derive(Debug, Default)]
+struct ResumeBudget {
+    consecutive: u32,
+}
+
+impl ResumeBudget {
+    /// 记录一次重建会话，返回是否还允许重建
+    ///
+    /// # Arguments
+    /// * `session_length` - 刚结束的会话持续的时长
+    fn try_resume(&mut self, session_length: Duration) -> bool {
+        if session_length >= STABLE_SESSION {
+            self.consecutive = 0;
+        }
+        self.consecutive += 1;
+        self.consecutive <= MAX_CONSECUTIVE_RESUMES
+    }
+}
+
+/// 发送任务结束后决定下一步
+///
+/// 用户停止或取消时不重连；录音仍在进行时，服务器结束会话（如会话超时，状态回到 Idle）
+/// 重新握手，其他中断按错误重试策略处理
+fn after_send(exit: &SendExit, state: &ConnectionState) -> AfterSend {
+    match (exit, state) {
+        (SendExit::Finished, _) => AfterSend::Stop,
+        (SendExit::Interrupted { .. }, ConnectionState::Idle) => AfterSend::ResumeSession,
+        (SendExit::Interrupted { .. }, _) => AfterSend::Retry,
+    }
+}
+
+/// 发送任务的控制信号
+struct SendControl {
+    /// 取消信号
+    cancel_rx: watch::Receiver<bool>,
+    /// 强制提交信号
+    commit_rx: mpsc::Receiver<()>,
+    /// 生效的静音提交窗口（提交被限流时由接收任务延长）
+    commit_interval_rx: watch::Receiver<Duration>,
+    /// 网络指标（记录发送的消息）
+    metrics_tx: watch::Sender<NetworkMetrics>,
+    /// 收尾状态输出
+    drain_tx: watch::Sender<DrainState>,
+    /// 接收任务已结束（服务器结束会话或连接断开）
+    recv_ended_rx: watch::Receiver<bool>,
+    /// 本连接上服务器已回应的 commit 数（定稿转写或提交被限流）
+    commit_responses_rx: watch::Receiver<u64>,
+    /// 上一个会话中断时尚未发送的音频，新会话先发送
+    pending: Vec<i16>,
+}
+
+/// 等待最终 commit 回应时关注的信号
+struct FinalCommitSignals<'a> {
+    commit_responses_rx: &'a mut watch::Receiver<u64>,
+    recv_ended_rx: &'a mut watch::Receiver<bool>,
+    cancel_rx: &'a mut watch::Receiver<bool>,
+}
+
+/// 接收任务的输出信号
+struct RecvControl {
+    /// 每收到一帧更新为当前时间（供死连接检测）
+    seen_tx: watch::Sender<Instant>,
+    /// 生效的静音提交窗口输出
+    commit_interval_tx: watch::Sender<Duration>,
+    /// 提交限流退避（每个连接重新开始）
+    backoff: CommitBackoff,
+    /// 网络指标（记录收到的消息和往返延迟）
+    metrics_tx: watch::Sender<NetworkMetrics>,
+    /// 接收任务结束时发送 true，通知发送任务交回输入通道
+    recv_ended_tx: watch::Sender<bool>,
+    /// 每收到一次 commit 的回应加一，发送任务据此在最终转写到达后才关闭连接
+    commit_responses_tx: watch::Sender<u64>,
+}
+
 /// 网络管理器
 ///
 /// 负责管理 WebSocket 连接生命周期、发送音频数据、接收转写结果
 pub struct NetworkManager {
-    client: ScribeClient,
+    config: ClientConfig,
+    /// 转写后端，未设置时首次连接前按客户端配置创建 Scribe 后端
+    backend: Option<Arc<dyn SttBackend>>,
     state: Arc<RwLock<StateMachine>>,
-    audio_rx: mpsc::Receiver<Vec<i16>>,
+    audio_rx: mpsc::Receiver<AudioFrame>,
     event_tx: mpsc::Sender<ServerMessage>,
+    cancel_rx: watch::Receiver<bool>,
+    commit_rx: mpsc::Receiver<()>,
+    commit_interval_tx: watch::Sender<Duration>,
+    metrics_tx: watch::Sender<NetworkMetrics>,
+    drain_tx: watch::Sender<DrainState>,
+    /// 上一个会话中断时尚未发送的音频
+    pending_audio: Vec<i16>,
+    resumes: ResumeBudget,
 }
 
 impl NetworkManager {
@@ -50,17 +206,99 @@ impl NetworkManager {
     /// * `event_tx` - 发送服务器事件的通道
     pub fn new(
         api_key: String,
-        audio_rx: mpsc::Receiver<Vec<i16>>,
+        audio_rx: mpsc::Receiver<AudioFrame>,
+        event_tx: mpsc::Sender<ServerMessage>,
+    ) -> Self {
+        Self::with_config(
+            ClientConfig {
+                api_key,
+                ..Default::default()
+            },
+            audio_rx,
+            event_tx,
+        )
+    }
+
+    /// 使用自定义客户端配置创建网络管理器
+    ///
+    /// # Arguments
+    /// * `config` - 客户端配置（API Key、语言等）
+    /// * `audio_rx` - 接收音频数据的通道
+    /// * `event_tx` - 发送服务器事件的通道
+    pub fn with_config(
+        mut config: ClientConfig,
+        audio_rx: mpsc::Receiver<AudioFrame>,
         event_tx: mpsc::Sender<ServerMessage>,
     ) -> Self {
+        config.batch_interval = clamp_batch_interval(config.batch_interval);
+        let state = StateMachine::new(config.max_retries, DEFAULT_RETRY_DELAY);
+        let (commit_interval_tx, _) = watch::channel(config.silence_commit);
+
         Self {
-            client: ScribeClient::new(api_key),
-            state: Arc::new(RwLock::new(StateMachine::default())),
+            backend: None,
+            config,
+            state: Arc::new(RwLock::new(state)),
             audio_rx,
             event_tx,
+            cancel_rx: watch::channel(false).1,
+            commit_rx: mpsc::channel(1).1,
+            commit_interval_tx,
+            metrics_tx: watch::Sender::new(NetworkMetrics::default()),
+            drain_tx: watch::Sender::new(DrainState::default()),
+            pending_audio: Vec::new(),
+            resumes: ResumeBudget::default(),
         }
     }
 
+    /// 设置转写后端（默认为 ElevenLabs Scribe）
+    pub fn with_backend(mut self, backend: Box<dyn SttBackend>) -> Self {
+        self.backend = Some(Arc::from(backend));
+        self
+    }
+
+    /// 当前的转写后端，未设置时创建默认的 Scribe 后端
+    fn backend(&mut self) -> Arc<dyn SttBackend> {
+        self.backend
+            .get_or_insert_with(|| Arc::new(ScribeBackend::new(self.config.clone())))
+            .clone()
+    }
+
+    /// 设置取消信号
+    ///
+    /// 信号变为 true 时丢弃尚未发送的音频并直接关闭连接，不发送 commit
+    pub fn with_cancel(mut self, cancel_rx: watch::Receiver<bool>) -> Self {
+        self.cancel_rx = cancel_rx;
+        self
+    }
+
+    /// 设置强制提交通道
+    ///
+    /// 收到信号时立即发送缓冲的音频并提交当前语句，不等待静音提交窗口
+    pub fn with_commit_trigger(mut self, commit_rx: mpsc::Receiver<()>) -> Self {
+        self.commit_rx = commit_rx;
+        self
+    }
+
+    /// 订阅生效的静音提交窗口
+    ///
+    /// 连续被服务器限流提交时窗口临时延长，成功提交后恢复配置值
+    pub fn commit_interval(&self) -> watch::Receiver<Duration> {
+        self.commit_interval_tx.subscribe()
+    }
+
+    /// 订阅网络指标（连接时长、往返延迟、收发消息数）
+    pub fn metrics(&self) -> MetricsReader {
+        MetricsReader::new(self.metrics_tx.subscribe(), self.state.clone())
+    }
+
+    /// 订阅收尾状态
+    ///
+    /// 音频输入关闭后，发送任务发出剩余音频和最终 commit 时更新，
+    /// 停止录音时据此决定是否等待最终转写
+    pub fn drain_state(&self) -> watch::Receiver<DrainState> {
+        self.drain_tx.subscribe()
+    }
+
     /// 启动网络管理器
     ///
     /// 建立连接并启动发送/接收任务
@@ -76,15 +314,26 @@ impl NetworkManager {
             }
 
             // 2. 建立连接
-            let (ws_sink, ws_stream) = match self.client.connect().await {
+            let backend = self.backend();
+            let (ws_sink, ws_stream) = match backend.connect().await {
                 Ok(conn) => conn,
                 Err(e) => {
                     error!("Connection failed: {}", e);
-                    self.state.write().await.transition_to_error(e.to_string());
+                    self.state
+                        .write()
+                        .await
+                        .transition_to_error(e.to_string(), e.is_retryable());
+
+                    // 认证失败不会重试，立即通知前端
+                    if let ClientError::AuthenticationFailed(error) = e {
+                        let message = ServerMessage::AuthError { error };
+                        if self.event_tx.send(message).await.is_err() {
+                            warn!("Event channel closed, auth error dropped");
+                        }
+                    }
 
-                    // 检查是否应该重试
-                    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-                    if self.state.read().await.should_retry() {
+                    // 按退避延迟等待后检查是否应该重试
+                    if self.wait_for_retry().await {
                         continue;
                     } else {
                         break;
@@ -92,165 +341,572 @@ impl NetworkManager {
                 }
             };
 
-            // 3. 启动发送和接收任务
-            let send_handle = self.spawn_send_task(ws_sink);
-            let recv_handle = self.spawn_recv_task(ws_stream);
+            // 3. 启动发送和接收任务（接收任务记录最近收到服务器数据的时间，发送任务据此检测死连接）
+            let (seen_tx, seen_rx) = watch::channel(Instant::now());
+            let (recv_ended_tx, recv_ended_rx) = watch::channel(false);
+            let (commit_responses_tx, commit_responses_rx) = watch::channel(0);
+            self.metrics_tx.send_modify(NetworkMetrics::on_reconnect);
+            let send_handle =
+                self.spawn_send_task(ws_sink, seen_rx, recv_ended_rx, commit_responses_rx);
+            self.spawn_recv_task(ws_stream, seen_tx, recv_ended_tx, commit_responses_tx);
+            let session_started = Instant::now();
 
-            // 4. 等待任一任务结束
-            tokio::select! {
-                _ = send_handle => {
-                    info!("Send task ended");
-                }
-                _ = recv_handle => {
-                    info!("Recv task ended");
+            // 4. 等待发送任务结束（接收任务结束时发送任务随之结束并交回输入通道）
+            let exit = match send_handle.await {
+                Ok(exit) => exit,
+                Err(e) => {
+                    error!("Send task failed: {}", e);
+                    break;
                 }
-            }
+            };
+            info!("Send task ended");
 
             // 5. 决定是否重连
-            let should_retry = self.state.read().await.should_retry();
-            if !should_retry {
-                info!("Not retrying, stopping network manager");
-                break;
+            let next = after_send(&exit, self.state.read().await.current_state());
+            if let SendExit::Interrupted {
+                audio_rx,
+                commit_rx,
+                pending,
+            } = exit
+            {
+                self.audio_rx = audio_rx;
+                self.commit_rx = commit_rx;
+                self.pending_audio = pending;
+            }
+
+            match next {
+                AfterSend::ResumeSession if self.resumes.try_resume(session_started.elapsed()) => {
+                    info!("Session ended by server while recording, starting a new session");
+                    // 与连接错误共用重试延迟和次数限制
+                    self.state
+                        .write()
+                        .await
+                        .transition_to_error("Session ended by server".to_string(), true);
+                    if !self.wait_for_retry().await {
+                        info!("Not resuming session, stopping network manager");
+                        break;
+                    }
+                }
+                AfterSend::ResumeSession => {
+                    warn!(
+                        "Session ended {} times in a row, stopping network manager",
+                        MAX_CONSECUTIVE_RESUMES + 1
+                    );
+                    break;
+                }
+                AfterSend::Retry if self.wait_for_retry().await => {}
+                AfterSend::Retry | AfterSend::Stop => {
+                    info!("Not retrying, stopping network manager");
+                    break;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// 等待退避延迟，返回是否应该重试
+    ///
+    /// 不在错误状态或重试次数已用尽时立即返回 false
+    async fn wait_for_retry(&self) -> bool {
+        let delay = {
+            let state = self.state.read().await;
+            if !state.can_retry() {
+                return false;
+            }
+            state.next_retry_delay()
+        };
+
+        if !delay.is_zero() {
+            info!("Retrying connection in {:?}", delay);
+            tokio::time::sleep(delay).await;
+        }
+
+        self.state.read().await.should_retry()
+    }
+
     /// 生成发送任务
-    fn spawn_send_task(&mut self, mut ws_sink: WsSink) -> tokio::task::JoinHandle<()> {
-        let mut audio_rx = std::mem::replace(
+    fn spawn_send_task(
+        &mut self,
+        ws_sink: WsSink,
+        seen_rx: watch::Receiver<Instant>,
+        recv_ended_rx: watch::Receiver<bool>,
+        commit_responses_rx: watch::Receiver<u64>,
+    ) -> tokio::task::JoinHandle<SendExit> {
+        let audio_rx = std::mem::replace(
             &mut self.audio_rx,
             mpsc::channel(1).1, // 创建一个虚拟接收器
         );
+        let control = SendControl {
+            cancel_rx: self.cancel_rx.clone(),
+            commit_rx: std::mem::replace(&mut self.commit_rx, mpsc::channel(1).1),
+            commit_interval_rx: self.commit_interval_tx.subscribe(),
+            metrics_tx: self.metrics_tx.clone(),
+            drain_tx: self.drain_tx.clone(),
+            recv_ended_rx,
+            commit_responses_rx,
+            pending: std::mem::take(&mut self.pending_audio),
+        };
+
+        tokio::spawn(Self::send_loop(
+            ws_sink,
+            audio_rx,
+            control,
+            seen_rx,
+            self.state.clone(),
+            self.backend(),
+            self.config.clone(),
+        ))
+    }
+
+    /// 发送循环：按调度器的决定发送音频和 commit，并定时保活
+    ///
+    /// ping 保活时，超过 `pong_timeout` 没有收到服务器任何数据则认为连接已断开，
+    /// 切换到可重试的错误状态并结束，由 `run` 重新连接。
+    /// 录音仍在进行时结束（连接中断、接收任务结束）会交回输入通道
+    ///
+    /// # Arguments
+    /// * `ws_sink` - WebSocket 发送端
+    /// * `audio_rx` - 16kHz 音频输入
+    /// * `control` - 取消、强制提交和静音提交窗口变化信号
+    /// * `seen_rx` - 最近一次收到服务器数据（含 pong）的时间
+    /// * `state` - 连接状态机
+    /// * `backend` - 转写后端（编码音频和控制消息）
+    /// * `config` - 客户端配置（保活、批量间隔、静音提交窗口等）
+    async fn send_loop<S>(
+        mut ws_sink: S,
+        mut audio_rx: mpsc::Receiver<AudioFrame>,
+        control: SendControl,
+        seen_rx: watch::Receiver<Instant>,
+        state: Arc<RwLock<StateMachine>>,
+        backend: Arc<dyn SttBackend>,
+        config: ClientConfig,
+    ) -> SendExit
+    where
+        S: Sink<Message, Error = tungstenite::Error> + Unpin,
+    {
+        let keepalive = config.keepalive;
+        let keepalive_interval = config.keepalive_interval;
+        let max_frame_age = config.max_frame_age;
+        let SendControl {
+            mut cancel_rx,
+            mut commit_rx,
+            mut commit_interval_rx,
+            metrics_tx,
+            drain_tx,
+            mut recv_ended_rx,
+            mut commit_responses_rx,
+            pending,
+        } = control;
+        let mut scheduler = SendScheduler::new(&config, Instant::now());
+        scheduler.set_silence_commit(*commit_interval_rx.borrow_and_update());
+        if !pending.is_empty() {
+            info!(
+                "Resending {} samples buffered before the previous session ended",
+                pending.len()
+            );
+            scheduler.restore(pending, Instant::now());
+        }
 
-        tokio::spawn(async move {
-            info!("Send task started");
+        info!("Send task started");
 
-            let mut buffer = Vec::new();
-            let mut last_send = tokio::time::Instant::now();
-            let mut last_audio_received = tokio::time::Instant::now();
-            let mut committed = false; // 是否已发送 commit
+        // 连续丢弃的过旧音频帧数
+        let mut stale_frames = 0usize;
+        // 本连接上已发送的 commit 数
+        let mut commits_sent = 0u64;
+        let mut keepalive_timer =
+            tokio::time::interval_at(Instant::now() + keepalive_interval, keepalive_interval);
 
-            const BATCH_INTERVAL_MS: u64 = 500; // 累积 500ms 再发送
-            const SILENCE_COMMIT_MS: u64 = 2000; // 静音 2 秒后自动 commit
+        // 录音是否已结束（音频输入关闭或取消）
+        let finished = 'send: loop {
+            tokio::select! {
+                // 取消优先于音频通道关闭，避免取消时仍发送剩余音频和 commit
+                biased;
 
-            loop {
-                tokio::select! {
-                    // 接收音频数据
-                    Some(audio_chunk) = audio_rx.recv() => {
-                        buffer.extend_from_slice(&audio_chunk);
-                        last_audio_received = tokio::time::Instant::now();
-                        committed = false; // 收到新音频，重置 commit 状态
+                _ = Self::wait_for_flag(&mut cancel_rx) => {
+                    info!("Session cancelled, discarding {} buffered samples", scheduler.buffered_samples());
+                    scheduler.clear();
+                    if let Err(e) = ws_sink.close().await {
+                        warn!("Failed to close WebSocket: {}", e);
                     }
+                    break true;
+                }
 
-                    // 定时发送
-                    _ = tokio::time::sleep_until(last_send + tokio::time::Duration::from_millis(BATCH_INTERVAL_MS)) => {
-                        if !buffer.is_empty() {
-                            // 创建消息
-                            let msg = ClientMessage::audio_chunk(&buffer);
-
-                            // 序列化为 JSON
-                            let json = match msg.to_json() {
-                                Ok(j) => j,
-                                Err(e) => {
-                                    error!("Failed to serialize message: {}", e);
-                                    buffer.clear();
-                                    last_send = tokio::time::Instant::now();
-                                    continue;
-                                }
+                // 接收音频数据
+                chunk = audio_rx.recv() => {
+                    let Some(frame) = chunk else {
+                        // 音频通道关闭（录音停止）：发送剩余音频和最终 commit，收到回应后关闭连接
+                        info!("Audio channel closed, flushing and closing connection");
+                        let committed = Self::flush(&mut ws_sink, scheduler.finish(), backend.as_ref(), &metrics_tx).await;
+                        drain_tx.send_replace(DrainState::Flushed { committed });
+                        if committed {
+                            commits_sent += 1;
+                            let responses = FinalCommitSignals {
+                                commit_responses_rx: &mut commit_responses_rx,
+                                recv_ended_rx: &mut recv_ended_rx,
+                                cancel_rx: &mut cancel_rx,
                             };
+                            Self::wait_for_final_commit(responses, commits_sent, config.final_commit_timeout).await;
+                        }
+                        if let Err(e) = ws_sink.close().await {
+                            warn!("Failed to close WebSocket: {}", e);
+                        }
+                        break true;
+                    };
+
+                    // 网络阻塞时积压的音频已失去实时性，直接丢弃
+                    if frame.is_stale(max_frame_age) {
+                        if stale_frames == 0 {
+                            warn!("Audio is {:?} old (max {:?}), dropping stale frames", frame.age(), max_frame_age);
+                        }
+                        stale_frames += 1;
+                        continue;
+                    }
+                    if stale_frames > 0 {
+                        warn!("Dropped {} stale audio frames", stale_frames);
+                        stale_frames = 0;
+                    }
+
+                    scheduler.on_audio(&frame.samples, Instant::now());
+                }
+
+                // 强制提交（如说出了需要立即提交的符号关键词）
+                Some(()) = commit_rx.recv() => {
+                    info!("Force commit requested");
+                    for action in scheduler.force_commit() {
+                        if let Err(e) = Self::send_action(&mut ws_sink, &action, backend.as_ref(), &metrics_tx).await {
+                            error!("Failed to send {}: {}", Self::action_name(&action), e);
+                            break 'send false;
+                        }
+                        commits_sent += u64::from(matches!(action, SendAction::Commit));
+                    }
+                }
+
+                // 服务器结束会话或连接断开：缓冲的音频和未读取的音频都留给新会话
+                _ = Self::wait_for_flag(&mut recv_ended_rx) => {
+                    info!("Recv task ended, keeping {} buffered samples for the next session", scheduler.buffered_samples());
+                    if let Err(e) = ws_sink.close().await {
+                        debug!("Failed to close WebSocket: {}", e);
+                    }
+                    break false;
+                }
+
+                // 提交被限流时延长静音提交窗口，成功提交后恢复
+                Ok(()) = commit_interval_rx.changed() => {
+                    let interval = *commit_interval_rx.borrow_and_update();
+                    debug!("Silence commit window set to {:?}", interval);
+                    scheduler.set_silence_commit(interval);
+                }
+
+                // 定时保活
+                _ = keepalive_timer.tick() => {
+                    if let Some(silent) = Self::pong_overdue(&config, &seen_rx, Instant::now()) {
+                        error!("No pong from server for {:?}, connection considered dead", silent);
+                        state
+                            .write()
+                            .await
+                            .transition_to_error(format!("Pong timeout after {:?}", silent), true);
+                        break false;
+                    }
+
+                    let frame = match Self::keepalive_frame(keepalive, backend.as_ref()) {
+                        Ok(frame) => frame,
+                        Err(e) => {
+                            error!("Failed to serialize keepalive: {}", e);
+                            continue;
+                        }
+                    };
+
+                    if let Err(e) = ws_sink.send(frame).await {
+                        error!("Failed to send keepalive: {}", e);
+                        break false;
+                    }
+                    debug!("Sent keepalive ({:?})", keepalive);
+                }
 
-                            // 发送
-                            if let Err(e) = ws_sink.send(Message::Text(json.into())).await {
-                                error!("Failed to send audio: {}", e);
-                                break;
-                            }
-
-                            debug!("Sent batched audio: {} samples (~{}ms)",
-                                buffer.len(),
-                                (buffer.len() as f64 / 16000.0 * 1000.0) as u64
-                            );
-
-                            buffer.clear();
-                            last_send = tokio::time::Instant::now();
-                        } else {
-                            // 缓冲区为空，检查是否需要发送 commit
-                            let silence_duration = last_audio_received.elapsed();
-                            if !committed && silence_duration.as_millis() >= SILENCE_COMMIT_MS as u128 {
-                                info!("Silence detected for {}ms, sending commit signal", silence_duration.as_millis());
-
-                                let commit_msg = ClientMessage::commit();
-                                if let Ok(json) = commit_msg.to_json() {
-                                    if let Err(e) = ws_sink.send(Message::Text(json.into())).await {
-                                        error!("Failed to send commit: {}", e);
-                                        break;
-                                    }
-                                    committed = true;
-                                }
-                            }
-
-                            last_send = tokio::time::Instant::now();
+                // 定时发送
+                _ = tokio::time::sleep_until(scheduler.next_deadline()) => {
+                    for action in scheduler.on_tick(Instant::now()) {
+                        if let Err(e) = Self::send_action(&mut ws_sink, &action, backend.as_ref(), &metrics_tx).await {
+                            error!("Failed to send {}: {}", Self::action_name(&action), e);
+                            break 'send false;
                         }
+                        commits_sent += u64::from(matches!(action, SendAction::Commit));
                     }
                 }
             }
+        };
+
+        info!("Send task stopped");
+
+        if finished {
+            SendExit::Finished
+        } else {
+            SendExit::Interrupted {
+                audio_rx,
+                commit_rx,
+                pending: scheduler.take_buffered(),
+            }
+        }
+    }
+
+    /// 检查是否超时未收到 pong
+    ///
+    /// 只在 ping 保活且超时不为零时检测；应用层保活消息不保证有回应
+    ///
+    /// # Returns
+    /// * `Some(silent)` - 已超时，`silent` 为距上次收到服务器数据的时长
+    fn pong_overdue(
+        config: &ClientConfig,
+        seen_rx: &watch::Receiver<Instant>,
+        now: Instant,
+    ) -> Option<Duration> {
+        if config.keepalive != KeepAlive::Ping || config.pong_timeout.is_zero() {
+            return None;
+        }
+
+        let silent = now.saturating_duration_since(*seen_rx.borrow());
+        (silent >= config.pong_timeout).then_some(silent)
+    }
+
+    /// 等待信号变为 true（取消、接收任务结束）；发送端已丢弃且仍为 false 时永不返回
+    async fn wait_for_flag(flag_rx: &mut watch::Receiver<bool>) {
+        if flag_rx.wait_for(|set| *set).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// 发送一个调度动作，发送成功后计入网络指标
+    ///
+    /// 序列化失败时记录错误并跳过该动作，只有发送失败才返回错误
+    async fn send_action<S>(
+        ws_sink: &mut S,
+        action: &SendAction,
+        backend: &dyn SttBackend,
+        metrics_tx: &watch::Sender<NetworkMetrics>,
+    ) -> std::result::Result<(), tungstenite::Error>
+    where
+        S: Sink<Message, Error = tungstenite::Error> + Unpin,
+    {
+        let frame = match action {
+            SendAction::Audio(samples) => backend.encode_audio(samples),
+            SendAction::Commit => backend.encode_commit(),
+        };
+
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                error!("Failed to serialize {}: {}", Self::action_name(action), e);
+                return Ok(());
+            }
+        };
+
+        ws_sink.send(frame).await?;
+        metrics_tx.send_modify(|metrics| metrics.record_sent(action, Instant::now()));
+
+        if let SendAction::Audio(samples) = action {
+            debug!("Sent batched audio: {} samples", samples.len());
+        }
+
+        Ok(())
+    }
+
+    /// 调度动作名称（用于日志）
+    fn action_name(action: &SendAction) -> &'static str {
+        match action {
+            SendAction::Audio(_) => "audio",
+            SendAction::Commit => "commit",
+        }
+    }
+
+    /// 根据配置生成保活帧（应用层保活消息由后端编码）
+    fn keepalive_frame(
+        keepalive: KeepAlive,
+        backend: &dyn SttBackend,
+    ) -> std::result::Result<Message, serde_json::Error> {
+        match keepalive {
+            KeepAlive::Ping => Ok(Message::Ping(Default::default())),
+            KeepAlive::Message => backend.encode_keepalive(),
+        }
+    }
+
+    /// 发送剩余音频和最终 commit
+    ///
+    /// 连接保持打开，由调用方在收到最终转写（或超时）后关闭
+    ///
+    /// # Returns
+    /// * `true` - 已发送最终 commit，之后应收到最终转写
+    async fn flush<S>(
+        ws_sink: &mut S,
+        actions: Vec<SendAction>,
+        backend: &dyn SttBackend,
+        metrics_tx: &watch::Sender<NetworkMetrics>,
+    ) -> bool
+    where
+        S: Sink<Message, Error = tungstenite::Error> + Unpin,
+    {
+        for action in &actions {
+            if let Err(e) = Self::send_action(ws_sink, action, backend, metrics_tx).await {
+                error!("Failed to flush {}: {}", Self::action_name(action), e);
+                return false;
+            }
+        }
+
+        actions
+            .iter()
+            .any(|action| matches!(action, SendAction::Commit))
+    }
+
+    /// 等待服务器回应最终 commit
+    ///
+    /// 连接在 commit 发出后立即关闭时服务器可能不再返回最终转写，
+    /// 因此等到接收任务收到第 `commits_sent` 个回应、接收任务结束、取消或超时
+    async fn wait_for_final_commit(
+        signals: FinalCommitSignals<'_>,
+        commits_sent: u64,
+        timeout: Duration,
+    ) {
+        let FinalCommitSignals {
+            commit_responses_rx,
+            recv_ended_rx,
+            cancel_rx,
+        } = signals;
 
-            info!("Send task stopped");
-        })
+        tokio::select! {
+            result = commit_responses_rx.wait_for(|responses| *responses >= commits_sent) => {
+                if result.is_ok() {
+                    debug!("Final commit answered by server");
+                } else {
+                    debug!("Recv task gone before the final commit was answered");
+                }
+            }
+            _ = Self::wait_for_flag(recv_ended_rx) => {
+                debug!("Recv task ended before the final commit was answered");
+            }
+            _ = Self::wait_for_flag(cancel_rx) => {
+                info!("Session cancelled while waiting for the final transcript");
+            }
+            _ = tokio::time::sleep(timeout) => {
+                warn!("No response to the final commit within {:?}, closing connection", timeout);
+            }
+        }
     }
 
     /// 生成接收任务
-    fn spawn_recv_task(&self, mut ws_stream: WsStream) -> tokio::task::JoinHandle<()> {
-        let state = self.state.clone();
-        let event_tx = self.event_tx.clone();
-
-        tokio::spawn(async move {
-            info!("Recv task started");
-
-            while let Some(msg) = ws_stream.next().await {
-                match msg {
-                    Ok(Message::Text(text)) => {
-                        debug!("Received message: {}", text);
-
-                        // 解析消息
-                        match ServerMessage::from_json(&text) {
-                            Ok(server_msg) => {
-                                // 处理状态更新
-                                Self::handle_state_update(&state, &server_msg).await;
-
-                                // 转发事件
-                                if event_tx.send(server_msg).await.is_err() {
-                                    error!("Event channel closed");
-                                    break;
-                                }
-                            }
-                            Err(e) => {
-                                error!("Failed to parse server message: {}", e);
-                            }
-                        }
+    fn spawn_recv_task(
+        &mut self,
+        ws_stream: WsStream,
+        seen_tx: watch::Sender<Instant>,
+        recv_ended_tx: watch::Sender<bool>,
+        commit_responses_tx: watch::Sender<u64>,
+    ) -> tokio::task::JoinHandle<()> {
+        let control = RecvControl {
+            seen_tx,
+            commit_interval_tx: self.commit_interval_tx.clone(),
+            backoff: CommitBackoff::new(self.config.silence_commit),
+            metrics_tx: self.metrics_tx.clone(),
+            recv_ended_tx,
+            commit_responses_tx,
+        };
+
+        tokio::spawn(Self::recv_loop(
+            ws_stream,
+            self.state.clone(),
+            self.backend(),
+            self.event_tx.clone(),
+            control,
+        ))
+    }
+
+    /// 接收循环：解码服务器消息、更新连接状态并转发事件
+    ///
+    /// # Arguments
+    /// * `ws_stream` - WebSocket 接收端
+    /// * `state` - 连接状态机
+    /// * `backend` - 转写后端（解码服务器消息）
+    /// * `event_tx` - 服务器事件输出
+    /// * `control` - 活动时间、静音提交窗口和网络指标输出
+    async fn recv_loop<S>(
+        mut ws_stream: S,
+        state: Arc<RwLock<StateMachine>>,
+        backend: Arc<dyn SttBackend>,
+        event_tx: mpsc::Sender<ServerMessage>,
+        control: RecvControl,
+    ) where
+        S: Stream<Item = std::result::Result<Message, tungstenite::Error>> + Unpin,
+    {
+        let RecvControl {
+            seen_tx,
+            commit_interval_tx,
+            mut backoff,
+            metrics_tx,
+            recv_ended_tx,
+            commit_responses_tx,
+        } = control;
+
+        info!("Recv task started");
+        Self::publish_commit_interval(&commit_interval_tx, backoff.interval());
+
+        while let Some(msg) = ws_stream.next().await {
+            if msg.is_ok() {
+                seen_tx.send_replace(Instant::now());
+            }
+
+            match msg {
+                Ok(Message::Text(text)) => {
+                    debug!("Received message: {}", text);
+
+                    // 解码消息（无法识别的消息由后端记录并跳过）
+                    let Some(server_msg) = backend.decode_message(&text) else {
+                        continue;
+                    };
+
+                    metrics_tx.send_modify(|metrics| {
+                        metrics.record_received(&server_msg, Instant::now())
+                    });
+
+                    // 处理状态更新
+                    Self::handle_state_update(&state, &server_msg).await;
+                    Self::handle_commit_backoff(&mut backoff, &commit_interval_tx, &server_msg);
+                    if matches!(
+                        server_msg,
+                        ServerMessage::CommittedTranscript { .. }
+                            | ServerMessage::CommitThrottled { .. }
+                    ) {
+                        commit_responses_tx.send_modify(|responses| *responses += 1);
                     }
-                    Ok(Message::Close(frame)) => {
-                        info!("WebSocket closed by server: {:?}", frame);
+
+                    // 转发事件；服务器结束会话后本连接不再有转写
+                    let session_ended = matches!(server_msg, ServerMessage::SessionEnded { .. });
+                    if event_tx.send(server_msg).await.is_err() {
+                        error!("Event channel closed");
                         break;
                     }
-                    Ok(Message::Ping(_)) => {
-                        debug!("Received ping");
-                    }
-                    Ok(Message::Pong(_)) => {
-                        debug!("Received pong");
-                    }
-                    Err(e) => {
-                        error!("WebSocket error: {}", e);
-                        state.write().await.transition_to_error(e.to_string());
+                    if session_ended {
                         break;
                     }
-                    _ => {}
                 }
+                Ok(Message::Close(frame)) => {
+                    info!("WebSocket closed by server: {:?}", frame);
+                    break;
+                }
+                Ok(Message::Ping(_)) => {
+                    debug!("Received ping");
+                }
+                Ok(Message::Pong(_)) => {
+                    debug!("Received pong");
+                }
+                Err(e) => {
+                    error!("WebSocket error: {}", e);
+                    state.write().await.transition_to_error(e.to_string(), true);
+                    break;
+                }
+                _ => {}
             }
+        }
 
-            info!("Recv task stopped");
-        })
+        info!("Recv task stopped");
+        recv_ended_tx.send_replace(true);
     }
 
     /// 处理状态更新
@@ -269,14 +925,14 @@ impl NetworkManager {
                 state
                     .write()
                     .await
-                    .transition_to_error(error_message.clone());
+                    .transition_to_error(error_message.clone(), true);
             }
             ServerMessage::AuthError { error } => {
                 error!("Authentication error: {}", error);
                 state
                     .write()
                     .await
-                    .transition_to_error(error.clone());
+                    .transition_to_error(error.clone(), false);
             }
             ServerMessage::CommitThrottled { error } => {
                 // 这是一个警告，不是致命错误，不需要关闭连接
@@ -290,6 +946,35 @@ impl NetworkManager {
         }
     }
 
+    /// 根据提交结果调整静音提交窗口
+    ///
+    /// 被限流时延长窗口，收到定稿转写时恢复
+    fn handle_commit_backoff(
+        backoff: &mut CommitBackoff,
+        commit_interval_tx: &watch::Sender<Duration>,
+        message: &ServerMessage,
+    ) {
+        match message {
+            ServerMessage::CommitThrottled { .. } => {
+                let interval = backoff.on_throttled();
+                Self::publish_commit_interval(commit_interval_tx, interval);
+            }
+            ServerMessage::CommittedTranscript { .. } if backoff.on_committed() => {
+                Self::publish_commit_interval(commit_interval_tx, backoff.interval());
+            }
+            _ => {}
+        }
+    }
+
+    /// 发布静音提交窗口（只在变化时通知订阅者）
+    fn publish_commit_interval(commit_interval_tx: &watch::Sender<Duration>, interval: Duration) {
+        commit_interval_tx.send_if_modified(|current| {
+            let changed = *current != interval;
+            *current = interval;
+            changed
+        });
+    }
+
     /// 获取当前连接状态
     pub async fn get_state(&self) -> ConnectionState {
         self.state.read().await.current_state().clone()
@@ -304,6 +989,161 @@ impl NetworkManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::network::pcm_bytes;
+    use std::pin::Pin;
+
+    /// 将发送的帧转发到通道的模拟 WebSocket 发送端
+    fn recording_sink() -> (
+        Pin<Box<impl Sink<Message, Error = tungstenite::Error>>>,
+        mpsc::UnboundedReceiver<Message>,
+    ) {
+        let (sent_tx, sent_rx) = mpsc::unbounded_channel::<Message>();
+        let sink = Box::pin(futures_util::sink::unfold(
+            sent_tx,
+            |sent_tx, message: Message| async move {
+                let _ = sent_tx.send(message);
+                Ok::<_, tungstenite::Error>(sent_tx)
+            },
+        ));
+
+        (sink, sent_rx)
+    }
+
+    /// 启动发送循环（服务器活动时间固定为启动时刻，状态机为初始状态，没有强制提交）
+    fn send_loop<S>(
+        sink: S,
+        audio_rx: mpsc::Receiver<AudioFrame>,
+        cancel_rx: watch::Receiver<bool>,
+        config: ClientConfig,
+    ) -> impl Future<Output = SendExit>
+    where
+        S: Sink<Message, Error = tungstenite::Error> + Unpin,
+    {
+        send_loop_with_commit(sink, audio_rx, cancel_rx, mpsc::channel(1).1, config)
+    }
+
+    /// 没有静音提交窗口变化的控制信号
+    fn send_control(
+        cancel_rx: watch::Receiver<bool>,
+        commit_rx: mpsc::Receiver<()>,
+        config: &ClientConfig,
+    ) -> SendControl {
+        SendControl {
+            cancel_rx,
+            commit_rx,
+            commit_interval_rx: watch::channel(config.silence_commit).1,
+            metrics_tx: watch::Sender::new(NetworkMetrics::default()),
+            drain_tx: watch::Sender::new(DrainState::default()),
+            recv_ended_rx: watch::channel(false).1,
+            commit_responses_rx: watch::channel(0).1,
+            pending: Vec::new(),
+        }
+    }
+
+    /// 启动带强制提交通道的发送循环
+    fn send_loop_with_commit<S>(
+        sink: S,
+        audio_rx: mpsc::Receiver<AudioFrame>,
+        cancel_rx: watch::Receiver<bool>,
+        commit_rx: mpsc::Receiver<()>,
+        config: ClientConfig,
+    ) -> impl Future<Output = SendExit>
+    where
+        S: Sink<Message, Error = tungstenite::Error> + Unpin,
+    {
+        let (_seen_tx, seen_rx) = watch::channel(Instant::now());
+        let state = Arc::new(RwLock::new(StateMachine::new(0, DEFAULT_RETRY_DELAY)));
+        let control = send_control(cancel_rx, commit_rx, &config);
+        let backend = scribe_backend(&config);
+        NetworkManager::send_loop(sink, audio_rx, control, seen_rx, state, backend, config)
+    }
+
+    fn scribe_backend(config: &ClientConfig) -> Arc<dyn SttBackend> {
+        Arc::new(ScribeBackend::new(config.clone()))
+    }
+
+    /// 接收任务的输出信号（网络指标不检查）
+    fn recv_control(
+        seen_tx: watch::Sender<Instant>,
+        commit_interval_tx: watch::Sender<Duration>,
+        silence_commit: Duration,
+    ) -> RecvControl {
+        RecvControl {
+            seen_tx,
+            commit_interval_tx,
+            backoff: CommitBackoff::new(silence_commit),
+            metrics_tx: watch::Sender::new(NetworkMetrics::default()),
+            recv_ended_tx: watch::Sender::new(false),
+            commit_responses_tx: watch::Sender::new(0),
+        }
+    }
+
+    /// 模拟 WebSocket 发送端的操作：发送的帧，或关闭（None）
+    struct EventSink {
+        events_tx: mpsc::UnboundedSender<Option<Message>>,
+    }
+
+    impl Sink<Message> for EventSink {
+        type Error = tungstenite::Error;
+
+        fn poll_ready(
+            self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::result::Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: Message) -> std::result::Result<(), Self::Error> {
+            let _ = self.events_tx.send(Some(item));
+            Ok(())
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::result::Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::result::Result<(), Self::Error>> {
+            let _ = self.events_tx.send(None);
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    /// 启动发送循环，返回发送端操作和 commit 回应计数的发送端
+    fn send_loop_until_close(
+        config: ClientConfig,
+    ) -> (
+        tokio::task::JoinHandle<SendExit>,
+        mpsc::Sender<AudioFrame>,
+        mpsc::UnboundedReceiver<Option<Message>>,
+        watch::Sender<u64>,
+    ) {
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let (audio_tx, audio_rx) = mpsc::channel(100);
+        let (commit_responses_tx, commit_responses_rx) = watch::channel(0);
+        let control = SendControl {
+            commit_responses_rx,
+            ..send_control(watch::channel(false).1, mpsc::channel(1).1, &config)
+        };
+        let (_seen_tx, seen_rx) = watch::channel(Instant::now());
+        let state = Arc::new(RwLock::new(StateMachine::new(0, DEFAULT_RETRY_DELAY)));
+        let backend = scribe_backend(&config);
+        let task = tokio::spawn(NetworkManager::send_loop(
+            EventSink { events_tx },
+            audio_rx,
+            control,
+            seen_rx,
+            state,
+            backend,
+            config,
+        ));
+        (task, audio_tx, events_rx, commit_responses_tx)
+    }
 
     #[test]
     fn test_manager_creation() {
@@ -315,6 +1155,635 @@ mod tests {
         drop(audio_tx);
     }
 
+    #[test]
+    fn test_keepalive_frame_follows_config() {
+        let backend = ScribeBackend::new(ClientConfig::default());
+        let ping = NetworkManager::keepalive_frame(KeepAlive::Ping, &backend).unwrap();
+        assert!(matches!(ping, Message::Ping(_)));
+
+        let message = NetworkManager::keepalive_frame(KeepAlive::Message, &backend).unwrap();
+        match message {
+            Message::Text(text) => assert_eq!(text.as_str(), r#"{"message_type":"keep_alive"}"#),
+            other => panic!("Expected text frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_audio_frame_follows_config() {
+        let pcm_data = vec![1i16, -2, 300];
+
+        let binary = ScribeBackend::new(ClientConfig {
+            binary_audio: true,
+            ..Default::default()
+        });
+        match binary.encode_audio(&pcm_data).unwrap() {
+            Message::Binary(bytes) => assert_eq!(bytes.as_ref(), pcm_bytes(&pcm_data).as_slice()),
+            other => panic!("Expected binary frame, got {:?}", other),
+        }
+
+        let text = ScribeBackend::new(ClientConfig::default());
+        match text.encode_audio(&pcm_data).unwrap() {
+            Message::Text(text) => assert!(text.as_str().contains("input_audio_chunk")),
+            other => panic!("Expected text frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_batch_interval_clamped() {
+        let (_audio_tx, audio_rx) = mpsc::channel(100);
+        let (event_tx, _event_rx) = mpsc::channel(100);
+        let config = ClientConfig {
+            batch_interval: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let manager = NetworkManager::with_config(config, audio_rx, event_tx);
+        assert_eq!(manager.config.batch_interval, MIN_BATCH_INTERVAL);
+
+        assert_eq!(
+            clamp_batch_interval(Duration::from_secs(10)),
+            MAX_BATCH_INTERVAL
+        );
+        assert_eq!(
+            clamp_batch_interval(Duration::from_millis(300)),
+            Duration::from_millis(300)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_commit_after_silence_window() {
+        let (sink, mut sent_rx) = recording_sink();
+
+        let config = ClientConfig {
+            keepalive_interval: Duration::from_secs(60),
+            trim_leading_silence: false,
+            binary_audio: true,
+            batch_interval: Duration::from_millis(100),
+            silence_commit: Duration::from_millis(600),
+            ..Default::default()
+        };
+        let (audio_tx, audio_rx) = mpsc::channel(100);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let task = tokio::spawn(send_loop(sink, audio_rx, cancel_rx, config));
+
+        let started = Instant::now();
+        audio_tx
+            .send(AudioFrame::now(vec![100i16; 1600]))
+            .await
+            .unwrap();
+
+        // 音频按批量间隔以二进制帧发出，静音窗口内不提交
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let mut frames = Vec::new();
+        while let Ok(frame) = sent_rx.try_recv() {
+            frames.push(frame);
+        }
+        assert!(matches!(frames.as_slice(), [Message::Binary(_)]));
+
+        // 静音超过配置的窗口后发送 commit
+        let frame = tokio::time::timeout(Duration::from_secs(2), sent_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(600));
+        match frame {
+            Message::Text(text) => assert!(text.as_str().contains(r#""commit":true"#)),
+            other => panic!("Expected commit frame, got {:?}", other),
+        }
+
+        drop(audio_tx);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_buffered_audio_flushed_before_exit() {
+        let (sink, mut sent_rx) = recording_sink();
+
+        // 批量间隔很长，音频输入关闭时音频仍在缓冲区中
+        let config = ClientConfig {
+            keepalive_interval: Duration::from_secs(60),
+            trim_leading_silence: false,
+            binary_audio: true,
+            batch_interval: MAX_BATCH_INTERVAL,
+            ..Default::default()
+        };
+        let (audio_tx, audio_rx) = mpsc::channel(100);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let control = send_control(cancel_rx, mpsc::channel(1).1, &config);
+        let mut drain_rx = control.drain_tx.subscribe();
+        let (_seen_tx, seen_rx) = watch::channel(Instant::now());
+        let state = Arc::new(RwLock::new(StateMachine::new(0, DEFAULT_RETRY_DELAY)));
+        let backend = scribe_backend(&config);
+        let task = tokio::spawn(NetworkManager::send_loop(
+            sink, audio_rx, control, seen_rx, state, backend, config,
+        ));
+
+        audio_tx
+            .send(AudioFrame::now(vec![7i16; 800]))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(sent_rx.try_recv().is_err());
+        assert_eq!(*drain_rx.borrow_and_update(), DrainState::Sending);
+
+        // 停止录音：发送任务先发出缓冲的音频和最终 commit 再退出
+        drop(audio_tx);
+        tokio::time::timeout(Duration::from_secs(1), task)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let frames: Vec<Message> = std::iter::from_fn(|| sent_rx.try_recv().ok()).collect();
+        match frames.as_slice() {
+            [Message::Binary(audio), Message::Text(commit)] => {
+                assert_eq!(audio.as_ref(), pcm_bytes(&[7i16; 800]).as_slice());
+                assert!(commit.as_str().contains(r#""commit":true"#));
+            }
+            other => panic!("Expected audio then commit, got {:?}", other),
+        }
+        assert_eq!(*drain_rx.borrow(), DrainState::Flushed { committed: true });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_close_waits_for_final_commit_response() {
+        let config = ClientConfig {
+            keepalive_interval: Duration::from_secs(60),
+            trim_leading_silence: false,
+            batch_interval: MAX_BATCH_INTERVAL,
+            final_commit_timeout: Duration::from_secs(3),
+            ..Default::default()
+        };
+        let (task, audio_tx, mut events_rx, commit_responses_tx) = send_loop_until_close(config);
+
+        audio_tx
+            .send(AudioFrame::now(vec![7i16; 800]))
+            .await
+            .unwrap();
+        drop(audio_tx);
+
+        // 音频和最终 commit 发出后连接保持打开
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let events: Vec<_> = std::iter::from_fn(|| events_rx.try_recv().ok()).collect();
+        match events.as_slice() {
+            [Some(_audio), Some(Message::Text(commit))] => {
+                assert!(commit.as_str().contains(r#""commit":true"#));
+            }
+            other => panic!("Expected audio then commit, got {:?}", other),
+        }
+        assert!(!task.is_finished());
+
+        // 收到最终转写后才关闭
+        commit_responses_tx.send_replace(1);
+        assert!(matches!(task.await.unwrap(), SendExit::Finished));
+        assert_eq!(events_rx.try_recv(), Ok(None));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_close_after_final_commit_timeout() {
+        let config = ClientConfig {
+            keepalive_interval: Duration::from_secs(60),
+            trim_leading_silence: false,
+            batch_interval: MAX_BATCH_INTERVAL,
+            final_commit_timeout: Duration::from_secs(3),
+            ..Default::default()
+        };
+        let (task, audio_tx, mut events_rx, _commit_responses_tx) = send_loop_until_close(config);
+
+        audio_tx
+            .send(AudioFrame::now(vec![7i16; 800]))
+            .await
+            .unwrap();
+        let started = Instant::now();
+        drop(audio_tx);
+
+        // 服务器一直没有回应：超时后关闭
+        task.await.unwrap();
+        assert_eq!(started.elapsed(), Duration::from_secs(3));
+        let events: Vec<_> = std::iter::from_fn(|| events_rx.try_recv().ok()).collect();
+        assert!(matches!(events.as_slice(), [Some(_), Some(_), None]));
+    }
+
+    #[tokio::test]
+    async fn test_session_end_resumes_while_recording() {
+        let (sink, _sent_rx) = recording_sink();
+        let config = ClientConfig {
+            keepalive_interval: Duration::from_secs(60),
+            batch_interval: MAX_BATCH_INTERVAL,
+            ..Default::default()
+        };
+        let state = Arc::new(RwLock::new(StateMachine::new(0, DEFAULT_RETRY_DELAY)));
+        state.write().await.transition_to_connecting().unwrap();
+
+        // 服务器开始会话后因超时结束会话，但不关闭连接
+        let messages = [
+            r#"{"message_type":"session_started","session_id":"s1","config":{}}"#,
+            r#"{"message_type":"session_ended","reason":"timeout"}"#,
+        ];
+        let stream = futures_util::stream::iter(messages.map(|text| Ok(Message::text(text))))
+            .chain(futures_util::stream::pending());
+        let (seen_tx, seen_rx) = watch::channel(Instant::now());
+        let (commit_interval_tx, _) = watch::channel(config.silence_commit);
+        let (event_tx, mut event_rx) = mpsc::channel(10);
+        let (recv_ended_tx, recv_ended_rx) = watch::channel(false);
+        let control = RecvControl {
+            recv_ended_tx,
+            ..recv_control(seen_tx, commit_interval_tx, config.silence_commit)
+        };
+        tokio::spawn(NetworkManager::recv_loop(
+            stream,
+            state.clone(),
+            scribe_backend(&config),
+            event_tx,
+            control,
+        ));
+
+        let (audio_tx, audio_rx) = mpsc::channel(10);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let control = SendControl {
+            recv_ended_rx,
+            ..send_control(cancel_rx, mpsc::channel(1).1, &config)
+        };
+        // 会话结束前读出但尚未发送的音频
+        audio_tx
+            .send(AudioFrame::now(vec![8000i16; 160]))
+            .await
+            .unwrap();
+        let exit = tokio::time::timeout(
+            Duration::from_secs(1),
+            NetworkManager::send_loop(
+                sink,
+                audio_rx,
+                control,
+                seen_rx,
+                state.clone(),
+                scribe_backend(&config),
+                config,
+            ),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(
+            event_rx.recv().await,
+            Some(ServerMessage::SessionStarted { .. })
+        ));
+        assert!(matches!(
+            event_rx.recv().await,
+            Some(ServerMessage::SessionEnded { .. })
+        ));
+
+        // 录音仍在进行：重新建立会话，输入通道交回给新会话
+        let state_now = state.read().await.current_state().clone();
+        assert_eq!(after_send(&exit, &state_now), AfterSend::ResumeSession);
+        let SendExit::Interrupted {
+            mut audio_rx,
+            pending,
+            ..
+        } = exit
+        else {
+            panic!("Expected interrupted send task, got {:?}", exit);
+        };
+        assert_eq!(pending, vec![8000i16; 160]);
+        audio_tx
+            .send(AudioFrame::now(vec![1i16; 160]))
+            .await
+            .unwrap();
+        assert_eq!(audio_rx.recv().await.unwrap().samples.len(), 160);
+
+        // 用户停止后不重连；连接出错时按重试策略处理
+        assert_eq!(after_send(&SendExit::Finished, &state_now), AfterSend::Stop);
+        let mut errored = StateMachine::new(3, DEFAULT_RETRY_DELAY);
+        errored.transition_to_error("reset".to_string(), true);
+        let interrupted = SendExit::Interrupted {
+            audio_rx,
+            commit_rx: mpsc::channel(1).1,
+            pending: Vec::new(),
+        };
+        assert_eq!(
+            after_send(&interrupted, errored.current_state()),
+            AfterSend::Retry
+        );
+    }
+
+    #[test]
+    fn test_consecutive_resumes_capped() {
+        let mut budget = ResumeBudget::default();
+
+        // 会话刚建立就被结束时只重建有限次
+        for _ in 0..MAX_CONSECUTIVE_RESUMES {
+            assert!(budget.try_resume(Duration::from_secs(1)));
+        }
+        assert!(!budget.try_resume(Duration::from_secs(1)));
+
+        // 正常持续的会话结束后重新计数
+        assert!(budget.try_resume(STABLE_SESSION));
+        assert!(budget.try_resume(Duration::from_secs(1)));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_discards_audio_without_commit() {
+        let (sink, mut sent_rx) = recording_sink();
+
+        let config = ClientConfig {
+            keepalive_interval: Duration::from_secs(60),
+            trim_leading_silence: false,
+            batch_interval: MAX_BATCH_INTERVAL,
+            ..Default::default()
+        };
+        let (audio_tx, audio_rx) = mpsc::channel(100);
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let task = tokio::spawn(send_loop(sink, audio_rx, cancel_rx, config));
+
+        audio_tx
+            .send(AudioFrame::now(vec![100i16; 1600]))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // 先取消再关闭音频通道（与 AppController 的顺序一致）
+        cancel_tx.send(true).unwrap();
+        drop(audio_tx);
+
+        tokio::time::timeout(Duration::from_secs(1), task)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // 缓冲的音频和 commit 都没有发送
+        assert!(sent_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_force_commit_flushes_immediately() {
+        let (sink, mut sent_rx) = recording_sink();
+
+        let config = ClientConfig {
+            keepalive_interval: Duration::from_secs(60),
+            trim_leading_silence: false,
+            binary_audio: true,
+            batch_interval: MAX_BATCH_INTERVAL,
+            silence_commit: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let (audio_tx, audio_rx) = mpsc::channel(100);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let (commit_tx, commit_rx) = mpsc::channel(1);
+        let task = tokio::spawn(send_loop_with_commit(
+            sink, audio_rx, cancel_rx, commit_rx, config,
+        ));
+
+        audio_tx
+            .send(AudioFrame::now(vec![100i16; 1600]))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(sent_rx.try_recv().is_err());
+
+        // 不等批量间隔和静音窗口，立即发送缓冲的音频和 commit
+        commit_tx.send(()).await.unwrap();
+        let mut frames = Vec::new();
+        for _ in 0..2 {
+            let frame = tokio::time::timeout(Duration::from_secs(1), sent_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            frames.push(frame);
+        }
+        assert!(matches!(frames[0], Message::Binary(_)));
+        match &frames[1] {
+            Message::Text(text) => assert!(text.as_str().contains(r#""commit":true"#)),
+            other => panic!("Expected commit frame, got {:?}", other),
+        }
+
+        drop(audio_tx);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stale_frames_dropped() {
+        let (sink, mut sent_rx) = recording_sink();
+
+        let config = ClientConfig {
+            keepalive_interval: Duration::from_secs(60),
+            trim_leading_silence: false,
+            binary_audio: true,
+            batch_interval: MAX_BATCH_INTERVAL,
+            silence_commit: Duration::from_secs(60),
+            max_frame_age: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let (audio_tx, audio_rx) = mpsc::channel(100);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let task = tokio::spawn(send_loop(sink, audio_rx, cancel_rx, config));
+
+        // 网络阻塞期间积压的两秒前的音频被丢弃，新采集的音频照常发送
+        let captured_at = std::time::Instant::now() - Duration::from_secs(2);
+        audio_tx
+            .send(AudioFrame::new(vec![1i16; 1600], captured_at))
+            .await
+            .unwrap();
+        audio_tx
+            .send(AudioFrame::new(vec![2i16; 1600], captured_at))
+            .await
+            .unwrap();
+        audio_tx
+            .send(AudioFrame::now(vec![3i16; 800]))
+            .await
+            .unwrap();
+        drop(audio_tx);
+
+        tokio::time::timeout(Duration::from_secs(1), task)
+            .await
+            .unwrap()
+            .unwrap();
+
+        match sent_rx.try_recv() {
+            Ok(Message::Binary(bytes)) => {
+                assert_eq!(bytes.as_ref(), pcm_bytes(&[3i16; 800]).as_slice())
+            }
+            other => panic!("Expected fresh audio frame, got {:?}", other),
+        }
+        assert!(matches!(sent_rx.try_recv(), Ok(Message::Text(_))));
+        assert!(sent_rx.try_recv().is_err());
+    }
+
+    /// 运行中的收发循环
+    struct LivenessCheck {
+        state: Arc<RwLock<StateMachine>>,
+        sent_rx: mpsc::UnboundedReceiver<Message>,
+        audio_tx: mpsc::Sender<AudioFrame>,
+        send: tokio::task::JoinHandle<SendExit>,
+    }
+
+    /// 启动收发循环，接收端使用给定的模拟流
+    fn spawn_liveness_check<R>(stream: R, config: ClientConfig) -> LivenessCheck
+    where
+        R: Stream<Item = std::result::Result<Message, tungstenite::Error>> + Unpin + Send + 'static,
+    {
+        let (sink, sent_rx) = recording_sink();
+        let state = Arc::new(RwLock::new(StateMachine::new(3, DEFAULT_RETRY_DELAY)));
+        let (seen_tx, seen_rx) = watch::channel(Instant::now());
+        let (event_tx, _event_rx) = mpsc::channel(10);
+        let (audio_tx, audio_rx) = mpsc::channel(10);
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+
+        let (commit_interval_tx, _) = watch::channel(config.silence_commit);
+        let control = send_control(cancel_rx, mpsc::channel(1).1, &config);
+
+        tokio::spawn(NetworkManager::recv_loop(
+            stream,
+            state.clone(),
+            scribe_backend(&config),
+            event_tx,
+            recv_control(seen_tx, commit_interval_tx, config.silence_commit),
+        ));
+        let send = tokio::spawn(NetworkManager::send_loop(
+            sink,
+            audio_rx,
+            control,
+            seen_rx,
+            state.clone(),
+            scribe_backend(&config),
+            config,
+        ));
+
+        LivenessCheck {
+            state,
+            sent_rx,
+            audio_tx,
+            send,
+        }
+    }
+
+    fn liveness_config() -> ClientConfig {
+        ClientConfig {
+            keepalive_interval: Duration::from_millis(50),
+            pong_timeout: Duration::from_millis(200),
+            batch_interval: MAX_BATCH_INTERVAL,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_dead_without_pong() {
+        // 模拟一个永远不回应 pong 的服务器
+        let LivenessCheck {
+            state,
+            mut sent_rx,
+            audio_tx: _audio_tx,
+            send,
+        } = spawn_liveness_check(futures_util::stream::pending(), liveness_config());
+
+        let started = Instant::now();
+        tokio::time::timeout(Duration::from_secs(2), send)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(200));
+
+        // 超时前发送过 ping，超时后切换到可重试的错误状态
+        assert!(matches!(sent_rx.try_recv(), Ok(Message::Ping(_))));
+        let state = state.read().await;
+        assert_eq!(state.current_state().name(), "error");
+        assert!(state.can_retry());
+    }
+
+    #[tokio::test]
+    async fn test_pong_keeps_connection_alive() {
+        // 模拟服务器每 30ms 回应一次 pong
+        let pongs = futures_util::stream::unfold((), |_| async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            Some((Ok(Message::Pong(Default::default())), ()))
+        });
+        let LivenessCheck {
+            state,
+            audio_tx,
+            send,
+            ..
+        } = spawn_liveness_check(Box::pin(pongs), liveness_config());
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(!send.is_finished());
+        assert_ne!(state.read().await.current_state().name(), "error");
+
+        drop(audio_tx);
+        tokio::time::timeout(Duration::from_secs(1), send)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_pong_timeout_only_for_ping_keepalive() {
+        let (_seen_tx, seen_rx) = watch::channel(Instant::now());
+        let later = Instant::now() + Duration::from_secs(60);
+
+        let config = ClientConfig::default();
+        assert!(NetworkManager::pong_overdue(&config, &seen_rx, later).is_some());
+        assert!(NetworkManager::pong_overdue(&config, &seen_rx, Instant::now()).is_none());
+
+        let config = ClientConfig {
+            keepalive: KeepAlive::Message,
+            ..Default::default()
+        };
+        assert!(NetworkManager::pong_overdue(&config, &seen_rx, later).is_none());
+
+        let config = ClientConfig {
+            pong_timeout: Duration::ZERO,
+            ..Default::default()
+        };
+        assert!(NetworkManager::pong_overdue(&config, &seen_rx, later).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_commit_throttle_extends_commit_interval() {
+        let throttled = r#"{"message_type":"commit_throttled","error":"too short"}"#;
+        let committed = r#"{"message_type":"committed_transcript","text":"hello"}"#;
+        let messages: Vec<std::result::Result<Message, tungstenite::Error>> =
+            [throttled, throttled, throttled, committed]
+                .into_iter()
+                .map(|json| Ok(Message::text(json)))
+                .collect();
+
+        let base = Duration::from_millis(500);
+        let state = Arc::new(RwLock::new(StateMachine::new(0, DEFAULT_RETRY_DELAY)));
+        let (seen_tx, _seen_rx) = watch::channel(Instant::now());
+        let (event_tx, mut event_rx) = mpsc::channel(10);
+        let (commit_interval_tx, mut commit_interval_rx) = watch::channel(base);
+
+        // 事件通道逐条放行，每条消息处理后检查静音提交窗口
+        let (message_tx, message_rx) = mpsc::unbounded_channel();
+        let stream = Box::pin(futures_util::stream::unfold(message_rx, |mut rx| async {
+            rx.recv().await.map(|message| (message, rx))
+        }));
+        let task = tokio::spawn(NetworkManager::recv_loop(
+            stream,
+            state,
+            scribe_backend(&ClientConfig::default()),
+            event_tx,
+            recv_control(seen_tx, commit_interval_tx, base),
+        ));
+
+        let mut intervals = Vec::new();
+        for message in messages {
+            message_tx.send(message).unwrap();
+            event_rx.recv().await.unwrap();
+            intervals.push(*commit_interval_rx.borrow_and_update());
+        }
+
+        assert_eq!(
+            intervals,
+            vec![
+                Duration::from_millis(1000),
+                Duration::from_millis(2000),
+                Duration::from_millis(2000),
+                base,
+            ]
+        );
+
+        drop(message_tx);
+        task.await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_get_state() {
         let (_audio_tx, audio_rx) = mpsc::channel(100);
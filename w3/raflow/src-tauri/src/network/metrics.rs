@@ -0,0 +1,289 @@
+//! 网络指标模块
+//!
+//! 统计连接时长、音频发送到收到转写的往返延迟和收发消息数，用于诊断转写慢的问题
+
+use super::protocol::ServerMessage;
+use super::scheduler::SendAction;
+use super::state_machine::StateMachine;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, watch};
+use tokio::time::Instant;
+
+/// 平均延迟使用的最近样本数
+pub const LATENCY_WINDOW: usize = 20;
+
+/// 最多记录的未收到转写的音频批次（服务器长时间不返回时丢弃最旧的）
+const MAX_PENDING_BATCHES: usize = 256;
+
+/// 滚动平均
+///
+/// 只保留最近 `capacity` 个样本
+#[derive(Debug, Clone)]
+pub struct RollingAverage {
+    samples: VecDeque<Duration>,
+    capacity: usize,
+    sum: Duration,
+}
+
+impl RollingAverage {
+    /// 创建滚动平均
+    ///
+    /// # Arguments
+    /// * `capacity` - 窗口大小（至少为 1）
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            sum: Duration::ZERO,
+        }
+    }
+
+    /// 加入一个样本，窗口已满时移除最旧的样本
+    pub fn push(&mut self, sample: Duration) {
+        if self.samples.len() == self.capacity
+            && let Some(oldest) = self.samples.pop_front()
+        {
+            self.sum = self.sum.saturating_sub(oldest);
+        }
+        self.samples.push_back(sample);
+        self.sum = self.sum.saturating_add(sample);
+    }
+
+    /// 窗口内样本的平均值（没有样本时为 None）
+    pub fn average(&self) -> Option<Duration> {
+        let count = u32::try_from(self.samples.len()).ok().filter(|n| *n > 0)?;
+        Some(self.sum / count)
+    }
+
+    /// 窗口内的样本数
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// 窗口是否为空
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+/// 往返延迟跟踪
+///
+/// 服务器的转写不与发送的批次一一对应：收到转写时，以最早一批尚未得到转写的音频计算延迟，
+/// 该转写已反映到目前为止发送的全部音频，之后重新开始计时
+#[derive(Debug, Clone)]
+pub struct LatencyTracker {
+    pending: VecDeque<Instant>,
+    average: RollingAverage,
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            average: RollingAverage::new(LATENCY_WINDOW),
+        }
+    }
+}
+
+impl LatencyTracker {
+    /// 记录一批音频的发送时间
+    pub fn on_sent(&mut self, now: Instant) {
+        if self.pending.len() == MAX_PENDING_BATCHES {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(now);
+    }
+
+    /// 收到转写，返回本次测得的往返延迟（没有待确认的音频时为 None）
+    pub fn on_transcript(&mut self, now: Instant) -> Option<Duration> {
+        let sent_at = self.pending.front().copied()?;
+        self.pending.clear();
+
+        let latency = now.saturating_duration_since(sent_at);
+        self.average.push(latency);
+        Some(latency)
+    }
+
+    /// 丢弃待确认的批次（连接断开后旧连接上的音频不会再有转写）
+    pub fn reset_pending(&mut self) {
+        self.pending.clear();
+    }
+
+    /// 最近的平均往返延迟
+    pub fn average(&self) -> Option<Duration> {
+        self.average.average()
+    }
+}
+
+/// 网络会话指标
+///
+/// 发送任务和接收任务通过 watch 通道更新，读取端按需生成快照
+#[derive(Debug, Clone, Default)]
+pub struct NetworkMetrics {
+    latency: LatencyTracker,
+    messages_sent: u64,
+    messages_received: u64,
+}
+
+impl NetworkMetrics {
+    /// 记录发送成功的调度动作（音频批次或 commit）
+    pub fn record_sent(&mut self, action: &SendAction, now: Instant) {
+        self.messages_sent += 1;
+        if let SendAction::Audio(_) = action {
+            self.latency.on_sent(now);
+        }
+    }
+
+    /// 记录收到的服务器消息，转写消息用于计算往返延迟
+    pub fn record_received(&mut self, message: &ServerMessage, now: Instant) {
+        self.messages_received += 1;
+        if matches!(
+            message,
+            ServerMessage::PartialTranscript { .. } | ServerMessage::CommittedTranscript { .. }
+        ) {
+            self.latency.on_transcript(now);
+        }
+    }
+
+    /// 重新连接：丢弃旧连接上待确认的批次，计数保留
+    pub fn on_reconnect(&mut self) {
+        self.latency.reset_pending();
+    }
+
+    /// 生成快照
+    ///
+    /// # Arguments
+    /// * `connection_duration` - 当前连接时长（未连接时为 None）
+    pub fn snapshot(&self, connection_duration: Option<Duration>) -> MetricsSnapshot {
+        MetricsSnapshot {
+            connection_duration_ms: connection_duration.map_or(0, |d| d.as_millis() as u64),
+            avg_latency_ms: self.latency.average().map(|d| d.as_millis() as u64),
+            messages_sent: self.messages_sent,
+            messages_received: self.messages_received,
+        }
+    }
+}
+
+/// 发送到前端的指标快照
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct MetricsSnapshot {
+    /// 当前连接时长（毫秒），未连接时为 0
+    pub connection_duration_ms: u64,
+    /// 最近的平均往返延迟（毫秒），还没有测量结果时为 None
+    pub avg_latency_ms: Option<u64>,
+    /// 已发送的消息数（音频批次和 commit）
+    pub messages_sent: u64,
+    /// 已收到的服务器消息数
+    pub messages_received: u64,
+}
+
+/// 网络指标读取端
+///
+/// 由 `NetworkManager::metrics` 创建，网络管理器停止后 `is_closed` 返回 true
+#[derive(Clone)]
+pub struct MetricsReader {
+    metrics_rx: watch::Receiver<NetworkMetrics>,
+    state: Arc<RwLock<StateMachine>>,
+}
+
+impl MetricsReader {
+    pub(super) fn new(
+        metrics_rx: watch::Receiver<NetworkMetrics>,
+        state: Arc<RwLock<StateMachine>>,
+    ) -> Self {
+        Self { metrics_rx, state }
+    }
+
+    /// 当前指标快照（连接时长取自连接状态机）
+    pub async fn snapshot(&self) -> MetricsSnapshot {
+        let connection_duration = self.state.read().await.connection_duration();
+        self.metrics_rx.borrow().snapshot(connection_duration)
+    }
+
+    /// 网络管理器是否已停止
+    pub fn is_closed(&self) -> bool {
+        self.metrics_rx.has_changed().is_err()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_average() {
+        let mut average = RollingAverage::new(3);
+        assert_eq!(average.average(), None);
+
+        average.push(Duration::from_millis(100));
+        average.push(Duration::from_millis(200));
+        assert_eq!(average.average(), Some(Duration::from_millis(150)));
+
+        average.push(Duration::from_millis(300));
+        assert_eq!(average.average(), Some(Duration::from_millis(200)));
+
+        // 窗口已满，最旧的 100ms 被移出
+        average.push(Duration::from_millis(600));
+        assert_eq!(average.len(), 3);
+        assert_eq!(average.average().map(|d| d.as_millis()), Some(366));
+    }
+
+    #[test]
+    fn test_latency_measured_from_oldest_pending_batch() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut tracker = LatencyTracker::default();
+
+        // 没有发送音频时收到的转写不计入
+        assert_eq!(tracker.on_transcript(at(0)), None);
+
+        tracker.on_sent(at(0));
+        tracker.on_sent(at(100));
+        assert_eq!(
+            tracker.on_transcript(at(300)),
+            Some(Duration::from_millis(300))
+        );
+
+        // 上一条转写之后发送的批次重新计时
+        tracker.on_sent(at(400));
+        assert_eq!(
+            tracker.on_transcript(at(500)),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(tracker.on_transcript(at(600)), None);
+        assert_eq!(tracker.average(), Some(Duration::from_millis(200)));
+
+        // 重连后旧连接的批次不再计算延迟
+        tracker.on_sent(at(700));
+        tracker.reset_pending();
+        assert_eq!(tracker.on_transcript(at(5000)), None);
+    }
+
+    #[test]
+    fn test_metrics_snapshot() {
+        let now = Instant::now();
+        let mut metrics = NetworkMetrics::default();
+        metrics.record_sent(&SendAction::Audio(vec![0; 160]), now);
+        metrics.record_sent(&SendAction::Commit, now);
+        metrics.record_received(
+            &ServerMessage::SessionEnded {
+                reason: "done".to_string(),
+            },
+            now,
+        );
+
+        assert_eq!(
+            metrics.snapshot(Some(Duration::from_secs(2))),
+            MetricsSnapshot {
+                connection_duration_ms: 2000,
+                avg_latency_ms: None,
+                messages_sent: 2,
+                messages_received: 1,
+            }
+        );
+    }
+}
@@ -2,12 +2,34 @@
 //!
 //! 包含 WebSocket 客户端、协议定义、状态管理等功能
 
+mod backend;
 mod client;
 mod manager;
+mod metrics;
 mod protocol;
+mod proxy;
+mod scheduler;
+mod silence;
 mod state_machine;
+mod throttle;
 
-pub use client::{ClientConfig, ClientError, ScribeClient, WsSink, WsStream};
-pub use manager::{ManagerError, NetworkManager};
-pub use protocol::{ClientMessage, ServerMessage};
+pub use backend::{ScribeBackend, SttBackend, SttProvider, TranscriptEvent, backend_for};
+pub use client::{
+    API_KEY_CHECK_TIMEOUT, ClientConfig, ClientError, DEFAULT_BASE_URL, DEFAULT_ENCODING,
+    DEFAULT_MODEL_ID, KeepAlive, ScribeClient, WsSink, WsStream, check_first_message,
+    encoding_to_rate, is_known_language, language_code_for, model_for_language,
+    validate_endpoint,
+};
+pub use manager::{DrainState, ManagerError, NetworkManager};
+pub use metrics::{
+    LATENCY_WINDOW, LatencyTracker, MetricsReader, MetricsSnapshot, NetworkMetrics, RollingAverage,
+};
+pub use protocol::{ClientMessage, ServerMessage, pcm_bytes};
+pub use proxy::{
+    NO_PROXY_ENV_VARS, PROXY_ENV_VARS, ProxyConfig, ProxyError, ProxyScheme, no_proxy_matches,
+    resolve_proxy_url,
+};
+pub use scheduler::{SendAction, SendScheduler};
+pub use silence::{DEFAULT_PRE_ROLL_MS, LeadingSilenceTrimmer};
 pub use state_machine::{ConnectionState, StateError, StateMachine};
+pub use throttle::{CommitBackoff, MAX_COMMIT_BACKOFF_FACTOR};
@@ -18,6 +18,10 @@ pub enum ClientMessage {
         #[serde(skip_serializing_if = "Option::is_none")]
         commit: Option<bool>,
     },
+
+    /// 应用层保活消息（用于要求应用层保活而非 WebSocket ping 的服务商）
+    #[serde(rename = "keep_alive")]
+    KeepAlive,
 }
 
 impl ClientMessage {
@@ -37,14 +41,8 @@ impl ClientMessage {
     /// let message = ClientMessage::audio_chunk(&pcm_data);
     /// ```
     pub fn audio_chunk(pcm_data: &[i16]) -> Self {
-        // 将 i16 转换为字节（小端序）
-        let bytes: Vec<u8> = pcm_data
-            .iter()
-            .flat_map(|&sample| sample.to_le_bytes())
-            .collect();
-
         // Base64 编码
-        let audio_base_64 = general_purpose::STANDARD.encode(bytes);
+        let audio_base_64 = general_purpose::STANDARD.encode(pcm_bytes(pcm_data));
 
         Self::AudioChunk {
             audio_base_64,
@@ -68,6 +66,16 @@ impl ClientMessage {
     }
 }
 
+/// 将 i16 PCM 数据转换为字节（小端序）
+///
+/// 用于二进制音频帧，与 `audio_chunk` 中 Base64 编码前的字节一致
+pub fn pcm_bytes(pcm_data: &[i16]) -> Vec<u8> {
+    pcm_data
+        .iter()
+        .flat_map(|&sample| sample.to_le_bytes())
+        .collect()
+}
+
 /// 服务器发送的消息类型
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 #[serde(tag = "message_type")]
@@ -167,6 +175,17 @@ impl ServerMessage {
             _ => None,
         }
     }
+
+    /// 取出文本内容的所有权（如果是转写消息）
+    ///
+    /// 处理完消息后需要转移文本时使用，避免复制较长的转写结果
+    pub fn into_text(self) -> Option<String> {
+        match self {
+            ServerMessage::PartialTranscript { text, .. } => Some(text),
+            ServerMessage::CommittedTranscript { text, .. } => Some(text),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -179,14 +198,36 @@ mod tests {
         let message = ClientMessage::audio_chunk(&pcm_data);
 
         match message {
-            ClientMessage::AudioChunk { audio_base_64 } => {
+            ClientMessage::AudioChunk { audio_base_64, .. } => {
                 // 验证 Base64 编码正确
                 let decoded = general_purpose::STANDARD.decode(&audio_base_64).unwrap();
                 assert_eq!(decoded.len(), pcm_data.len() * 2); // 每个 i16 占 2 字节
             }
+            other => panic!("Expected AudioChunk, got {:?}", other),
         }
     }
 
+    #[test]
+    fn test_pcm_bytes_match_base64_payload() {
+        let pcm_data = vec![0i16, 1, -1, 256, i16::MAX, i16::MIN];
+
+        let ClientMessage::AudioChunk { audio_base_64, .. } = ClientMessage::audio_chunk(&pcm_data)
+        else {
+            panic!("Expected AudioChunk");
+        };
+        let decoded = general_purpose::STANDARD.decode(&audio_base_64).unwrap();
+
+        let bytes = pcm_bytes(&pcm_data);
+        assert_eq!(bytes, decoded);
+        assert_eq!(&bytes[..6], &[0x00, 0x00, 0x01, 0x00, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn test_keep_alive_serialization() {
+        let json = ClientMessage::KeepAlive.to_json().unwrap();
+        assert_eq!(json, r#"{"message_type":"keep_alive"}"#);
+    }
+
     #[test]
     fn test_client_message_serialization() {
         let pcm_data = vec![100i16, -100, 200];
@@ -259,6 +300,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_into_text() {
+        let partial = ServerMessage::PartialTranscript {
+            text: "hello".to_string(),
+            created_at_ms: None,
+        };
+        assert_eq!(partial.into_text(), Some("hello".to_string()));
+
+        let committed = ServerMessage::CommittedTranscript {
+            text: "final text".to_string(),
+            confidence: Some(0.9),
+        };
+        assert_eq!(committed.into_text(), Some("final text".to_string()));
+
+        let ended = ServerMessage::SessionEnded {
+            reason: "done".to_string(),
+        };
+        assert_eq!(ended.into_text(), None);
+
+        let auth = ServerMessage::AuthError {
+            error: "bad key".to_string(),
+        };
+        assert_eq!(auth.into_text(), None);
+    }
+
     #[test]
     fn test_input_error_deserialization() {
         let json = r#"{
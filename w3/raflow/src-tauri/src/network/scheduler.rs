@@ -0,0 +1,380 @@
+//! 发送调度模块
+//!
+//! 决定何时发送累积的音频、何时提交当前语句。调度器不持有连接也不计时，
+//! 由发送任务传入当前时间驱动，因此批量和提交行为可以脱离 WebSocket 单独测试
+
+use super::client::ClientConfig;
+use super::silence::LeadingSilenceTrimmer;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::info;
+
+/// 发送动作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendAction {
+    /// 发送一批音频（采样率由编码格式决定）
+    Audio(Vec<i16>),
+    /// 提交当前语句
+    Commit,
+}
+
+/// 发送调度器
+///
+/// 音频先累积到缓冲区，每个批量间隔发送一次；缓冲区为空且静音超过
+/// 提交窗口时发送一次 commit，收到新音频后才会再次提交
+#[derive(Debug)]
+pub struct SendScheduler {
+    batch_interval: Duration,
+    silence_commit: Duration,
+    trimmer: LeadingSilenceTrimmer,
+    buffer: Vec<i16>,
+    last_send: Instant,
+    last_audio: Instant,
+    committed: bool,
+}
+
+impl SendScheduler {
+    /// 创建发送调度器
+    ///
+    /// # Arguments
+    /// * `config` - 客户端配置（批量间隔、静音提交窗口、开头静音裁剪）
+    /// * `now` - 当前时间，作为第一个批量间隔和静音计时的起点
+    pub fn new(config: &ClientConfig, now: Instant) -> Self {
+        Self {
+            batch_interval: config.batch_interval,
+            silence_commit: config.silence_commit,
+            trimmer: LeadingSilenceTrimmer::with_sample_rate(
+                config.trim_leading_silence,
+                config.pre_roll_ms,
+                config.sample_rate(),
+            ),
+            buffer: Vec::new(),
+            last_send: now,
+            last_audio: now,
+            committed: false,
+        }
+    }
+
+    /// 调整静音提交窗口（提交被限流时临时延长）
+    pub fn set_silence_commit(&mut self, silence_commit: Duration) {
+        self.silence_commit = silence_commit;
+    }
+
+    /// 收到一个音频块
+    ///
+    /// 会话开头的静音在检测到语音前被丢弃，不计入缓冲区
+    pub fn on_audio(&mut self, chunk: &[i16], now: Instant) {
+        let samples = self.trimmer.process(chunk);
+        if samples.is_empty() {
+            return;
+        }
+
+        self.buffer.extend_from_slice(&samples);
+        self.last_audio = now;
+        self.committed = false; // 收到新音频，重置 commit 状态
+    }
+
+    /// 下一次批量发送的时间
+    pub fn next_deadline(&self) -> Instant {
+        self.last_send + self.batch_interval
+    }
+
+    /// 定时检查：到达批量间隔时发送累积的音频，没有音频时检查静音提交
+    pub fn on_tick(&mut self, now: Instant) -> Vec<SendAction> {
+        if now < self.next_deadline() {
+            return Vec::new();
+        }
+
+        self.last_send = now;
+
+        if !self.buffer.is_empty() {
+            return vec![SendAction::Audio(std::mem::take(&mut self.buffer))];
+        }
+
+        self.on_silence(now).into_iter().collect()
+    }
+
+    /// 静音检查：缓冲区为空、尚未提交且静音超过提交窗口时返回 commit
+    pub fn on_silence(&mut self, now: Instant) -> Option<SendAction> {
+        if !self.buffer.is_empty() || self.committed {
+            return None;
+        }
+
+        let silence = now.saturating_duration_since(self.last_audio);
+        if silence < self.silence_commit {
+            return None;
+        }
+
+        info!(
+            "Silence detected for {}ms, sending commit signal",
+            silence.as_millis()
+        );
+        self.committed = true;
+        Some(SendAction::Commit)
+    }
+
+    /// 结束会话（音频输入关闭）：发送剩余音频并强制提交
+    ///
+    /// 从未检测到语音，或上次提交后没有新音频时不再提交
+    pub fn finish(&mut self) -> Vec<SendAction> {
+        self.force_commit()
+    }
+
+    /// 立即提交当前语句（不等待静音）：发送剩余音频并提交
+    ///
+    /// 与 `finish` 相同，从未检测到语音或上次提交后没有新音频时不提交；
+    /// 之后收到新音频会重新开始计时
+    pub fn force_commit(&mut self) -> Vec<SendAction> {
+        let mut actions = Vec::new();
+
+        if !self.buffer.is_empty() {
+            actions.push(SendAction::Audio(std::mem::take(&mut self.buffer)));
+            actions.push(SendAction::Commit);
+        } else if self.is_commit_pending() {
+            actions.push(SendAction::Commit);
+        }
+
+        self.committed = true;
+        actions
+    }
+
+    /// 缓冲区中等待发送的采样数
+    pub fn buffered_samples(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// 是否有已发送但尚未提交的音频
+    pub fn is_commit_pending(&self) -> bool {
+        self.trimmer.has_started() && !self.committed
+    }
+
+    /// 丢弃缓冲区中尚未发送的音频
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// 取出缓冲区中尚未发送的音频（会话中断时交给下一个会话）
+    pub fn take_buffered(&mut self) -> Vec<i16> {
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// 放回上一个会话尚未发送的音频
+    ///
+    /// 这些音频排在缓冲区最前面，在下一个批量间隔发送；
+    /// 语音已经开始，之后的音频不再做开头静音裁剪
+    pub fn restore(&mut self, mut samples: Vec<i16>, now: Instant) {
+        if samples.is_empty() {
+            return;
+        }
+
+        self.trimmer.mark_started();
+        samples.append(&mut self.buffer);
+        self.buffer = samples;
+        self.last_audio = now;
+        self.committed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BATCH: Duration = Duration::from_millis(500);
+    const SILENCE: Duration = Duration::from_millis(2000);
+
+    fn new_scheduler(trim_leading_silence: bool, start: Instant) -> SendScheduler {
+        let config = ClientConfig {
+            trim_leading_silence,
+            batch_interval: BATCH,
+            silence_commit: SILENCE,
+            ..Default::default()
+        };
+        SendScheduler::new(&config, start)
+    }
+
+    fn ms(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    #[test]
+    fn test_batches_audio_at_interval() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(false, start);
+
+        scheduler.on_audio(&[1; 160], start + ms(100));
+        scheduler.on_audio(&[2; 160], start + ms(200));
+        assert_eq!(scheduler.buffered_samples(), 320);
+
+        // 批量间隔未到，不发送
+        assert!(scheduler.on_tick(start + ms(400)).is_empty());
+
+        let actions = scheduler.on_tick(start + BATCH);
+        let mut expected = vec![1; 160];
+        expected.extend([2; 160]);
+        assert_eq!(actions, vec![SendAction::Audio(expected)]);
+        assert_eq!(scheduler.buffered_samples(), 0);
+        assert_eq!(scheduler.next_deadline(), start + BATCH + BATCH);
+
+        // 空缓冲区且未到静音窗口，不发送任何内容
+        assert!(scheduler.on_tick(start + BATCH + BATCH).is_empty());
+        assert!(scheduler.is_commit_pending());
+    }
+
+    #[test]
+    fn test_commits_once_after_silence_window() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(false, start);
+
+        scheduler.on_audio(&[1; 160], start);
+        assert_eq!(scheduler.on_tick(start + BATCH).len(), 1);
+
+        // 静音未超过窗口
+        assert_eq!(scheduler.on_silence(start + SILENCE - ms(1)), None);
+        assert!(scheduler.on_tick(start + ms(1500)).is_empty());
+
+        assert_eq!(scheduler.on_tick(start + SILENCE), vec![SendAction::Commit]);
+        assert!(!scheduler.is_commit_pending());
+
+        // 持续静音不重复提交
+        assert!(scheduler.on_tick(start + ms(5000)).is_empty());
+        assert_eq!(scheduler.on_silence(start + ms(9000)), None);
+    }
+
+    #[test]
+    fn test_new_audio_rearms_commit() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(false, start);
+
+        scheduler.on_audio(&[1; 160], start);
+        scheduler.on_tick(start + BATCH);
+        assert_eq!(
+            scheduler.on_silence(start + SILENCE),
+            Some(SendAction::Commit)
+        );
+
+        // 新音频到达后，静音窗口从这段音频开始重新计时
+        let resumed = start + ms(3000);
+        scheduler.on_audio(&[3; 160], resumed);
+        assert!(scheduler.is_commit_pending());
+        assert_eq!(
+            scheduler.on_tick(resumed + BATCH),
+            vec![SendAction::Audio(vec![3; 160])]
+        );
+        assert_eq!(scheduler.on_silence(resumed + ms(1000)), None);
+        assert_eq!(
+            scheduler.on_silence(resumed + SILENCE),
+            Some(SendAction::Commit)
+        );
+    }
+
+    #[test]
+    fn test_no_silence_commit_while_audio_buffered() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(false, start);
+
+        scheduler.on_audio(&[1; 160], start);
+        assert_eq!(scheduler.on_silence(start + ms(5000)), None);
+    }
+
+    #[test]
+    fn test_finish_flushes_and_forces_commit() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(false, start);
+
+        scheduler.on_audio(&[1; 160], start);
+        assert_eq!(
+            scheduler.finish(),
+            vec![SendAction::Audio(vec![1; 160]), SendAction::Commit]
+        );
+        assert!(!scheduler.is_commit_pending());
+        assert!(scheduler.finish().is_empty());
+    }
+
+    #[test]
+    fn test_finish_commits_sent_audio() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(false, start);
+
+        // 音频已发送但尚未提交
+        scheduler.on_audio(&[1; 160], start);
+        scheduler.on_tick(start + BATCH);
+        assert_eq!(scheduler.finish(), vec![SendAction::Commit]);
+
+        // 静音提交之后结束，无需再次提交
+        let mut scheduler = new_scheduler(false, start);
+        scheduler.on_audio(&[1; 160], start);
+        scheduler.on_tick(start + BATCH);
+        assert_eq!(
+            scheduler.on_silence(start + SILENCE),
+            Some(SendAction::Commit)
+        );
+        assert!(scheduler.finish().is_empty());
+    }
+
+    #[test]
+    fn test_finish_without_speech_skips_commit() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(true, start);
+
+        // 开头静音被裁剪，从未检测到语音
+        scheduler.on_audio(&[0; 1600], start + ms(100));
+        assert_eq!(scheduler.buffered_samples(), 0);
+        assert!(!scheduler.is_commit_pending());
+        assert!(scheduler.finish().is_empty());
+    }
+
+    #[test]
+    fn test_force_commit_mid_session() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(false, start);
+
+        scheduler.on_audio(&[1; 160], start);
+        assert_eq!(
+            scheduler.force_commit(),
+            vec![SendAction::Audio(vec![1; 160]), SendAction::Commit]
+        );
+        assert!(scheduler.force_commit().is_empty());
+        assert_eq!(scheduler.on_silence(start + SILENCE), None);
+
+        // 新音频重新开始一个语句
+        scheduler.on_audio(&[2; 160], start + ms(100));
+        assert!(scheduler.is_commit_pending());
+        assert_eq!(
+            scheduler.force_commit(),
+            vec![SendAction::Audio(vec![2; 160]), SendAction::Commit]
+        );
+    }
+
+    #[test]
+    fn test_clear_discards_buffered_audio() {
+        let start = Instant::now();
+        let mut scheduler = new_scheduler(false, start);
+
+        scheduler.on_audio(&[1; 160], start);
+        scheduler.clear();
+        assert_eq!(scheduler.buffered_samples(), 0);
+        assert!(scheduler.on_tick(start + BATCH).is_empty());
+    }
+
+    #[test]
+    fn test_buffered_audio_carried_to_next_session() {
+        let start = Instant::now();
+        let mut interrupted = new_scheduler(false, start);
+        interrupted.on_audio(&[1; 160], start);
+        let pending = interrupted.take_buffered();
+        assert_eq!(interrupted.buffered_samples(), 0);
+
+        // 放回的音频排在新音频之前；语音已经开始，之后的静音不再裁剪
+        let mut resumed = new_scheduler(true, start);
+        resumed.restore(pending, start);
+        resumed.on_audio(&[0; 160], start);
+
+        let mut expected = vec![1; 160];
+        expected.extend([0; 160]);
+        assert_eq!(
+            resumed.on_tick(start + BATCH),
+            vec![SendAction::Audio(expected)]
+        );
+        assert_eq!(resumed.force_commit(), vec![SendAction::Commit]);
+    }
+}
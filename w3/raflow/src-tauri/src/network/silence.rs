@@ -0,0 +1,180 @@
+//! 会话开头静音裁剪模块
+//!
+//! 从按下热键到开始说话之间通常是一段静音，发送它们既浪费带宽，
+//! 又可能让服务器产生多余的开头结果。在检测到语音之前丢弃静音，
+//! 但保留语音起点前的一小段预录音频，避免截掉开头的辅音
+
+use std::collections::VecDeque;
+
+/// 默认采样率（Hz）
+const SAMPLE_RATE: u32 = 16000;
+
+/// 语音检测帧时长（毫秒）
+const FRAME_MS: usize = 10;
+
+/// 语音起点 RMS 阈值（归一化到 -1.0 ~ 1.0）
+const ONSET_RMS_THRESHOLD: f32 = 0.01;
+
+/// 默认保留的预录时长（毫秒）
+pub const DEFAULT_PRE_ROLL_MS: u64 = 200;
+
+/// 开头静音裁剪器
+#[derive(Debug)]
+pub struct LeadingSilenceTrimmer {
+    enabled: bool,
+    voiced: bool,
+    pre_roll: VecDeque<i16>,
+    pre_roll_samples: usize,
+    frame_samples: usize,
+}
+
+impl LeadingSilenceTrimmer {
+    /// 创建裁剪器
+    ///
+    /// # Arguments
+    /// * `enabled` - 是否启用，禁用时所有音频原样通过
+    /// * `pre_roll_ms` - 语音起点前保留的音频时长（毫秒）
+    pub fn new(enabled: bool, pre_roll_ms: u64) -> Self {
+        Self::with_sample_rate(enabled, pre_roll_ms, SAMPLE_RATE)
+    }
+
+    /// 创建指定采样率的裁剪器
+    ///
+    /// # Arguments
+    /// * `enabled` - 是否启用，禁用时所有音频原样通过
+    /// * `pre_roll_ms` - 语音起点前保留的音频时长（毫秒）
+    /// * `sample_rate` - 音频采样率（Hz）
+    pub fn with_sample_rate(enabled: bool, pre_roll_ms: u64, sample_rate: u32) -> Self {
+        let sample_rate = sample_rate.max(100) as usize;
+        let pre_roll_samples = sample_rate * pre_roll_ms as usize / 1000;
+
+        Self {
+            enabled,
+            voiced: false,
+            pre_roll: VecDeque::with_capacity(pre_roll_samples),
+            pre_roll_samples,
+            frame_samples: sample_rate * FRAME_MS / 1000,
+        }
+    }
+
+    /// 处理一个音频块，返回应发送的采样
+    ///
+    /// 检测到语音之前返回空；检测到语音时返回预录音频加上从语音帧开始的剩余部分；
+    /// 之后的音频原样返回
+    pub fn process(&mut self, chunk: &[i16]) -> Vec<i16> {
+        if !self.enabled || self.voiced {
+            return chunk.to_vec();
+        }
+
+        for (index, frame) in chunk.chunks(self.frame_samples).enumerate() {
+            if frame_rms(frame) >= ONSET_RMS_THRESHOLD {
+                self.voiced = true;
+
+                let mut output: Vec<i16> = self.pre_roll.drain(..).collect();
+                output.extend_from_slice(&chunk[index * self.frame_samples..]);
+                return output;
+            }
+
+            self.pre_roll.extend(frame);
+            let excess = self.pre_roll.len().saturating_sub(self.pre_roll_samples);
+            self.pre_roll.drain(..excess);
+        }
+
+        Vec::new()
+    }
+
+    /// 语音已经开始（如中断后恢复的会话），之后的音频原样通过
+    pub fn mark_started(&mut self) {
+        self.voiced = true;
+        self.pre_roll.clear();
+    }
+
+    /// 是否已开始发送音频（已检测到语音，或未启用裁剪）
+    pub fn has_started(&self) -> bool {
+        !self.enabled || self.voiced
+    }
+}
+
+/// 计算一帧 i16 音频的归一化 RMS
+fn frame_rms(frame: &[i16]) -> f32 {
+    if frame.is_empty() {
+        return 0.0;
+    }
+
+    let sum_squares: f32 = frame
+        .iter()
+        .map(|&s| {
+            let s = s as f32 / i16::MAX as f32;
+            s * s
+        })
+        .sum();
+
+    (sum_squares / frame.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16kHz 下一个检测帧的采样数
+    const FRAME_SAMPLES: usize = 160;
+
+    fn voiced(len: usize) -> Vec<i16> {
+        (0..len)
+            .map(|i| if i % 2 == 0 { 8000 } else { -8000 })
+            .collect()
+    }
+
+    #[test]
+    fn test_leading_silence_dropped_before_voice() {
+        let mut trimmer = LeadingSilenceTrimmer::new(true, 0);
+
+        // 500ms 静音全部丢弃
+        assert!(trimmer.process(&vec![0i16; 8000]).is_empty());
+        assert!(!trimmer.has_started());
+
+        // 静音 + 语音：从语音帧开始发送
+        let mut chunk = vec![0i16; FRAME_SAMPLES * 3];
+        chunk.extend(voiced(FRAME_SAMPLES * 2));
+        let output = trimmer.process(&chunk);
+
+        assert!(trimmer.has_started());
+        assert_eq!(output, voiced(FRAME_SAMPLES * 2));
+
+        // 语音开始后，静音也原样发送
+        assert_eq!(trimmer.process(&[0i16; 100]), vec![0i16; 100]);
+    }
+
+    #[test]
+    fn test_pre_roll_is_kept() {
+        // 20ms 预录
+        let mut trimmer = LeadingSilenceTrimmer::new(true, 20);
+
+        let quiet: Vec<i16> = (0..FRAME_SAMPLES * 5).map(|i| (i % 7) as i16).collect();
+        assert!(trimmer.process(&quiet).is_empty());
+
+        let output = trimmer.process(&voiced(FRAME_SAMPLES));
+
+        // 预录为静音的最后 20ms
+        assert_eq!(output.len(), FRAME_SAMPLES * 2 + FRAME_SAMPLES);
+        assert_eq!(&output[..FRAME_SAMPLES * 2], &quiet[FRAME_SAMPLES * 3..]);
+        assert_eq!(&output[FRAME_SAMPLES * 2..], &voiced(FRAME_SAMPLES)[..]);
+    }
+
+    #[test]
+    fn test_pre_roll_follows_sample_rate() {
+        // 8kHz 下 20ms 预录为 160 个采样
+        let mut trimmer = LeadingSilenceTrimmer::with_sample_rate(true, 20, 8000);
+
+        assert!(trimmer.process(&[0i16; 800]).is_empty());
+        let output = trimmer.process(&voiced(80));
+        assert_eq!(output.len(), 160 + 80);
+    }
+
+    #[test]
+    fn test_disabled_passes_through() {
+        let mut trimmer = LeadingSilenceTrimmer::new(false, DEFAULT_PRE_ROLL_MS);
+        assert!(trimmer.has_started());
+        assert_eq!(trimmer.process(&[0i16; 320]), vec![0i16; 320]);
+    }
+}
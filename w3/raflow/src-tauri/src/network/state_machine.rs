@@ -13,8 +13,23 @@ pub enum StateError {
 
     #[error("Max retries reached: {0}")]
     MaxRetriesReached(u32),
+
+    #[error("Error is not retryable: {0}")]
+    NotRetryable(String),
 }
 
+/// 默认最大重试次数
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// 默认重试基础延迟
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// 重试延迟上限
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// 重试延迟抖动比例（±20%）
+const RETRY_JITTER: f64 = 0.2;
+
 /// 连接状态枚举
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionState {
@@ -43,6 +58,8 @@ pub enum ConnectionState {
         retry_at: Instant,
         /// 已重试次数
         attempt: u32,
+        /// 是否可重试（认证失败等致命错误不可重试）
+        retryable: bool,
     },
 
     /// 正在断开
@@ -66,10 +83,15 @@ impl ConnectionState {
         matches!(self, ConnectionState::Connected { .. })
     }
 
-    /// 是否可以重试
-    pub fn can_retry(&self) -> bool {
+    /// 在给定的最大重试次数下是否可以重试
+    ///
+    /// # Arguments
+    /// * `max_retries` - 最大重试次数
+    pub fn can_retry(&self, max_retries: u32) -> bool {
         match self {
-            ConnectionState::Error { attempt, .. } => *attempt < 3,
+            ConnectionState::Error {
+                attempt, retryable, ..
+            } => *retryable && *attempt < max_retries,
             _ => false,
         }
     }
@@ -87,7 +109,7 @@ impl StateMachine {
     ///
     /// # Arguments
     /// * `max_retries` - 最大重试次数（默认 3）
-    /// * `retry_delay` - 重试延迟（默认 2 秒）
+    /// * `retry_delay` - 首次重试的基础延迟（默认 2 秒），之后每次翻倍，上限 30 秒
     pub fn new(max_retries: u32, retry_delay: Duration) -> Self {
         Self {
             state: ConnectionState::Idle,
@@ -109,6 +131,11 @@ impl StateMachine {
                 self.state = ConnectionState::Connecting { attempt: 1 };
                 Ok(())
             }
+            ConnectionState::Error {
+                retryable: false,
+                message,
+                ..
+            } => Err(StateError::NotRetryable(message.clone())),
             ConnectionState::Error { attempt, .. } if *attempt < self.max_retries => {
                 let new_attempt = attempt + 1;
                 info!("State: Error -> Connecting (attempt {})", new_attempt);
@@ -144,7 +171,11 @@ impl StateMachine {
     }
 
     /// 转换到错误状态
-    pub fn transition_to_error(&mut self, message: String) {
+    ///
+    /// # Arguments
+    /// * `message` - 错误消息
+    /// * `retryable` - 是否可重试，为 false 时不再重连（如 API Key 无效）
+    pub fn transition_to_error(&mut self, message: String, retryable: bool) {
         let attempt = match &self.state {
             ConnectionState::Connecting { attempt } => *attempt,
             ConnectionState::Error { attempt, .. } => *attempt,
@@ -152,16 +183,21 @@ impl StateMachine {
         };
 
         warn!(
-            "State: {} -> Error (attempt {}, message: {})",
+            "State: {} -> Error (attempt {}, retryable: {}, message: {})",
             self.state.name(),
             attempt,
+            retryable,
             message
         );
 
+        let jitter = fastrand::f64() * 2.0 - 1.0;
+        let delay = backoff_delay(self.retry_delay, attempt, jitter);
+
         self.state = ConnectionState::Error {
             message,
-            retry_at: Instant::now() + self.retry_delay,
+            retry_at: Instant::now() + delay,
             attempt,
+            retryable,
         };
     }
 
@@ -177,16 +213,36 @@ impl StateMachine {
         self.state = ConnectionState::Disconnecting;
     }
 
+    /// 检查是否还有剩余重试次数（不考虑重试延迟）
+    pub fn can_retry(&self) -> bool {
+        self.state.can_retry(self.max_retries)
+    }
+
     /// 检查是否应该重试
     pub fn should_retry(&self) -> bool {
         match &self.state {
             ConnectionState::Error {
-                retry_at, attempt, ..
-            } => *attempt < self.max_retries && Instant::now() >= *retry_at,
+                retry_at,
+                attempt,
+                retryable,
+                ..
+            } => *retryable && *attempt < self.max_retries && Instant::now() >= *retry_at,
             _ => false,
         }
     }
 
+    /// 距离下一次重试的剩余等待时间
+    ///
+    /// 延迟在进入错误状态时按指数退避计算；不在错误状态时返回 0
+    pub fn next_retry_delay(&self) -> Duration {
+        match &self.state {
+            ConnectionState::Error { retry_at, .. } => {
+                retry_at.saturating_duration_since(Instant::now())
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
     /// 获取连接时长（如果已连接）
     pub fn connection_duration(&self) -> Option<Duration> {
         match &self.state {
@@ -204,10 +260,21 @@ impl StateMachine {
 
 impl Default for StateMachine {
     fn default() -> Self {
-        Self::new(3, Duration::from_secs(2))
+        Self::new(DEFAULT_MAX_RETRIES, DEFAULT_RETRY_DELAY)
     }
 }
 
+/// 计算第 `attempt` 次失败后的重试延迟
+///
+/// `base * 2^(attempt - 1)`，叠加 `jitter`（-1.0 ~ 1.0，对应 ±20%）后不超过 30 秒
+fn backoff_delay(base: Duration, attempt: u32, jitter: f64) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    let delay = base.saturating_mul(1 << exponent).min(MAX_RETRY_DELAY);
+    let factor = 1.0 + RETRY_JITTER * jitter.clamp(-1.0, 1.0);
+
+    delay.mul_f64(factor).min(MAX_RETRY_DELAY)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,7 +307,7 @@ mod tests {
     fn test_transition_to_error() {
         let mut sm = StateMachine::default();
         sm.transition_to_connecting().unwrap();
-        sm.transition_to_error("Connection failed".to_string());
+        sm.transition_to_error("Connection failed".to_string(), true);
         assert_eq!(sm.current_state().name(), "error");
     }
 
@@ -248,10 +315,10 @@ mod tests {
     fn test_retry_logic() {
         let mut sm = StateMachine::new(3, Duration::from_millis(10));
         sm.transition_to_connecting().unwrap();
-        sm.transition_to_error("Test error".to_string());
+        sm.transition_to_error("Test error".to_string(), true);
 
         // 应该可以重试
-        assert!(sm.current_state().can_retry());
+        assert!(sm.current_state().can_retry(3));
 
         // 等待重试延迟
         std::thread::sleep(Duration::from_millis(20));
@@ -267,19 +334,105 @@ mod tests {
 
         // 第一次连接失败
         sm.transition_to_connecting().unwrap();
-        sm.transition_to_error("Error 1".to_string());
+        sm.transition_to_error("Error 1".to_string(), true);
 
         // 第二次连接失败
         std::thread::sleep(Duration::from_millis(2));
         sm.transition_to_connecting().unwrap();
-        sm.transition_to_error("Error 2".to_string());
+        sm.transition_to_error("Error 2".to_string(), true);
 
         // 第三次应该失败（超过 max_retries）
-        std::thread::sleep(Duration::from_millis(2));
+        assert!(!sm.can_retry());
+        std::thread::sleep(Duration::from_millis(3));
         let result = sm.transition_to_connecting();
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_retry_limit_follows_config() {
+        let mut sm = StateMachine::new(5, Duration::ZERO);
+        for _ in 0..4 {
+            sm.transition_to_connecting().unwrap();
+            sm.transition_to_error("Connection reset".to_string(), true);
+        }
+
+        // 已失败 4 次，超过旧的固定上限 3 次但仍在配置的 5 次以内
+        assert!(sm.can_retry());
+        assert!(sm.current_state().can_retry(5));
+        assert!(!sm.current_state().can_retry(4));
+    }
+
+    #[test]
+    fn test_non_retryable_error() {
+        let mut sm = StateMachine::new(3, Duration::from_millis(1));
+        sm.transition_to_connecting().unwrap();
+        sm.transition_to_error("401 Unauthorized".to_string(), false);
+
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!sm.current_state().can_retry(3));
+        assert!(!sm.can_retry());
+        assert!(!sm.should_retry());
+        assert!(matches!(
+            sm.transition_to_connecting(),
+            Err(StateError::NotRetryable(_))
+        ));
+    }
+
+    #[test]
+    fn test_retryable_error() {
+        let mut sm = StateMachine::new(3, Duration::from_millis(1));
+        sm.transition_to_connecting().unwrap();
+        sm.transition_to_error("Connection reset".to_string(), true);
+
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(sm.can_retry());
+        assert!(sm.should_retry());
+        assert!(sm.transition_to_connecting().is_ok());
+    }
+
+    #[test]
+    fn test_backoff_grows_per_attempt() {
+        let base = Duration::from_secs(2);
+
+        assert_eq!(backoff_delay(base, 1, 0.0), Duration::from_secs(2));
+        assert_eq!(backoff_delay(base, 2, 0.0), Duration::from_secs(4));
+        assert_eq!(backoff_delay(base, 3, 0.0), Duration::from_secs(8));
+        assert_eq!(backoff_delay(base, 4, 0.0), Duration::from_secs(16));
+    }
+
+    #[test]
+    fn test_backoff_respects_cap() {
+        let base = Duration::from_secs(2);
+
+        assert_eq!(backoff_delay(base, 5, 0.0), MAX_RETRY_DELAY);
+        assert_eq!(backoff_delay(base, 5, 1.0), MAX_RETRY_DELAY);
+        assert_eq!(backoff_delay(base, u32::MAX, 1.0), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_backoff_jitter_range() {
+        let base = Duration::from_secs(2);
+
+        assert_eq!(backoff_delay(base, 2, 1.0), Duration::from_millis(4800));
+        assert_eq!(backoff_delay(base, 2, -1.0), Duration::from_millis(3200));
+    }
+
+    #[test]
+    fn test_next_retry_delay() {
+        let mut sm = StateMachine::new(3, Duration::from_secs(2));
+        assert_eq!(sm.next_retry_delay(), Duration::ZERO);
+
+        sm.transition_to_connecting().unwrap();
+        sm.transition_to_error("Error 1".to_string(), true);
+        let first = sm.next_retry_delay();
+        assert!(first > Duration::from_millis(1500) && first <= Duration::from_millis(2400));
+
+        sm.transition_to_connecting().unwrap();
+        sm.transition_to_error("Error 2".to_string(), true);
+        let second = sm.next_retry_delay();
+        assert!(second > Duration::from_millis(3100) && second <= Duration::from_millis(4800));
+    }
+
     #[test]
     fn test_connection_duration() {
         let mut sm = StateMachine::default();
@@ -0,0 +1,97 @@
+//! 提交限流退避模块
+//!
+//! 服务器在提交的音频过短时返回 `commit_throttled`。连续被限流说明静音提交过于频繁，
+//! 临时延长静音提交窗口让每次提交携带更多音频，成功提交后恢复配置值
+
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// 静音提交窗口最多延长到配置值的倍数
+pub const MAX_COMMIT_BACKOFF_FACTOR: u32 = 4;
+
+/// 提交限流退避
+///
+/// 每次被限流时静音提交窗口翻倍，最多延长到配置值的 `MAX_COMMIT_BACKOFF_FACTOR` 倍
+#[derive(Debug, Clone)]
+pub struct CommitBackoff {
+    base: Duration,
+    interval: Duration,
+    consecutive: u32,
+}
+
+impl CommitBackoff {
+    /// 创建退避器
+    ///
+    /// # Arguments
+    /// * `base` - 配置的静音提交窗口
+    pub fn new(base: Duration) -> Self {
+        Self {
+            base,
+            interval: base,
+            consecutive: 0,
+        }
+    }
+
+    /// 当前生效的静音提交窗口
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 连续被限流的次数
+    pub fn consecutive(&self) -> u32 {
+        self.consecutive
+    }
+
+    /// 收到提交限流，返回延长后的静音提交窗口
+    pub fn on_throttled(&mut self) -> Duration {
+        self.consecutive = self.consecutive.saturating_add(1);
+        let max = self.base.saturating_mul(MAX_COMMIT_BACKOFF_FACTOR);
+        self.interval = self.interval.saturating_mul(2).min(max);
+        debug!(
+            "Commit throttled {} time(s) in a row, silence commit window now {:?}",
+            self.consecutive, self.interval
+        );
+        self.interval
+    }
+
+    /// 提交成功，恢复配置的静音提交窗口
+    ///
+    /// # Returns
+    /// 窗口是否发生了变化
+    pub fn on_committed(&mut self) -> bool {
+        self.consecutive = 0;
+        if self.interval == self.base {
+            return false;
+        }
+
+        info!(
+            "Commit succeeded, silence commit window restored to {:?}",
+            self.base
+        );
+        self.interval = self.base;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_resets() {
+        let base = Duration::from_millis(500);
+        let mut backoff = CommitBackoff::new(base);
+        assert_eq!(backoff.interval(), base);
+        assert!(!backoff.on_committed());
+
+        assert_eq!(backoff.on_throttled(), Duration::from_millis(1000));
+        assert_eq!(backoff.on_throttled(), Duration::from_millis(2000));
+        // 达到上限后不再增长
+        assert_eq!(backoff.on_throttled(), Duration::from_millis(2000));
+        assert_eq!(backoff.consecutive(), 3);
+
+        assert!(backoff.on_committed());
+        assert_eq!(backoff.interval(), base);
+        assert_eq!(backoff.consecutive(), 0);
+    }
+}
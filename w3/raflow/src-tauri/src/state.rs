@@ -2,28 +2,307 @@
 //!
 //! 使用 channel 模式管理应用状态，避免锁竞争
 
+use crate::audio::{AudioLevel, LevelMeter};
 use crate::config::AppConfig;
+use crate::core::bundle::SessionArtifacts;
+use crate::core::history::{TranscriptEntry, TranscriptHistory};
+use crate::input::injector::InjectionConfig;
+use crate::network::MetricsSnapshot;
+use crate::system::window::WindowInfo;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
 use tokio::sync::{mpsc, oneshot, watch};
-use tracing::info;
+use tracing::{debug, info, warn};
+
+/// 等待用户确认的注入
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingInjection {
+    /// 待注入的文本
+    pub text: String,
+    /// 提交时的目标窗口（确认时注入到这里，而不是确认时的焦点窗口）
+    pub target: Option<WindowInfo>,
+}
 
 /// 录音状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordingState {
     Idle,
     Recording,
+    /// 暂停：停止采集音频，但保留 WebSocket 会话
+    Paused,
+    /// 处理中：已停止采集，等待剩余音频发出和最终转写
     Processing,
 }
 
+impl RecordingState {
+    /// 状态名称（发送到前端）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RecordingState::Idle => "idle",
+            RecordingState::Recording => "recording",
+            RecordingState::Paused => "paused",
+            RecordingState::Processing => "processing",
+        }
+    }
+}
+
+/// 把状态变化逐个交给 `on_change`，直到状态发送端被丢弃
+///
+/// 控制任务、热键和托盘都会改变状态，前端通过该转发保持同步
+///
+/// # Arguments
+/// * `state_rx` - 状态订阅（见 `AppState::subscribe`）
+/// * `on_change` - 每次状态变化时调用，参数为新状态
+pub async fn forward_state_changes<F>(
+    mut state_rx: watch::Receiver<RecordingState>,
+    mut on_change: F,
+) where
+    F: FnMut(RecordingState),
+{
+    while state_rx.changed().await.is_ok() {
+        let state = *state_rx.borrow_and_update();
+        debug!("Recording state changed: {:?}", state);
+        on_change(state);
+    }
+}
+
+/// 控制任务管理的录音会话（见 `handle_start`）
+pub trait RecordingSession {
+    /// 开始录音
+    async fn start(&mut self) -> Result<(), ControlError>;
+
+    /// 取消录音，丢弃进行中的音频和转写
+    async fn cancel(&mut self) -> Result<(), ControlError>;
+
+    /// 暂停采集，保留转写会话
+    async fn pause(&mut self) -> Result<(), ControlError>;
+
+    /// 恢复采集，复用暂停前的转写会话
+    async fn resume(&mut self) -> Result<(), ControlError>;
+}
+
+/// 处理暂停录音命令：只有录音中可以暂停，成功后切换到暂停状态
+///
+/// # Arguments
+/// * `session` - 当前会话（未在录音时为 None）
+/// * `state_tx` - 录音状态
+pub async fn handle_pause<S: RecordingSession>(
+    session: Option<&mut S>,
+    state_tx: &watch::Sender<RecordingState>,
+) -> Result<(), ControlError> {
+    let result = match session {
+        Some(session) if *state_tx.borrow() == RecordingState::Recording => session.pause().await,
+        _ => Err(ControlError::NotRecording),
+    };
+    if result.is_ok() {
+        state_tx.send_replace(RecordingState::Paused);
+    }
+    result
+}
+
+/// 处理恢复录音命令：只有暂停中可以恢复，成功后切换回录音状态
+///
+/// # Arguments
+/// * `session` - 当前会话（未在录音时为 None）
+/// * `state_tx` - 录音状态
+pub async fn handle_resume<S: RecordingSession>(
+    session: Option<&mut S>,
+    state_tx: &watch::Sender<RecordingState>,
+) -> Result<(), ControlError> {
+    let result = match session {
+        Some(session) if *state_tx.borrow() == RecordingState::Paused => session.resume().await,
+        _ => Err(ControlError::NotPaused),
+    };
+    if result.is_ok() {
+        state_tx.send_replace(RecordingState::Recording);
+    }
+    result
+}
+
+/// 处理开始录音命令
+///
+/// 已在录音时回复 `AlreadyRunning`，不创建新会话；调用方等待超时放弃后
+/// （包括启动完成时调用方已不再等待结果）取消启动到一半的会话
+///
+/// # Arguments
+/// * `running` - 是否已在录音
+/// * `session` - 创建新会话（已在录音时不调用）
+/// * `abort` - 调用方放弃启动的信号（发送端被丢弃时不算放弃）
+/// * `response` - 回复调用方
+///
+/// # Returns
+/// 启动成功且调用方已收到结果时返回新会话
+pub async fn handle_start<S, F>(
+    running: bool,
+    session: F,
+    mut abort: oneshot::Receiver<()>,
+    response: oneshot::Sender<Result<(), ControlError>>,
+) -> Option<S>
+where
+    S: RecordingSession,
+    F: FnOnce() -> S,
+{
+    if running {
+        let _ = response.send(Err(ControlError::AlreadyRunning));
+        return None;
+    }
+
+    let mut session = session();
+    let started = tokio::select! {
+        result = session.start() => Some(result),
+        Ok(()) = &mut abort => None,
+    };
+    // 启动中的同步步骤无法被打断，完成时调用方可能已经放弃
+    let started = match started {
+        Some(Ok(())) if abort.try_recv().is_ok() => None,
+        started => started,
+    };
+
+    match started {
+        Some(Ok(())) => {
+            if response.send(Ok(())).is_ok() {
+                return Some(session);
+            }
+            // 调用方已不再等待结果，不保留没人知道的录音
+            warn!("Start finished after the caller gave up");
+        }
+        Some(Err(e)) => {
+            let _ = response.send(Err(e));
+            return None;
+        }
+        None => {
+            // 调用方已收到超时错误，这里的回复只是兜底
+            warn!("Start aborted after timeout");
+            let _ = response.send(Err(ControlError::StartFailed("timeout".to_string())));
+        }
+    }
+
+    if let Err(e) = session.cancel().await {
+        warn!("Failed to clean up abandoned start: {}", e);
+    }
+    None
+}
+
+/// 开始录音的触发来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartTrigger {
+    /// 全局热键
+    Hotkey,
+    /// 托盘菜单点击
+    Tray,
+}
+
+impl StartTrigger {
+    /// 开始采集前的等待时间
+    ///
+    /// 点击托盘后再开口的节奏与按住热键不同，托盘触发使用单独（通常更长）的等待，
+    /// 避免录进点击声和切换窗口时仓促开头的语音
+    pub fn grace(self, config: &AppConfig) -> Duration {
+        let ms = match self {
+            StartTrigger::Hotkey => config.hotkey_start_grace_ms,
+            StartTrigger::Tray => config.tray_start_grace_ms,
+        };
+        Duration::from_millis(ms)
+    }
+}
+
+/// 控制命令错误
+///
+/// 发送到前端时序列化为 `{ "code": ..., "message": ... }`，
+/// 前端按稳定的 `code` 区分处理（如忽略连按热键导致的重复开始）
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ControlError {
+    #[error("Already running")]
+    AlreadyRunning,
+
+    #[error("Not configured: {0}")]
+    NotConfigured(String),
+
+    #[error("Start failed: {0}")]
+    StartFailed(String),
+
+    #[error("Not recording")]
+    NotRecording,
+
+    #[error("Not paused")]
+    NotPaused,
+
+    #[error("{0}")]
+    Failed(String),
+
+    #[error("Control task closed")]
+    ControlTaskClosed,
+}
+
+impl ControlError {
+    /// 稳定的错误代码（发送到前端）
+    pub fn code(&self) -> &'static str {
+        match self {
+            ControlError::AlreadyRunning => "already_running",
+            ControlError::NotConfigured(_) => "not_configured",
+            ControlError::StartFailed(_) => "start_failed",
+            ControlError::NotRecording => "not_recording",
+            ControlError::NotPaused => "not_paused",
+            ControlError::Failed(_) => "failed",
+            ControlError::ControlTaskClosed => "control_task_closed",
+        }
+    }
+}
+
+impl Serialize for ControlError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("ControlError", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<ControlError> for String {
+    fn from(error: ControlError) -> Self {
+        error.to_string()
+    }
+}
+
 /// 控制命令
 pub enum ControlCommand {
     /// 开始录音
     Start {
-        config: AppConfig,
-        response: oneshot::Sender<Result<(), String>>,
+        config: Box<AppConfig>,
+        /// 调用方等待超时后发出，控制任务收到后放弃启动到一半的录音
+        abort: oneshot::Receiver<()>,
+        response: oneshot::Sender<Result<(), ControlError>>,
     },
     /// 停止录音
     Stop {
-        response: oneshot::Sender<Result<(), String>>,
+        response: oneshot::Sender<Result<(), ControlError>>,
+    },
+    /// 取消录音：丢弃进行中的音频和转写，不注入
+    Cancel {
+        response: oneshot::Sender<Result<(), ControlError>>,
+    },
+    /// 暂停录音（保持网络连接）
+    Pause {
+        response: oneshot::Sender<Result<(), ControlError>>,
+    },
+    /// 恢复录音（复用现有会话）
+    Resume {
+        response: oneshot::Sender<Result<(), ControlError>>,
+    },
+    /// 立即提交当前语句（不等静音窗口）
+    Commit {
+        response: oneshot::Sender<Result<(), ControlError>>,
+    },
+    /// 采集设备故障：在默认设备上重新打开采集流，返回新的设备名称
+    SwitchDevice {
+        response: oneshot::Sender<Result<String, ControlError>>,
+    },
+    /// 应用退出：停止录音并等待最后的转写处理完成
+    Shutdown {
+        response: oneshot::Sender<Result<(), ControlError>>,
     },
 }
 
@@ -37,6 +316,24 @@ pub struct AppState {
     pub control_tx: mpsc::Sender<ControlCommand>,
     /// 状态接收端（watch channel，可以多个订阅者）
     pub state_rx: watch::Receiver<RecordingState>,
+    /// 按住说话模式下热键是否处于按下状态
+    talk_key_held: watch::Sender<bool>,
+    /// 最近一次提交的转写，供重新注入使用
+    last_transcript: watch::Sender<Option<String>>,
+    /// 当前录音会话是否正在降噪
+    noise_suppression_active: watch::Sender<bool>,
+    /// 等待用户确认的大段注入
+    pending_injection: watch::Sender<Option<PendingInjection>>,
+    /// 最新音频电平（录音时由音频消费者任务更新）
+    level_meter: Arc<LevelMeter>,
+    /// 最近一次的网络指标（录音结束后保留，供诊断）
+    metrics: watch::Sender<MetricsSnapshot>,
+    /// 最近一次会话生成的调试文件，供导出会话包
+    last_session: watch::Sender<Option<SessionArtifacts>>,
+    /// 本次会话提交的转写（会话结束后保留，新会话开始时清空）
+    transcript_history: watch::Sender<TranscriptHistory>,
+    /// 悬浮窗延迟隐藏的代次，每次安排或取消隐藏时递增，过期的隐藏不再执行
+    overlay_hide: watch::Sender<u64>,
 }
 
 impl AppState {
@@ -56,30 +353,69 @@ impl AppState {
         let state = Self {
             control_tx,
             state_rx,
+            talk_key_held: watch::Sender::new(false),
+            last_transcript: watch::Sender::new(None),
+            noise_suppression_active: watch::Sender::new(false),
+            pending_injection: watch::Sender::new(None),
+            level_meter: Arc::new(LevelMeter::new()),
+            metrics: watch::Sender::new(MetricsSnapshot::default()),
+            last_session: watch::Sender::new(None),
+            transcript_history: watch::Sender::new(TranscriptHistory::default()),
+            overlay_hide: watch::Sender::new(0),
         };
 
         (state, control_rx, state_tx)
     }
 
     /// 发送开始录音命令
-    pub async fn start_recording(&self, config: AppConfig) -> Result<(), String> {
+    ///
+    /// 按触发来源等待后才发送命令，等待期间控制任务仍可处理其他命令；
+    /// 音频初始化或建立连接卡住时，超过 `start_timeout_ms` 返回 `StartFailed("timeout")`，
+    /// 并通知控制任务放弃启动到一半的录音，界面不会一直停在"正在连接"
+    ///
+    /// # Arguments
+    /// * `config` - 本次录音使用的配置
+    /// * `trigger` - 触发来源，决定开始采集前的等待时间
+    pub async fn start_recording(
+        &self,
+        config: AppConfig,
+        trigger: StartTrigger,
+    ) -> Result<(), ControlError> {
+        // 上一次停止安排的隐藏尚未执行时取消，避免录音中悬浮窗消失
+        self.cancel_overlay_hide();
+
+        // 等待点击声和窗口切换过去再开始采集
+        let grace = trigger.grace(&config);
+        if !grace.is_zero() {
+            debug!("Waiting {:?} before starting capture", grace);
+            tokio::time::sleep(grace).await;
+        }
+
         let (response_tx, response_rx) = oneshot::channel();
+        let (abort_tx, abort_rx) = oneshot::channel();
+        let timeout = Duration::from_millis(config.start_timeout_ms);
 
         self.control_tx
             .send(ControlCommand::Start {
-                config,
+                config: Box::new(config),
+                abort: abort_rx,
                 response: response_tx,
             })
             .await
-            .map_err(|_| "Control channel closed".to_string())?;
+            .map_err(|_| ControlError::ControlTaskClosed)?;
 
-        response_rx
-            .await
-            .map_err(|_| "Response channel closed".to_string())?
+        match tokio::time::timeout(timeout, response_rx).await {
+            Ok(response) => response.map_err(|_| ControlError::ControlTaskClosed)?,
+            Err(_) => {
+                warn!("Start recording timed out after {:?}, aborting", timeout);
+                let _ = abort_tx.send(());
+                Err(ControlError::StartFailed("timeout".to_string()))
+            }
+        }
     }
 
     /// 发送停止录音命令
-    pub async fn stop_recording(&self) -> Result<(), String> {
+    pub async fn stop_recording(&self) -> Result<(), ControlError> {
         let (response_tx, response_rx) = oneshot::channel();
 
         self.control_tx
@@ -87,11 +423,168 @@ impl AppState {
                 response: response_tx,
             })
             .await
-            .map_err(|_| "Control channel closed".to_string())?;
+            .map_err(|_| ControlError::ControlTaskClosed)?;
 
         response_rx
             .await
-            .map_err(|_| "Response channel closed".to_string())?
+            .map_err(|_| ControlError::ControlTaskClosed)?
+    }
+
+    /// 点击悬浮窗停止录音
+    ///
+    /// 与热键停止走同一条 `stop_recording` 路径；空闲或已在处理中时忽略点击，
+    /// 返回是否发送了停止命令
+    pub async fn stop_from_overlay(&self) -> Result<bool, ControlError> {
+        match self.get_state() {
+            RecordingState::Recording | RecordingState::Paused => {
+                self.stop_recording().await?;
+                Ok(true)
+            }
+            RecordingState::Idle | RecordingState::Processing => Ok(false),
+        }
+    }
+
+    /// 发送取消录音命令
+    pub async fn cancel_recording(&self) -> Result<(), ControlError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.control_tx
+            .send(ControlCommand::Cancel {
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?
+    }
+
+    /// 发送暂停录音命令
+    pub async fn pause_recording(&self) -> Result<(), ControlError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.control_tx
+            .send(ControlCommand::Pause {
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?
+    }
+
+    /// 发送恢复录音命令
+    pub async fn resume_recording(&self) -> Result<(), ControlError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.control_tx
+            .send(ControlCommand::Resume {
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?
+    }
+
+    /// 发送手动提交命令，强制结束当前语句
+    pub async fn commit_now(&self) -> Result<(), ControlError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.control_tx
+            .send(ControlCommand::Commit {
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?
+    }
+
+    /// 发送切换到默认输入设备命令
+    ///
+    /// # Returns
+    /// 新的输入设备名称
+    pub async fn switch_audio_device(&self) -> Result<String, ControlError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.control_tx
+            .send(ControlCommand::SwitchDevice {
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?
+    }
+
+    /// 发送退出命令
+    pub async fn shutdown(&self) -> Result<(), ControlError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.control_tx
+            .send(ControlCommand::Shutdown {
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| ControlError::ControlTaskClosed)?
+    }
+
+    /// 按住说话：热键按下
+    ///
+    /// 按键状态在调用时立即记录，返回的 future 负责开始录音；
+    /// 如果开始完成前热键已松开，会立即停止，避免快速按放后一直录音
+    pub fn push_to_talk_pressed(
+        &self,
+        config: AppConfig,
+    ) -> impl Future<Output = Result<(), ControlError>> + Send + 'static {
+        self.talk_key_held.send_replace(true);
+        // 热键处理中已显示悬浮窗，在开始录音之前就取消待执行的隐藏
+        self.cancel_overlay_hide();
+        let state = self.clone();
+
+        async move {
+            state.start_recording(config, StartTrigger::Hotkey).await?;
+
+            if !*state.talk_key_held.borrow() {
+                debug!("Talk key released before recording started, stopping");
+                state.stop_recording().await?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// 按住说话：热键松开
+    ///
+    /// 尚未完成开始时忽略停止请求，由按下时的 future 在开始完成后负责停止
+    pub fn push_to_talk_released(
+        &self,
+    ) -> impl Future<Output = Result<(), ControlError>> + Send + 'static {
+        self.talk_key_held.send_replace(false);
+        let state = self.clone();
+
+        async move {
+            if state.get_state() == RecordingState::Idle {
+                debug!("Ignoring stop: no start has completed");
+                return Ok(());
+            }
+
+            state.stop_recording().await
+        }
     }
 
     /// 获取当前状态
@@ -103,6 +596,169 @@ impl AppState {
     pub fn subscribe(&self) -> watch::Receiver<RecordingState> {
         self.state_rx.clone()
     }
+
+    /// 记录最近一次提交的转写
+    pub fn remember_transcript(&self, text: String) {
+        self.last_transcript.send_replace(Some(text));
+    }
+
+    /// 清除记录的转写
+    pub fn clear_last_transcript(&self) {
+        self.last_transcript.send_replace(None);
+    }
+
+    /// 最近一次提交的转写
+    pub fn last_transcript(&self) -> Option<String> {
+        self.last_transcript.borrow().clone()
+    }
+
+    /// 更新当前会话的降噪状态
+    pub fn set_noise_suppression_active(&self, active: bool) {
+        self.noise_suppression_active.send_replace(active);
+    }
+
+    /// 当前会话是否正在降噪
+    pub fn noise_suppression_active(&self) -> bool {
+        *self.noise_suppression_active.borrow()
+    }
+
+    /// 暂存等待确认的注入（替换尚未确认的旧注入）
+    pub fn hold_injection(&self, pending: PendingInjection) {
+        self.pending_injection.send_replace(Some(pending));
+    }
+
+    /// 取出等待确认的注入
+    pub fn take_pending_injection(&self) -> Option<PendingInjection> {
+        self.pending_injection.send_replace(None)
+    }
+
+    /// 超过确认阈值的提交暂存等待确认，连同提交时的目标窗口一起保存
+    ///
+    /// # Returns
+    /// * `Some(text)` - 无需确认，应直接注入
+    /// * `None` - 已暂存，等待确认
+    pub fn hold_for_confirmation(
+        &self,
+        config: &InjectionConfig,
+        text: String,
+        target: Option<WindowInfo>,
+    ) -> Option<String> {
+        if !config.needs_confirmation(&text) {
+            return Some(text);
+        }
+
+        info!(
+            "Holding {} chars for confirmation (threshold: {:?})",
+            text.chars().count(),
+            config.confirm_above_chars
+        );
+        self.hold_injection(PendingInjection { text, target });
+        None
+    }
+
+    /// 是否有等待确认的注入
+    pub fn has_pending_injection(&self) -> bool {
+        self.pending_injection.borrow().is_some()
+    }
+
+    /// 共享的电平表，交给音频管理器更新
+    pub fn level_meter(&self) -> Arc<LevelMeter> {
+        self.level_meter.clone()
+    }
+
+    /// 最新的音频电平（未录音时为零）
+    pub fn current_level(&self) -> AudioLevel {
+        self.level_meter.load()
+    }
+
+    /// 更新网络指标
+    pub fn set_metrics(&self, metrics: MetricsSnapshot) {
+        self.metrics.send_replace(metrics);
+    }
+
+    /// 最近一次的网络指标（尚未录音时为零）
+    pub fn metrics(&self) -> MetricsSnapshot {
+        *self.metrics.borrow()
+    }
+
+    /// 记录本次会话的调试文件（开始录音时更新）
+    pub fn set_last_session(&self, artifacts: SessionArtifacts) {
+        self.last_session.send_replace(Some(artifacts));
+    }
+
+    /// 最近一次会话的调试文件（尚未录音时为 None）
+    pub fn last_session(&self) -> Option<SessionArtifacts> {
+        self.last_session.borrow().clone()
+    }
+
+    /// 清空转写历史（新会话开始时调用）
+    ///
+    /// # Arguments
+    /// * `capacity` - 本次会话最多保留的条数
+    pub fn reset_transcript_history(&self, capacity: usize) {
+        self.transcript_history
+            .send_modify(|history| history.reset(capacity));
+    }
+
+    /// 追加一条转写历史
+    pub fn push_transcript_history(&self, entry: TranscriptEntry) {
+        self.transcript_history
+            .send_modify(|history| history.push(entry));
+    }
+
+    /// 本次（或最近一次）会话的转写历史，从早到晚排列
+    pub fn transcript_history(&self) -> Vec<TranscriptEntry> {
+        self.transcript_history.borrow().entries()
+    }
+
+    /// 安排延迟隐藏悬浮窗
+    ///
+    /// 返回的 future 等待 `delay` 后执行 `hide`；等待期间再次安排隐藏或开始新的录音时
+    /// 本次隐藏被取消。返回是否执行了隐藏
+    ///
+    /// # Arguments
+    /// * `delay` - 隐藏前的等待时间
+    /// * `hide` - 隐藏悬浮窗
+    pub fn schedule_overlay_hide<F>(
+        &self,
+        delay: Duration,
+        hide: F,
+    ) -> impl Future<Output = bool> + Send + 'static
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let generation = self.next_overlay_hide();
+        let current = self.overlay_hide.subscribe();
+
+        async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+
+            if *current.borrow() != generation {
+                debug!("Pending overlay hide cancelled");
+                return false;
+            }
+
+            hide();
+            true
+        }
+    }
+
+    /// 取消尚未执行的延迟隐藏
+    pub fn cancel_overlay_hide(&self) {
+        self.next_overlay_hide();
+    }
+
+    /// 递增隐藏代次，返回新的代次
+    fn next_overlay_hide(&self) -> u64 {
+        let mut generation = 0;
+        self.overlay_hide.send_modify(|current| {
+            *current += 1;
+            generation = *current;
+        });
+        generation
+    }
 }
 
 impl Clone for AppState {
@@ -110,6 +766,15 @@ impl Clone for AppState {
         Self {
             control_tx: self.control_tx.clone(),
             state_rx: self.state_rx.clone(),
+            talk_key_held: self.talk_key_held.clone(),
+            last_transcript: self.last_transcript.clone(),
+            noise_suppression_active: self.noise_suppression_active.clone(),
+            pending_injection: self.pending_injection.clone(),
+            level_meter: self.level_meter.clone(),
+            metrics: self.metrics.clone(),
+            last_session: self.last_session.clone(),
+            transcript_history: self.transcript_history.clone(),
+            overlay_hide: self.overlay_hide.clone(),
         }
     }
 }
@@ -138,6 +803,480 @@ mod tests {
         assert_eq!(*subscriber.borrow(), RecordingState::Recording);
     }
 
+    #[tokio::test]
+    async fn test_last_transcript_shared_across_clones() {
+        let (state, _control_rx, _state_tx) = AppState::new();
+        assert_eq!(state.last_transcript(), None);
+
+        // 事件处理持有的克隆写入，命令持有的克隆读取
+        state.clone().remember_transcript("hello".to_string());
+        assert_eq!(state.last_transcript().as_deref(), Some("hello"));
+
+        state.clear_last_transcript();
+        assert_eq!(state.last_transcript(), None);
+    }
+
+    #[tokio::test]
+    async fn test_large_text_held_with_target() {
+        let (state, _control_rx, _state_tx) = AppState::new();
+        let config = InjectionConfig {
+            confirm_above_chars: Some(5),
+            ..Default::default()
+        };
+        let target = WindowInfo {
+            app_name: "Notes".to_string(),
+            title: String::new(),
+            process_id: 42,
+            position: (0, 0, 0, 0),
+        };
+
+        // 未超过阈值（按字符计算）直接注入
+        assert_eq!(
+            state
+                .hold_for_confirmation(&config, "你好世界。".to_string(), Some(target.clone()))
+                .as_deref(),
+            Some("你好世界。")
+        );
+        assert!(!state.has_pending_injection());
+
+        // 超过阈值时连同提交时的目标窗口一起暂存，确认时取出
+        assert!(
+            state
+                .hold_for_confirmation(&config, "hello world".to_string(), Some(target.clone()))
+                .is_none()
+        );
+        assert!(state.has_pending_injection());
+        assert_eq!(
+            state.take_pending_injection(),
+            Some(PendingInjection {
+                text: "hello world".to_string(),
+                target: Some(target),
+            })
+        );
+        assert!(!state.has_pending_injection());
+
+        // 未配置阈值时从不确认
+        let config = InjectionConfig::default();
+        let long = "a".repeat(10_000);
+        assert_eq!(
+            state.hold_for_confirmation(&config, long.clone(), None),
+            Some(long)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_forwarded() {
+        let (state, _control_rx, state_tx) = AppState::new();
+        let (changed_tx, mut changed_rx) = mpsc::unbounded_channel();
+
+        let forward = tokio::spawn(forward_state_changes(state.subscribe(), move |s| {
+            let _ = changed_tx.send(s.as_str());
+        }));
+
+        state_tx.send(RecordingState::Recording).unwrap();
+        assert_eq!(changed_rx.recv().await, Some("recording"));
+
+        state_tx.send(RecordingState::Idle).unwrap();
+        assert_eq!(changed_rx.recv().await, Some("idle"));
+
+        // 状态发送端丢弃后转发结束
+        drop(state_tx);
+        forward.await.unwrap();
+        assert_eq!(changed_rx.recv().await, None);
+    }
+
+    /// 模拟后台控制任务，开始录音需要一定时间
+    ///
+    /// 开始时启动一个模拟网络任务，只有停止时才会结束它
+    fn spawn_control_task(
+        mut control_rx: mpsc::Receiver<ControlCommand>,
+        state_tx: watch::Sender<RecordingState>,
+    ) -> tokio::task::JoinHandle<Vec<&'static str>> {
+        tokio::spawn(async move {
+            let mut log = Vec::new();
+            let mut network: Option<tokio::task::JoinHandle<()>> = None;
+
+            while let Some(cmd) = control_rx.recv().await {
+                match cmd {
+                    ControlCommand::Start { response, .. } => {
+                        if network.is_some() {
+                            let _ = response.send(Err(ControlError::AlreadyRunning));
+                            continue;
+                        }
+                        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                        log.push("start");
+                        network = Some(tokio::spawn(std::future::pending()));
+                        let _ = state_tx.send(RecordingState::Recording);
+                        let _ = response.send(Ok(()));
+                    }
+                    ControlCommand::Pause { response } => {
+                        if *state_tx.borrow() != RecordingState::Recording {
+                            let _ = response.send(Err(ControlError::NotRecording));
+                            continue;
+                        }
+                        log.push("pause");
+                        let _ = state_tx.send(RecordingState::Paused);
+                        let _ = response.send(Ok(()));
+                    }
+                    ControlCommand::Resume { response } => {
+                        if *state_tx.borrow() != RecordingState::Paused {
+                            let _ = response.send(Err(ControlError::NotPaused));
+                            continue;
+                        }
+                        // 恢复时网络任务必须仍在运行
+                        if network.as_ref().is_some_and(|n| !n.is_finished()) {
+                            log.push("resume");
+                        }
+                        let _ = state_tx.send(RecordingState::Recording);
+                        let _ = response.send(Ok(()));
+                    }
+                    ControlCommand::Commit { response } => {
+                        if *state_tx.borrow() == RecordingState::Idle {
+                            let _ = response.send(Err(ControlError::NotRecording));
+                            continue;
+                        }
+                        log.push("commit");
+                        let _ = response.send(Ok(()));
+                    }
+                    ControlCommand::SwitchDevice { response } => {
+                        log.push("switch_device");
+                        let _ = response.send(Ok("default".to_string()));
+                    }
+                    ControlCommand::Cancel { response } => {
+                        log.push("cancel");
+                        if let Some(network) = network.take() {
+                            network.abort();
+                        }
+                        let _ = state_tx.send(RecordingState::Idle);
+                        let _ = response.send(Ok(()));
+                    }
+                    ControlCommand::Stop { response } | ControlCommand::Shutdown { response } => {
+                        log.push("stop");
+                        if let Some(network) = network.take() {
+                            network.abort();
+                        }
+                        let _ = state_tx.send(RecordingState::Idle);
+                        let _ = response.send(Ok(()));
+                    }
+                }
+            }
+
+            log
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_recording_cancels_pending_overlay_hide() {
+        let (state, control_rx, state_tx) = AppState::new();
+        let control = spawn_control_task(control_rx, state_tx);
+        let (hidden_tx, mut hidden_rx) = mpsc::unbounded_channel();
+        let delay = Duration::from_millis(500);
+
+        // 无新录音时按延迟隐藏
+        let tx = hidden_tx.clone();
+        let hide = tokio::spawn(state.schedule_overlay_hide(delay, move || {
+            let _ = tx.send("first");
+        }));
+        tokio::time::sleep(Duration::from_millis(499)).await;
+        assert!(hidden_rx.try_recv().is_err());
+        assert!(hide.await.unwrap());
+        assert_eq!(hidden_rx.try_recv(), Ok("first"));
+
+        // 延迟期间开始新的录音，隐藏被取消
+        let tx = hidden_tx.clone();
+        let hide = tokio::spawn(state.schedule_overlay_hide(delay, move || {
+            let _ = tx.send("second");
+        }));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        state
+            .start_recording(AppConfig::default(), StartTrigger::Hotkey)
+            .await
+            .unwrap();
+        assert!(!hide.await.unwrap());
+        assert!(hidden_rx.try_recv().is_err());
+
+        // 再次停止后重新安排的隐藏照常执行
+        state.stop_recording().await.unwrap();
+        let hide = tokio::spawn(state.schedule_overlay_hide(delay, move || {
+            let _ = hidden_tx.send("third");
+        }));
+        assert!(hide.await.unwrap());
+        assert_eq!(hidden_rx.try_recv(), Ok("third"));
+
+        drop(state);
+        assert_eq!(control.await.unwrap(), vec!["start", "stop"]);
+    }
+
+    /// 测试会话：启动耗时 `start_delay`，把调用记录发送到 `log_tx`
+    struct FakeSession {
+        start_delay: Duration,
+        start_result: Result<(), ControlError>,
+        log_tx: mpsc::UnboundedSender<&'static str>,
+    }
+
+    impl RecordingSession for FakeSession {
+        async fn start(&mut self) -> Result<(), ControlError> {
+            tokio::time::sleep(self.start_delay).await;
+            let _ = self.log_tx.send("start");
+            self.start_result.clone()
+        }
+
+        async fn cancel(&mut self) -> Result<(), ControlError> {
+            let _ = self.log_tx.send("cancel");
+            Ok(())
+        }
+
+        async fn pause(&mut self) -> Result<(), ControlError> {
+            let _ = self.log_tx.send("pause");
+            Ok(())
+        }
+
+        async fn resume(&mut self) -> Result<(), ControlError> {
+            let _ = self.log_tx.send("resume");
+            Ok(())
+        }
+    }
+
+    fn fake_session(
+        start_result: Result<(), ControlError>,
+    ) -> (FakeSession, mpsc::UnboundedReceiver<&'static str>) {
+        let (log_tx, log_rx) = mpsc::unbounded_channel();
+        let session = FakeSession {
+            start_delay: Duration::from_millis(100),
+            start_result,
+            log_tx,
+        };
+        (session, log_rx)
+    }
+
+    fn drain(log_rx: &mut mpsc::UnboundedReceiver<&'static str>) -> Vec<&'static str> {
+        std::iter::from_fn(|| log_rx.try_recv().ok()).collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_second_start_yields_already_running() {
+        let (session, mut log_rx) = fake_session(Ok(()));
+        let (_abort_tx, abort_rx) = oneshot::channel();
+        let (response_tx, response_rx) = oneshot::channel();
+
+        // 连按热键导致的重复开始返回可区分的错误，不创建新会话
+        let started = handle_start(true, || session, abort_rx, response_tx).await;
+        assert!(started.is_none());
+        let error = response_rx.await.unwrap().unwrap_err();
+        assert_eq!(error, ControlError::AlreadyRunning);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({"code": "already_running", "message": "Already running"})
+        );
+        assert!(drain(&mut log_rx).is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_start_outcomes() {
+        // 启动成功：返回新会话
+        let (session, mut log_rx) = fake_session(Ok(()));
+        let (_abort_tx, abort_rx) = oneshot::channel();
+        let (response_tx, response_rx) = oneshot::channel();
+        let started = handle_start(false, || session, abort_rx, response_tx).await;
+        assert!(started.is_some());
+        assert_eq!(response_rx.await.unwrap(), Ok(()));
+        assert_eq!(drain(&mut log_rx), vec!["start"]);
+
+        // 启动失败：错误原样回复，不需要清理
+        let error = ControlError::NotConfigured("API key".to_string());
+        let (session, mut log_rx) = fake_session(Err(error.clone()));
+        let (_abort_tx, abort_rx) = oneshot::channel();
+        let (response_tx, response_rx) = oneshot::channel();
+        let started = handle_start(false, || session, abort_rx, response_tx).await;
+        assert!(started.is_none());
+        assert_eq!(response_rx.await.unwrap(), Err(error));
+        assert_eq!(drain(&mut log_rx), vec!["start"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_abandoned_start_is_cancelled() {
+        // 启动过程中调用方放弃：不等启动完成，取消会话
+        let (session, mut log_rx) = fake_session(Ok(()));
+        let (abort_tx, abort_rx) = oneshot::channel();
+        let (response_tx, _response_rx) = oneshot::channel();
+        let start = tokio::spawn(handle_start(false, || session, abort_rx, response_tx));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        abort_tx.send(()).unwrap();
+        assert!(start.await.unwrap().is_none());
+        assert_eq!(drain(&mut log_rx), vec!["cancel"]);
+
+        // 启动完成时调用方已不再等待结果：同样取消
+        let (session, mut log_rx) = fake_session(Ok(()));
+        let (_abort_tx, abort_rx) = oneshot::channel();
+        let (response_tx, response_rx) = oneshot::channel();
+        drop(response_rx);
+        let started = handle_start(false, || session, abort_rx, response_tx).await;
+        assert!(started.is_none());
+        assert_eq!(drain(&mut log_rx), vec!["start", "cancel"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unanswered_start_times_out() {
+        let (state, mut control_rx, _state_tx) = AppState::new();
+        let config = AppConfig {
+            start_timeout_ms: 3000,
+            ..Default::default()
+        };
+
+        let start =
+            tokio::spawn(async move { state.start_recording(config, StartTrigger::Hotkey).await });
+
+        // 控制任务收到命令但一直不回应（模拟卡住的连接）
+        let Some(ControlCommand::Start {
+            abort, response, ..
+        }) = control_rx.recv().await
+        else {
+            panic!("expected start command");
+        };
+
+        let started_at = tokio::time::Instant::now();
+        assert_eq!(
+            start.await.unwrap(),
+            Err(ControlError::StartFailed("timeout".to_string()))
+        );
+        assert_eq!(started_at.elapsed(), Duration::from_millis(3000));
+
+        // 控制任务收到放弃信号，之后的回应无人接收
+        assert_eq!(abort.await, Ok(()));
+        assert!(response.send(Ok(())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_commit_now_requires_session() {
+        let (state, control_rx, state_tx) = AppState::new();
+        let control = spawn_control_task(control_rx, state_tx);
+
+        assert!(state.commit_now().await.is_err());
+
+        state
+            .start_recording(AppConfig::default(), StartTrigger::Hotkey)
+            .await
+            .unwrap();
+        state.commit_now().await.unwrap();
+        // 手动提交不结束录音
+        assert_eq!(state.get_state(), RecordingState::Recording);
+
+        state.stop_recording().await.unwrap();
+
+        drop(state);
+        assert_eq!(control.await.unwrap(), vec!["start", "commit", "stop"]);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_keeps_session() {
+        let (mut session, mut log_rx) = fake_session(Ok(()));
+        let state_tx = watch::Sender::new(RecordingState::Idle);
+
+        // 未在录音时不能暂停或恢复
+        assert_eq!(
+            handle_pause::<FakeSession>(None, &state_tx).await,
+            Err(ControlError::NotRecording)
+        );
+        assert_eq!(
+            handle_resume(Some(&mut session), &state_tx).await,
+            Err(ControlError::NotPaused)
+        );
+
+        state_tx.send_replace(RecordingState::Recording);
+        assert_eq!(handle_pause(Some(&mut session), &state_tx).await, Ok(()));
+        assert_eq!(*state_tx.borrow(), RecordingState::Paused);
+        assert_eq!(
+            handle_pause(Some(&mut session), &state_tx).await,
+            Err(ControlError::NotRecording)
+        );
+
+        // 恢复复用同一个会话
+        assert_eq!(handle_resume(Some(&mut session), &state_tx).await, Ok(()));
+        assert_eq!(*state_tx.borrow(), RecordingState::Recording);
+        assert_eq!(
+            handle_resume(Some(&mut session), &state_tx).await,
+            Err(ControlError::NotPaused)
+        );
+
+        // 处理中（已停止采集）不能暂停
+        state_tx.send_replace(RecordingState::Processing);
+        assert!(handle_pause(Some(&mut session), &state_tx).await.is_err());
+        assert_eq!(*state_tx.borrow(), RecordingState::Processing);
+
+        assert_eq!(drain(&mut log_rx), vec!["pause", "resume"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_returns_to_idle() {
+        let (state, control_rx, state_tx) = AppState::new();
+        let control = spawn_control_task(control_rx, state_tx);
+
+        state
+            .start_recording(AppConfig::default(), StartTrigger::Hotkey)
+            .await
+            .unwrap();
+        state.cancel_recording().await.unwrap();
+        assert_eq!(state.get_state(), RecordingState::Idle);
+
+        drop(state);
+        assert_eq!(control.await.unwrap(), vec!["start", "cancel"]);
+    }
+
+    #[tokio::test]
+    async fn test_overlay_click_stops_to_idle() {
+        let (state, control_rx, state_tx) = AppState::new();
+        let control = spawn_control_task(control_rx, state_tx);
+
+        // 空闲时点击不发送任何命令
+        assert!(!state.stop_from_overlay().await.unwrap());
+
+        state
+            .start_recording(AppConfig::default(), StartTrigger::Hotkey)
+            .await
+            .unwrap();
+        state.pause_recording().await.unwrap();
+        assert!(state.stop_from_overlay().await.unwrap());
+        assert_eq!(state.get_state(), RecordingState::Idle);
+        assert!(!state.stop_from_overlay().await.unwrap());
+
+        drop(state);
+        assert_eq!(control.await.unwrap(), vec!["start", "pause", "stop"]);
+    }
+
+    #[tokio::test]
+    async fn test_push_to_talk_rapid_release() {
+        let (state, control_rx, state_tx) = AppState::new();
+        let control = spawn_control_task(control_rx, state_tx);
+
+        // 按下后立即松开，开始尚未完成
+        let pressed = tokio::spawn(state.push_to_talk_pressed(AppConfig::default()));
+        state.push_to_talk_released().await.unwrap();
+        pressed.await.unwrap().unwrap();
+
+        assert_eq!(state.get_state(), RecordingState::Idle);
+
+        drop(state);
+        assert_eq!(control.await.unwrap(), vec!["start", "stop"]);
+    }
+
+    #[tokio::test]
+    async fn test_push_to_talk_hold_and_release() {
+        let (state, control_rx, state_tx) = AppState::new();
+        let control = spawn_control_task(control_rx, state_tx);
+
+        state
+            .push_to_talk_pressed(AppConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(state.get_state(), RecordingState::Recording);
+
+        state.push_to_talk_released().await.unwrap();
+        assert_eq!(state.get_state(), RecordingState::Idle);
+
+        drop(state);
+        assert_eq!(control.await.unwrap(), vec!["start", "stop"]);
+    }
+
     #[tokio::test]
     async fn test_control_command_send() {
         let (state, mut control_rx, _state_tx) = AppState::new();
@@ -148,7 +1287,8 @@ mod tests {
             let _ = state
                 .control_tx
                 .send(ControlCommand::Start {
-                    config: AppConfig::default(),
+                    config: Box::default(),
+                    abort: oneshot::channel().1,
                     response: tx,
                 })
                 .await;
@@ -158,4 +1298,43 @@ mod tests {
         let cmd = control_rx.recv().await;
         assert!(cmd.is_some());
     }
+
+    /// 发送开始命令，返回控制任务收到命令前经过的时间
+    async fn received_grace(config: AppConfig, trigger: StartTrigger) -> Duration {
+        let (state, mut control_rx, _state_tx) = AppState::new();
+        let started_at = tokio::time::Instant::now();
+
+        let start = tokio::spawn(async move { state.start_recording(config, trigger).await });
+
+        let Some(ControlCommand::Start { response, .. }) = control_rx.recv().await else {
+            panic!("expected start command");
+        };
+        let grace = started_at.elapsed();
+        let _ = response.send(Ok(()));
+        start.await.unwrap().unwrap();
+
+        grace
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_start_grace_by_trigger() {
+        let config = AppConfig {
+            hotkey_start_grace_ms: 50,
+            tray_start_grace_ms: 400,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            received_grace(config.clone(), StartTrigger::Hotkey).await,
+            Duration::from_millis(50)
+        );
+        assert_eq!(
+            received_grace(config, StartTrigger::Tray).await,
+            Duration::from_millis(400)
+        );
+
+        // 默认配置下托盘触发的等待更长
+        let defaults = AppConfig::default();
+        assert!(StartTrigger::Tray.grace(&defaults) > StartTrigger::Hotkey.grace(&defaults));
+    }
 }
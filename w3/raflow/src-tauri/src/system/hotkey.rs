@@ -2,10 +2,14 @@
 //!
 //! 使用 tauri-plugin-global-shortcut 实现全局热键
 
-use tauri::{AppHandle, Emitter};
+use crate::AppState;
+use crate::commands::hide_overlay_after_stop;
+use crate::config::{AppConfig, ConfigManager, HotkeyMode};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 use thiserror::Error;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 
 #[derive(Error, Debug)]
 pub enum HotkeyError {
@@ -17,56 +21,293 @@ pub enum HotkeyError {
 
     #[error("Invalid hotkey format: {0}")]
     InvalidFormat(String),
+
+    #[error("Duplicate hotkey: {0}")]
+    Duplicate(String),
 }
 
 type Result<T> = std::result::Result<T, HotkeyError>;
 
+/// 解析后的热键
+///
+/// 供设置界面在保存前校验热键并显示规范化结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParsedHotkey {
+    /// 规范化的热键字符串（如 "Ctrl+Shift+A"），可再次解析
+    pub normalized: String,
+    /// 修饰键（按 Ctrl、Alt、Shift、Cmd/Super 排序）
+    pub modifiers: Vec<String>,
+    /// 按键
+    pub key: String,
+}
+
+/// 热键触发的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// 主热键：按配置的模式开始或停止录音
+    Record(HotkeyMode),
+    /// 切换录音
+    Toggle,
+    /// 取消录音
+    Cancel,
+    /// 打开设置窗口
+    Settings,
+    /// 立即提交当前语句
+    Commit,
+}
+
+impl HotkeyAction {
+    /// 对应的配置项名称（用于日志和错误信息）
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Record(_) => "hotkey",
+            Self::Toggle => "hotkeys.toggle",
+            Self::Cancel => "hotkeys.cancel",
+            Self::Settings => "hotkeys.settings",
+            Self::Commit => "hotkeys.commit",
+        }
+    }
+}
+
+/// 解析后的热键绑定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyBinding {
+    /// 配置中的热键字符串
+    pub hotkey: String,
+    pub shortcut: Shortcut,
+    pub action: HotkeyAction,
+}
+
 /// 热键管理器
 pub struct HotkeyManager;
 
 impl HotkeyManager {
-    /// 注册全局热键
+    /// 注册配置中的全部全局热键
+    ///
+    /// 主热键按 `hotkey_mode` 工作，附加热键（`hotkeys`）各自注册独立的回调。
+    /// 任一热键注册失败时注销已注册的热键并返回错误
     ///
     /// # Arguments
     /// * `app` - Tauri AppHandle
-    /// * `hotkey_str` - 热键字符串（如 "CommandOrControl+Shift+\"）
+    /// * `config` - 应用配置
     ///
     /// # Example
     /// ```no_run
+    /// use raflow_lib::config::AppConfig;
     /// use raflow_lib::system::HotkeyManager;
     ///
     /// fn setup(app: &tauri::AppHandle) {
-    ///     HotkeyManager::register(app, "CommandOrControl+Shift+\\").unwrap();
+    ///     HotkeyManager::register(app, &AppConfig::default()).unwrap();
     /// }
     /// ```
-    pub fn register(app: &AppHandle, hotkey_str: &str) -> Result<()> {
-        info!("Registering global hotkey: {}", hotkey_str);
-
-        // 解析热键字符串
-        let shortcut = Self::parse_hotkey(hotkey_str)?;
+    pub fn register(app: &AppHandle, config: &AppConfig) -> Result<()> {
+        let bindings = Self::bindings(config)?;
 
-        // 注册热键（仅响应 Pressed 事件，用于切换）
-        app.global_shortcut()
-            .on_shortcut(shortcut, move |app, _shortcut, event| {
-                // 只处理按键按下事件（切换模式）
-                if event.state != ShortcutState::Pressed {
-                    return;
+        for binding in &bindings {
+            if let Err(e) = Self::register_binding(app, binding) {
+                if let Err(e) = Self::unregister_all(app) {
+                    warn!("Failed to roll back registered hotkeys: {}", e);
                 }
+                return Err(e);
+            }
+        }
 
-                info!("Hotkey pressed - toggling recording");
+        Ok(())
+    }
 
-                // 发送切换事件到前端
-                if let Err(e) = app.emit("hotkey_toggle", ()) {
-                    warn!("Failed to emit hotkey_toggle event: {}", e);
-                }
-            })
+    /// 注册单个热键，回调按动作分发
+    fn register_binding(app: &AppHandle, binding: &HotkeyBinding) -> Result<()> {
+        info!(
+            "Registering global hotkey: {} ({:?})",
+            binding.hotkey, binding.action
+        );
+
+        let action = binding.action;
+        app.global_shortcut()
+            .on_shortcut(
+                binding.shortcut,
+                move |app, _shortcut, event| match action {
+                    HotkeyAction::Record(HotkeyMode::Toggle) | HotkeyAction::Toggle => {
+                        Self::handle_toggle(app, event.state)
+                    }
+                    HotkeyAction::Record(HotkeyMode::PushToTalk) => {
+                        Self::handle_push_to_talk(app, event.state)
+                    }
+                    HotkeyAction::Cancel => Self::handle_cancel(app, event.state),
+                    HotkeyAction::Settings => Self::handle_settings(app, event.state),
+                    HotkeyAction::Commit => Self::handle_commit(app, event.state),
+                },
+            )
             .map_err(|e| HotkeyError::RegisterFailed(e.to_string()))?;
 
-        info!("Hotkey registered successfully: {}", hotkey_str);
+        info!("Hotkey registered successfully: {}", binding.hotkey);
 
         Ok(())
     }
 
+    /// 解析配置中的全部热键
+    ///
+    /// 主热键在前，之后依次是切换、取消、设置、提交热键；未配置或为空的附加热键跳过
+    ///
+    /// # Errors
+    /// 任一热键格式无效时返回 `InvalidFormat`，两个热键解析结果相同时返回 `Duplicate`
+    pub fn bindings(config: &AppConfig) -> Result<Vec<HotkeyBinding>> {
+        let extras = config.hotkeys.iter().flat_map(|hotkeys| {
+            [
+                (hotkeys.toggle.as_deref(), HotkeyAction::Toggle),
+                (hotkeys.cancel.as_deref(), HotkeyAction::Cancel),
+                (hotkeys.settings.as_deref(), HotkeyAction::Settings),
+                (hotkeys.commit.as_deref(), HotkeyAction::Commit),
+            ]
+        });
+        let candidates = std::iter::once((
+            config.hotkey.as_str(),
+            HotkeyAction::Record(config.hotkey_mode),
+        ))
+        .chain(extras.filter_map(|(hotkey, action)| {
+            hotkey
+                .filter(|hotkey| !hotkey.trim().is_empty())
+                .map(|hotkey| (hotkey, action))
+        }));
+
+        let mut bindings: Vec<HotkeyBinding> = Vec::new();
+        for (hotkey, action) in candidates {
+            let shortcut = Self::parse_hotkey(hotkey)?;
+            if let Some(existing) = bindings.iter().find(|b| b.shortcut == shortcut) {
+                return Err(HotkeyError::Duplicate(format!(
+                    "\"{}\" is bound to both {} and {}",
+                    hotkey,
+                    existing.action.name(),
+                    action.name()
+                )));
+            }
+
+            bindings.push(HotkeyBinding {
+                hotkey: hotkey.to_string(),
+                shortcut,
+                action,
+            });
+        }
+
+        Ok(bindings)
+    }
+
+    /// 切换模式：仅响应按下事件，由前端切换录音状态
+    fn handle_toggle(app: &AppHandle, state: ShortcutState) {
+        if state != ShortcutState::Pressed {
+            return;
+        }
+
+        info!("Hotkey pressed - toggling recording");
+
+        // 发送切换事件到前端
+        if let Err(e) = app.emit("hotkey_toggle", ()) {
+            warn!("Failed to emit hotkey_toggle event: {}", e);
+        }
+    }
+
+    /// 按住说话模式：按下开始录音，松开立即停止
+    fn handle_push_to_talk(app: &AppHandle, state: ShortcutState) {
+        let app_state = app.state::<AppState>().inner().clone();
+        let overlay = app.get_webview_window("overlay");
+
+        match state {
+            ShortcutState::Pressed => {
+                info!("Hotkey pressed - push-to-talk start");
+
+                let config = match ConfigManager::load(app) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        error!("Failed to load config: {}", e);
+                        return;
+                    }
+                };
+
+                // 按键状态在此同步记录，保证与松开事件的顺序一致；
+                // 同时取消上一次松开安排的隐藏，之后才显示悬浮窗
+                let start = app_state.push_to_talk_pressed(config);
+
+                if let Some(overlay) = &overlay {
+                    let _ = overlay.show();
+                }
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = start.await {
+                        warn!("Push-to-talk start failed: {}", e);
+                        if let Some(overlay) = overlay {
+                            let _ = overlay.hide();
+                        }
+                    }
+                });
+            }
+            ShortcutState::Released => {
+                info!("Hotkey released - push-to-talk stop");
+
+                let stop = app_state.push_to_talk_released();
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = stop.await {
+                        warn!("Push-to-talk stop failed: {}", e);
+                    }
+                    hide_overlay_after_stop(&app, &app_state).await;
+                });
+            }
+        }
+    }
+
+    /// 取消热键：仅响应按下事件，丢弃进行中的录音
+    fn handle_cancel(app: &AppHandle, state: ShortcutState) {
+        if state != ShortcutState::Pressed {
+            return;
+        }
+
+        info!("Cancel hotkey pressed");
+
+        let app_state = app.state::<AppState>().inner().clone();
+        let overlay = app.get_webview_window("overlay");
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = app_state.cancel_recording().await {
+                warn!("Hotkey cancel failed: {}", e);
+            }
+            if let Some(overlay) = overlay {
+                let _ = overlay.hide();
+            }
+        });
+    }
+
+    /// 设置热键：仅响应按下事件，显示并聚焦设置窗口
+    fn handle_settings(app: &AppHandle, state: ShortcutState) {
+        if state != ShortcutState::Pressed {
+            return;
+        }
+
+        info!("Settings hotkey pressed");
+
+        if let Some(window) = app.get_webview_window("main") {
+            if let Err(e) = window.show() {
+                error!("Failed to show settings window: {}", e);
+            }
+            if let Err(e) = window.set_focus() {
+                error!("Failed to focus settings window: {}", e);
+            }
+        }
+    }
+
+    /// 提交热键：仅响应按下事件，强制结束当前语句，录音继续
+    fn handle_commit(app: &AppHandle, state: ShortcutState) {
+        if state != ShortcutState::Pressed {
+            return;
+        }
+
+        info!("Commit hotkey pressed");
+
+        let app_state = app.state::<AppState>().inner().clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = app_state.commit_now().await {
+                debug!("Hotkey commit ignored: {}", e);
+            }
+        });
+    }
+
     /// 注销热键
     pub fn unregister(app: &AppHandle, hotkey_str: &str) -> Result<()> {
         info!("Unregistering hotkey: {}", hotkey_str);
@@ -82,27 +323,243 @@ impl HotkeyManager {
         Ok(())
     }
 
+    /// 注销本应用注册的全部热键
+    pub fn unregister_all(app: &AppHandle) -> Result<()> {
+        info!("Unregistering all hotkeys");
+
+        app.global_shortcut()
+            .unregister_all()
+            .map_err(|e| HotkeyError::UnregisterFailed(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// 更换已注册的全局热键
+    ///
+    /// 先注销全部旧热键再注册新热键；新热键注册失败（如已被其他应用占用）时恢复旧热键并返回错误
+    ///
+    /// # Arguments
+    /// * `app` - Tauri AppHandle
+    /// * `old` - 当前生效的配置
+    /// * `new` - 要应用的配置
+    pub fn reregister(app: &AppHandle, old: &AppConfig, new: &AppConfig) -> Result<()> {
+        info!("Re-registering hotkeys: {} -> {}", old.hotkey, new.hotkey);
+
+        // 先解析新热键，格式错误或重复时不动旧热键
+        Self::bindings(new)?;
+
+        if let Err(e) = Self::unregister_all(app) {
+            warn!("Failed to unregister previous hotkeys: {}", e);
+        }
+
+        if let Err(e) = Self::register(app, new) {
+            warn!("Failed to register new hotkeys, restoring {}", old.hotkey);
+            if let Err(restore) = Self::register(app, old) {
+                error!("Failed to restore previous hotkeys: {}", restore);
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// 判断配置变更后是否需要重新注册热键
+    ///
+    /// 热键按解析结果比较，写法不同但等价的热键（如 `ctrl+a` 与 `Control+A`）不重新注册；
+    /// 模式或附加热键改变时需要重新注册（回调按动作注册）。旧热键无法解析时总是重新注册
+    ///
+    /// # Errors
+    /// 新热键格式无效时返回 `InvalidFormat`，重复时返回 `Duplicate`
+    pub fn needs_reregister(old: &AppConfig, new: &AppConfig) -> Result<bool> {
+        let actions = |bindings: Vec<HotkeyBinding>| -> Vec<(Shortcut, HotkeyAction)> {
+            bindings
+                .into_iter()
+                .map(|binding| (binding.shortcut, binding.action))
+                .collect()
+        };
+
+        let new_bindings = actions(Self::bindings(new)?);
+        let unchanged = Self::bindings(old).is_ok_and(|old| actions(old) == new_bindings);
+        Ok(!unchanged)
+    }
+
+    /// 校验热键字符串，返回规范化表示
+    ///
+    /// 只解析，不注册
+    pub fn validate(hotkey_str: &str) -> Result<ParsedHotkey> {
+        let shortcut = Self::parse_hotkey(hotkey_str)?;
+
+        let super_name = if cfg!(target_os = "macos") {
+            "Cmd"
+        } else {
+            "Super"
+        };
+        let modifiers: Vec<String> = [
+            (Modifiers::CONTROL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, super_name),
+        ]
+        .into_iter()
+        .filter(|(modifier, _)| shortcut.mods.contains(*modifier))
+        .map(|(_, name)| name.to_string())
+        .collect();
+
+        let key = Self::code_name(shortcut.key);
+
+        let mut parts = modifiers.clone();
+        parts.push(key.clone());
+
+        Ok(ParsedHotkey {
+            normalized: parts.join("+"),
+            modifiers,
+            key,
+        })
+    }
+
+    /// 按键的显示名称（与 `parse_code` 接受的写法一致）
+    fn code_name(code: Code) -> String {
+        let name = code.to_string();
+        ["Key", "Digit", "Arrow"]
+            .iter()
+            .find_map(|prefix| name.strip_prefix(prefix))
+            .map(str::to_string)
+            .unwrap_or(name)
+    }
+
     /// 解析热键字符串
     ///
     /// 支持格式：
     /// - "CommandOrControl+Shift+A"
     /// - "Cmd+Shift+\"
     /// - "Ctrl+Alt+Space"
-    fn parse_hotkey(_hotkey_str: &str) -> Result<Shortcut> {
-        // 简化版：直接使用默认热键
-        // 完整的解析逻辑可以在未来添加
+    ///
+    /// 修饰键：CommandOrControl、Cmd/Command、Ctrl/Control、Alt/Option、Shift、Super/Meta
+    /// 按键：A-Z、0-9、Space、Backslash（或 `\`）、F1-F12、方向键
+    fn parse_hotkey(hotkey_str: &str) -> Result<Shortcut> {
+        let mut modifiers = Modifiers::empty();
+        let mut key: Option<Code> = None;
+
+        for token in hotkey_str.split('+') {
+            let token = token.trim();
+            if token.is_empty() {
+                return Err(HotkeyError::InvalidFormat(format!(
+                    "empty token in \"{}\"",
+                    hotkey_str
+                )));
+            }
+
+            if let Some(modifier) = Self::parse_modifier(token) {
+                modifiers |= modifier;
+                continue;
+            }
+
+            let code = Self::parse_code(token)
+                .ok_or_else(|| HotkeyError::InvalidFormat(token.to_string()))?;
+
+            if key.replace(code).is_some() {
+                return Err(HotkeyError::InvalidFormat(format!(
+                    "multiple keys in \"{}\"",
+                    hotkey_str
+                )));
+            }
+        }
 
-        // 默认：CommandOrControl+Shift+Backslash
-        let modifiers = Modifiers::SUPER | Modifiers::SHIFT;
-        let key = Code::Backslash;
+        let key = key.ok_or_else(|| {
+            HotkeyError::InvalidFormat(format!("missing key in \"{}\"", hotkey_str))
+        })?;
 
-        let shortcut = Shortcut::new(Some(modifiers), key);
+        let modifiers = (!modifiers.is_empty()).then_some(modifiers);
+        let shortcut = Shortcut::new(modifiers, key);
 
         debug!("Parsed hotkey: {:?}", shortcut);
 
         Ok(shortcut)
     }
 
+    /// 解析修饰键
+    fn parse_modifier(token: &str) -> Option<Modifiers> {
+        let modifier = match token.to_ascii_uppercase().as_str() {
+            "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" => {
+                if cfg!(target_os = "macos") {
+                    Modifiers::SUPER
+                } else {
+                    Modifiers::CONTROL
+                }
+            }
+            "CMD" | "COMMAND" | "SUPER" | "META" => Modifiers::SUPER,
+            "CTRL" | "CONTROL" => Modifiers::CONTROL,
+            "ALT" | "OPTION" => Modifiers::ALT,
+            "SHIFT" => Modifiers::SHIFT,
+            _ => return None,
+        };
+
+        Some(modifier)
+    }
+
+    /// 解析按键
+    fn parse_code(token: &str) -> Option<Code> {
+        let code = match token.to_ascii_uppercase().as_str() {
+            "A" => Code::KeyA,
+            "B" => Code::KeyB,
+            "C" => Code::KeyC,
+            "D" => Code::KeyD,
+            "E" => Code::KeyE,
+            "F" => Code::KeyF,
+            "G" => Code::KeyG,
+            "H" => Code::KeyH,
+            "I" => Code::KeyI,
+            "J" => Code::KeyJ,
+            "K" => Code::KeyK,
+            "L" => Code::KeyL,
+            "M" => Code::KeyM,
+            "N" => Code::KeyN,
+            "O" => Code::KeyO,
+            "P" => Code::KeyP,
+            "Q" => Code::KeyQ,
+            "R" => Code::KeyR,
+            "S" => Code::KeyS,
+            "T" => Code::KeyT,
+            "U" => Code::KeyU,
+            "V" => Code::KeyV,
+            "W" => Code::KeyW,
+            "X" => Code::KeyX,
+            "Y" => Code::KeyY,
+            "Z" => Code::KeyZ,
+            "0" => Code::Digit0,
+            "1" => Code::Digit1,
+            "2" => Code::Digit2,
+            "3" => Code::Digit3,
+            "4" => Code::Digit4,
+            "5" => Code::Digit5,
+            "6" => Code::Digit6,
+            "7" => Code::Digit7,
+            "8" => Code::Digit8,
+            "9" => Code::Digit9,
+            "SPACE" => Code::Space,
+            "BACKSLASH" | "\\" => Code::Backslash,
+            "F1" => Code::F1,
+            "F2" => Code::F2,
+            "F3" => Code::F3,
+            "F4" => Code::F4,
+            "F5" => Code::F5,
+            "F6" => Code::F6,
+            "F7" => Code::F7,
+            "F8" => Code::F8,
+            "F9" => Code::F9,
+            "F10" => Code::F10,
+            "F11" => Code::F11,
+            "F12" => Code::F12,
+            "UP" | "ARROWUP" => Code::ArrowUp,
+            "DOWN" | "ARROWDOWN" => Code::ArrowDown,
+            "LEFT" | "ARROWLEFT" => Code::ArrowLeft,
+            "RIGHT" | "ARROWRIGHT" => Code::ArrowRight,
+            _ => return None,
+        };
+
+        Some(code)
+    }
+
     /// 检查热键是否已注册
     pub fn is_registered(app: &AppHandle, hotkey_str: &str) -> bool {
         if let Ok(shortcut) = Self::parse_hotkey(hotkey_str) {
@@ -116,11 +573,233 @@ impl HotkeyManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::HotkeyBindings;
 
     #[test]
     fn test_parse_hotkey() {
-        let result = HotkeyManager::parse_hotkey("CommandOrControl+Shift+\\");
-        assert!(result.is_ok());
+        let shortcut = HotkeyManager::parse_hotkey("CommandOrControl+Shift+\\").unwrap();
+
+        let primary = if cfg!(target_os = "macos") {
+            Modifiers::SUPER
+        } else {
+            Modifiers::CONTROL
+        };
+        assert_eq!(shortcut.mods, primary | Modifiers::SHIFT);
+        assert_eq!(shortcut.key, Code::Backslash);
+    }
+
+    #[test]
+    fn test_parse_hotkey_ctrl_alt_space() {
+        let shortcut = HotkeyManager::parse_hotkey("Ctrl+Alt+Space").unwrap();
+        assert_eq!(shortcut.mods, Modifiers::CONTROL | Modifiers::ALT);
+        assert_eq!(shortcut.key, Code::Space);
+    }
+
+    #[test]
+    fn test_parse_hotkey_keys() {
+        let shortcut = HotkeyManager::parse_hotkey("cmd+option+f12").unwrap();
+        assert_eq!(shortcut.mods, Modifiers::SUPER | Modifiers::ALT);
+        assert_eq!(shortcut.key, Code::F12);
+
+        let shortcut = HotkeyManager::parse_hotkey("Meta+Up").unwrap();
+        assert_eq!(shortcut.mods, Modifiers::SUPER);
+        assert_eq!(shortcut.key, Code::ArrowUp);
+
+        let shortcut = HotkeyManager::parse_hotkey("7").unwrap();
+        assert!(shortcut.mods.is_empty());
+        assert_eq!(shortcut.key, Code::Digit7);
+    }
+
+    #[test]
+    fn test_parse_hotkey_malformed() {
+        let result = HotkeyManager::parse_hotkey("Cmd++A");
+        assert!(matches!(result, Err(HotkeyError::InvalidFormat(_))));
+
+        match HotkeyManager::parse_hotkey("Ctrl+Hyper+A") {
+            Err(HotkeyError::InvalidFormat(token)) => assert_eq!(token, "Hyper"),
+            other => panic!("Expected InvalidFormat, got {:?}", other),
+        }
+
+        assert!(HotkeyManager::parse_hotkey("Ctrl+Shift").is_err());
+        assert!(HotkeyManager::parse_hotkey("Ctrl+A+B").is_err());
+    }
+
+    #[test]
+    fn test_validate_normalizes() {
+        let parsed = HotkeyManager::validate("shift + ctrl + a").unwrap();
+        assert_eq!(parsed.normalized, "Ctrl+Shift+A");
+        assert_eq!(parsed.modifiers, vec!["Ctrl", "Shift"]);
+        assert_eq!(parsed.key, "A");
+
+        let parsed = HotkeyManager::validate("Option+Control+ArrowUp").unwrap();
+        assert_eq!(parsed.normalized, "Ctrl+Alt+Up");
+
+        let parsed = HotkeyManager::validate("F5").unwrap();
+        assert_eq!(parsed.normalized, "F5");
+        assert!(parsed.modifiers.is_empty());
+
+        let parsed = HotkeyManager::validate("Ctrl+\\").unwrap();
+        assert_eq!(parsed.normalized, "Ctrl+Backslash");
+
+        let parsed = HotkeyManager::validate("Alt+9").unwrap();
+        assert_eq!(parsed.key, "9");
+    }
+
+    #[test]
+    fn test_validate_normalized_round_trips() {
+        for hotkey in ["CommandOrControl+Shift+\\", "cmd+option+f12", "Ctrl+Alt+Space"] {
+            let parsed = HotkeyManager::validate(hotkey).unwrap();
+            let again = HotkeyManager::validate(&parsed.normalized).unwrap();
+            assert_eq!(parsed, again);
+        }
+    }
+
+    #[test]
+    fn test_validate_invalid() {
+        let error = HotkeyManager::validate("Ctrl+Hyper+A").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid hotkey format: Hyper");
+
+        let error = HotkeyManager::validate("Ctrl+Shift").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid hotkey format: missing key in \"Ctrl+Shift\""
+        );
+
+        let error = HotkeyManager::validate("").unwrap_err();
+        assert!(error.to_string().contains("empty token"));
+    }
+
+    fn hotkey_config(hotkey: &str, hotkey_mode: HotkeyMode) -> AppConfig {
+        AppConfig {
+            hotkey: hotkey.to_string(),
+            hotkey_mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_needs_reregister() {
+        use HotkeyMode::{PushToTalk, Toggle};
+        let needs = |old, old_mode, new, new_mode| {
+            HotkeyManager::needs_reregister(
+                &hotkey_config(old, old_mode),
+                &hotkey_config(new, new_mode),
+            )
+            .unwrap()
+        };
+
+        // 相同或等价写法
+        assert!(!needs("Ctrl+Shift+A", Toggle, "Ctrl+Shift+A", Toggle));
+        assert!(!needs(
+            "ctrl + shift + a",
+            Toggle,
+            "Shift+Control+A",
+            Toggle
+        ));
+
+        // 热键或模式改变
+        assert!(needs("Ctrl+Shift+A", Toggle, "Ctrl+Shift+B", Toggle));
+        assert!(needs("Ctrl+Shift+A", Toggle, "Ctrl+Shift+A", PushToTalk));
+
+        // 旧热键无效时总是重新注册
+        assert!(needs("Ctrl+Hyper+A", Toggle, "Ctrl+Shift+A", Toggle));
+
+        // 新热键无效时报错
+        assert!(matches!(
+            HotkeyManager::needs_reregister(
+                &hotkey_config("Ctrl+Shift+A", Toggle),
+                &hotkey_config("Ctrl+Shift", Toggle)
+            ),
+            Err(HotkeyError::InvalidFormat(_))
+        ));
+
+        // 附加热键改变
+        let old = hotkey_config("Ctrl+Shift+A", Toggle);
+        let new = AppConfig {
+            hotkeys: Some(HotkeyBindings {
+                cancel: Some("Ctrl+Shift+C".to_string()),
+                ..Default::default()
+            }),
+            ..old.clone()
+        };
+        assert!(HotkeyManager::needs_reregister(&old, &new).unwrap());
+        assert!(!HotkeyManager::needs_reregister(&new, &new.clone()).unwrap());
+    }
+
+    #[test]
+    fn test_bindings_full_set() {
+        let config = AppConfig {
+            hotkeys: Some(HotkeyBindings {
+                toggle: Some("Ctrl+Alt+T".to_string()),
+                cancel: Some("Ctrl+Alt+C".to_string()),
+                settings: Some("Ctrl+Alt+S".to_string()),
+                commit: Some("Ctrl+Alt+M".to_string()),
+            }),
+            ..hotkey_config("Ctrl+Alt+Space", HotkeyMode::PushToTalk)
+        };
+
+        let bindings = HotkeyManager::bindings(&config).unwrap();
+        let actions: Vec<_> = bindings
+            .iter()
+            .map(|b| (b.shortcut.key, b.action))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (Code::Space, HotkeyAction::Record(HotkeyMode::PushToTalk)),
+                (Code::KeyT, HotkeyAction::Toggle),
+                (Code::KeyC, HotkeyAction::Cancel),
+                (Code::KeyS, HotkeyAction::Settings),
+                (Code::KeyM, HotkeyAction::Commit),
+            ]
+        );
+        assert!(
+            bindings
+                .iter()
+                .all(|b| b.shortcut.mods == Modifiers::CONTROL | Modifiers::ALT)
+        );
+
+        // 未配置或为空的附加热键不注册
+        let config = AppConfig {
+            hotkeys: Some(HotkeyBindings {
+                cancel: Some(" ".to_string()),
+                ..Default::default()
+            }),
+            ..hotkey_config("Ctrl+Alt+Space", HotkeyMode::Toggle)
+        };
+        assert_eq!(HotkeyManager::bindings(&config).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_bindings_collision() {
+        let config = AppConfig {
+            hotkeys: Some(HotkeyBindings {
+                toggle: Some("Ctrl+Alt+T".to_string()),
+                settings: Some("alt + ctrl + t".to_string()),
+                ..Default::default()
+            }),
+            ..hotkey_config("Ctrl+Alt+Space", HotkeyMode::Toggle)
+        };
+
+        let error = HotkeyManager::bindings(&config).unwrap_err();
+        assert!(matches!(error, HotkeyError::Duplicate(_)));
+        assert_eq!(
+            error.to_string(),
+            "Duplicate hotkey: \"alt + ctrl + t\" is bound to both hotkeys.toggle and hotkeys.settings"
+        );
+
+        // 与主热键冲突
+        let config = AppConfig {
+            hotkeys: Some(HotkeyBindings {
+                cancel: Some("Control+Alt+Space".to_string()),
+                ..Default::default()
+            }),
+            ..config
+        };
+        assert!(matches!(
+            HotkeyManager::bindings(&config),
+            Err(HotkeyError::Duplicate(_))
+        ));
     }
 
     // 实际的热键注册测试需要 Tauri 运行时
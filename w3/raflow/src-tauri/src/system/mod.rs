@@ -1,11 +1,13 @@
 //! 系统集成模块
 //!
-//! 包含窗口追踪、热键管理、系统托盘等系统级功能
+//! 包含窗口追踪、热键管理、系统托盘、首次运行引导等系统级功能
 
 pub mod hotkey;
+pub mod setup;
 pub mod tray;
 pub mod window;
 
-pub use hotkey::{HotkeyError, HotkeyManager};
+pub use hotkey::{HotkeyAction, HotkeyBinding, HotkeyError, HotkeyManager, ParsedHotkey};
+pub use setup::SetupStatus;
 pub use tray::setup_tray;
-pub use window::{WindowError, WindowInfo, WindowTracker};
+pub use window::{DEFAULT_BLACKLIST, WindowError, WindowInfo, WindowTracker};
@@ -0,0 +1,70 @@
+//! 首次运行引导模块
+//!
+//! 汇总各项设置检查结果，供前端驱动引导流程
+
+use crate::audio::InputAvailability;
+use serde::Serialize;
+
+/// 设置状态
+///
+/// 每个字段为 `true` 表示对应步骤尚未完成
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+pub struct SetupStatus {
+    /// 未配置 API Key
+    pub api_key_missing: bool,
+    /// 需要授予麦克风权限
+    pub mic_permission_needed: bool,
+    /// 没有可用的输入设备
+    pub no_input_device: bool,
+    /// 热键注册失败（可能与其他应用冲突）
+    pub hotkey_conflict: bool,
+}
+
+impl SetupStatus {
+    /// 根据各项检查结果构建设置状态
+    ///
+    /// # Arguments
+    /// * `api_key` - 当前配置的 API Key
+    /// * `input` - 输入设备检查结果
+    /// * `hotkey_registered` - 热键是否已成功注册
+    pub fn from_checks(api_key: &str, input: InputAvailability, hotkey_registered: bool) -> Self {
+        Self {
+            api_key_missing: api_key.trim().is_empty(),
+            mic_permission_needed: input == InputAvailability::AccessDenied,
+            no_input_device: input == InputAvailability::NoDevice,
+            hotkey_conflict: !hotkey_registered,
+        }
+    }
+
+    /// 是否所有步骤都已完成
+    pub fn is_complete(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_complete() {
+        let status = SetupStatus::from_checks("key", InputAvailability::Available, true);
+        assert!(status.is_complete());
+    }
+
+    #[test]
+    fn test_setup_incomplete_steps() {
+        let status = SetupStatus::from_checks("  ", InputAvailability::AccessDenied, false);
+        assert!(status.api_key_missing);
+        assert!(status.mic_permission_needed);
+        assert!(!status.no_input_device);
+        assert!(status.hotkey_conflict);
+        assert!(!status.is_complete());
+
+        let status = SetupStatus::from_checks("key", InputAvailability::NoDevice, true);
+        assert!(!status.api_key_missing);
+        assert!(!status.mic_permission_needed);
+        assert!(status.no_input_device);
+        assert!(!status.hotkey_conflict);
+    }
+}
@@ -2,12 +2,15 @@
 //!
 //! 创建和管理系统托盘图标和菜单
 
+use crate::AppState;
+use crate::commands::toggle_with_trigger;
+use crate::state::StartTrigger;
 use tauri::{
-    AppHandle, Manager, Runtime,
+    AppHandle, Manager,
     menu::{Menu, MenuItemBuilder, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
 };
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// 设置系统托盘
 ///
@@ -22,16 +25,17 @@ use tracing::{debug, error};
 ///     setup_tray(app).unwrap();
 /// }
 /// ```
-pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
     debug!("Setting up system tray");
 
     // 创建菜单项
+    let toggle_item = MenuItemBuilder::with_id("toggle_recording", "开始/停止听写").build(app)?;
     let settings_item = MenuItemBuilder::with_id("settings", "设置").build(app)?;
     let separator = PredefinedMenuItem::separator(app)?;
     let quit_item = MenuItemBuilder::with_id("quit", "退出 RAFlow").build(app)?;
 
     // 创建菜单
-    let menu = Menu::with_items(app, &[&settings_item, &separator, &quit_item])?;
+    let menu = Menu::with_items(app, &[&toggle_item, &settings_item, &separator, &quit_item])?;
 
     // 创建托盘图标
     let _tray = TrayIconBuilder::new()
@@ -42,6 +46,17 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
             debug!("Tray menu event: {:?}", event.id());
 
             match event.id().as_ref() {
+                "toggle_recording" => {
+                    // 托盘触发使用单独的开始等待时间，避开点击声
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let state = app.state::<AppState>().inner().clone();
+                        if let Err(e) = toggle_with_trigger(&app, &state, StartTrigger::Tray).await
+                        {
+                            warn!("Tray toggle recording failed: {}", e);
+                        }
+                    });
+                }
                 "settings" => {
                     // 显示设置窗口
                     if let Some(window) = app.get_webview_window("main") {
@@ -55,6 +70,7 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                 }
                 "quit" => {
                     debug!("Quit requested from tray");
+                    // 退出请求会先经过 RunEvent::ExitRequested，优雅停止录音后再退出
                     app.exit(0);
                 }
                 _ => {}
@@ -66,14 +82,12 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                     button,
                     button_state,
                     ..
-                } => {
-                    if button == MouseButton::Left && button_state == MouseButtonState::Up {
-                        // 左键单击 - 显示设置
-                        debug!("Tray icon left clicked");
-                        if let Some(app) = tray.app_handle().get_webview_window("main") {
-                            let _ = app.show();
-                            let _ = app.set_focus();
-                        }
+                } if button == MouseButton::Left && button_state == MouseButtonState::Up => {
+                    // 左键单击 - 显示设置
+                    debug!("Tray icon left clicked");
+                    if let Some(app) = tray.app_handle().get_webview_window("main") {
+                        let _ = app.show();
+                        let _ = app.set_focus();
                     }
                 }
                 TrayIconEvent::DoubleClick { .. } => {
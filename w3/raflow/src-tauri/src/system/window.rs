@@ -18,6 +18,16 @@ pub enum WindowError {
 
 type Result<T> = std::result::Result<T, WindowError>;
 
+/// 默认黑名单（密码管理器等敏感应用）
+pub const DEFAULT_BLACKLIST: &[&str] = &[
+    "1Password",
+    "Bitwarden",
+    "Keychain Access",
+    "LastPass",
+    "KeePass",
+    "Dashlane",
+];
+
 /// 窗口信息
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct WindowInfo {
@@ -48,8 +58,19 @@ impl WindowInfo {
     }
 
     /// 检查是否为黑名单应用
-    pub fn is_blacklisted(&self) -> bool {
-        WindowTracker::is_blacklisted(self)
+    ///
+    /// # Arguments
+    /// * `patterns` - 黑名单应用名称模式
+    pub fn is_blacklisted<S: AsRef<str>>(&self, patterns: &[S]) -> bool {
+        WindowTracker::is_blacklisted(self, patterns)
+    }
+
+    /// 检查是否为允许列表中的应用
+    ///
+    /// # Arguments
+    /// * `patterns` - 允许列表应用名称模式
+    pub fn is_allowlisted<S: AsRef<str>>(&self, patterns: &[S]) -> bool {
+        WindowTracker::is_allowlisted(self, patterns)
     }
 }
 
@@ -78,29 +99,36 @@ impl WindowTracker {
     /// 检查窗口是否在黑名单中
     ///
     /// 黑名单应用不应该接收自动文本注入（如密码管理器）
-    pub fn is_blacklisted(window: &WindowInfo) -> bool {
-        const BLACKLIST: &[&str] = &[
-            "1Password",
-            "Bitwarden",
-            "Keychain Access",
-            "LastPass",
-            "KeePass",
-            "Dashlane",
-        ];
+    ///
+    /// # Arguments
+    /// * `window` - 窗口信息
+    /// * `patterns` - 黑名单应用名称模式（应用名包含任一模式即命中）
+    pub fn is_blacklisted<S: AsRef<str>>(window: &WindowInfo, patterns: &[S]) -> bool {
+        Self::matches_app(window, patterns)
+    }
 
-        BLACKLIST.iter().any(|&app| window.app_name.contains(app))
+    /// 检查窗口是否在允许列表中
+    ///
+    /// 匹配规则与黑名单相同；空列表不匹配任何应用
+    ///
+    /// # Arguments
+    /// * `window` - 窗口信息
+    /// * `patterns` - 允许列表应用名称模式（应用名包含任一模式即命中）
+    pub fn is_allowlisted<S: AsRef<str>>(window: &WindowInfo, patterns: &[S]) -> bool {
+        Self::matches_app(window, patterns)
     }
 
-    /// 列出所有应该排除的应用
+    /// 应用名是否包含任一非空模式
+    fn matches_app<S: AsRef<str>>(window: &WindowInfo, patterns: &[S]) -> bool {
+        patterns.iter().any(|pattern| {
+            let pattern = pattern.as_ref().trim();
+            !pattern.is_empty() && window.app_name.contains(pattern)
+        })
+    }
+
+    /// 列出默认应该排除的应用
     pub fn get_blacklist() -> Vec<String> {
-        vec![
-            "1Password".to_string(),
-            "Bitwarden".to_string(),
-            "Keychain Access".to_string(),
-            "LastPass".to_string(),
-            "KeePass".to_string(),
-            "Dashlane".to_string(),
-        ]
+        DEFAULT_BLACKLIST.iter().map(|app| app.to_string()).collect()
     }
 
     /// 检查是否为终端应用
@@ -117,6 +145,34 @@ impl WindowTracker {
         TERMINALS.iter().any(|&term| window.app_name.contains(term))
     }
 
+    /// 进程是否仍在运行
+    ///
+    /// 用于判断记下的注入目标是否已关闭；无法判断的平台视为仍在运行
+    pub fn is_process_alive(process_id: u32) -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            std::path::Path::new("/proc")
+                .join(process_id.to_string())
+                .exists()
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            // 信号 0 只检查进程是否存在，不影响目标进程
+            std::process::Command::new("kill")
+                .args(["-0", &process_id.to_string()])
+                .stderr(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        }
+
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        {
+            let _ = process_id;
+            true
+        }
+    }
+
     /// 监听窗口变化（轮询方式）
     ///
     /// # Arguments
@@ -174,8 +230,8 @@ mod tests {
             position: (0, 0, 800, 600),
         };
 
-        assert!(WindowTracker::is_blacklisted(&password_manager));
-        assert!(password_manager.is_blacklisted());
+        assert!(WindowTracker::is_blacklisted(&password_manager, DEFAULT_BLACKLIST));
+        assert!(password_manager.is_blacklisted(DEFAULT_BLACKLIST));
     }
 
     #[test]
@@ -187,7 +243,23 @@ mod tests {
             position: (0, 0, 1920, 1080),
         };
 
-        assert!(!WindowTracker::is_blacklisted(&chrome));
+        assert!(!WindowTracker::is_blacklisted(&chrome, DEFAULT_BLACKLIST));
+    }
+
+    #[test]
+    fn test_custom_blacklist() {
+        let bank = WindowInfo {
+            app_name: "Internal Banking".to_string(),
+            title: "Login".to_string(),
+            process_id: 12345,
+            position: (0, 0, 800, 600),
+        };
+
+        assert!(!bank.is_blacklisted(DEFAULT_BLACKLIST));
+        assert!(bank.is_blacklisted(&["Banking".to_string()]));
+
+        // 空模式不匹配任何应用
+        assert!(!bank.is_blacklisted(&["", "  "]));
     }
 
     #[test]
@@ -209,6 +281,11 @@ mod tests {
         assert!(blacklist.contains(&"1Password".to_string()));
     }
 
+    #[test]
+    fn test_own_process_alive() {
+        assert!(WindowTracker::is_process_alive(std::process::id()));
+    }
+
     #[tokio::test]
     #[ignore] // 长时间运行测试
     async fn test_watch_window() {
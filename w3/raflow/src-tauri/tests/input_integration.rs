@@ -3,7 +3,7 @@
 //! 测试窗口追踪和文本注入的完整流程
 
 use raflow_lib::input::{InjectionConfig, InjectionStrategy};
-use raflow_lib::system::{WindowInfo, WindowTracker};
+use raflow_lib::system::{DEFAULT_BLACKLIST, WindowInfo, WindowTracker};
 
 #[test]
 fn test_window_info_serialization() {
@@ -44,7 +44,7 @@ fn test_blacklist_detection() {
         };
 
         assert!(
-            WindowTracker::is_blacklisted(&window),
+            WindowTracker::is_blacklisted(&window, DEFAULT_BLACKLIST),
             "{} should be blacklisted",
             app
         );
@@ -69,7 +69,7 @@ fn test_blacklist_detection() {
         };
 
         assert!(
-            !WindowTracker::is_blacklisted(&window),
+            !WindowTracker::is_blacklisted(&window, DEFAULT_BLACKLIST),
             "{} should not be blacklisted",
             app
         );
@@ -2,6 +2,7 @@
 //!
 //! 测试音频数据流到网络传输的完整流程
 
+use raflow_lib::audio::AudioFrame;
 use raflow_lib::network::{ClientMessage, NetworkManager, ServerMessage};
 use tokio::sync::mpsc;
 use tokio::time::{Duration, timeout};
@@ -32,7 +33,7 @@ async fn test_protocol_roundtrip() {
 #[tokio::test]
 async fn test_audio_to_network_pipeline() {
     // 创建通道
-    let (audio_tx, audio_rx) = mpsc::channel::<Vec<i16>>(100);
+    let (audio_tx, audio_rx) = mpsc::channel::<AudioFrame>(100);
     let (event_tx, mut event_rx) = mpsc::channel::<ServerMessage>(100);
 
     // 创建网络管理器（不启动实际连接）
@@ -42,7 +43,7 @@ async fn test_audio_to_network_pipeline() {
     tokio::spawn(async move {
         for i in 0..5 {
             let audio_chunk = vec![i as i16; 160]; // 10ms @ 16kHz
-            if audio_tx.send(audio_chunk).await.is_err() {
+            if audio_tx.send(AudioFrame::now(audio_chunk)).await.is_err() {
                 break;
             }
             tokio::time::sleep(Duration::from_millis(10)).await;
@@ -90,7 +91,7 @@ async fn test_message_parsing_edge_cases() {
 
 #[tokio::test]
 async fn test_concurrent_audio_sending() {
-    let (audio_tx, audio_rx) = mpsc::channel::<Vec<i16>>(100);
+    let (audio_tx, audio_rx) = mpsc::channel::<AudioFrame>(100);
     let (event_tx, _event_rx) = mpsc::channel::<ServerMessage>(100);
 
     let _manager = NetworkManager::new("test-key".to_string(), audio_rx, event_tx);
@@ -102,7 +103,7 @@ async fn test_concurrent_audio_sending() {
         let handle = tokio::spawn(async move {
             for j in 0..10 {
                 let data = vec![(i * 100 + j) as i16; 160];
-                if tx.send(data).await.is_err() {
+                if tx.send(AudioFrame::now(data)).await.is_err() {
                     break;
                 }
             }
@@ -124,7 +125,7 @@ async fn test_concurrent_audio_sending() {
 async fn test_real_websocket_connection() {
     let api_key = std::env::var("ELEVENLABS_API_KEY").expect("ELEVENLABS_API_KEY not set");
 
-    let (_audio_tx, audio_rx) = mpsc::channel::<Vec<i16>>(100);
+    let (_audio_tx, audio_rx) = mpsc::channel::<AudioFrame>(100);
     let (event_tx, mut event_rx) = mpsc::channel::<ServerMessage>(100);
 
     let mut manager = NetworkManager::new(api_key, audio_rx, event_tx);
@@ -5,6 +5,7 @@
  */
 
 import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { useTranscriptStore } from '../store/transcript';
 
@@ -51,8 +52,17 @@ export function OverlayWindow() {
     };
   }, [addCommitted, setPartial, setAudioLevel]);
 
+  // 点击悬浮窗停止录音，无需热键（未在录音时后端会忽略）
+  const handleClick = async () => {
+    try {
+      await invoke('stop_via_overlay');
+    } catch (error) {
+      console.error('Failed to stop recording:', error);
+    }
+  };
+
   return (
-    <div className="overlay-container">
+    <div className="overlay-container" onClick={handleClick}>
       {/* 状态指示器 */}
       <div className="status-indicator">
         <div className={`dot ${isRecording ? 'recording' : connectionState}`} />
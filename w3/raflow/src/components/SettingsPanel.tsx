@@ -64,11 +64,13 @@ export function SettingsPanel() {
           keyboard_max_chars: keyboardMaxChars,
           enable_blacklist: enableBlacklist,
         },
+        // API Key 有变化时先校验，避免开始录音后才发现 Key 无效
+        validateKey: true,
       });
       setMessage('设置已保存');
     } catch (error) {
       console.error('Failed to save settings:', error);
-      setMessage('保存失败');
+      setMessage(typeof error === 'string' ? error : '保存失败');
     } finally {
       setSaving(false);
     }
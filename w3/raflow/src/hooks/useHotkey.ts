@@ -17,6 +17,10 @@ export function useHotkey() {
       try {
         await invoke('toggle_recording');
       } catch (error) {
+        // 连按热键导致的重复开始不是错误
+        if ((error as { code?: string } | null)?.code === 'already_running') {
+          return;
+        }
         console.error('Failed to toggle recording:', error);
       }
     });